@@ -0,0 +1,270 @@
+//! Read access to the Ads API: ad accounts, their campaigns and line items.
+//!
+//! Enabled by the `ads` cargo feature. The Ads API lives on its own host,
+//! [`ADS_BASE_URL`], and only takes OAuth 1.0a user context from an app approved for
+//! Ads API access, for a user with access to the ad accounts. Requests go through the
+//! same client, signing and [`TweetyError`] as the rest of the API.
+//!
+//! ```rust,no_run
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! for account in client.get_ad_accounts(None).await?.data {
+//!     let campaigns = client.get_campaigns(&account.id, None).await?;
+//!     for campaign in campaigns.data {
+//!         println!("{}: {} ({})", account.name, campaign.name, campaign.entity_status);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ADS_BASE_URL`]: crate::api::endpoint::ADS_BASE_URL
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::query::Query;
+use crate::types::meta::Paginated;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Query parameters shared by the Ads API list endpoints.
+/// [Docs](https://developer.x.com/en/docs/x-ads-api/campaign-management/api-reference/campaigns#get-accounts-account-id-campaigns)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AdsParams {
+    /// The number of results per page, between 1 and 1000. The default is 200.
+    pub count: Option<u32>,
+    /// The `next_cursor` of a previous page.
+    pub cursor: Option<String>,
+    /// Include deleted entities.
+    pub with_deleted: Option<bool>,
+    /// Report the number of matching entities in `total_count`.
+    pub with_total_count: Option<bool>,
+}
+
+impl AdsParams {
+    pub fn to_query_string(&self) -> String {
+        self.to_query().to_query_string()
+    }
+
+    pub(crate) fn to_query(&self) -> Query {
+        let mut query = Query::new();
+        query
+            .push_opt("count", self.count)
+            .push_opt("cursor", self.cursor.as_ref())
+            .push_opt("with_deleted", self.with_deleted)
+            .push_opt("with_total_count", self.with_total_count);
+        query
+    }
+}
+
+impl Paginated for AdsParams {
+    fn set_page_token(&mut self, token: String) {
+        self.cursor = Some(token);
+    }
+}
+
+/// A page of Ads API entities.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdsResponse<T> {
+    #[serde(default = "Vec::new")]
+    pub data: Vec<T>,
+    /// Set when there is a page after this one.
+    pub next_cursor: Option<String>,
+    /// Only reported when requested with [`AdsParams::with_total_count`].
+    pub total_count: Option<u64>,
+}
+
+impl<T> AdsResponse<T> {
+    /// `params` set up to fetch the next page, or `None` on the last page.
+    pub fn next_request<P: Paginated>(&self, mut params: P) -> Option<P> {
+        params.set_page_token(self.next_cursor.clone()?);
+        Some(params)
+    }
+}
+
+/// An ad account the authenticated user has access to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct AdAccount {
+    pub id: String,
+    pub name: String,
+    pub business_id: Option<String>,
+    pub business_name: Option<String>,
+    /// IANA time zone the account's reporting and schedules use, e.g. `America/New_York`.
+    pub timezone: Option<String>,
+    /// When `timezone` last changed.
+    pub timezone_switch_at: Option<String>,
+    /// The account's salt for conversion tracking.
+    pub salt: Option<String>,
+    /// `ACCEPTED`, `PENDING` or `REJECTED`.
+    pub approval_status: Option<String>,
+    #[serde(default)]
+    pub deleted: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+/// A campaign of an ad account. Amounts are in micros of the account's currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct Campaign {
+    pub id: String,
+    pub name: String,
+    pub account_id: Option<String>,
+    pub funding_instrument_id: Option<String>,
+    /// `ACTIVE`, `DRAFT` or `PAUSED`.
+    pub entity_status: String,
+    /// `ACTIVE`, `SCHEDULED` or `EXPIRED`, from the campaign's flight dates.
+    pub effective_status: Option<String>,
+    pub currency: Option<String>,
+    pub daily_budget_amount_local_micro: Option<i64>,
+    pub total_budget_amount_local_micro: Option<i64>,
+    /// `CAMPAIGN` when the budget is shared by the line items, `LINE_ITEM` otherwise.
+    pub budget_optimization: Option<String>,
+    /// Spend the budget evenly over the day instead of as fast as possible.
+    pub standard_delivery: Option<bool>,
+    pub purchase_order_number: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    /// How many times a user may see the campaign's ads in `duration_in_days`.
+    pub frequency_cap: Option<u32>,
+    pub duration_in_days: Option<u32>,
+    /// Whether the campaign can run, see `reasons_not_servable` when it can't.
+    pub servable: Option<bool>,
+    #[serde(default)]
+    pub reasons_not_servable: Vec<String>,
+    #[serde(default)]
+    pub deleted: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+/// A line item of a campaign: what it bids on, where and for how much. Amounts are in
+/// micros of the account's currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct LineItem {
+    pub id: String,
+    pub name: Option<String>,
+    pub campaign_id: String,
+    /// `ACTIVE`, `DRAFT` or `PAUSED`.
+    pub entity_status: String,
+    /// What the line item optimizes for, e.g. `ENGAGEMENTS` or `WEBSITE_CLICKS`.
+    pub objective: Option<String>,
+    pub product_type: Option<String>,
+    #[serde(default)]
+    pub placements: Vec<String>,
+    /// `AUTO`, `MAX` or `TARGET`.
+    pub bid_strategy: Option<String>,
+    pub bid_amount_local_micro: Option<i64>,
+    pub target_cpa_local_micro: Option<i64>,
+    /// What the bid pays for, e.g. `ENGAGEMENT` or `IMPRESSION`.
+    pub pay_by: Option<String>,
+    pub goal: Option<String>,
+    pub daily_budget_amount_local_micro: Option<i64>,
+    pub total_budget_amount_local_micro: Option<i64>,
+    pub standard_delivery: Option<bool>,
+    pub frequency_cap: Option<u32>,
+    pub duration_in_days: Option<u32>,
+    pub currency: Option<String>,
+    pub funding_instrument_id: Option<String>,
+    pub advertiser_user_id: Option<String>,
+    pub advertiser_domain: Option<String>,
+    /// IAB category codes of the advertiser's business.
+    #[serde(default)]
+    pub categories: Vec<String>,
+    pub primary_web_event_tag: Option<String>,
+    pub android_app_store_identifier: Option<String>,
+    pub ios_app_store_identifier: Option<String>,
+    /// `EXPANDED`, `BROAD` or `DEFINED`.
+    pub audience_expansion: Option<String>,
+    /// `MANUAL` or `PROMOTED_ACCOUNT`.
+    pub creative_source: Option<String>,
+    /// Third-party measurement tags, objects with `tracking_partner` and `tracking_tag`.
+    #[serde(default)]
+    pub tracking_tags: Vec<Value>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    #[serde(default)]
+    pub deleted: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+/// Ads API
+/// [Docs](https://developer.x.com/en/docs/x-ads-api)
+impl TweetyClient {
+    /// GET /12/accounts
+    /// Returns the ad accounts the authenticated user has access to.
+    /// [Docs](https://developer.x.com/en/docs/x-ads-api/campaign-management/api-reference/accounts#get-accounts)
+    pub async fn get_ad_accounts(
+        &self,
+        params: Option<AdsParams>,
+    ) -> Result<AdsResponse<AdAccount>, TweetyError> {
+        let query = params.map(|params| params.to_query()).unwrap_or_default();
+        self.get_ads_page(&Endpoint::AdAccounts, &query).await
+    }
+
+    /// GET /12/accounts/:account_id/campaigns
+    /// Returns the campaigns of the ad account `account_id`.
+    /// [Docs](https://developer.x.com/en/docs/x-ads-api/campaign-management/api-reference/campaigns#get-accounts-account-id-campaigns)
+    pub async fn get_campaigns(
+        &self,
+        account_id: &str,
+        params: Option<AdsParams>,
+    ) -> Result<AdsResponse<Campaign>, TweetyError> {
+        let query = params.map(|params| params.to_query()).unwrap_or_default();
+        self.get_ads_page(&Endpoint::AdCampaigns(account_id.to_string()), &query)
+            .await
+    }
+
+    /// GET /12/accounts/:account_id/line_items
+    /// Returns the line items of the ad account `account_id`, only those of
+    /// `campaign_ids` unless it is empty.
+    /// [Docs](https://developer.x.com/en/docs/x-ads-api/campaign-management/api-reference/line-items#get-accounts-account-id-line-items)
+    pub async fn get_line_items(
+        &self,
+        account_id: &str,
+        campaign_ids: &[&str],
+        params: Option<AdsParams>,
+    ) -> Result<AdsResponse<LineItem>, TweetyError> {
+        let mut query = params.map(|params| params.to_query()).unwrap_or_default();
+        if !campaign_ids.is_empty() {
+            query.push("campaign_ids", campaign_ids.join(","));
+        }
+        self.get_ads_page(&Endpoint::AdLineItems(account_id.to_string()), &query)
+            .await
+    }
+
+    async fn get_ads_page<T: DeserializeOwned>(
+        &self,
+        endpoint: &Endpoint,
+        query: &Query,
+    ) -> Result<AdsResponse<T>, TweetyError> {
+        match self.send_request::<()>(endpoint, query, None).await {
+            Ok(value) => match serde_json::from_value::<AdsResponse<T>>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+}
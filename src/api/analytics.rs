@@ -0,0 +1,436 @@
+//! Follower-count history, for growth dashboards without an external database.
+//!
+//! A [`MetricsTracker`] looks up the public metrics of a set of accounts every
+//! interval and records them in a [`MetricsStore`]: [`MemoryStore`] for a process that
+//! runs the dashboard itself, [`JsonLinesStore`] to keep the history across restarts,
+//! or an implementation of the trait backed by anything else. The history is then read
+//! back with [`MetricsTracker::history`], [`MetricsTracker::follower_growth`] and
+//! [`MetricsTracker::downsample`].
+//!
+//! [`summarize_engagement`] aggregates the public metrics of a set of tweets into an
+//! [`EngagementReport`].
+//!
+//! ```rust,no_run
+//! use std::time::{Duration, SystemTime};
+//! use tweety_rs::api::analytics::{JsonLinesStore, MetricsTracker};
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let tracker = MetricsTracker::new(client, JsonLinesStore::new("followers.jsonl"))
+//!     .track("2244994945")
+//!     .interval(Duration::from_secs(3600));
+//!
+//! let week_ago = SystemTime::now() - Duration::from_secs(7 * 86_400);
+//! let growth = tracker.follower_growth("2244994945", week_ago, SystemTime::now())?;
+//! println!("{:?} new followers this week", growth);
+//!
+//! tracker.run().await
+//! # }
+//! ```
+
+use crate::api::client::TweetyClient;
+use crate::api::error::TweetyError;
+use crate::api::mentions::TweetData;
+use crate::api::retry::{DefaultRetryClassifier, RetryClassifier, RetryDecision};
+use crate::api::shutdown::Shutdown;
+use crate::api::user::{UserFields, UserQueryParams};
+use crate::types::ids::UserId;
+use crate::types::tweet::TweetPublicMetrics;
+use crate::types::user::ApiResponse;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Default time between two samples.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Tweets listed in each ranking of an [`EngagementReport`].
+pub const TOP_TWEETS: usize = 5;
+
+/// Most ids `GET /2/users` takes at once.
+const USERS_PER_REQUEST: usize = 100;
+
+/// An account's public metrics at one point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricsSample {
+    pub user_id: UserId,
+    pub at: SystemTime,
+    pub followers_count: u32,
+    pub following_count: u32,
+    pub tweet_count: u32,
+    pub listed_count: u32,
+}
+
+/// Where a [`MetricsTracker`] keeps its samples.
+pub trait MetricsStore: Send + Sync {
+    fn record(&self, sample: &MetricsSample) -> Result<(), TweetyError>;
+
+    /// Every sample of `user_id`, oldest first.
+    fn samples(&self, user_id: &UserId) -> Result<Vec<MetricsSample>, TweetyError>;
+}
+
+/// Keeps the samples in memory; the history is lost when the process exits.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    samples: Mutex<Vec<MetricsSample>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+}
+
+impl MetricsStore for MemoryStore {
+    fn record(&self, sample: &MetricsSample) -> Result<(), TweetyError> {
+        if let Ok(mut samples) = self.samples.lock() {
+            samples.push(sample.clone());
+        }
+        Ok(())
+    }
+
+    fn samples(&self, user_id: &UserId) -> Result<Vec<MetricsSample>, TweetyError> {
+        let samples = self.samples.lock().map(|samples| {
+            samples
+                .iter()
+                .filter(|sample| &sample.user_id == user_id)
+                .cloned()
+                .collect()
+        });
+        Ok(samples.unwrap_or_default())
+    }
+}
+
+/// Appends the samples to a file, one JSON object per line.
+#[derive(Debug)]
+pub struct JsonLinesStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl JsonLinesStore {
+    /// Stores the samples in `path`, which is created on the first sample.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        JsonLinesStore {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+impl MetricsStore for JsonLinesStore {
+    fn record(&self, sample: &MetricsSample) -> Result<(), TweetyError> {
+        let mut line = serde_json::to_string(sample)
+            .map_err(|err| TweetyError::SerializeError(err.to_string()))?;
+        line.push('\n');
+
+        let _guard = self.lock.lock();
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|err| TweetyError::FileIOError(err.to_string()))
+    }
+
+    fn samples(&self, user_id: &UserId) -> Result<Vec<MetricsSample>, TweetyError> {
+        let _guard = self.lock.lock();
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(TweetyError::FileIOError(err.to_string())),
+        };
+
+        let mut samples = Vec::new();
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let sample: MetricsSample = serde_json::from_str(line)?;
+            if &sample.user_id == user_id {
+                samples.push(sample);
+            }
+        }
+        Ok(samples)
+    }
+}
+
+/// Samples the public metrics of a set of accounts into a [`MetricsStore`].
+pub struct MetricsTracker {
+    client: TweetyClient,
+    store: Arc<dyn MetricsStore>,
+    user_ids: Vec<UserId>,
+    interval: Duration,
+    retry_classifier: Arc<dyn RetryClassifier>,
+    shutdown: Option<Shutdown>,
+}
+
+impl MetricsTracker {
+    pub fn new(client: TweetyClient, store: impl MetricsStore + 'static) -> Self {
+        MetricsTracker {
+            client,
+            store: Arc::new(store),
+            user_ids: Vec::new(),
+            interval: DEFAULT_INTERVAL,
+            retry_classifier: Arc::new(DefaultRetryClassifier),
+            shutdown: None,
+        }
+    }
+
+    /// Adds an account to sample.
+    pub fn track(mut self, user_id: impl Into<UserId>) -> Self {
+        self.user_ids.push(user_id.into());
+        self
+    }
+
+    /// Time between two samples, [`DEFAULT_INTERVAL`] unless set.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Decides which errors [`run`](MetricsTracker::run) keeps sampling after. Defaults
+    /// to [`DefaultRetryClassifier`].
+    pub fn retry_classifier(mut self, classifier: impl RetryClassifier + 'static) -> Self {
+        self.retry_classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Makes [`run`](MetricsTracker::run) return once `shutdown` is requested, after the
+    /// samples being taken are recorded.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// Looks up the tracked accounts once and records a sample for each.
+    pub async fn sample(&self) -> Result<Vec<MetricsSample>, TweetyError> {
+        let mut samples = Vec::new();
+        for ids in self.user_ids.chunks(USERS_PER_REQUEST) {
+            let params = UserQueryParams {
+                expansions: None,
+                tweet_fields: None,
+                user_fields: Some(vec![UserFields::PublicMetrics]),
+            };
+            let response = self.client.get_users(ids.to_vec(), Some(params)).await?;
+            let users: ApiResponse = serde_json::from_value(response)?;
+
+            let at = SystemTime::now();
+            for user in users.data {
+                let Some(metrics) = user.public_metrics else {
+                    continue;
+                };
+                let sample = MetricsSample {
+                    user_id: UserId::new(user.id),
+                    at,
+                    followers_count: metrics.followers_count.unwrap_or_default(),
+                    following_count: metrics.following_count.unwrap_or_default(),
+                    tweet_count: metrics.tweet_count.unwrap_or_default(),
+                    listed_count: metrics.listed_count.unwrap_or_default(),
+                };
+                self.store.record(&sample)?;
+                samples.push(sample);
+            }
+        }
+        Ok(samples)
+    }
+
+    /// Samples every interval. Errors the
+    /// [`retry_classifier`](MetricsTracker::retry_classifier) deems retryable are logged
+    /// and that round is skipped; the first one it deems permanent is returned.
+    /// Returns `Ok(())` once a shutdown is requested.
+    pub async fn run(&self) -> Result<(), TweetyError> {
+        let _running = self.shutdown.as_ref().map(Shutdown::running);
+
+        loop {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_requested) {
+                return Ok(());
+            }
+
+            let mut wait = self.interval;
+            let mut rate_limited = None;
+            if let Err(err) = self.sample().await {
+                match self.retry_classifier.classify(&err) {
+                    RetryDecision::Retry => {}
+                    RetryDecision::RetryAfter(retry_after) => wait = wait.max(retry_after),
+                    RetryDecision::Permanent => return Err(err),
+                }
+                rate_limited = err.rate_limit_family();
+                log::warn!("sampling metrics failed: {}", err);
+            }
+
+            let shutdown = self.shutdown.as_ref();
+            if self
+                .client
+                .wait_for_rate_limit(rate_limited, shutdown, wait)
+                .await
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Samples of `user_id` taken from `start` up to and including `end`, oldest first.
+    pub fn history(
+        &self,
+        user_id: impl Into<UserId>,
+        start: SystemTime,
+        end: SystemTime,
+    ) -> Result<Vec<MetricsSample>, TweetyError> {
+        let mut samples = self.store.samples(&user_id.into())?;
+        samples.retain(|sample| sample.at >= start && sample.at <= end);
+        samples.sort_by_key(|sample| sample.at);
+        Ok(samples)
+    }
+
+    /// Followers gained between the first and the last sample in the range, negative
+    /// for a loss. `None` with fewer than two samples.
+    pub fn follower_growth(
+        &self,
+        user_id: impl Into<UserId>,
+        start: SystemTime,
+        end: SystemTime,
+    ) -> Result<Option<i64>, TweetyError> {
+        let samples = self.history(user_id, start, end)?;
+        let growth = match (samples.first(), samples.last()) {
+            (Some(first), Some(last)) if samples.len() > 1 => {
+                Some(i64::from(last.followers_count) - i64::from(first.followers_count))
+            }
+            _ => None,
+        };
+        Ok(growth)
+    }
+
+    /// The history in the range with one sample per `bucket`, the last one taken in it,
+    /// e.g. daily values for a chart from hourly samples.
+    pub fn downsample(
+        &self,
+        user_id: impl Into<UserId>,
+        start: SystemTime,
+        end: SystemTime,
+        bucket: Duration,
+    ) -> Result<Vec<MetricsSample>, TweetyError> {
+        let bucket = bucket.as_secs().max(1);
+        let bucket_of = |sample: &MetricsSample| {
+            sample
+                .at
+                .duration_since(start)
+                .map(|elapsed| elapsed.as_secs() / bucket)
+                .unwrap_or_default()
+        };
+
+        let mut downsampled: Vec<MetricsSample> = Vec::new();
+        for sample in self.history(user_id, start, end)? {
+            match downsampled.last_mut() {
+                Some(last) if bucket_of(last) == bucket_of(&sample) => *last = sample,
+                _ => downsampled.push(sample),
+            }
+        }
+        Ok(downsampled)
+    }
+}
+
+/// Engagement counts added up over tweets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngagementTotals {
+    pub retweets: u64,
+    pub replies: u64,
+    pub likes: u64,
+    pub quotes: u64,
+    pub bookmarks: u64,
+    pub impressions: u64,
+}
+
+/// Engagement counts per tweet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EngagementAverages {
+    pub retweets: f64,
+    pub replies: f64,
+    pub likes: f64,
+    pub quotes: f64,
+    pub bookmarks: f64,
+    pub impressions: f64,
+}
+
+/// A tweet in one of the rankings of an [`EngagementReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopTweet {
+    pub id: String,
+    pub count: u64,
+}
+
+/// Result of [`summarize_engagement`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EngagementReport {
+    /// Tweets that carried public metrics; the others are left out of the report.
+    pub tweet_count: usize,
+    pub totals: EngagementTotals,
+    pub averages: EngagementAverages,
+    /// The [`TOP_TWEETS`] most liked tweets, most liked first.
+    pub top_by_likes: Vec<TopTweet>,
+    pub top_by_retweets: Vec<TopTweet>,
+    pub top_by_impressions: Vec<TopTweet>,
+}
+
+/// Aggregates the public metrics of `tweets`, which need `tweet.fields=public_metrics`.
+///
+/// ```
+/// use tweety_rs::api::analytics::summarize_engagement;
+///
+/// let report = summarize_engagement(&[]);
+/// assert_eq!(report.tweet_count, 0);
+/// assert!(report.top_by_likes.is_empty());
+/// ```
+pub fn summarize_engagement(tweets: &[TweetData]) -> EngagementReport {
+    let measured: Vec<_> = tweets
+        .iter()
+        .filter_map(|tweet| Some((tweet.id.as_str(), tweet.public_metrics.as_ref()?)))
+        .collect();
+
+    let mut totals = EngagementTotals::default();
+    for (_, metrics) in &measured {
+        totals.retweets += metrics.retweet_count.unwrap_or_default();
+        totals.replies += metrics.reply_count.unwrap_or_default();
+        totals.likes += metrics.like_count.unwrap_or_default();
+        totals.quotes += metrics.quote_count.unwrap_or_default();
+        totals.bookmarks += metrics.bookmark_count.unwrap_or_default();
+        totals.impressions += metrics.impression_count.unwrap_or_default();
+    }
+
+    let averages = if measured.is_empty() {
+        EngagementAverages::default()
+    } else {
+        let count = measured.len() as f64;
+        EngagementAverages {
+            retweets: totals.retweets as f64 / count,
+            replies: totals.replies as f64 / count,
+            likes: totals.likes as f64 / count,
+            quotes: totals.quotes as f64 / count,
+            bookmarks: totals.bookmarks as f64 / count,
+            impressions: totals.impressions as f64 / count,
+        }
+    };
+
+    let top_by = |count: fn(&TweetPublicMetrics) -> Option<u64>| {
+        let mut ranked: Vec<TopTweet> = measured
+            .iter()
+            .map(|(id, metrics)| TopTweet {
+                id: id.to_string(),
+                count: count(metrics).unwrap_or_default(),
+            })
+            .collect();
+        // Stable, so ties keep the order the tweets came in.
+        ranked.sort_by_key(|top| std::cmp::Reverse(top.count));
+        ranked.truncate(TOP_TWEETS);
+        ranked
+    };
+
+    EngagementReport {
+        tweet_count: measured.len(),
+        totals,
+        averages,
+        top_by_likes: top_by(|metrics| metrics.like_count),
+        top_by_retweets: top_by(|metrics| metrics.retweet_count),
+        top_by_impressions: top_by(|metrics| metrics.impression_count),
+    }
+}
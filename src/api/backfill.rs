@@ -0,0 +1,278 @@
+//! Historical backfills over the full-archive search.
+//!
+//! A long date range is split into [`TimeWindows`], and each window is searched page by
+//! page before moving on to the next, so a failure loses at most one window of work and
+//! the results come out oldest first.
+//!
+//! ```rust,no_run
+//! use futures_util::StreamExt;
+//! use std::time::{Duration, SystemTime};
+//! use tweety_rs::api::backfill::{Backfill, TimeWindows};
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) {
+//! let end = SystemTime::now();
+//! let start = end - Duration::from_secs(7 * 24 * 60 * 60);
+//! let backfill = Backfill::new("#rustlang -is:retweet", start, end).window(TimeWindows::HOUR);
+//!
+//! let mut tweets = Box::pin(client.backfill(backfill));
+//! while let Some(tweet) = tweets.next().await {
+//!     match tweet {
+//!         Ok(tweet) => println!("{}: {}", tweet.id, tweet.text),
+//!         Err(err) => eprintln!("backfill stopped: {}", err),
+//!     }
+//! }
+//! # }
+//! ```
+
+use crate::api::client::TweetyClient;
+use crate::api::conversation::compare_ids;
+use crate::api::dedup::SeenIds;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::mentions::TweetData;
+use crate::api::query::{join_fields, Query};
+use crate::api::search::TweetField;
+use crate::types::meta::Meta;
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Splits `[start, end)` into consecutive windows of a fixed length; the last one is
+/// cut short at `end`.
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use tweety_rs::api::backfill::TimeWindows;
+///
+/// let start = UNIX_EPOCH;
+/// let end = start + Duration::from_secs(90 * 60);
+/// let windows: Vec<_> = TimeWindows::new(start, end, TimeWindows::HOUR).collect();
+///
+/// assert_eq!(windows.len(), 2);
+/// assert_eq!(windows[1], (start + TimeWindows::HOUR, end));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TimeWindows {
+    next: SystemTime,
+    end: SystemTime,
+    window: Duration,
+}
+
+impl TimeWindows {
+    pub const HOUR: Duration = Duration::from_secs(60 * 60);
+    pub const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// A zero `window` yields the whole range at once.
+    pub fn new(start: SystemTime, end: SystemTime, window: Duration) -> Self {
+        TimeWindows {
+            next: start,
+            end,
+            window,
+        }
+    }
+}
+
+impl Iterator for TimeWindows {
+    type Item = (SystemTime, SystemTime);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+
+        let start = self.next;
+        let end = match start.checked_add(self.window) {
+            Some(end) if !self.window.is_zero() && end < self.end => end,
+            _ => self.end,
+        };
+        self.next = end;
+
+        Some((start, end))
+    }
+}
+
+/// A full-archive search over a date range, see [`TweetyClient::backfill`].
+#[derive(Debug, Clone)]
+pub struct Backfill {
+    query: String,
+    windows: TimeWindows,
+    tweet_fields: Option<String>,
+    max_results: u32,
+}
+
+impl Backfill {
+    /// Searches `query` from `start` up to `end`, one day at a time.
+    pub fn new(query: &str, start: SystemTime, end: SystemTime) -> Self {
+        Backfill {
+            query: query.to_string(),
+            windows: TimeWindows::new(start, end, TimeWindows::DAY),
+            tweet_fields: None,
+            max_results: 500,
+        }
+    }
+
+    /// Length of each searched window. Busy queries want shorter windows, so a failure
+    /// costs less to retry.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.windows.window = window;
+        self
+    }
+
+    pub fn tweet_fields(mut self, fields: &[TweetField]) -> Self {
+        self.tweet_fields = Some(join_fields(fields));
+        self
+    }
+
+    /// Page size, between 10 and 500. Defaults to 500.
+    pub fn max_results(mut self, max_results: u32) -> Self {
+        self.max_results = max_results;
+        self
+    }
+
+    fn query(&self, start: SystemTime, end: SystemTime, next_token: Option<&String>) -> Query {
+        let mut query = Query::new();
+        query
+            .push("query", &self.query)
+            .push("start_time", to_rfc3339(start))
+            .push("end_time", to_rfc3339(end))
+            .push("max_results", self.max_results)
+            .push_opt("tweet.fields", self.tweet_fields.as_ref())
+            .push_opt("next_token", next_token);
+        query
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchPage {
+    #[serde(default)]
+    data: Vec<TweetData>,
+    meta: Meta,
+}
+
+struct BackfillState {
+    client: TweetyClient,
+    backfill: Backfill,
+    ready: VecDeque<TweetData>,
+    seen: SeenIds,
+    failed: bool,
+}
+
+impl BackfillState {
+    /// Collects every page of the next window that has tweets, sorted oldest first.
+    async fn fill(&mut self) -> Result<(), TweetyError> {
+        while self.ready.is_empty() {
+            let Some((start, end)) = self.backfill.windows.next() else {
+                return Ok(());
+            };
+
+            let mut tweets = Vec::new();
+            let mut next_token = None;
+            loop {
+                let query = self.backfill.query(start, end, next_token.as_ref());
+                let value = self
+                    .client
+                    .send_request::<()>(&Endpoint::FullArchiveSearch, &query, None)
+                    .await?;
+                let page = serde_json::from_value::<SearchPage>(value)?;
+
+                tweets.extend(page.data);
+                match page.meta.next_token {
+                    Some(token) => next_token = Some(token),
+                    None => break,
+                }
+            }
+
+            self.seen.retain_new(&mut tweets);
+            tweets.sort_by(|a, b| compare_ids(&a.id, &b.id));
+            self.ready.extend(tweets);
+        }
+
+        Ok(())
+    }
+}
+
+impl TweetyClient {
+    /// Runs a full-archive search window by window and yields the tweets oldest first,
+    /// each tweet once.
+    ///
+    /// The stream ends after the first error, which is yielded. Needs the Pro tier or
+    /// above, like [`TweetyClient::full_archive_search`].
+    /// [Docs](https://developer.x.com/en/docs/x-api/tweets/search/api-reference/get-tweets-search-all)
+    pub fn backfill(
+        &self,
+        backfill: Backfill,
+    ) -> impl Stream<Item = Result<TweetData, TweetyError>> + Send + 'static {
+        let state = BackfillState {
+            client: self.clone(),
+            backfill,
+            ready: VecDeque::new(),
+            seen: SeenIds::default(),
+            failed: false,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            if state.failed {
+                return None;
+            }
+            if let Err(err) = state.fill().await {
+                state.failed = true;
+                return Some((Err(err), state));
+            }
+
+            let tweet = state.ready.pop_front()?;
+            Some((Ok(tweet), state))
+        })
+    }
+}
+
+/// Formats `time` as an RFC 3339 UTC timestamp with second precision, as the
+/// `start_time` and `end_time` parameters expect.
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use tweety_rs::api::backfill::to_rfc3339;
+///
+/// let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// assert_eq!(to_rfc3339(time), "2023-11-14T22:13:20Z");
+/// ```
+pub fn to_rfc3339(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+/// The year, month and day of the date `days` days after the epoch, after Howard
+/// Hinnant's `civil_from_days`.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month as u32, day as u32)
+}
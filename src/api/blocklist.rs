@@ -0,0 +1,469 @@
+//! Blocking and muting accounts, and sharing block and mute lists.
+//!
+//! [`TweetyClient::export_blocklist`] writes the ids of the accounts a user blocks or
+//! mutes to a file, one per line. [`BlocklistImport`] applies such a file to another
+//! account, pausing between requests to stay under the rate limit and, with
+//! [`persist_to`](BlocklistImport::persist_to), picking up where an interrupted import
+//! stopped. [`TweetyClient::block_list_members`] blocks everyone on a List the same way.
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::blocklist::{BlocklistImport, BlocklistKind};
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(source: TweetyClient, target: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! source
+//!     .export_blocklist(BlocklistKind::Block, "2244994945", "blocked.txt")
+//!     .await?;
+//!
+//! let summary = BlocklistImport::new(target, BlocklistKind::Block, "blocked.txt")?
+//!     .persist_to("blocked.progress")?
+//!     .run("783214")
+//!     .await?;
+//! println!("blocked {}, skipped {}", summary.applied, summary.skipped.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+#[cfg(feature = "lists")]
+use crate::api::lists::ListFollowersParams;
+use crate::api::query::Query;
+use crate::api::retry::{DefaultRetryClassifier, RetryClassifier, RetryDecision};
+use crate::api::shutdown::{self, Shutdown};
+#[cfg(feature = "lists")]
+use crate::types::ids::ListId;
+use crate::types::ids::UserId;
+use crate::types::meta::Meta;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default pause between two blocks or mutes: X allows 50 per 15 minutes.
+pub const DEFAULT_IMPORT_DELAY: Duration = Duration::from_secs(18);
+
+/// Most users a page of `GET /2/users/:id/blocking` or `/muting` returns.
+const PAGE_SIZE: u32 = 1000;
+
+/// Which of a user's lists to export or import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlocklistKind {
+    Block,
+    Mute,
+}
+
+impl BlocklistKind {
+    fn list_endpoint(self, user_id: UserId) -> Endpoint {
+        match self {
+            BlocklistKind::Block => Endpoint::Blocking(user_id),
+            BlocklistKind::Mute => Endpoint::Muting(user_id),
+        }
+    }
+
+    fn apply_endpoint(self, user_id: UserId) -> Endpoint {
+        match self {
+            BlocklistKind::Block => Endpoint::Block(user_id),
+            BlocklistKind::Mute => Endpoint::Mute(user_id),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BlocklistBody {
+    pub target_user_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BlockResponse {
+    pub data: BlockData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BlockData {
+    pub blocking: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MuteResponse {
+    pub data: MuteData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MuteData {
+    pub muting: bool,
+}
+
+/// A page of blocked or muted users.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BlocklistPage {
+    #[serde(default)]
+    pub data: Vec<BlocklistUser>,
+    #[serde(default)]
+    pub meta: Meta,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BlocklistUser {
+    pub id: String,
+    pub name: String,
+    pub username: String,
+}
+
+/// Options of [`TweetyClient::block_list_members`].
+#[derive(Debug, Clone)]
+pub struct BlockListMembersOptions {
+    /// Members left alone, such as the moderators themselves.
+    pub exclude: HashSet<String>,
+    /// Pause between two blocks.
+    pub delay: Duration,
+}
+
+impl Default for BlockListMembersOptions {
+    fn default() -> Self {
+        BlockListMembersOptions {
+            exclude: HashSet::new(),
+            delay: DEFAULT_IMPORT_DELAY,
+        }
+    }
+}
+
+/// Result of [`BlocklistImport::run`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Accounts blocked or muted by this run.
+    pub applied: usize,
+    /// Accounts the API refused, such as deleted or suspended ones, with the error.
+    pub skipped: Vec<(String, String)>,
+}
+
+impl TweetyClient {
+    /// Blocks `target_user_id` as `user_id`.
+    /// [Docs](https://developer.x.com/en/docs/x-api/users/blocks/api-reference/post-users-user_id-blocking)
+    pub async fn block_user(
+        &self,
+        user_id: impl Into<UserId>,
+        target_user_id: impl Into<UserId>,
+    ) -> Result<BlockResponse, TweetyError> {
+        let body = BlocklistBody {
+            target_user_id: target_user_id.into().into_inner(),
+        };
+        let value = self
+            .send_request(&Endpoint::Block(user_id.into()), &Query::new(), Some(body))
+            .await?;
+        serde_json::from_value(value).map_err(TweetyError::JsonParseError)
+    }
+
+    /// Unblocks `target_user_id` as `user_id`.
+    /// [Docs](https://developer.x.com/en/docs/x-api/users/blocks/api-reference/delete-users-user_id-blocking)
+    pub async fn unblock_user(
+        &self,
+        user_id: impl Into<UserId>,
+        target_user_id: impl Into<UserId>,
+    ) -> Result<BlockResponse, TweetyError> {
+        let endpoint = Endpoint::Unblock {
+            user_id: user_id.into(),
+            target_user_id: target_user_id.into(),
+        };
+        let value = self
+            .send_request::<()>(&endpoint, &Query::new(), None)
+            .await?;
+        serde_json::from_value(value).map_err(TweetyError::JsonParseError)
+    }
+
+    /// Mutes `target_user_id` as `user_id`.
+    /// [Docs](https://developer.x.com/en/docs/x-api/users/mutes/api-reference/post-users-user_id-muting)
+    pub async fn mute_user(
+        &self,
+        user_id: impl Into<UserId>,
+        target_user_id: impl Into<UserId>,
+    ) -> Result<MuteResponse, TweetyError> {
+        let body = BlocklistBody {
+            target_user_id: target_user_id.into().into_inner(),
+        };
+        let value = self
+            .send_request(&Endpoint::Mute(user_id.into()), &Query::new(), Some(body))
+            .await?;
+        serde_json::from_value(value).map_err(TweetyError::JsonParseError)
+    }
+
+    /// Unmutes `target_user_id` as `user_id`.
+    /// [Docs](https://developer.x.com/en/docs/x-api/users/mutes/api-reference/delete-users-user_id-muting)
+    pub async fn unmute_user(
+        &self,
+        user_id: impl Into<UserId>,
+        target_user_id: impl Into<UserId>,
+    ) -> Result<MuteResponse, TweetyError> {
+        let endpoint = Endpoint::Unmute {
+            user_id: user_id.into(),
+            target_user_id: target_user_id.into(),
+        };
+        let value = self
+            .send_request::<()>(&endpoint, &Query::new(), None)
+            .await?;
+        serde_json::from_value(value).map_err(TweetyError::JsonParseError)
+    }
+
+    /// One page of the accounts `user_id` blocks or mutes.
+    /// [Docs](https://developer.x.com/en/docs/x-api/users/blocks/api-reference/get-users-blocking)
+    pub async fn get_blocklist_page(
+        &self,
+        kind: BlocklistKind,
+        user_id: impl Into<UserId>,
+        pagination_token: Option<&str>,
+    ) -> Result<BlocklistPage, TweetyError> {
+        let mut query = Query::new();
+        query
+            .push("max_results", PAGE_SIZE)
+            .push_opt("pagination_token", pagination_token);
+        let value = self
+            .send_request::<()>(&kind.list_endpoint(user_id.into()), &query, None)
+            .await?;
+        serde_json::from_value(value).map_err(TweetyError::JsonParseError)
+    }
+
+    /// Writes the ids of every account `user_id` blocks or mutes to `path`, one per
+    /// line, and returns how many there were.
+    pub async fn export_blocklist(
+        &self,
+        kind: BlocklistKind,
+        user_id: impl Into<UserId>,
+        path: impl AsRef<Path>,
+    ) -> Result<usize, TweetyError> {
+        let user_id: UserId = user_id.into();
+        let mut ids = Vec::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let page = self
+                .get_blocklist_page(kind, user_id.clone(), next_token.as_deref())
+                .await?;
+            ids.extend(page.data.into_iter().map(|user| user.id));
+            match page.meta.next_token {
+                Some(token) => next_token = Some(token),
+                None => break,
+            }
+        }
+
+        let mut contents = ids.join("\n");
+        contents.push('\n');
+        fs::write(path, contents).map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+        Ok(ids.len())
+    }
+
+    /// Blocks every member of `list_id` as `user_id`, except `user_id` itself and the
+    /// members in `options.exclude`.
+    ///
+    /// The members are gathered first, so `on_progress(handled, total)` knows the total.
+    /// Blocks are paced and retried as in [`BlocklistImport::run`].
+    #[cfg(feature = "lists")]
+    pub async fn block_list_members<F>(
+        &self,
+        user_id: impl Into<UserId>,
+        list_id: impl Into<ListId>,
+        options: BlockListMembersOptions,
+        on_progress: F,
+    ) -> Result<ImportSummary, TweetyError>
+    where
+        F: FnMut(usize, usize) + Send + 'static,
+    {
+        let user_id: UserId = user_id.into();
+        let list_id: ListId = list_id.into();
+
+        let mut ids = Vec::new();
+        let mut pagination_token = None;
+        loop {
+            let params = ListFollowersParams {
+                max_results: Some(100),
+                pagination_token,
+                ..ListFollowersParams::default()
+            };
+            let page = self.get_list_members(list_id.clone(), Some(params)).await?;
+            ids.extend(
+                page.data
+                    .into_iter()
+                    .map(|member| member.id)
+                    .filter(|id| id != user_id.as_ref() && !options.exclude.contains(id)),
+            );
+            match page.meta.next_token {
+                Some(token) => pagination_token = Some(token),
+                None => break,
+            }
+        }
+
+        BlocklistImport::from_ids(self.clone(), BlocklistKind::Block, ids)
+            .delay(options.delay)
+            .on_progress(on_progress)
+            .run(user_id)
+            .await
+    }
+}
+
+/// Blocks or mutes every account of a list written by
+/// [`TweetyClient::export_blocklist`].
+///
+/// Blank lines and lines starting with `#` are ignored, so hand-maintained lists can
+/// carry comments.
+pub struct BlocklistImport {
+    client: TweetyClient,
+    kind: BlocklistKind,
+    ids: Vec<String>,
+    delay: Duration,
+    done: usize,
+    state_file: Option<PathBuf>,
+    retry_classifier: Arc<dyn RetryClassifier>,
+    on_progress: Option<Box<dyn FnMut(usize, usize) + Send>>,
+    shutdown: Option<Shutdown>,
+}
+
+impl BlocklistImport {
+    /// Reads the ids to block or mute from `path`.
+    pub fn new(
+        client: TweetyClient,
+        kind: BlocklistKind,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, TweetyError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+        let ids = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+
+        Ok(BlocklistImport::from_ids(client, kind, ids))
+    }
+
+    /// Blocks or mutes `ids`, gathered some other way than from a file.
+    pub fn from_ids(client: TweetyClient, kind: BlocklistKind, ids: Vec<String>) -> Self {
+        BlocklistImport {
+            client,
+            kind,
+            ids,
+            delay: DEFAULT_IMPORT_DELAY,
+            done: 0,
+            state_file: None,
+            retry_classifier: Arc::new(DefaultRetryClassifier),
+            on_progress: None,
+            shutdown: None,
+        }
+    }
+
+    /// Pause between two requests, [`DEFAULT_IMPORT_DELAY`] unless set.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Saves how many ids were handled to `path` after each one, and skips the ids
+    /// already handled according to it.
+    pub fn persist_to(mut self, path: impl AsRef<Path>) -> Result<Self, TweetyError> {
+        let path = path.as_ref().to_path_buf();
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                self.done = contents.trim().parse().unwrap_or_default();
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(TweetyError::FileIOError(err.to_string())),
+        }
+
+        self.state_file = Some(path);
+        Ok(self)
+    }
+
+    /// Decides which errors are retried and which end the import. Defaults to
+    /// [`DefaultRetryClassifier`].
+    pub fn retry_classifier(mut self, classifier: impl RetryClassifier + 'static) -> Self {
+        self.retry_classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Calls `on_progress(handled, total)` after each id, skipped ones included.
+    pub fn on_progress<F>(mut self, on_progress: F) -> Self
+    where
+        F: FnMut(usize, usize) + Send + 'static,
+    {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    /// Makes [`run`](BlocklistImport::run) return early once `shutdown` is requested,
+    /// after the id in progress is handled and saved. A later run with the same
+    /// [`persist_to`](BlocklistImport::persist_to) file picks up from there.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// Ids left to handle.
+    pub fn remaining(&self) -> &[String] {
+        self.ids.get(self.done..).unwrap_or_default()
+    }
+
+    /// Blocks or mutes the remaining ids as `user_id`, or those handled before a
+    /// shutdown is requested.
+    ///
+    /// Retryable errors are retried after the classifier's wait. Ids the API refuses
+    /// with a permanent error are skipped and reported in the summary; any other
+    /// permanent error, such as rejected credentials, ends the import.
+    pub async fn run(&mut self, user_id: impl Into<UserId>) -> Result<ImportSummary, TweetyError> {
+        let user_id: UserId = user_id.into();
+        let endpoint = self.kind.apply_endpoint(user_id);
+        let mut summary = ImportSummary::default();
+        let _running = self.shutdown.as_ref().map(Shutdown::running);
+
+        while let Some(target_user_id) = self.ids.get(self.done).cloned() {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_requested) {
+                break;
+            }
+
+            let body = BlocklistBody {
+                target_user_id: target_user_id.clone(),
+            };
+            let result = self
+                .client
+                .send_request::<BlocklistBody>(&endpoint, &Query::new(), Some(body))
+                .await;
+
+            let wait = match result {
+                Ok(_) => {
+                    summary.applied += 1;
+                    self.advance()?;
+                    self.delay
+                }
+                Err(err) => match self.retry_classifier.classify(&err) {
+                    RetryDecision::Retry => self.delay,
+                    RetryDecision::RetryAfter(wait) => wait.max(self.delay),
+                    RetryDecision::Permanent if err.status().is_some() => {
+                        summary.skipped.push((target_user_id, err.to_string()));
+                        self.advance()?;
+                        self.delay
+                    }
+                    RetryDecision::Permanent => return Err(err),
+                },
+            };
+
+            if self.done < self.ids.len() && shutdown::sleep(self.shutdown.as_ref(), wait).await {
+                break;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    fn advance(&mut self) -> Result<(), TweetyError> {
+        self.done += 1;
+        if let Some(ref path) = self.state_file {
+            fs::write(path, self.done.to_string())
+                .map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+        }
+        if let Some(ref mut on_progress) = self.on_progress {
+            on_progress(self.done, self.ids.len());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+//! Publishing to Bluesky, as a [`CrossPoster`].
+//!
+//! Enabled by the `bluesky` cargo feature. [`Bluesky`] signs in with a handle and an
+//! app password, created under Settings → App Passwords, and creates posts in the
+//! account's repository. The session is created on the first post and again when it
+//! expires. Links in the text are marked up so Bluesky shows them as links:
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::bluesky::Bluesky;
+//! use tweety_rs::api::crosspost::{CrossPost, Post};
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) {
+//! let cross_post = CrossPost::new()
+//!     .to(client)
+//!     .to(Bluesky::new("tweety.bsky.social", "app-password"));
+//!
+//! let post = Post::new("tweety-rs 1.4.0 is out!").link("https://crates.io/crates/tweety-rs");
+//! let results = cross_post.publish(&post).await;
+//! # let _ = results;
+//! # }
+//! ```
+
+use crate::api::backfill::to_rfc3339;
+use crate::api::crosspost::{CrossPosted, CrossPoster};
+use crate::api::error::TweetyError;
+use crate::api::secret::SecretString;
+use crate::api::text::grapheme_count;
+use futures_util::future::BoxFuture;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::Mutex;
+use url::Url;
+
+/// The longest post Bluesky accepts, in grapheme clusters.
+pub const MAX_LENGTH: usize = 300;
+
+/// The service accounts are on unless they run their own PDS, see [`Bluesky::service`].
+pub const DEFAULT_SERVICE: &str = "https://bsky.social";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Session {
+    did: String,
+    access_jwt: SecretString,
+}
+
+#[derive(Debug, Deserialize)]
+struct Record {
+    uri: String,
+}
+
+/// Creates posts on Bluesky. Clones share the session.
+#[derive(Debug, Clone)]
+pub struct Bluesky {
+    http: reqwest::Client,
+    service: String,
+    identifier: String,
+    app_password: SecretString,
+    session: Arc<Mutex<Option<Session>>>,
+}
+
+impl Bluesky {
+    /// Signs in as `identifier`, a handle such as `tweety.bsky.social` or a DID, with
+    /// `app_password`.
+    pub fn new(identifier: &str, app_password: &str) -> Self {
+        Bluesky {
+            http: reqwest::Client::new(),
+            service: DEFAULT_SERVICE.to_string(),
+            identifier: identifier.to_string(),
+            app_password: SecretString::new(app_password),
+            session: Arc::default(),
+        }
+    }
+
+    /// Talks to the PDS at `service` instead of [`DEFAULT_SERVICE`].
+    pub fn service(mut self, service: &str) -> Self {
+        self.service = service.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// POST /xrpc/com.atproto.repo.createRecord
+    /// Creates a post with `text`, signing in first when there is no session yet or it
+    /// expired.
+    /// [Docs](https://docs.bsky.app/docs/advanced-guides/posts)
+    pub async fn create_post(&self, text: &str) -> Result<CrossPosted, TweetyError> {
+        let mut session = self.session.lock().await;
+        let mut signed_in = false;
+        loop {
+            let current = match session.as_ref() {
+                Some(current) => current.clone(),
+                None => {
+                    signed_in = true;
+                    session.insert(self.create_session().await?).clone()
+                }
+            };
+            let body = json!({
+                "repo": current.did,
+                "collection": "app.bsky.feed.post",
+                "record": {
+                    "$type": "app.bsky.feed.post",
+                    "text": text,
+                    "facets": link_facets(text),
+                    "createdAt": to_rfc3339(SystemTime::now()),
+                },
+            });
+
+            match self
+                .xrpc("com.atproto.repo.createRecord", &body, Some(&current))
+                .await
+            {
+                Err(err) if !signed_in && is_expired(&err) => *session = None,
+                Err(err) => return Err(err),
+                Ok(value) => {
+                    let record: Record = serde_json::from_value(value)?;
+                    let key = record.uri.rsplit('/').next().unwrap_or_default();
+                    return Ok(CrossPosted {
+                        url: Some(format!(
+                            "https://bsky.app/profile/{}/post/{}",
+                            current.did, key
+                        )),
+                        id: record.uri,
+                    });
+                }
+            }
+        }
+    }
+
+    /// POST /xrpc/com.atproto.server.createSession
+    async fn create_session(&self) -> Result<Session, TweetyError> {
+        let body = json!({
+            "identifier": self.identifier,
+            "password": self.app_password.expose_secret(),
+        });
+        let value = self
+            .xrpc("com.atproto.server.createSession", &body, None)
+            .await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    async fn xrpc(
+        &self,
+        method: &str,
+        body: &Value,
+        session: Option<&Session>,
+    ) -> Result<Value, TweetyError> {
+        let url = Url::parse(&format!("{}/xrpc/{}", self.service, method))?;
+        let mut request = self.http.post(url).json(body);
+        if let Some(session) = session {
+            request = request.bearer_auth(session.access_jwt.expose_secret());
+        }
+        let response = request.send().await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(TweetyError::api(status, &body));
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+impl CrossPoster for Bluesky {
+    fn network(&self) -> &str {
+        "bluesky"
+    }
+
+    fn max_length(&self) -> usize {
+        MAX_LENGTH
+    }
+
+    /// Bluesky counts grapheme clusters, so an emoji counts 1 however many `char`s
+    /// it is made of.
+    fn length(&self, text: &str) -> usize {
+        grapheme_count(text)
+    }
+
+    fn publish<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<CrossPosted, TweetyError>> {
+        Box::pin(self.create_post(text))
+    }
+}
+
+/// Whether `err` says the session's access token expired.
+fn is_expired(err: &TweetyError) -> bool {
+    match err {
+        TweetyError::ApiError { status, body } => {
+            *status == StatusCode::UNAUTHORIZED
+                || (*status == StatusCode::BAD_REQUEST && body.contains("ExpiredToken"))
+        }
+        _ => false,
+    }
+}
+
+/// A link facet for each `http://` or `https://` word in `text`. Facets index the
+/// UTF-8 bytes of the text.
+fn link_facets(text: &str) -> Vec<Value> {
+    let mut facets = Vec::new();
+    let mut offset = 0;
+    for word in text.split_inclusive(char::is_whitespace) {
+        let link = word.trim_end();
+        if link.starts_with("https://") || link.starts_with("http://") {
+            facets.push(json!({
+                "index": { "byteStart": offset, "byteEnd": offset + link.len() },
+                "features": [{ "$type": "app.bsky.richtext.facet#link", "uri": link }],
+            }));
+        }
+        offset += word.len();
+    }
+    facets
+}
@@ -1,20 +1,28 @@
 use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
-use reqwest::Method;
+use crate::api::query::{field_names, Query};
+use crate::types::ids::{TweetId, UserId};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Expansions {
+    #[serde(rename = "attachments.poll_ids")]
     AttachmentsPollIds,
+    #[serde(rename = "attachments.media_keys")]
     AttachmentsMediaKeys,
     AuthorId,
     EditHistoryTweetIds,
-    EntitiesMentionsUsername(String), // Allow username for mentions
+    #[serde(rename = "entities.mentions.username")]
+    EntitiesMentionsUsername,
+    #[serde(rename = "geo.place_id")]
     GeoPlaceId,
     InReplyToUserId,
+    #[serde(rename = "referenced_tweets.id")]
     ReferencedTweetsId,
+    #[serde(rename = "referenced_tweets.id.author_id")]
     ReferencedTweetsIdAuthorId,
 }
 
@@ -74,6 +82,7 @@ pub enum TweetFields {
     InReplyToUserId,
     Lang,
     NonPublicMetrics,
+    NoteTweet,
     PublicMetrics,
     OrganicMetrics,
     PromotedMetrics,
@@ -106,6 +115,15 @@ pub enum UserFields {
     Withheld,
 }
 
+field_names!(
+    Expansions,
+    MediaFields,
+    PlaceFields,
+    PollFields,
+    TweetFields,
+    UserFields
+);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BookmarkParams {
     pub expansions: Option<Vec<Expansions>>,
@@ -126,28 +144,38 @@ pub struct BookmarkParams {
 impl TweetyClient {
     /// Bookmarks lookup
     /// Lookup a user's Bookmarks
-    pub async fn get_user_bookmark(self, user_id: &str) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/users/{}/bookmarks", user_id);
+    pub async fn get_user_bookmark(
+        &self,
+        user_id: impl Into<UserId>,
+    ) -> Result<Value, TweetyError> {
+        let user_id: UserId = user_id.into();
+        let endpoint = Endpoint::Bookmarks(user_id);
 
-        self.send_request::<()>(&url, Method::GET, None).await
+        self.send_request::<()>(&endpoint, &Query::new(), None)
+            .await
     }
     /// Bookmark a Post
-    pub async fn bookmark_post(self, post_id: &str) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/users/{}/bookmarks", post_id);
+    pub async fn bookmark_post(
+        &self,
+        user_id: impl Into<UserId>,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<Value, TweetyError> {
+        let user_id: UserId = user_id.into();
+        let body = serde_json::json!({ "tweet_id": tweet_id.into() });
 
-        self.send_request::<()>(&url, Method::POST, None).await
+        self.send_request(&Endpoint::Bookmark(user_id), &Query::new(), Some(body))
+            .await
     }
     /// Remove a Bookmark of a Post
     pub async fn delete_bookmark(
-        self,
-        user_id: &str,
-        tweet_id: &str,
+        &self,
+        user_id: impl Into<UserId>,
+        tweet_id: impl Into<TweetId>,
     ) -> Result<Value, TweetyError> {
-        let url = format!(
-            "https://api.x.com/2/users/{}/bookmarks/{}",
-            user_id, tweet_id
-        );
+        let (user_id, tweet_id): (UserId, TweetId) = (user_id.into(), tweet_id.into());
+        let endpoint = Endpoint::RemoveBookmark { user_id, tweet_id };
 
-        self.send_request::<()>(&url, Method::DELETE, None).await
+        self.send_request::<()>(&endpoint, &Query::new(), None)
+            .await
     }
 }
@@ -0,0 +1,616 @@
+//! A mention listener, command router and throttled replies for building reply bots.
+
+use crate::api::client::TweetyClient;
+use crate::api::dedup::SeenIds;
+use crate::api::error::TweetyError;
+use crate::api::mentions::{ExpansionType, QueryParams, TweetData, TweetField, UserData};
+use crate::api::poller::{Cursor, PollSchedule};
+use crate::api::retry::RetryClassifier;
+use crate::api::shutdown::Shutdown;
+use crate::api::state::StateStore;
+use crate::api::tweet::PostTweetResponseData;
+use crate::types::ids::UserId;
+use crate::types::tweet::PostTweetParams;
+use futures_util::future::BoxFuture;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default time between two polls of the mentions timeline.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How many handled mention ids are remembered to skip duplicates.
+const SEEN_CAPACITY: usize = 1024;
+
+type Handler =
+    Arc<dyn Fn(MentionContext) -> BoxFuture<'static, Result<(), TweetyError>> + Send + Sync>;
+
+type CommandHandler = Arc<
+    dyn Fn(MentionContext, Command) -> BoxFuture<'static, Result<(), TweetyError>> + Send + Sync,
+>;
+
+/// What a handler gets for each mention: the tweet, its author and a client to act on it.
+#[derive(Clone)]
+pub struct MentionContext {
+    pub client: TweetyClient,
+    /// The id of the account the listener runs as.
+    pub bot_user_id: UserId,
+    pub mention: Arc<TweetData>,
+    /// The author, from the `author_id` expansion of the mentions response.
+    pub author: Option<Arc<UserData>>,
+}
+
+impl MentionContext {
+    /// The complete text of the mention.
+    pub fn text(&self) -> &str {
+        self.mention.full_text()
+    }
+
+    /// Replies to the mention.
+    pub async fn reply(&self, text: &str) -> Result<PostTweetResponseData, TweetyError> {
+        let params = PostTweetParams::builder()
+            .in_reply_to(&self.mention.id)
+            .build();
+
+        self.client.post_tweet(text, Some(params)).await
+    }
+
+    /// Likes the mention as the bot account.
+    pub async fn like(&self) -> Result<Value, TweetyError> {
+        self.client
+            .like_a_post(&self.bot_user_id, &self.mention.id)
+            .await
+    }
+}
+
+/// What [`AutoReplier`] does with a reply that would exceed its limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Skip the reply.
+    Drop,
+    /// Wait until the reply fits the limits, or skip it if that takes longer than `max_wait`.
+    Queue { max_wait: Duration },
+}
+
+/// Reply caps for [`AutoReplier`]. A limit of `0` or a zero duration disables it.
+#[derive(Debug, Clone)]
+pub struct ReplyLimits {
+    /// Replies to the same user allowed within `per_user_window`.
+    pub per_user_max: u32,
+    pub per_user_window: Duration,
+    /// Replies overall allowed within `global_window`.
+    pub global_max: u32,
+    pub global_window: Duration,
+    /// Minimum time between two replies to the same user.
+    pub user_cooldown: Duration,
+    /// Minimum time between any two replies.
+    pub global_cooldown: Duration,
+    pub policy: OverflowPolicy,
+}
+
+impl Default for ReplyLimits {
+    /// Conservative caps that stay well inside X's automation rules: 5 replies per user
+    /// and 100 overall per hour, a minute between replies to the same user.
+    fn default() -> Self {
+        ReplyLimits {
+            per_user_max: 5,
+            per_user_window: Duration::from_secs(60 * 60),
+            global_max: 100,
+            global_window: Duration::from_secs(60 * 60),
+            user_cooldown: Duration::from_secs(60),
+            global_cooldown: Duration::from_secs(2),
+            policy: OverflowPolicy::Drop,
+        }
+    }
+}
+
+/// The result of [`AutoReplier::reply`].
+#[derive(Debug)]
+pub enum ReplyOutcome {
+    Sent(PostTweetResponseData),
+    /// The reply was skipped; it would have fit the limits after `retry_after`.
+    Dropped {
+        retry_after: Duration,
+    },
+}
+
+#[derive(Debug, Default)]
+struct ReplyLog {
+    global: VecDeque<Instant>,
+    users: HashMap<String, VecDeque<Instant>>,
+}
+
+/// Replies to mentions within per-user and global rate caps and cooldowns.
+///
+/// Clones share their limits and history, so one replier can be moved into several
+/// handlers.
+#[derive(Debug, Clone)]
+pub struct AutoReplier {
+    limits: ReplyLimits,
+    log: Arc<Mutex<ReplyLog>>,
+}
+
+impl AutoReplier {
+    pub fn new(limits: ReplyLimits) -> Self {
+        AutoReplier {
+            limits,
+            log: Arc::new(Mutex::new(ReplyLog::default())),
+        }
+    }
+
+    pub fn limits(&self) -> &ReplyLimits {
+        &self.limits
+    }
+
+    /// Replies to the mention in `ctx` if the limits allow it, applying the
+    /// [`OverflowPolicy`] otherwise.
+    pub async fn reply(
+        &self,
+        ctx: &MentionContext,
+        text: &str,
+    ) -> Result<ReplyOutcome, TweetyError> {
+        let user = ctx.mention.author_id.as_deref();
+        let mut waited = Duration::ZERO;
+
+        loop {
+            let retry_after = match self.try_acquire(user) {
+                Ok(()) => return ctx.reply(text).await.map(ReplyOutcome::Sent),
+                Err(retry_after) => retry_after,
+            };
+
+            match self.limits.policy {
+                OverflowPolicy::Queue { max_wait } if waited + retry_after <= max_wait => {
+                    tokio::time::sleep(retry_after).await;
+                    waited += retry_after;
+                }
+                _ => return Ok(ReplyOutcome::Dropped { retry_after }),
+            }
+        }
+    }
+
+    /// Takes a reply slot for `user_id`, or returns how long until one frees up.
+    ///
+    /// [`reply`](Self::reply) calls this before posting; use it directly to throttle
+    /// other actions with the same limits. `None` only counts against the global limits.
+    pub fn try_acquire(&self, user_id: Option<&str>) -> Result<(), Duration> {
+        let limits = &self.limits;
+        let now = Instant::now();
+        let mut log = self.log.lock().unwrap_or_else(|err| err.into_inner());
+
+        prune(
+            &mut log.global,
+            limits.global_window.max(limits.global_cooldown),
+            now,
+        );
+        let user_window = limits.per_user_window.max(limits.user_cooldown);
+        log.users.retain(|_, times| {
+            prune(times, user_window, now);
+            !times.is_empty()
+        });
+
+        let mut wait = wait_for(&log.global, limits.global_max, limits.global_window, now)
+            .max(cooldown_left(&log.global, limits.global_cooldown, now));
+        if let Some(times) = user_id.and_then(|user| log.users.get(user)) {
+            wait = wait
+                .max(wait_for(
+                    times,
+                    limits.per_user_max,
+                    limits.per_user_window,
+                    now,
+                ))
+                .max(cooldown_left(times, limits.user_cooldown, now));
+        }
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        log.global.push_back(now);
+        if let Some(user) = user_id {
+            log.users
+                .entry(user.to_string())
+                .or_default()
+                .push_back(now);
+        }
+        Ok(())
+    }
+}
+
+/// Drops the entries older than `keep`.
+fn prune(times: &mut VecDeque<Instant>, keep: Duration, now: Instant) {
+    while times
+        .front()
+        .is_some_and(|time| now.duration_since(*time) >= keep)
+    {
+        times.pop_front();
+    }
+}
+
+/// Time until fewer than `max` entries fall within the last `window`.
+fn wait_for(times: &VecDeque<Instant>, max: u32, window: Duration, now: Instant) -> Duration {
+    if max == 0 || window.is_zero() {
+        return Duration::ZERO;
+    }
+
+    let in_window: Vec<&Instant> = times
+        .iter()
+        .filter(|time| now.duration_since(**time) < window)
+        .collect();
+    if in_window.len() < max as usize {
+        return Duration::ZERO;
+    }
+
+    let oldest_to_expire = in_window[in_window.len() - max as usize];
+    (*oldest_to_expire + window).saturating_duration_since(now)
+}
+
+fn cooldown_left(times: &VecDeque<Instant>, cooldown: Duration, now: Instant) -> Duration {
+    times
+        .back()
+        .map(|last| (*last + cooldown).saturating_duration_since(now))
+        .unwrap_or_default()
+}
+
+/// A command addressed to the bot: the first word after its @mention and what follows.
+///
+/// `@alice @mybot translate fr "good morning"` parses to the command `translate` with
+/// the arguments `fr` and `good morning`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    /// Lowercased, without a leading `/` or `!`.
+    pub name: String,
+    /// Whitespace-separated arguments; double quotes group words into one argument.
+    pub args: Vec<String>,
+    /// Everything after the command name, as written.
+    pub rest: String,
+}
+
+impl Command {
+    /// Parses the command following the first `@bot_username` in `text`, matched
+    /// case-insensitively. `None` if the bot isn't mentioned or nothing follows it.
+    pub fn parse(text: &str, bot_username: &str) -> Option<Command> {
+        let bot_username = bot_username.trim_start_matches('@');
+        let mut tokens = text
+            .split_whitespace()
+            .skip_while(|token| !is_mention_of(token, bot_username));
+        tokens.next()?;
+
+        let token = tokens.next()?;
+        if token.starts_with('@') {
+            return None;
+        }
+        let name = token.trim_start_matches(['/', '!']).to_lowercase();
+        if name.is_empty() {
+            return None;
+        }
+
+        // Tokens borrow from `text`, so the remainder starts right after this one.
+        let offset = token.as_ptr() as usize - text.as_ptr() as usize + token.len();
+        let rest = text[offset..].trim().to_string();
+        let args = split_args(&rest);
+
+        Some(Command { name, args, rest })
+    }
+}
+
+fn is_mention_of(token: &str, username: &str) -> bool {
+    token
+        .strip_prefix('@')
+        .map(|handle| handle.trim_end_matches(|c: char| !(c.is_alphanumeric() || c == '_')))
+        .is_some_and(|handle| handle.eq_ignore_ascii_case(username))
+}
+
+fn split_args(rest: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut started = false;
+
+    for c in rest.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    args.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            c => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        args.push(current);
+    }
+    args
+}
+
+/// Polls the mentions of the authenticated user and hands each new one to the
+/// registered handlers, oldest first.
+///
+/// ```no_run
+/// use tweety_rs::api::bot::MentionListener;
+/// use tweety_rs::TweetyClient;
+///
+/// # async fn run() -> Result<(), tweety_rs::api::error::TweetyError> {
+/// let client = TweetyClient::from_env()?;
+/// let mut listener = MentionListener::new(client)
+///     .persist_to("mentions.cursor")?
+///     .on_mention(|ctx| async move {
+///         ctx.like().await?;
+///         ctx.reply("Thanks for the mention!").await?;
+///         Ok(())
+///     })
+///     .command("echo", |ctx, command| async move {
+///         ctx.reply(&command.rest).await?;
+///         Ok(())
+///     });
+///
+/// listener.run().await
+/// # }
+/// ```
+pub struct MentionListener {
+    client: TweetyClient,
+    user_id: Option<UserId>,
+    username: Option<String>,
+    schedule: PollSchedule,
+    replay_backlog: bool,
+    cursor: Cursor,
+    seen: SeenIds,
+    handlers: Vec<Handler>,
+    commands: HashMap<String, CommandHandler>,
+}
+
+impl MentionListener {
+    /// Creates a listener for the account `client` is authenticated as.
+    pub fn new(client: TweetyClient) -> Self {
+        MentionListener {
+            client,
+            user_id: None,
+            username: None,
+            schedule: PollSchedule::new(DEFAULT_POLL_INTERVAL),
+            replay_backlog: false,
+            cursor: Cursor::default(),
+            seen: SeenIds::new(SEEN_CAPACITY),
+            handlers: Vec::new(),
+            commands: HashMap::new(),
+        }
+    }
+
+    /// Listens for mentions of `user_id` instead of looking up the authenticated user.
+    pub fn user_id(mut self, user_id: impl Into<UserId>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// The bot's username, used to find commands. Looked up from the user id when not set.
+    pub fn username(mut self, username: &str) -> Self {
+        self.username = Some(username.trim_start_matches('@').to_string());
+        self
+    }
+
+    /// Time between two polls, [`DEFAULT_POLL_INTERVAL`] by default.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.schedule.interval = interval;
+        self
+    }
+
+    /// Decides which polling errors [`run`](MentionListener::run) keeps going after.
+    /// Defaults to [`DefaultRetryClassifier`](crate::api::retry::DefaultRetryClassifier).
+    pub fn retry_classifier(mut self, classifier: impl RetryClassifier + 'static) -> Self {
+        self.schedule.retry_classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Makes [`run`](MentionListener::run) return once `shutdown` is requested, after
+    /// the poll in progress has been handled and its cursor saved.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.schedule.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// Whether the first poll without a saved cursor dispatches the mentions already on
+    /// the timeline. Off by default, so a new bot only answers mentions made after it
+    /// started.
+    pub fn replay_backlog(mut self, replay: bool) -> Self {
+        self.replay_backlog = replay;
+        self
+    }
+
+    /// Saves the id of the last handled mention to `path` after each mention, and resumes
+    /// from the id already saved there.
+    pub fn persist_to(mut self, path: impl AsRef<Path>) -> Result<Self, TweetyError> {
+        self.cursor.persist_to(path.as_ref())?;
+        Ok(self)
+    }
+
+    /// Saves the id of the last handled mention under `key` in `store` after each
+    /// mention, and resumes from the id stored there when the first poll has no cursor
+    /// yet.
+    pub fn state_store(mut self, store: impl StateStore + 'static, key: &str) -> Self {
+        self.cursor.state_store(Arc::new(store), key);
+        self
+    }
+
+    /// Registers a handler, called for every new mention in registration order.
+    pub fn on_mention<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(MentionContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), TweetyError>> + Send + 'static,
+    {
+        self.handlers.push(Arc::new(move |ctx| {
+            Box::pin(handler(ctx)) as BoxFuture<'static, _>
+        }));
+        self
+    }
+
+    /// Registers a handler for `@bot <name> ...` mentions, see [`Command::parse`].
+    ///
+    /// Command handlers run after the [`on_mention`](Self::on_mention) handlers, which
+    /// still see every mention. Registering a name twice replaces the earlier handler.
+    pub fn command<F, Fut>(mut self, name: &str, handler: F) -> Self
+    where
+        F: Fn(MentionContext, Command) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), TweetyError>> + Send + 'static,
+    {
+        let name = name.trim_start_matches(['/', '!']).to_lowercase();
+        self.commands.insert(
+            name,
+            Arc::new(move |ctx, command| Box::pin(handler(ctx, command)) as BoxFuture<'static, _>),
+        );
+        self
+    }
+
+    /// The id of the newest mention handled so far.
+    pub fn last_seen_id(&self) -> Option<&str> {
+        self.cursor.last_seen_id()
+    }
+
+    /// Polls until a shutdown is requested, see [`shutdown_on`](MentionListener::shutdown_on),
+    /// or a request fails with an error the
+    /// [`retry_classifier`](MentionListener::retry_classifier) deems permanent, like
+    /// missing or rejected credentials. Other polling errors are logged and the listener
+    /// keeps going, as it does after handler errors.
+    pub async fn run(&mut self) -> Result<(), TweetyError> {
+        let _running = self.schedule.running();
+
+        while !self.schedule.is_shutdown() {
+            let result = self.poll_once().await;
+            if self
+                .schedule
+                .wait(&self.client, result, "polling mentions")
+                .await?
+            {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the mentions since the last poll and dispatches them, returning how many
+    /// were handled.
+    pub async fn poll_once(&mut self) -> Result<usize, TweetyError> {
+        if !self.client.is_initialized() {
+            return Err(TweetyError::MissingCredentials);
+        }
+        let user_id = self.resolve_user_id().await?;
+        let username = self.resolve_username(&user_id).await?;
+
+        let client = &self.client;
+        let (mentions, users) = self
+            .cursor
+            .fetch_new(self.replay_backlog, |since_id, pagination_token| {
+                client.get_users_mentions(&user_id, Some(mention_query(since_id, pagination_token)))
+            })
+            .await?;
+        let authors: HashMap<_, _> = users
+            .into_iter()
+            .map(|user| (user.id.clone(), Arc::new(user)))
+            .collect();
+
+        let mut handled = 0;
+        for mention in mentions {
+            if !self.remember(&mention.id) {
+                continue;
+            }
+
+            let id = mention.id.clone();
+            let ctx = MentionContext {
+                client: self.client.clone(),
+                bot_user_id: user_id.clone(),
+                author: mention
+                    .author_id
+                    .as_ref()
+                    .and_then(|author_id| authors.get(author_id).cloned()),
+                mention: Arc::new(mention),
+            };
+
+            for handler in &self.handlers {
+                if let Err(err) = handler(ctx.clone()).await {
+                    log::warn!("mention handler failed for tweet {}: {}", id, err);
+                }
+            }
+
+            let command = username
+                .as_deref()
+                .and_then(|username| Command::parse(ctx.text(), username));
+            if let Some(command) = command {
+                if let Some(handler) = self.commands.get(&command.name) {
+                    let name = command.name.clone();
+                    if let Err(err) = handler(ctx, command).await {
+                        log::warn!("command {} failed for tweet {}: {}", name, id, err);
+                    }
+                }
+            }
+
+            self.cursor.advance(id).await?;
+            handled += 1;
+        }
+
+        Ok(handled)
+    }
+
+    async fn resolve_user_id(&mut self) -> Result<UserId, TweetyError> {
+        if let Some(ref id) = self.user_id {
+            return Ok(id.clone());
+        }
+
+        let verified = self.client.verify_credentials().await?;
+        let user = verified.user.ok_or_else(|| {
+            TweetyError::ConfigError(
+                "MentionListener needs user context or an explicit user_id".to_string(),
+            )
+        })?;
+
+        let user_id = UserId::new(user.id);
+        self.user_id = Some(user_id.clone());
+        self.username.get_or_insert(user.username);
+        Ok(user_id)
+    }
+
+    /// The bot's username, looked up only when there are commands to match.
+    async fn resolve_username(&mut self, user_id: &UserId) -> Result<Option<String>, TweetyError> {
+        if self.commands.is_empty() || self.username.is_some() {
+            return Ok(self.username.clone());
+        }
+
+        let value = self.client.get_user_by_id(user_id, None).await?;
+        let username = value["data"]["username"]
+            .as_str()
+            .ok_or_else(|| TweetyError::UnexpectedResponse("Missing username".to_string()))?;
+
+        self.username = Some(username.to_string());
+        Ok(self.username.clone())
+    }
+
+    /// Records `id` as handled; `false` if it already was.
+    fn remember(&mut self, id: &str) -> bool {
+        self.seen.insert(id)
+    }
+}
+
+fn mention_query(since_id: Option<String>, pagination_token: Option<String>) -> QueryParams {
+    QueryParams {
+        max_results: Some(100),
+        since_id,
+        pagination_token,
+        expansions: Some(vec![ExpansionType::AuthorId]),
+        tweet_fields: Some(vec![
+            TweetField::AuthorId,
+            TweetField::ConversationId,
+            TweetField::CreatedAt,
+            TweetField::Entities,
+            TweetField::InReplyToUserId,
+            TweetField::NoteTweet,
+            TweetField::ReferencedTweets,
+        ]),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,89 @@
+//! Failing fast while X is down.
+//!
+//! A [`CircuitBreaker`] counts consecutive 5xx responses per [`RateLimitFamily`]. Once
+//! a family reaches the threshold its circuit opens, and requests to it fail with
+//! [`TweetyError::CircuitOpen`] without being sent until the cooldown has passed. The
+//! first request after that goes through; another 5xx opens the circuit again at once,
+//! any other response closes it.
+//!
+//! ```
+//! use std::time::Duration;
+//! use tweety_rs::api::circuit_breaker::CircuitBreaker;
+//! use tweety_rs::TweetyClient;
+//!
+//! let client = TweetyClient::from_bearer_token("token")
+//!     .with_circuit_breaker(CircuitBreaker::new(5, Duration::from_secs(60)));
+//! ```
+//!
+//! [`TweetyError::CircuitOpen`]: crate::api::error::TweetyError::CircuitOpen
+
+use crate::api::endpoint::RateLimitFamily;
+use crate::api::error::TweetyError;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Per-family circuits, shared by every clone of the client it is set on.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    circuits: Mutex<HashMap<RateLimitFamily, Circuit>>,
+}
+
+impl CircuitBreaker {
+    /// Opens a family's circuit after `threshold` consecutive 5xx responses, for
+    /// `cooldown`.
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            threshold: threshold.max(1),
+            cooldown,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// How long requests to `family` keep failing fast, or `None` when its circuit is
+    /// closed.
+    pub fn open_for(&self, family: RateLimitFamily) -> Option<Duration> {
+        let circuits = self.circuits.lock().ok()?;
+        let open_until = circuits.get(&family)?.open_until?;
+        open_until
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Fails with [`TweetyError::CircuitOpen`] while `family`'s circuit is open.
+    pub(crate) fn check(&self, family: RateLimitFamily) -> Result<(), TweetyError> {
+        match self.open_for(family) {
+            Some(retry_after) => Err(TweetyError::CircuitOpen {
+                family,
+                retry_after,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Counts the status of a response from `family`.
+    pub(crate) fn record(&self, family: RateLimitFamily, status: StatusCode) {
+        let Ok(mut circuits) = self.circuits.lock() else {
+            return;
+        };
+        let circuit = circuits.entry(family).or_default();
+
+        if status.is_server_error() {
+            circuit.consecutive_failures = circuit.consecutive_failures.saturating_add(1);
+            if circuit.consecutive_failures >= self.threshold {
+                circuit.open_until = Some(Instant::now() + self.cooldown);
+            }
+        } else {
+            *circuit = Circuit::default();
+        }
+    }
+}
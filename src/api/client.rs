@@ -1,16 +1,171 @@
-use crate::api::error::TweetyError;
+use crate::api::circuit_breaker::CircuitBreaker;
+use crate::api::endpoint::{Endpoint, Host, RateLimitFamily};
+use crate::api::error::{self, TweetyError};
+use crate::api::http::HttpConfig;
+use crate::api::mentions::check_app_only_fields;
+use crate::api::oauth2::OAuth2Token;
+use crate::api::observer::{ClientEvent, Observer};
+use crate::api::query::{build_url, Query};
+use crate::api::rate_limit::{self, RateLimitStatus, RateLimits};
+#[cfg(feature = "request-log")]
+use crate::api::request_log::RequestLog;
+use crate::api::secret::SecretString;
+use crate::api::signing::{self, CLOCK_SKEW_TOLERANCE};
+use crate::api::tier::{posts_in_body, posts_in_value, Tier, Usage, DEFAULT_CAP_RESET_DAY};
+use crate::api::usage::{UsageGuard, UsageKind};
 use reqwest::Method;
-use reqwest_oauth1::{self, OAuthClientProvider};
+use reqwest_oauth1::{self, OAuthClientProvider, OAuthParameters};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::env;
+use std::fmt;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
 use url::Url;
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Environment variable holding the OAuth consumer key (API key).
+pub const CONSUMER_KEY_ENV: &str = "CONSUMER_API_KEY";
+/// Environment variable holding the OAuth consumer key secret (API secret key).
+pub const CONSUMER_KEY_SECRET_ENV: &str = "CONSUMER_API_SECRET";
+/// Environment variable holding the OAuth access token.
+pub const ACCESS_TOKEN_ENV: &str = "ACCESS_TOKEN";
+/// Environment variable holding the OAuth access token secret.
+pub const ACCESS_TOKEN_SECRET_ENV: &str = "ACCESS_TOKEN_SECRET";
+/// Environment variable holding an app-only bearer token.
+pub const BEARER_TOKEN_ENV: &str = "BEARER_TOKEN";
+
+/// How a `TweetyClient` authenticates its requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMode {
+    /// OAuth 1.0a user context, signed with the consumer and access token secrets.
+    OAuth1User,
+    /// An OAuth 2.0 user access token.
+    OAuth2User,
+    /// An app-only bearer token, with no user context.
+    AppOnly,
+}
+
+/// A user's tokens, for acting on their behalf with [`TweetyClient::on_behalf_of`].
+#[derive(Debug, Clone)]
+pub enum UserTokens {
+    /// An OAuth 1.0a access token and secret, signed with the client's consumer key.
+    OAuth1 {
+        access_token: SecretString,
+        access_token_secret: SecretString,
+    },
+    /// An OAuth 2.0 user access token.
+    OAuth2(OAuth2Token),
+}
+
+impl UserTokens {
+    pub fn oauth1(access_token: &str, access_token_secret: &str) -> Self {
+        UserTokens::OAuth1 {
+            access_token: access_token.into(),
+            access_token_secret: access_token_secret.into(),
+        }
+    }
+}
+
+impl From<OAuth2Token> for UserTokens {
+    fn from(token: OAuth2Token) -> Self {
+        UserTokens::OAuth2(token)
+    }
+}
+
+/// Credentials shared by every clone of a `TweetyClient`.
+///
+/// The OAuth 1.0a fields are empty when the client was built from a bearer
+/// token only.
+#[derive(Debug)]
+pub(crate) struct Credentials {
+    pub(crate) consumer_key: SecretString,
+    pub(crate) access_token: SecretString,
+    pub(crate) consumer_key_secret: SecretString,
+    pub(crate) access_token_secret: SecretString,
+    pub(crate) bearer_token: Option<SecretString>,
+    /// The OAuth 2.0 user token, held in a watch channel so it can be replaced after a
+    /// refresh and observed for expiry.
+    pub(crate) oauth2_token: watch::Sender<Option<OAuth2Token>>,
+}
+
+/// Client for the X (Twitter) API.
+///
+/// The client is cheap to clone: clones share the same credentials through an
+/// internal `Arc`, and the same connection pool, so a single client can be handed to
+/// several tasks.
+///
+/// `Debug` output masks the credentials, see [`SecretString`].
+#[derive(Clone)]
 pub struct TweetyClient {
-    pub(crate) consumer_key: String,
-    pub(crate) access_token: String,
-    pub(crate) consumer_key_secret: String,
-    pub(crate) access_token_secret: String,
+    pub(crate) credentials: Arc<Credentials>,
+    /// The connection pool, shared by clones and [`TweetyClient::on_behalf_of`] handles.
+    pub(crate) http: reqwest::Client,
+    /// See [`TweetyClient::with_http_config`].
+    http_config: HttpConfig,
+    /// Replaces the X hosts of every [`Endpoint`], see [`TweetyClient::with_base_url`].
+    base_url: Option<Arc<str>>,
+    /// See [`TweetyClient::with_tier`].
+    tier: Option<Tier>,
+    /// Posts sent and read, shared by clones so the monthly caps are counted once.
+    usage: Arc<Mutex<Usage>>,
+    /// See [`TweetyClient::with_usage_guard`].
+    usage_guard: Option<Arc<UsageGuard>>,
+    /// See [`TweetyClient::with_auth_mode`].
+    auth_override: Option<AuthMode>,
+    /// See [`TweetyClient::with_circuit_breaker`].
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    /// See [`TweetyClient::rate_limits`].
+    rate_limits: Arc<watch::Sender<RateLimits>>,
+    /// See [`TweetyClient::clock_skew`].
+    clock_skew: Arc<AtomicI64>,
+    /// See [`TweetyClient::with_observer`].
+    observer: Option<Arc<dyn Observer>>,
+    /// See [`TweetyClient::tagged`].
+    tag: Option<Arc<str>>,
+    /// See [`TweetyClient::with_upload_chunk_size`].
+    #[cfg(feature = "media-upload")]
+    pub(crate) upload_chunk_size: u64,
+    /// See [`TweetyClient::with_compression_threshold`].
+    #[cfg(feature = "compression")]
+    compression_threshold: Option<usize>,
+    /// See [`TweetyClient::with_request_log`].
+    #[cfg(feature = "request-log")]
+    request_log: Option<Arc<RequestLog>>,
+}
+
+impl fmt::Debug for TweetyClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let credentials = &self.credentials;
+
+        let mut debug = f.debug_struct("TweetyClient");
+        debug
+            .field("consumer_key", &credentials.consumer_key)
+            .field("access_token", &credentials.access_token)
+            .field("consumer_key_secret", &credentials.consumer_key_secret)
+            .field("access_token_secret", &credentials.access_token_secret)
+            .field("bearer_token", &credentials.bearer_token)
+            .field("oauth2_token", &*credentials.oauth2_token.borrow())
+            .field("http_config", &self.http_config)
+            .field("base_url", &self.base_url)
+            .field("tier", &self.tier)
+            .field("usage_guard", &self.usage_guard)
+            .field("auth_override", &self.auth_override)
+            .field("circuit_breaker", &self.circuit_breaker)
+            .field("rate_limits", &*self.rate_limits.borrow())
+            .field("clock_skew", &self.clock_skew())
+            .field("observer", &self.observer.is_some())
+            .field("tag", &self.tag);
+        #[cfg(feature = "media-upload")]
+        debug.field("upload_chunk_size", &self.upload_chunk_size);
+        #[cfg(feature = "compression")]
+        debug.field("compression_threshold", &self.compression_threshold);
+        #[cfg(feature = "request-log")]
+        debug.field("request_log", &self.request_log);
+        debug.finish()
+    }
 }
 
 impl TweetyClient {
@@ -40,97 +195,887 @@ impl TweetyClient {
         consumer_key_secret: &str,
         access_token_secret: &str,
     ) -> Self {
+        TweetyClient::from_credentials(Credentials {
+            consumer_key: consumer_key.into(),
+            access_token: access_token.into(),
+            consumer_key_secret: consumer_key_secret.into(),
+            access_token_secret: access_token_secret.into(),
+            bearer_token: None,
+            oauth2_token: watch::Sender::new(None),
+        })
+    }
+    pub(crate) fn from_credentials(credentials: Credentials) -> Self {
         TweetyClient {
-            consumer_key: consumer_key.to_string(),
-            access_token: access_token.to_string(),
-            consumer_key_secret: consumer_key_secret.to_string(),
-            access_token_secret: access_token_secret.to_string(),
+            credentials: Arc::new(credentials),
+            http: reqwest::Client::new(),
+            http_config: HttpConfig::new(),
+            base_url: None,
+            tier: None,
+            usage: Arc::default(),
+            usage_guard: None,
+            auth_override: None,
+            circuit_breaker: None,
+            rate_limits: Arc::new(watch::Sender::new(RateLimits::new())),
+            clock_skew: Arc::default(),
+            observer: None,
+            tag: None,
+            #[cfg(feature = "media-upload")]
+            upload_chunk_size: crate::api::uploads::DEFAULT_CHUNK_SIZE,
+            #[cfg(feature = "compression")]
+            compression_threshold: None,
+            #[cfg(feature = "request-log")]
+            request_log: None,
         }
     }
+    /// Sends every request to `base_url` instead of the X hosts, e.g. a mock server in
+    /// tests or a proxy. Endpoint paths are appended unchanged.
+    ///
+    /// ```rust
+    /// use tweety_rs::TweetyClient;
+    ///
+    /// let client = TweetyClient::from_bearer_token("token").with_base_url("http://localhost:8080/");
+    /// ```
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.trim_end_matches('/').into());
+        self
+    }
+    /// Sets the access tier of the developer account, see [`crate::api::tier`].
+    ///
+    /// Requests the tier can't make then fail with [`TweetyError::TierLimit`] before they
+    /// are sent, and warnings are logged as the tier's monthly caps come near. Without a
+    /// tier nothing is checked.
+    pub fn with_tier(mut self, tier: Tier) -> Self {
+        self.tier = Some(tier);
+        self
+    }
+    /// Counts the posts sent and read against a monthly budget, refusing or warning
+    /// about requests once it is used up, see [`crate::api::usage`].
+    pub fn with_usage_guard(mut self, usage_guard: UsageGuard) -> Self {
+        self.usage_guard = Some(Arc::new(usage_guard));
+        self
+    }
+    /// A handle to the same client that authenticates with `mode` instead of the mode
+    /// [`TweetyClient::auth_mode`] would pick, for clients holding both user and app
+    /// credentials. Requests fail with [`TweetyError::MissingCredentials`] when the
+    /// client has no credentials for `mode`.
+    ///
+    /// ```rust
+    /// use tweety_rs::api::client::AuthMode;
+    /// use tweety_rs::TweetyClient;
+    ///
+    /// let client = TweetyClient::from_bearer_token("token");
+    /// assert_eq!(client.with_auth_mode(AuthMode::AppOnly).auth_mode(), Some(AuthMode::AppOnly));
+    /// assert_eq!(client.with_auth_mode(AuthMode::OAuth1User).auth_mode(), None);
+    /// ```
+    pub fn with_auth_mode(&self, mode: AuthMode) -> Self {
+        TweetyClient {
+            auth_override: Some(mode),
+            ..self.clone()
+        }
+    }
+    /// A handle that sends requests with the bearer token, e.g. for searches that
+    /// shouldn't count against a user's rate limits.
+    ///
+    /// ```rust,no_run
+    /// # async fn run(client: tweety_rs::TweetyClient) {
+    /// let results = client.as_app_only().recent_search("rustlang", None).await;
+    /// # }
+    /// ```
+    pub fn as_app_only(&self) -> Self {
+        self.with_auth_mode(AuthMode::AppOnly)
+    }
+    /// A handle that sends requests in user context: OAuth 1.0a when the client has
+    /// those credentials, otherwise the OAuth 2.0 user token.
+    pub fn as_user(&self) -> Self {
+        let mode = if self.has_oauth1_credentials() {
+            AuthMode::OAuth1User
+        } else {
+            AuthMode::OAuth2User
+        };
+        self.with_auth_mode(mode)
+    }
+    /// A handle to the same client whose requests carry `tag`, such as the product
+    /// area making them. The tag is passed to the observer with every
+    /// [`ClientEvent::Response`] and [`ClientEvent::PostsConsumed`], and written in the
+    /// request log, so rate-limit and usage consumption can be attributed.
+    ///
+    /// ```rust
+    /// use tweety_rs::TweetyClient;
+    ///
+    /// let client = TweetyClient::from_bearer_token("token");
+    /// let search = client.tagged("search");
+    /// assert_eq!(search.tag(), Some("search"));
+    /// assert_eq!(client.tag(), None);
+    /// ```
+    pub fn tagged(&self, tag: &str) -> Self {
+        TweetyClient {
+            tag: Some(tag.into()),
+            ..self.clone()
+        }
+    }
+    /// The tag set with [`TweetyClient::tagged`].
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+    /// A handle to the same client that acts for another user, with their `tokens` in
+    /// place of the client's own user credentials. It shares the client's connection
+    /// pool, settings and usage counts, so one client can serve many authorized users.
+    ///
+    /// OAuth 1.0a tokens are signed with the client's consumer key and secret.
+    ///
+    /// ```rust,no_run
+    /// use tweety_rs::api::client::UserTokens;
+    /// use tweety_rs::TweetyClient;
+    ///
+    /// # async fn run(client: TweetyClient) {
+    /// let alice = UserTokens::oauth1("alice_access_token", "alice_access_token_secret");
+    /// let result = client.on_behalf_of(alice).post_tweet("Hello from Alice", None).await;
+    /// # }
+    /// ```
+    pub fn on_behalf_of(&self, tokens: impl Into<UserTokens>) -> Self {
+        let own = &self.credentials;
+        let (access_token, access_token_secret, oauth2_token, mode) = match tokens.into() {
+            UserTokens::OAuth1 {
+                access_token,
+                access_token_secret,
+            } => (
+                access_token,
+                access_token_secret,
+                None,
+                AuthMode::OAuth1User,
+            ),
+            UserTokens::OAuth2(token) => (
+                SecretString::default(),
+                SecretString::default(),
+                Some(token),
+                AuthMode::OAuth2User,
+            ),
+        };
+
+        let credentials = Credentials {
+            consumer_key: own.consumer_key.clone(),
+            access_token,
+            consumer_key_secret: own.consumer_key_secret.clone(),
+            access_token_secret,
+            bearer_token: own.bearer_token.clone(),
+            oauth2_token: watch::Sender::new(oauth2_token),
+        };
+
+        TweetyClient {
+            credentials: Arc::new(credentials),
+            auth_override: Some(mode),
+            rate_limits: Arc::new(watch::Sender::new(RateLimits::new())),
+            ..self.clone()
+        }
+    }
+    /// Replaces the connection pool with one built from `config`, see
+    /// [`crate::api::http`]. Clones made before keep the old pool.
+    ///
+    /// # Panics
+    ///
+    /// Like `reqwest::Client::new`, when the TLS backend can't be initialized.
+    pub fn with_http_config(mut self, config: HttpConfig) -> Self {
+        self.http = config.build().expect("failed to build the HTTP client");
+        self.http_config = config;
+        self
+    }
+    /// The connection settings set with [`TweetyClient::with_http_config`].
+    pub fn http_config(&self) -> &HttpConfig {
+        &self.http_config
+    }
+    /// Reports what the client is doing to `observer`, such as waiting out a rate limit,
+    /// see [`crate::api::observer`]. Shared by clones made after.
+    pub fn with_observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+    /// Logs every request and its response status through the `log` crate, with
+    /// credentials redacted, see [`crate::api::request_log`].
+    #[cfg(feature = "request-log")]
+    pub fn with_request_log(mut self, request_log: RequestLog) -> Self {
+        self.request_log = Some(Arc::new(request_log));
+        self
+    }
+    /// Fails requests fast while X keeps answering an endpoint family with server
+    /// errors, see [`crate::api::circuit_breaker`].
+    pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(Arc::new(circuit_breaker));
+        self
+    }
+    /// Bytes sent per APPEND segment of chunked uploads, between 1 byte and
+    /// [`MAX_CHUNK_SIZE`](crate::api::uploads::MAX_CHUNK_SIZE).
+    /// [`DEFAULT_CHUNK_SIZE`](crate::api::uploads::DEFAULT_CHUNK_SIZE) unless changed.
+    ///
+    /// Smaller segments cost less to send again when a connection drops, larger ones
+    /// take fewer requests. Files too large for X's 1000 segments at this size are sent
+    /// in larger segments.
+    #[cfg(feature = "media-upload")]
+    pub fn with_upload_chunk_size(mut self, bytes: u64) -> Self {
+        self.upload_chunk_size = bytes.clamp(1, crate::api::uploads::MAX_CHUNK_SIZE);
+        self
+    }
+    /// Gzips JSON request bodies of at least `bytes` bytes, sent with
+    /// `Content-Encoding: gzip`. Off unless set. Media is sent as is: it is compressed
+    /// already.
+    #[cfg(feature = "compression")]
+    pub fn with_compression_threshold(mut self, bytes: usize) -> Self {
+        self.compression_threshold = Some(bytes);
+        self
+    }
+    /// The usage guard set with [`TweetyClient::with_usage_guard`].
+    pub fn usage_guard(&self) -> Option<&UsageGuard> {
+        self.usage_guard.as_deref()
+    }
+    /// The circuit breaker set with [`TweetyClient::with_circuit_breaker`].
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.circuit_breaker.as_deref()
+    }
+    /// Subscribes to the rate-limit state of every endpoint family, updated after each
+    /// response, see [`crate::api::rate_limit`].
+    pub fn rate_limits(&self) -> watch::Receiver<RateLimits> {
+        self.rate_limits.subscribe()
+    }
+    /// The rate-limit state of `family` as of its last response.
+    pub fn rate_limit(&self, family: RateLimitFamily) -> Option<RateLimitStatus> {
+        self.rate_limits.borrow().get(&family).copied()
+    }
+    /// The access tier set with [`TweetyClient::with_tier`].
+    pub fn tier(&self) -> Option<Tier> {
+        self.tier
+    }
+    /// The base URL requests to `host` are sent to.
+    pub fn base_url(&self, host: Host) -> &str {
+        match self.base_url {
+            Some(ref base_url) => base_url,
+            None => host.base_url(),
+        }
+    }
+    /// The full URL of `endpoint` with `query` appended.
+    pub(crate) fn endpoint_url(&self, endpoint: &Endpoint, query: &Query) -> String {
+        build_url(
+            &format!("{}{}", self.base_url(endpoint.host()), endpoint.path()),
+            query,
+        )
+    }
+    /// Creates a `TweetyClient` that authenticates with an app-only bearer token.
+    ///
+    /// App-only clients can only call endpoints that support OAuth 2.0 App-only
+    /// authentication, such as lookups and recent search.
+    pub fn from_bearer_token(bearer_token: &str) -> Self {
+        TweetyClient::from_credentials(Credentials {
+            consumer_key: SecretString::default(),
+            access_token: SecretString::default(),
+            consumer_key_secret: SecretString::default(),
+            access_token_secret: SecretString::default(),
+            bearer_token: Some(bearer_token.into()),
+            oauth2_token: watch::Sender::new(None),
+        })
+    }
+    /// Creates a `TweetyClient` acting for a user through an OAuth 2.0 user access token.
+    ///
+    /// See [`crate::api::oauth2`] for obtaining one. Requests to endpoints the token isn't
+    /// scoped for log a warning before they are sent.
+    pub fn from_oauth2_token(token: OAuth2Token) -> Self {
+        TweetyClient::from_credentials(Credentials {
+            consumer_key: SecretString::default(),
+            access_token: SecretString::default(),
+            consumer_key_secret: SecretString::default(),
+            access_token_secret: SecretString::default(),
+            bearer_token: None,
+            oauth2_token: watch::Sender::new(Some(token)),
+        })
+    }
+    /// Creates a `TweetyClient` from environment variables, loading a `.env` file first if present.
+    ///
+    /// The OAuth 1.0a credentials are read from [`CONSUMER_KEY_ENV`], [`ACCESS_TOKEN_ENV`],
+    /// [`CONSUMER_KEY_SECRET_ENV`] and [`ACCESS_TOKEN_SECRET_ENV`]. When none of them is set,
+    /// [`BEARER_TOKEN_ENV`] is used for app-only authentication instead.
+    ///
+    /// Returns `TweetyError::MissingEnvironmentVariables` naming every variable that is missing:
+    /// the rest of the OAuth 1.0a variables when only some are set, even if a bearer token is.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use tweety_rs::TweetyClient;
+    ///
+    /// let client = TweetyClient::from_env().expect("credentials are not configured");
+    /// ```
+    pub fn from_env() -> Result<Self, TweetyError> {
+        dotenvy::dotenv().ok();
+
+        let read = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+
+        let oauth1_names = [
+            CONSUMER_KEY_ENV,
+            ACCESS_TOKEN_ENV,
+            CONSUMER_KEY_SECRET_ENV,
+            ACCESS_TOKEN_SECRET_ENV,
+        ];
+        let oauth1_values = oauth1_names.map(read);
+        let bearer_token = read(BEARER_TOKEN_ENV);
+
+        if let [Some(consumer_key), Some(access_token), Some(consumer_key_secret), Some(access_token_secret)] =
+            oauth1_values
+        {
+            return Ok(TweetyClient::from_credentials(Credentials {
+                consumer_key: consumer_key.into(),
+                access_token: access_token.into(),
+                consumer_key_secret: consumer_key_secret.into(),
+                access_token_secret: access_token_secret.into(),
+                bearer_token: bearer_token.map(SecretString::from),
+                oauth2_token: watch::Sender::new(None),
+            }));
+        }
+
+        let partly_configured = oauth1_values.iter().any(Option::is_some);
+        if let (false, Some(bearer_token)) = (partly_configured, bearer_token) {
+            return Ok(TweetyClient::from_bearer_token(&bearer_token));
+        }
+
+        let mut missing: Vec<String> = oauth1_names
+            .iter()
+            .zip(oauth1_values.iter())
+            .filter(|(_, value)| value.is_none())
+            .map(|(name, _)| name.to_string())
+            .collect();
+        if !partly_configured {
+            missing.push(format!("{} (alternative to OAuth 1.0a)", BEARER_TOKEN_ENV));
+        }
+
+        Err(TweetyError::MissingEnvironmentVariables(missing))
+    }
+
+    /// Returns `true` when the client holds OAuth 1.0a credentials, an OAuth 2.0 user
+    /// token or a bearer token.
     pub fn is_initialized(&self) -> bool {
-        !self.consumer_key.is_empty()
-            && !self.access_token.is_empty()
-            && !self.consumer_key_secret.is_empty()
-            && !self.access_token_secret.is_empty()
+        self.has_oauth1_credentials()
+            || self.credentials.oauth2_token.borrow().is_some()
+            || self.credentials.bearer_token.is_some()
     }
-    pub(crate) async fn send_request<T>(
+    /// The authentication the client's requests are sent with, or `None` when it holds
+    /// no credentials for it.
+    ///
+    /// Unless one was chosen with [`TweetyClient::with_auth_mode`], OAuth 1.0a is
+    /// preferred when several are configured, then an OAuth 2.0 user token, then the
+    /// bearer token.
+    pub fn auth_mode(&self) -> Option<AuthMode> {
+        match self.auth_override {
+            Some(mode) => self.has_credentials_for(mode).then_some(mode),
+            None => [
+                AuthMode::OAuth1User,
+                AuthMode::OAuth2User,
+                AuthMode::AppOnly,
+            ]
+            .into_iter()
+            .find(|mode| self.has_credentials_for(*mode)),
+        }
+    }
+    fn has_credentials_for(&self, mode: AuthMode) -> bool {
+        match mode {
+            AuthMode::OAuth1User => self.has_oauth1_credentials(),
+            AuthMode::OAuth2User => self.credentials.oauth2_token.borrow().is_some(),
+            AuthMode::AppOnly => self.credentials.bearer_token.is_some(),
+        }
+    }
+    /// Returns `true` when all four OAuth 1.0a credentials are set.
+    pub(crate) fn has_oauth1_credentials(&self) -> bool {
+        let credentials = &self.credentials;
+
+        !credentials.consumer_key.is_empty()
+            && !credentials.access_token.is_empty()
+            && !credentials.consumer_key_secret.is_empty()
+            && !credentials.access_token_secret.is_empty()
+    }
+    /// Builds the OAuth1 secrets used to sign a request.
+    pub(crate) fn oauth1_secrets(&self) -> reqwest_oauth1::Secrets<'_> {
+        let credentials = &self.credentials;
+
+        reqwest_oauth1::Secrets::new(
+            credentials.consumer_key.expose_secret(),
+            credentials.consumer_key_secret.expose_secret(),
+        )
+        .token(
+            credentials.access_token.expose_secret(),
+            credentials.access_token_secret.expose_secret(),
+        )
+    }
+    /// The current Unix time as X's clock has it, going by [`TweetyClient::clock_skew`].
+    pub(crate) fn oauth1_timestamp(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now.saturating_add_signed(self.clock_skew())
+    }
+    /// Seconds X's clock is ahead of this machine's, negative when it is behind.
+    ///
+    /// OAuth 1.0a signatures carry a timestamp that X rejects with a 401 when it is
+    /// too far off. When a request signed with OAuth 1.0a gets a 401 whose `Date`
+    /// header is more than [`CLOCK_SKEW_TOLERANCE`] away from the local clock, the
+    /// difference is kept here, shared by clones, and the request is signed again and
+    /// resent once. Later signatures use the corrected time from the start.
+    pub fn clock_skew(&self) -> i64 {
+        self.clock_skew.load(Ordering::Relaxed)
+    }
+    /// Creates a request builder carrying the client's authorization, picked by
+    /// [`TweetyClient::auth_mode`].
+    pub(crate) fn authorized_request(
         &self,
-        url: &str,
         method: Method,
+        url: &str,
+    ) -> Result<reqwest::RequestBuilder, TweetyError> {
+        let token = match self.auth_mode() {
+            Some(AuthMode::OAuth1User) => {
+                let parameters = match self.clock_skew() {
+                    0 => OAuthParameters::new(),
+                    _ => OAuthParameters::new().timestamp(self.oauth1_timestamp()),
+                };
+                return self
+                    .http
+                    .clone()
+                    .oauth1_with_params(self.oauth1_secrets(), parameters)
+                    .request(method, url)
+                    .generate_signature()
+                    .map_err(|_| TweetyError::AuthError);
+            }
+            Some(AuthMode::OAuth2User) => self
+                .credentials
+                .oauth2_token
+                .borrow()
+                .as_ref()
+                .map(|token| token.access_token.clone()),
+            Some(AuthMode::AppOnly) => self.credentials.bearer_token.clone(),
+            None => None,
+        };
+        let token = token.ok_or(TweetyError::MissingCredentials)?;
+
+        Ok(self
+            .http
+            .request(method, url)
+            .bearer_auth(token.expose_secret()))
+    }
+    pub(crate) async fn send_request<T>(
+        &self,
+        endpoint: &Endpoint,
+        query: &Query,
         body: Option<T>,
     ) -> Result<Value, TweetyError>
     where
         T: Serialize + Deserialize<'static>,
     {
+        let json_body = match body {
+            Some(ref body) => Some(
+                serde_json::to_string(body)
+                    .map_err(|err| TweetyError::SerializeError(err.to_string()))?,
+            ),
+            None => None,
+        };
+
+        let response = self.dispatch(endpoint, query, json_body).await?;
+        let value = Self::parse_response(response).await?;
+        self.record_usage(endpoint, || posts_in_value(&value));
+        self.save_usage(endpoint).await;
+
+        Ok(value)
+    }
+
+    /// Sends a request to `endpoint` and deserializes the response straight into `T`,
+    /// for endpoints without a typed method or responses modeled by your own structs.
+    /// An empty response body deserializes as JSON `null`.
+    ///
+    /// ```rust,no_run
+    /// use serde::Deserialize;
+    /// use tweety_rs::api::endpoint::Endpoint;
+    /// use tweety_rs::TweetyClient;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Community {
+    ///     name: String,
+    ///     member_count: u64,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct CommunityResponse {
+    ///     data: Community,
+    /// }
+    ///
+    /// # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+    /// let response: CommunityResponse = client
+    ///     .send_request_as(
+    ///         &Endpoint::Community("1493446837214187523".to_string()),
+    ///         &[("community.fields", "member_count")],
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// println!("{} has {} members", response.data.name, response.data.member_count);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_request_as<T: DeserializeOwned>(
+        &self,
+        endpoint: &Endpoint,
+        query: &[(&str, &str)],
+        body: Option<Value>,
+    ) -> Result<T, TweetyError> {
+        let mut params = Query::new();
+        for (key, value) in query {
+            params.push(key, value);
+        }
+        let json_body = body.map(|body| body.to_string());
+
+        let response = self.dispatch(endpoint, &params, json_body).await?;
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            return Err(Self::error_from_body(status, text));
+        }
+        self.record_usage(endpoint, || posts_in_body(&text));
+        self.save_usage(endpoint).await;
+
+        let text = if text.trim().is_empty() {
+            "null"
+        } else {
+            &text
+        };
+        serde_json::from_str(text).map_err(TweetyError::JsonParseError)
+    }
+
+    /// Sends a request with an optional JSON body and records the response's status and
+    /// rate-limit headers.
+    async fn dispatch(
+        &self,
+        endpoint: &Endpoint,
+        query: &Query,
+        json_body: Option<String>,
+    ) -> Result<reqwest::Response, TweetyError> {
+        if let Some(ref usage_guard) = self.usage_guard {
+            usage_guard.restore().await?;
+        }
+        let parsed_url = self.prepare(endpoint, query)?;
+        let mut response = self
+            .send_authorized(endpoint, &parsed_url, json_body.clone())
+            .await?;
+        if self.learn_clock_skew(&response) {
+            response = self
+                .send_authorized(endpoint, &parsed_url, json_body)
+                .await?;
+        }
+
+        self.record_response(endpoint, &response);
+        Self::check_rate_limited(endpoint, response).await
+    }
+
+    /// Turns a 429 into [`TweetyError::RateLimited`], with the wait X asked for.
+    async fn check_rate_limited(
+        endpoint: &Endpoint,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response, TweetyError> {
+        if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Ok(response);
+        }
+        let retry_after = rate_limit::retry_after(response.headers());
+        let body = response.text().await?;
+        Err(TweetyError::rate_limited(
+            endpoint.rate_limit_family(),
+            retry_after,
+            &body,
+        ))
+    }
+
+    pub(crate) fn emit(&self, event: &ClientEvent) {
+        if let Some(ref observer) = self.observer {
+            observer.on_event(event);
+        }
+    }
+
+    /// Sends `request`, logging it when [`TweetyClient::with_request_log`] is set.
+    async fn execute(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, TweetyError> {
+        #[cfg(feature = "request-log")]
+        if let Some(ref request_log) = self.request_log {
+            return request_log
+                .send(&self.http, request.build()?, self.tag())
+                .await;
+        }
+        Ok(request.send().await?)
+    }
+
+    async fn send_authorized(
+        &self,
+        endpoint: &Endpoint,
+        url: &Url,
+        json_body: Option<String>,
+    ) -> Result<reqwest::Response, TweetyError> {
+        let mut request = self.authorized_request(endpoint.method(), url.as_str())?;
+
+        if let Some(json_body) = json_body {
+            request = request.header("Content-Type", "application/json");
+            request = self.attach_body(request, json_body)?;
+        }
+
+        self.execute(request).await
+    }
+
+    /// Updates [`TweetyClient::clock_skew`] from a 401 to an OAuth 1.0a request, and
+    /// returns `true` when it changed enough that the request is worth signing again.
+    fn learn_clock_skew(&self, response: &reqwest::Response) -> bool {
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED
+            || self.auth_mode() != Some(AuthMode::OAuth1User)
+        {
+            return false;
+        }
+        let Some(skew) = signing::clock_skew_of(response.headers()) else {
+            return false;
+        };
+        let tolerance = CLOCK_SKEW_TOLERANCE.as_secs() as i64;
+        if skew.abs() <= tolerance || (skew - self.clock_skew()).abs() <= tolerance {
+            return false;
+        }
+
+        log::warn!(
+            "X's clock is {}s {} this machine's, signing requests with X's time",
+            skew.abs(),
+            if skew > 0 { "ahead of" } else { "behind" }
+        );
+        self.clock_skew.store(skew, Ordering::Relaxed);
+        true
+    }
+
+    /// Sets `json_body` as the body, gzipped when it reaches the compression threshold.
+    #[cfg(feature = "compression")]
+    fn attach_body(
+        &self,
+        request: reqwest::RequestBuilder,
+        json_body: String,
+    ) -> Result<reqwest::RequestBuilder, TweetyError> {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        match self.compression_threshold {
+            Some(threshold) if json_body.len() >= threshold => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(json_body.as_bytes())
+                    .map_err(|err| TweetyError::SerializeError(err.to_string()))?;
+                let compressed = encoder
+                    .finish()
+                    .map_err(|err| TweetyError::SerializeError(err.to_string()))?;
+                Ok(request.header("Content-Encoding", "gzip").body(compressed))
+            }
+            _ => Ok(request.body(json_body)),
+        }
+    }
+
+    #[cfg(not(feature = "compression"))]
+    fn attach_body(
+        &self,
+        request: reqwest::RequestBuilder,
+        json_body: String,
+    ) -> Result<reqwest::RequestBuilder, TweetyError> {
+        Ok(request.body(json_body))
+    }
+
+    /// Checks a request before it is sent and returns its URL.
+    fn prepare(&self, endpoint: &Endpoint, query: &Query) -> Result<Url, TweetyError> {
         if !self.is_initialized() {
             return Err(TweetyError::MissingCredentials);
         };
+        if let Some(tier) = self.tier {
+            tier.check(endpoint, query)?;
+        }
+        if self.auth_mode() == Some(AuthMode::AppOnly) {
+            check_app_only_fields(query)?;
+        }
+        if let Some(ref usage_guard) = self.usage_guard {
+            usage_guard.check(endpoint, self.tier)?;
+        }
+        if let Some(ref circuit_breaker) = self.circuit_breaker {
+            circuit_breaker.check(endpoint.rate_limit_family())?;
+        }
 
-        let parsed_url = match Url::parse(url) {
-            Ok(url) => url,
-            Err(err) => {
-                println!("{}", err);
-                return Err(TweetyError::UrlParseError(err));
-            }
+        let parsed_url = Url::parse(&self.endpoint_url(endpoint, query))?;
+        self.warn_missing_scopes(&endpoint.method(), &parsed_url);
+
+        Ok(parsed_url)
+    }
+
+    /// Counts the response's status for the circuit breaker, publishes its rate-limit
+    /// headers and reports it to the observer.
+    fn record_response(&self, endpoint: &Endpoint, response: &reqwest::Response) {
+        let family = endpoint.rate_limit_family();
+        if let Some(ref circuit_breaker) = self.circuit_breaker {
+            circuit_breaker.record(family, response.status());
+        }
+        let rate_limit = RateLimitStatus::from_headers(response.headers());
+        if let Some(status) = rate_limit {
+            self.rate_limits.send_modify(|rate_limits| {
+                rate_limits.insert(family, status);
+            });
+        }
+        if self.observer.is_some() {
+            self.emit(&ClientEvent::Response {
+                tag: self.tag.as_deref().map(str::to_string),
+                family,
+                status: response.status(),
+                rate_limit,
+            });
+        }
+    }
+
+    /// Records statuses learned from somewhere else than response headers, such as
+    /// [`V1::get_rate_limit_status`](crate::api::v1::V1::get_rate_limit_status).
+    #[cfg(feature = "v1")]
+    pub(crate) fn merge_rate_limits(&self, statuses: &RateLimits) {
+        if statuses.is_empty() {
+            return;
+        }
+        self.rate_limits.send_modify(|rate_limits| {
+            rate_limits.extend(statuses.iter().map(|(family, status)| (*family, *status)));
+        });
+    }
+
+    /// Counts posts against the tier's monthly caps, warning as they come near, and
+    /// against the usage guard's budget, and reports them to the observer. `posts`
+    /// counts the posts in the response, and is only called for endpoints whose posts
+    /// count.
+    fn record_usage(&self, endpoint: &Endpoint, posts: impl FnOnce() -> u64) {
+        let Some(kind) = UsageKind::of(endpoint) else {
+            return;
+        };
+        if self.tier.is_none() && self.usage_guard.is_none() && self.observer.is_none() {
+            return;
+        }
+        let posts = posts();
+        if self.observer.is_some() {
+            self.emit(&ClientEvent::PostsConsumed {
+                tag: self.tag.as_deref().map(str::to_string),
+                kind,
+                posts,
+            });
+        }
+        if let Some(ref usage_guard) = self.usage_guard {
+            usage_guard.record(endpoint, posts);
+        }
+        let Some(tier) = self.tier else {
+            return;
         };
 
-        let secrets = reqwest_oauth1::Secrets::new(&self.consumer_key, &self.consumer_key_secret)
-            .token(&self.access_token, &self.access_token_secret);
-
-        let client = reqwest::Client::new();
-        let mut json_body = String::new();
-
-        if body.is_some() {
-            json_body = serde_json::to_string(&body).unwrap();
-        }
-
-        let response = if method == "POST" {
-            client
-                .oauth1(secrets)
-                .post(&parsed_url.to_string())
-                .header("Content-Type", "application/json")
-                .body(json_body)
-                .send()
-        } else if method == "GET" {
-            client.oauth1(secrets).get(&parsed_url.to_string()).send()
-        } else if method == "DELETE" {
-            client
-                .oauth1(secrets)
-                .delete(&parsed_url.to_string())
-                .send()
-        } else if method == "PUT" {
-            client.oauth1(secrets).put(&parsed_url.to_string()).send()
-        } else {
-            //TODO : a good way to handle this without panicking
-            panic!("Invalid method");
+        let reset_day = self
+            .usage_guard
+            .as_ref()
+            .map_or(DEFAULT_CAP_RESET_DAY, |usage_guard| usage_guard.reset_day());
+        let warning = match self.usage.lock() {
+            Ok(mut usage) => usage.record(tier, endpoint, reset_day, || posts),
+            Err(_) => None,
         };
+        if let Some(warning) = warning {
+            log::warn!("{}", warning);
+        }
+    }
 
-        match response.await {
-            Ok(response) => {
-                if response.status().is_success() {
-                    let api_response = response
-                        .json::<Value>()
-                        .await
-                        .map_err(|err| TweetyError::JsonParseError(err.to_string()))?;
-
-                    return Ok(api_response);
-                }
-                let status = response.status();
-
-                let status_text = response
-                    .json::<Value>()
-                    .await
-                    .map_err(|err| TweetyError::JsonParseError(err.to_string()))?;
-
-                Err(TweetyError::ApiError(format!(
-                    "HTTP {}: {}",
-                    status, status_text
-                )))
-            }
-            Err(err) => {
-                println!("Error while sending request: {}", err);
-                Err(TweetyError::NetworkError(err.to_string()))
+    /// Saves the usage guard's count of posts sent, see [`UsageGuard::state_store`]. The
+    /// posts were sent, so a failure is only logged.
+    async fn save_usage(&self, endpoint: &Endpoint) {
+        if let Some(ref usage_guard) = self.usage_guard {
+            if let Err(err) = usage_guard.save(endpoint).await {
+                log::warn!("saving the count of posts sent failed: {}", err);
             }
         }
     }
+
+    /// Sends a `multipart/form-data` body, as the v1.1 media upload commands expect.
+    ///
+    /// With OAuth 1.0a only the URL's query parameters are signed: unlike urlencoded
+    /// forms, multipart fields are left out of the signature base string.
+    #[cfg(feature = "media-upload")]
+    pub(crate) async fn send_multipart(
+        &self,
+        endpoint: &Endpoint,
+        query: &Query,
+        form: reqwest::multipart::Form,
+    ) -> Result<Value, TweetyError> {
+        let parsed_url = self.prepare(endpoint, query)?;
+        let request = self
+            .authorized_request(endpoint.method(), parsed_url.as_str())?
+            .multipart(form);
+
+        let response = self.execute(request).await?;
+        self.record_response(endpoint, &response);
+        let response = Self::check_rate_limited(endpoint, response).await?;
+        Self::parse_response(response).await
+    }
+
+    /// Connects to a streaming endpoint and returns the response with its body unread,
+    /// once the status shows the connection was accepted.
+    #[cfg(feature = "streams")]
+    pub(crate) async fn open_stream(
+        &self,
+        endpoint: &Endpoint,
+        query: &Query,
+    ) -> Result<reqwest::Response, TweetyError> {
+        let parsed_url = self.prepare(endpoint, query)?;
+        let request = self.authorized_request(endpoint.method(), parsed_url.as_str())?;
+        let response = self.execute(request).await?;
+
+        self.record_response(endpoint, &response);
+        let response = Self::check_rate_limited(endpoint, response).await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let text = response.text().await?;
+        Err(Self::error_from_body(status, text))
+    }
+
+    /// Fetches a file X serves publicly, such as an avatar on `pbs.twimg.com`, over the
+    /// client's connection pool. No credentials are sent.
+    pub(crate) async fn download(&self, url: &str) -> Result<Vec<u8>, TweetyError> {
+        let url = Url::parse(url)?;
+        let response = self.execute(self.http.get(url)).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await?;
+            return Err(Self::error_from_body(status, text));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Follows the redirects from `url` without credentials and returns where they end.
+    /// Only the headers are requested; the status of the last response doesn't matter.
+    pub(crate) async fn follow_redirects(&self, url: &str) -> Result<Url, TweetyError> {
+        let url = Url::parse(url)?;
+        let response = self.execute(self.http.head(url)).await?;
+        Ok(response.url().clone())
+    }
+
+    async fn parse_response(response: reqwest::Response) -> Result<Value, TweetyError> {
+        let status = response.status();
+        let text = response.text().await?;
+
+        // Likes, deletes and the like may answer 204 with nothing to parse.
+        if status.is_success() && text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+
+        match serde_json::from_str::<Value>(&text) {
+            Ok(value) if status.is_success() => Ok(value),
+            _ => Err(Self::error_from_body(status, text)),
+        }
+    }
+
+    fn error_from_body(status: reqwest::StatusCode, text: String) -> TweetyError {
+        match serde_json::from_str::<Value>(&text) {
+            Ok(value) => match error::duplicate_detail(status, &value) {
+                Some(detail) => TweetyError::DuplicateTweet { detail },
+                None => TweetyError::api(status, &text),
+            },
+            // HTML from a proxy or a plain-text 429, rather than an X API response.
+            Err(_) => TweetyError::NonJsonResponse { status, body: text },
+        }
+    }
 }
@@ -0,0 +1,145 @@
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::query::{field_names, Query};
+use crate::types::meta::{Meta, Paginated};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommunityFields {
+    Access,
+    CreatedAt,
+    Description,
+    Id,
+    JoinPolicy,
+    MemberCount,
+    Name,
+}
+
+field_names!(CommunityFields);
+
+/// A Community, as returned by the `/2/communities` endpoints.
+///
+/// Only `id` and `name` are returned by default; request the rest through
+/// `community_fields`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct Community {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+    /// Who can see the Community, e.g. `Public` or `Closed`.
+    pub access: Option<String>,
+    /// How users join the Community, e.g. `Open` or `RestrictedJoinRequestsRequireAdminApproval`.
+    pub join_policy: Option<String>,
+    pub member_count: Option<u64>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+/// Query parameters for `GET /2/communities/search`.
+/// [Docs](https://docs.x.com/x-api/communities/search-communities)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CommunitySearchParams {
+    /// The maximum number of results to be returned per page, between 10 and 100.
+    pub max_results: Option<u32>,
+    /// The `next_token` of a previous page.
+    pub next_token: Option<String>,
+    pub community_fields: Option<Vec<CommunityFields>>,
+}
+
+impl CommunitySearchParams {
+    pub fn to_query_string(&self) -> String {
+        self.to_query().to_query_string()
+    }
+
+    pub(crate) fn to_query(&self) -> Query {
+        let mut query = Query::new();
+
+        query
+            .push_opt("max_results", self.max_results)
+            .push_opt("next_token", self.next_token.as_ref());
+        if let Some(ref fields) = self.community_fields {
+            query.push_fields("community.fields", fields);
+        }
+
+        query
+    }
+}
+
+impl Paginated for CommunitySearchParams {
+    fn set_page_token(&mut self, token: String) {
+        self.next_token = Some(token);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommunityResponse {
+    pub data: Community,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommunitySearchResponse {
+    /// Absent from the response when nothing matches the query.
+    #[serde(default)]
+    pub data: Vec<Community>,
+    pub meta: Meta,
+}
+
+/// Communities
+/// [Docs](https://docs.x.com/x-api/communities/introduction)
+impl TweetyClient {
+    /// GET /2/communities/:id
+    /// Returns a single Community. Useful to validate a `community_id` before posting to it.
+    /// [Docs](https://docs.x.com/x-api/communities/get-community-by-id)
+    pub async fn get_community(
+        &self,
+        community_id: &str,
+        community_fields: Option<Vec<CommunityFields>>,
+    ) -> Result<CommunityResponse, TweetyError> {
+        let mut query = Query::new();
+        query.push_fields("community.fields", &community_fields.unwrap_or_default());
+        let endpoint = Endpoint::Community(community_id.to_string());
+
+        match self.send_request::<()>(&endpoint, &query, None).await {
+            Ok(value) => match serde_json::from_value::<CommunityResponse>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// GET /2/communities/search
+    /// Returns Communities whose name matches the query.
+    /// Pass `meta.next_token` back as `next_token` to fetch the next page.
+    /// [Docs](https://docs.x.com/x-api/communities/search-communities)
+    pub async fn search_communities(
+        &self,
+        query: &str,
+        params: Option<CommunitySearchParams>,
+    ) -> Result<CommunitySearchResponse, TweetyError> {
+        let mut search = Query::new();
+        search.push("query", query);
+        if let Some(params) = params {
+            search.extend(params.to_query());
+        }
+
+        match self
+            .send_request::<()>(&Endpoint::SearchCommunities, &search, None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<CommunitySearchResponse>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! Compliance streams, for data holders that must act on deletions and other changes
+//! to the X data they store.
+//!
+//! The streams are split into partitions, numbered 1 to 4; a consumer has to read all
+//! of them to see every event. They need the Enterprise tier.
+//!
+//! ```rust,no_run
+//! use futures_util::StreamExt;
+//! use tweety_rs::api::compliance::TweetComplianceEvent;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let mut events = Box::pin(client.tweets_compliance_stream(1).await?);
+//! while let Some(event) = events.next().await {
+//!     match event? {
+//!         TweetComplianceEvent::Delete(delete) => println!("forget {}", delete.tweet.id),
+//!         other => println!("{:?}", other),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::query::Query;
+use crate::api::stream::{messages, LineParser};
+use futures_util::stream::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Number of partitions each compliance stream is split into.
+pub const PARTITIONS: u8 = 4;
+
+/// The tweet an event is about.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplianceTweet {
+    pub id: String,
+    pub author_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TweetCompliance {
+    pub tweet: ComplianceTweet,
+    pub event_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TweetWithheld {
+    pub tweet: ComplianceTweet,
+    /// Country codes the tweet is withheld in.
+    pub withheld_in_countries: Vec<String>,
+    pub event_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TweetEdit {
+    /// The new version of the tweet.
+    pub tweet: ComplianceTweet,
+    pub initial_tweet_id: String,
+    /// Every version of the tweet, oldest first.
+    pub edit_tweet_ids: Vec<String>,
+    pub event_at: String,
+}
+
+/// An event of `GET /2/tweets/compliance/stream`.
+/// [Docs](https://developer.x.com/en/docs/x-api/compliance/streams/api-reference/get-tweets-compliance-stream)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TweetComplianceEvent {
+    /// The tweet was deleted and must be removed.
+    Delete(TweetCompliance),
+    /// The tweet is no longer visible in some countries.
+    Withheld(TweetWithheld),
+    /// The tweet is no longer available, for instance because its author was suspended.
+    Drop(TweetCompliance),
+    /// A dropped tweet is available again.
+    Undrop(TweetCompliance),
+    /// The tweet was edited, and the earlier versions are superseded.
+    TweetEdit(TweetEdit),
+}
+
+/// The account an event is about.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplianceUser {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserCompliance {
+    pub user: ComplianceUser,
+    pub event_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserWithheld {
+    pub user: ComplianceUser,
+    /// Country codes the account is withheld in.
+    pub withheld_in_countries: Vec<String>,
+    pub event_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScrubGeo {
+    pub user: ComplianceUser,
+    /// Location data must be removed from the user's tweets up to and including this one.
+    pub up_to_tweet_id: String,
+    pub event_at: String,
+}
+
+/// An event of `GET /2/users/compliance/stream`.
+/// [Docs](https://developer.x.com/en/docs/x-api/compliance/streams/api-reference/get-users-compliance-stream)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserComplianceEvent {
+    /// The account was deleted, and its data must be removed.
+    UserDelete(UserCompliance),
+    /// A deleted account was restored.
+    UserUndelete(UserCompliance),
+    /// The account's tweets became protected, visible to its followers only.
+    UserProtect(UserCompliance),
+    UserUnprotect(UserCompliance),
+    UserSuspend(UserCompliance),
+    UserUnsuspend(UserCompliance),
+    /// The account is no longer visible in some countries.
+    UserWithheld(UserWithheld),
+    /// The user removed the location data of their tweets.
+    ScrubGeo(ScrubGeo),
+}
+
+/// The like an event is about.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComplianceLike {
+    /// Id of the liked tweet.
+    pub id: String,
+    /// Id of the user who liked it.
+    pub user_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LikeCompliance {
+    pub favorite: ComplianceLike,
+    pub event_at: String,
+}
+
+/// An event of `GET /2/likes/compliance/stream`.
+/// [Docs](https://developer.x.com/en/docs/x-api/compliance/streams/api-reference/get-likes-compliance-stream)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LikeComplianceEvent {
+    Like(LikeCompliance),
+    /// The like was removed, and must be dropped from stored data.
+    #[serde(alias = "delete")]
+    Unlike(LikeCompliance),
+}
+
+/// Each line of a compliance stream carries its event under `data`.
+#[derive(Debug, Deserialize)]
+struct ComplianceMessage<T> {
+    data: T,
+}
+
+impl TweetyClient {
+    /// Connects to one partition of the tweets compliance stream and yields its events.
+    ///
+    /// Lines that aren't a known event come out as errors without ending the stream.
+    /// [Docs](https://developer.x.com/en/docs/x-api/compliance/streams/api-reference/get-tweets-compliance-stream)
+    pub async fn tweets_compliance_stream(
+        &self,
+        partition: u8,
+    ) -> Result<
+        impl Stream<Item = Result<TweetComplianceEvent, TweetyError>> + Send + 'static,
+        TweetyError,
+    > {
+        self.compliance_stream(&Endpoint::TweetsComplianceStream, partition)
+            .await
+    }
+
+    /// Connects to one partition of the users compliance stream and yields its events.
+    ///
+    /// Lines that aren't a known event come out as errors without ending the stream.
+    /// [Docs](https://developer.x.com/en/docs/x-api/compliance/streams/api-reference/get-users-compliance-stream)
+    pub async fn users_compliance_stream(
+        &self,
+        partition: u8,
+    ) -> Result<
+        impl Stream<Item = Result<UserComplianceEvent, TweetyError>> + Send + 'static,
+        TweetyError,
+    > {
+        self.compliance_stream(&Endpoint::UsersComplianceStream, partition)
+            .await
+    }
+
+    /// Connects to one partition of the likes compliance stream and yields its events.
+    ///
+    /// Lines that aren't a known event come out as errors without ending the stream.
+    /// [Docs](https://developer.x.com/en/docs/x-api/compliance/streams/api-reference/get-likes-compliance-stream)
+    pub async fn likes_compliance_stream(
+        &self,
+        partition: u8,
+    ) -> Result<
+        impl Stream<Item = Result<LikeComplianceEvent, TweetyError>> + Send + 'static,
+        TweetyError,
+    > {
+        self.compliance_stream(&Endpoint::LikesComplianceStream, partition)
+            .await
+    }
+
+    async fn compliance_stream<T>(
+        &self,
+        endpoint: &Endpoint,
+        partition: u8,
+    ) -> Result<impl Stream<Item = Result<T, TweetyError>> + Send + 'static, TweetyError>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        if !(1..=PARTITIONS).contains(&partition) {
+            return Err(TweetyError::ConfigError(format!(
+                "compliance stream partition is {}, it must be between 1 and {}",
+                partition, PARTITIONS
+            )));
+        }
+
+        let mut query = Query::new();
+        query.push("partition", partition);
+        let response = self.open_stream(endpoint, &query).await?;
+
+        let events = messages(response, LineParser::<ComplianceMessage<T>>::new())
+            .map(|message| message.map(|message| message.data));
+        Ok(events)
+    }
+}
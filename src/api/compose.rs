@@ -0,0 +1,602 @@
+//! Writing tweets from templates.
+//!
+//! A [`Composer`] holds named [`Template`]s such as `"{name} {version} is out!"`. A
+//! [`Draft`] fills one in and adds mentions, hashtags and a link, then turns into the
+//! text of one tweet or of a thread, counted the way X counts: most CJK characters and
+//! emoji count twice, and links count [`LINK_LENGTH`] whatever their length, see
+//! [`weighted_length`].
+//!
+//! When a single tweet is too long, the hashtags that don't fit are dropped, last
+//! first, then the text is cut after a word and ends with an ellipsis. Mentions and the
+//! link are never cut. A thread instead splits the text between tweets, at the end of a
+//! sentence where it can, with the mentions in the first tweet, the hashtags in the
+//! last and the link where [`LinkPlacement`] says.
+//!
+//! ```rust
+//! use tweety_rs::api::compose::Composer;
+//!
+//! # fn main() -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let composer = Composer::new()
+//!     .template("release", "{name} {version} is out! {highlights}".parse()?);
+//!
+//! let tweet = composer
+//!     .draft("release")?
+//!     .var("name", "tweety-rs")
+//!     .var("version", "1.4.0")
+//!     .var("highlights", "Threads, templates and cross-posting.")
+//!     .hashtag("rustlang")
+//!     .link("https://crates.io/crates/tweety-rs")
+//!     .to_text()?;
+//! assert_eq!(
+//!     tweet,
+//!     "tweety-rs 1.4.0 is out! Threads, templates and cross-posting. #rustlang https://crates.io/crates/tweety-rs"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::client::TweetyClient;
+use crate::api::crosspost::{LINK_LENGTH, TWEET_MAX_LENGTH};
+use crate::api::error::TweetyError;
+use crate::api::text;
+use crate::types::ids::TweetId;
+use crate::types::tweet::PostTweetParams;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+const ELLIPSIS: char = '…';
+
+/// Where a [`Draft`]'s link goes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LinkPlacement {
+    /// At the very end, after the hashtags.
+    #[default]
+    End,
+    /// Right after the text, before the hashtags.
+    BeforeHashtags,
+    /// In a tweet of its own at the end of a thread, since X shows tweets with links to
+    /// fewer people. A single tweet has it at the end.
+    Reply,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Var(String),
+}
+
+/// Text with `{name}` placeholders for variables. `{{` and `}}` stand for literal
+/// braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Parses `source`, failing with a [`TweetyError::ConfigError`] on an unclosed or
+    /// empty placeholder or a stray `}`.
+    pub fn parse(source: &str) -> Result<Self, TweetyError> {
+        let invalid =
+            |reason: &str| TweetyError::ConfigError(format!("template {:?}: {}", source, reason));
+
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(invalid("unclosed `{`")),
+                            Some(c) => name.push(c),
+                        }
+                    }
+                    let name = name.trim();
+                    if name.is_empty() {
+                        return Err(invalid("empty placeholder"));
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Var(name.to_string()));
+                }
+                '}' => return Err(invalid("`}` without a `{`, write `}}` for a brace")),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Template {
+            source: source.to_string(),
+            parts,
+        })
+    }
+
+    /// The names of the variables, in the order they appear.
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Var(name) => Some(name.as_str()),
+            Part::Literal(_) => None,
+        })
+    }
+
+    /// Fills in the placeholders from `vars`, failing with a
+    /// [`TweetyError::ConfigError`] when one has no value.
+    pub fn render(&self, vars: &HashMap<String, String>) -> Result<String, TweetyError> {
+        let mut text = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => text.push_str(literal),
+                Part::Var(name) => match vars.get(name) {
+                    Some(value) => text.push_str(value),
+                    None => {
+                        return Err(TweetyError::ConfigError(format!(
+                            "template {:?}: no value for {{{}}}",
+                            self.source, name
+                        )))
+                    }
+                },
+            }
+        }
+        Ok(text)
+    }
+}
+
+impl FromStr for Template {
+    type Err = TweetyError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Template::parse(source)
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Named templates and the rules for turning them into tweets.
+#[derive(Debug, Clone)]
+pub struct Composer {
+    templates: HashMap<String, Template>,
+    max_length: usize,
+    link_placement: LinkPlacement,
+    numbered: bool,
+}
+
+impl Default for Composer {
+    fn default() -> Self {
+        Composer {
+            templates: HashMap::new(),
+            max_length: TWEET_MAX_LENGTH,
+            link_placement: LinkPlacement::default(),
+            numbered: true,
+        }
+    }
+}
+
+impl Composer {
+    pub fn new() -> Self {
+        Composer::default()
+    }
+
+    /// Adds `template` as `name`, replacing any template of that name.
+    pub fn template(mut self, name: &str, template: Template) -> Self {
+        self.templates.insert(name.to_string(), template);
+        self
+    }
+
+    /// The longest tweet, as [`weighted_length`] counts it. Defaults to
+    /// [`TWEET_MAX_LENGTH`]; subscribers can post longer ones.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Where the link goes. Defaults to [`LinkPlacement::End`].
+    pub fn link_placement(mut self, link_placement: LinkPlacement) -> Self {
+        self.link_placement = link_placement;
+        self
+    }
+
+    /// Whether the tweets of a thread of more than one end with ` 1/3` and so on. On by
+    /// default.
+    pub fn numbered(mut self, numbered: bool) -> Self {
+        self.numbered = numbered;
+        self
+    }
+
+    /// Starts a draft from the template called `name`, failing with a
+    /// [`TweetyError::ConfigError`] when there is none.
+    pub fn draft(&self, name: &str) -> Result<Draft<'_>, TweetyError> {
+        let template = self
+            .templates
+            .get(name)
+            .ok_or_else(|| TweetyError::ConfigError(format!("no template named {:?}", name)))?;
+        Ok(self.draft_from(template.clone()))
+    }
+
+    /// Starts a draft from `text` as it is, with no placeholders.
+    pub fn draft_text(&self, text: &str) -> Draft<'_> {
+        self.draft_from(Template {
+            source: text.to_string(),
+            parts: vec![Part::Literal(text.to_string())],
+        })
+    }
+
+    fn draft_from(&self, template: Template) -> Draft<'_> {
+        Draft {
+            composer: self,
+            template,
+            vars: HashMap::new(),
+            mentions: Vec::new(),
+            hashtags: Vec::new(),
+            link: None,
+        }
+    }
+}
+
+/// A template being filled in, see [`Composer::draft`].
+#[derive(Debug, Clone)]
+pub struct Draft<'a> {
+    composer: &'a Composer,
+    template: Template,
+    vars: HashMap<String, String>,
+    mentions: Vec<String>,
+    hashtags: Vec<String>,
+    link: Option<String>,
+}
+
+impl Draft<'_> {
+    /// Sets the variable `name` to `value`.
+    pub fn var(mut self, name: &str, value: impl fmt::Display) -> Self {
+        self.vars.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Mentions `username`, with or without its `@`, at the start of the tweet.
+    pub fn mention(mut self, username: &str) -> Self {
+        self.mentions
+            .push(username.trim().trim_start_matches('@').to_string());
+        self
+    }
+
+    /// Adds `#tag` after the text. A leading `#` is optional, and spaces and
+    /// punctuation, which would end the hashtag, are left out: `"rust lang"` becomes
+    /// `#rustlang`.
+    pub fn hashtag(mut self, tag: &str) -> Self {
+        let tag: String = tag
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        if !self.hashtags.contains(&tag) {
+            self.hashtags.push(tag);
+        }
+        self
+    }
+
+    /// Places `url` as [`Composer::link_placement`] says.
+    pub fn link(mut self, url: &str) -> Self {
+        self.link = Some(url.trim().to_string());
+        self
+    }
+
+    /// The text of a single tweet, shortened as the [module docs](self) describe.
+    /// Fails with a [`TweetyError::ConfigError`] when a variable has no value, a
+    /// mention or hashtag is invalid, or the mentions and link leave no room for text.
+    pub fn to_text(&self) -> Result<String, TweetyError> {
+        let max_length = self.composer.max_length;
+        let parts = self.parts()?;
+        let mut hashtags = parts.hashtags.as_slice();
+        let fits = |text: &str, hashtags: &[String]| {
+            weighted_length(&parts.tweet(text, hashtags, true)) <= max_length
+        };
+
+        while !hashtags.is_empty() && !fits(&parts.text, hashtags) {
+            hashtags = &hashtags[..hashtags.len() - 1];
+        }
+        if fits(&parts.text, hashtags) {
+            return Ok(parts.tweet(&parts.text, hashtags, true));
+        }
+        cut_at_word(&parts.text, |cut| fits(cut, &[]))
+            .map(|cut| parts.tweet(&cut, &[], true))
+            .ok_or_else(|| {
+                TweetyError::ConfigError(format!(
+                    "the mentions and link leave no room for text in {} characters",
+                    max_length
+                ))
+            })
+    }
+
+    /// The texts of a thread, in posting order, as the [module docs](self) describe.
+    /// A draft that fits in one tweet is a thread of one. Fails like
+    /// [`Draft::to_text`].
+    pub fn to_thread(&self) -> Result<Vec<String>, TweetyError> {
+        let parts = self.parts()?;
+        let reply_link = self.composer.link_placement == LinkPlacement::Reply;
+        let whole = parts.tweet(&parts.text, &parts.hashtags, true);
+        if (!reply_link || parts.link.is_none())
+            && weighted_length(&whole) <= self.composer.max_length
+        {
+            return Ok(vec![whole]);
+        }
+
+        // Numbers take more room as the thread grows, so split again until it stops.
+        let mut count = 1;
+        loop {
+            let reserve = match self.composer.numbered {
+                true => weighted_length(&format!(" {}/{}", count, count)),
+                false => 0,
+            };
+            let limit = self.composer.max_length.saturating_sub(reserve);
+            let mut thread = parts.split(limit, reply_link)?;
+            thread.extend(parts.link.clone().filter(|_| reply_link));
+            if !self.composer.numbered || thread.len() <= count {
+                return Ok(self.number(thread));
+            }
+            count = thread.len();
+        }
+    }
+
+    fn number(&self, thread: Vec<String>) -> Vec<String> {
+        let count = thread.len();
+        if !self.composer.numbered || count < 2 {
+            return thread;
+        }
+        thread
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| format!("{} {}/{}", text, index + 1, count))
+            .collect()
+    }
+
+    fn parts(&self) -> Result<Parts, TweetyError> {
+        for mention in &self.mentions {
+            let valid = (1..=15).contains(&mention.len())
+                && mention
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(TweetyError::ConfigError(format!(
+                    "invalid username to mention: {:?}",
+                    mention
+                )));
+            }
+        }
+        for hashtag in &self.hashtags {
+            if hashtag.chars().all(|c| c.is_ascii_digit()) {
+                return Err(TweetyError::ConfigError(format!(
+                    "invalid hashtag: {:?}",
+                    hashtag
+                )));
+            }
+        }
+
+        let mentions = self.mentions.iter().map(|mention| format!("@{}", mention));
+        Ok(Parts {
+            mentions: mentions.collect::<Vec<_>>().join(" "),
+            text: self.template.render(&self.vars)?.trim().to_string(),
+            hashtags: self
+                .hashtags
+                .iter()
+                .map(|tag| format!("#{}", tag))
+                .collect(),
+            link: self.link.clone(),
+            link_placement: self.composer.link_placement,
+        })
+    }
+}
+
+/// A draft's pieces, filled in.
+struct Parts {
+    mentions: String,
+    text: String,
+    hashtags: Vec<String>,
+    link: Option<String>,
+    link_placement: LinkPlacement,
+}
+
+impl Parts {
+    /// One tweet of `text` with the mentions, `hashtags` and, when `with_link`, the link.
+    fn tweet(&self, text: &str, hashtags: &[String], with_link: bool) -> String {
+        self.head(text) + &self.tail(hashtags, with_link)
+    }
+
+    fn head(&self, text: &str) -> String {
+        join(&[&self.mentions, text])
+    }
+
+    /// The hashtags and link after the text, with a leading space when there are any.
+    fn tail(&self, hashtags: &[String], with_link: bool) -> String {
+        let link = self
+            .link
+            .as_deref()
+            .filter(|_| with_link)
+            .unwrap_or_default();
+        let hashtags = hashtags.join(" ");
+        let tail = match self.link_placement {
+            LinkPlacement::BeforeHashtags => join(&[link, hashtags.as_str()]),
+            LinkPlacement::End | LinkPlacement::Reply => join(&[hashtags.as_str(), link]),
+        };
+        match tail.is_empty() {
+            true => tail,
+            false => format!(" {}", tail),
+        }
+    }
+
+    /// Splits into tweets of at most `limit`: the text word by word, breaking after a
+    /// sentence when one ends in the second half of a tweet, with the mentions in the
+    /// first and the tail in the last.
+    fn split(&self, limit: usize, reply_link: bool) -> Result<Vec<String>, TweetyError> {
+        let tail = self.tail(&self.hashtags, !reply_link);
+        let mut words: Vec<&str> = self.text.split_whitespace().collect();
+        let mut thread = Vec::new();
+        let mut current: Vec<&str> = Vec::new();
+        let head = |current: &[&str], first: bool| match first {
+            true => self.head(&current.join(" ")),
+            false => current.join(" "),
+        };
+
+        let mut index = 0;
+        while index < words.len() {
+            let first = thread.is_empty();
+            let mut candidate = current.clone();
+            candidate.push(words[index]);
+            if weighted_length(&head(&candidate, first)) <= limit {
+                current = candidate;
+                index += 1;
+                continue;
+            }
+            if current.is_empty() {
+                // A word longer than a tweet, cut mid-word.
+                let word = words[index];
+                let ends: Vec<usize> = text::boundaries(word).collect();
+                let end = ends
+                    .into_iter()
+                    .rev()
+                    .skip(1)
+                    .find(|&end| weighted_length(&head(&[&word[..end]], first)) <= limit)
+                    .ok_or_else(|| no_room(limit))?;
+                thread.push(head(&[&word[..end]], first));
+                words[index] = &word[end..];
+                continue;
+            }
+
+            let sentence_end = current
+                .iter()
+                .rposition(|word| word.ends_with(['.', '!', '?', ELLIPSIS]))
+                .filter(|&end| end + 1 >= current.len().div_ceil(2) && end + 1 < current.len());
+            if let Some(end) = sentence_end {
+                let rest = current.split_off(end + 1);
+                thread.push(head(&current, first));
+                current = rest;
+            } else {
+                thread.push(head(&current, first));
+                current.clear();
+            }
+        }
+
+        let first = thread.is_empty();
+        let last = head(&current, first);
+        let with_tail = format!("{}{}", last, tail).trim_start().to_string();
+        if weighted_length(&with_tail) <= limit {
+            thread.push(with_tail);
+        } else {
+            if !last.is_empty() {
+                thread.push(last);
+            }
+            let tail = tail.trim_start().to_string();
+            if weighted_length(&tail) > limit {
+                return Err(no_room(limit));
+            }
+            thread.push(tail);
+        }
+        Ok(thread)
+    }
+}
+
+impl TweetyClient {
+    /// Posts `thread`, such as one from [`Draft::to_thread`], each tweet replying to the
+    /// one before. Returns the ids of the tweets posted; on an error, the tweets posted
+    /// before it stay up.
+    pub async fn post_thread(&self, thread: &[String]) -> Result<Vec<TweetId>, TweetyError> {
+        let mut ids: Vec<TweetId> = Vec::new();
+        for text in thread {
+            let params = ids
+                .last()
+                .map(|id| PostTweetParams::builder().in_reply_to(id).build());
+            let posted = self.post_tweet(text, params).await?;
+            ids.push(TweetId::new(posted.data.id));
+        }
+        Ok(ids)
+    }
+}
+
+/// How long X counts `text`: characters from Latin, Greek, Cyrillic and most other
+/// alphabets, and some punctuation, count 1; others, such as CJK characters, count 2;
+/// an emoji counts 2 with its modifiers and joined emoji; and each `http://` or
+/// `https://` link counts [`LINK_LENGTH`]. Emoji are told apart as grapheme clusters,
+/// see [`text`].
+///
+/// ```rust
+/// use tweety_rs::api::compose::weighted_length;
+///
+/// assert_eq!(weighted_length("hello"), 5);
+/// assert_eq!(weighted_length("こんにちは"), 10);
+/// assert_eq!(weighted_length("👍🏽 https://example.com/a/long/path"), 26);
+/// ```
+pub fn weighted_length(text: &str) -> usize {
+    let mut length = 0;
+    for word in text.split_inclusive(char::is_whitespace) {
+        let link = word.trim_end();
+        if link.starts_with("https://") || link.starts_with("http://") {
+            length += LINK_LENGTH + (word.len() - link.len());
+            continue;
+        }
+
+        for cluster in text::graphemes(word) {
+            length += match text::is_emoji(cluster) {
+                true => 2,
+                false => cluster.chars().map(char_weight).sum(),
+            };
+        }
+    }
+    length
+}
+
+fn char_weight(c: char) -> usize {
+    match c as u32 {
+        0x0000..=0x10FF | 0x2000..=0x200D | 0x2010..=0x201F | 0x2032..=0x2037 => 1,
+        _ => 2,
+    }
+}
+
+/// `text` cut after the last word for which `fits` accepts it with an ellipsis, or
+/// mid-word between grapheme clusters when no whole word fits, leaving out a comma,
+/// semicolon or colon before the ellipsis. `None` when not even one character fits.
+pub(crate) fn cut_at_word(text: &str, fits: impl Fn(&str) -> bool) -> Option<String> {
+    let candidate = |end: usize| {
+        let kept = text[..end].trim_end().trim_end_matches([',', ';', ':']);
+        let cut = format!("{}{}", kept, ELLIPSIS);
+        fits(&cut).then_some(cut)
+    };
+    let mut ends: Vec<usize> = text::boundaries(text).collect();
+    ends.pop();
+    let word_ends = ends.iter().rev().filter(|&&end| {
+        text[end..].starts_with(char::is_whitespace) && !text[..end].ends_with(char::is_whitespace)
+    });
+    word_ends
+        .chain(ends.iter().rev())
+        .find_map(|&end| candidate(end))
+}
+
+fn join(parts: &[&str]) -> String {
+    let parts: Vec<&str> = parts
+        .iter()
+        .copied()
+        .filter(|part| !part.is_empty())
+        .collect();
+    parts.join(" ")
+}
+
+fn no_room(limit: usize) -> TweetyError {
+    TweetyError::ConfigError(format!(
+        "the mentions, hashtags or link leave no room for text in {} characters",
+        limit
+    ))
+}
@@ -0,0 +1,167 @@
+//! Credentials files with named profiles.
+//!
+//! A credentials file holds one table per profile, much like AWS profiles. TOML is used
+//! unless the file has a `.json` extension.
+//!
+//! ```toml
+//! [default]
+//! consumer_key = "your_consumer_key"
+//! consumer_key_secret = "your_consumer_key_secret"
+//! access_token = "your_access_token"
+//! access_token_secret = "your_access_token_secret"
+//!
+//! [reader]
+//! bearer_token = "your_bearer_token"
+//! ```
+
+use crate::api::client::{Credentials, TweetyClient};
+use crate::api::error::TweetyError;
+use crate::api::secret::SecretString;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tokio::sync::watch;
+
+/// Profile used by [`TweetyClient::from_config_file`].
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Credentials stored under a single profile name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consumer_key: Option<SecretString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consumer_key_secret: Option<SecretString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<SecretString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_token_secret: Option<SecretString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bearer_token: Option<SecretString>,
+}
+
+/// A parsed credentials file, keyed by profile name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ConfigFile {
+    pub profiles: HashMap<String, Profile>,
+}
+
+impl ConfigFile {
+    /// Reads and parses a credentials file, choosing JSON or TOML from the extension.
+    pub fn load(path: &Path) -> Result<Self, TweetyError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+
+        let is_json = path
+            .extension()
+            .map(|extension| extension.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+
+        if is_json {
+            serde_json::from_str(&contents)
+                .map_err(|err| TweetyError::ConfigError(format!("{}: {}", path.display(), err)))
+        } else {
+            toml::from_str(&contents)
+                .map_err(|err| TweetyError::ConfigError(format!("{}: {}", path.display(), err)))
+        }
+    }
+
+    /// Returns the names of all profiles in the file.
+    pub fn profile_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+}
+
+impl Profile {
+    fn into_credentials(self, name: &str) -> Result<Credentials, TweetyError> {
+        let oauth1 = [
+            ("consumer_key", &self.consumer_key),
+            ("consumer_key_secret", &self.consumer_key_secret),
+            ("access_token", &self.access_token),
+            ("access_token_secret", &self.access_token_secret),
+        ];
+        let missing: Vec<&str> = oauth1
+            .iter()
+            .filter(|(_, value)| value.as_ref().map(SecretString::is_empty).unwrap_or(true))
+            .map(|(field, _)| *field)
+            .collect();
+
+        let bearer_token = self.bearer_token.filter(|token| !token.is_empty());
+        let partly_configured = !missing.is_empty() && missing.len() < oauth1.len();
+
+        if missing.is_empty() {
+            return Ok(Credentials {
+                consumer_key: self.consumer_key.unwrap_or_default(),
+                access_token: self.access_token.unwrap_or_default(),
+                consumer_key_secret: self.consumer_key_secret.unwrap_or_default(),
+                access_token_secret: self.access_token_secret.unwrap_or_default(),
+                bearer_token,
+                oauth2_token: watch::Sender::new(None),
+            });
+        }
+
+        // A partly filled OAuth1 block is an error, even with a bearer token to fall
+        // back to, like in `TweetyClient::from_env`.
+        if let (false, Some(bearer_token)) = (partly_configured, bearer_token) {
+            return Ok(Credentials {
+                consumer_key: SecretString::default(),
+                access_token: SecretString::default(),
+                consumer_key_secret: SecretString::default(),
+                access_token_secret: SecretString::default(),
+                bearer_token: Some(bearer_token),
+                oauth2_token: watch::Sender::new(None),
+            });
+        }
+
+        let alternative = if partly_configured {
+            ""
+        } else {
+            " (or a bearer_token)"
+        };
+        Err(TweetyError::ConfigError(format!(
+            "profile `{}` is missing {}{}",
+            name,
+            missing.join(", "),
+            alternative
+        )))
+    }
+}
+
+impl TweetyClient {
+    /// Creates a `TweetyClient` from the [`DEFAULT_PROFILE`] of a credentials file.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use std::path::Path;
+    /// use tweety_rs::TweetyClient;
+    ///
+    /// let client = TweetyClient::from_config_file(Path::new("tweety.toml")).unwrap();
+    /// ```
+    pub fn from_config_file(path: &Path) -> Result<Self, TweetyError> {
+        TweetyClient::from_config_profile(path, DEFAULT_PROFILE)
+    }
+
+    /// Creates a `TweetyClient` from a named profile of a credentials file.
+    ///
+    /// Useful for CLI tools that let users switch accounts with a `--profile` flag.
+    pub fn from_config_profile(path: &Path, profile: &str) -> Result<Self, TweetyError> {
+        let mut config = ConfigFile::load(path)?;
+
+        match config.profiles.remove(profile) {
+            Some(found) => Ok(TweetyClient::from_credentials(
+                found.into_credentials(profile)?,
+            )),
+            None => Err(TweetyError::ConfigError(format!(
+                "profile `{}` not found in {} (available: {})",
+                profile,
+                path.display(),
+                config.profile_names().join(", ")
+            ))),
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Reply trees for conversations.
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::mentions::TweetData;
+use crate::api::query::Query;
+use crate::api::tweet::LookupResponse;
+use crate::types::ids::TweetId;
+use crate::types::meta::Meta;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Tweet fields requested for every tweet of a conversation; `referenced_tweets` links
+/// a reply to its parent.
+const CONVERSATION_TWEET_FIELDS: &str =
+    "author_id,conversation_id,created_at,in_reply_to_user_id,referenced_tweets";
+
+/// A tweet and the replies to it, oldest first.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplyNode {
+    pub tweet: TweetData,
+    pub replies: Vec<ReplyNode>,
+}
+
+impl ReplyNode {
+    /// Number of tweets in this subtree, including this one.
+    pub fn len(&self) -> usize {
+        1 + self.replies.iter().map(ReplyNode::len).sum::<usize>()
+    }
+
+    /// Always `false`; a node holds at least its own tweet.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Length of the longest reply chain below this tweet.
+    pub fn depth(&self) -> usize {
+        self.replies
+            .iter()
+            .map(|reply| 1 + reply.depth())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Visits the tweets of this subtree depth-first, parents before their replies.
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a TweetData, usize)) {
+        self.walk_at(0, visit);
+    }
+
+    fn walk_at<'a>(&'a self, level: usize, visit: &mut impl FnMut(&'a TweetData, usize)) {
+        visit(&self.tweet, level);
+        for reply in &self.replies {
+            reply.walk_at(level + 1, visit);
+        }
+    }
+}
+
+/// A conversation rebuilt by [`TweetyClient::get_conversation`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Conversation {
+    pub root: ReplyNode,
+    /// Subtrees whose parent wasn't returned, e.g. replies to a deleted tweet or to one
+    /// older than the search window.
+    pub orphans: Vec<ReplyNode>,
+}
+
+impl Conversation {
+    /// Links `replies` to their parents through `referenced_tweets` of type `replied_to`.
+    ///
+    /// Replies to tweets that are neither `root` nor in `replies` end up in `orphans`.
+    pub fn from_tweets(root: TweetData, replies: Vec<TweetData>) -> Self {
+        let mut tweets: HashMap<String, TweetData> = replies
+            .into_iter()
+            .filter(|tweet| tweet.id != root.id)
+            .map(|tweet| (tweet.id.clone(), tweet))
+            .collect();
+
+        let mut children: HashMap<String, Vec<String>> = HashMap::new();
+        let mut orphan_ids = Vec::new();
+        for tweet in tweets.values() {
+            match parent_id(tweet) {
+                Some(parent) if parent == root.id || tweets.contains_key(parent) => children
+                    .entry(parent.to_string())
+                    .or_default()
+                    .push(tweet.id.clone()),
+                _ => orphan_ids.push(tweet.id.clone()),
+            }
+        }
+        for ids in children.values_mut() {
+            ids.sort_by(|a, b| compare_ids(a, b));
+        }
+        orphan_ids.sort_by(|a, b| compare_ids(a, b));
+
+        let root_replies = build_replies(&root.id, &mut tweets, &children);
+        let root = ReplyNode {
+            tweet: root,
+            replies: root_replies,
+        };
+        let orphans = orphan_ids
+            .iter()
+            .filter_map(|id| build_node(id, &mut tweets, &children))
+            .collect();
+
+        Conversation { root, orphans }
+    }
+
+    /// Number of tweets in the conversation, including the root and orphans.
+    pub fn len(&self) -> usize {
+        self.root.len() + self.orphans.iter().map(ReplyNode::len).sum::<usize>()
+    }
+
+    /// Always `false`; a conversation holds at least its root tweet.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+/// The tweet `tweet` replies to, if any.
+fn parent_id(tweet: &TweetData) -> Option<&str> {
+    tweet
+        .referenced_tweets
+        .iter()
+        .flatten()
+        .find(|reference| reference.r#type == "replied_to")
+        .map(|reference| reference.id.as_str())
+}
+
+/// Orders tweet ids chronologically; ids are increasing integers of varying length.
+pub(crate) fn compare_ids(a: &str, b: &str) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+fn build_replies(
+    parent: &str,
+    tweets: &mut HashMap<String, TweetData>,
+    children: &HashMap<String, Vec<String>>,
+) -> Vec<ReplyNode> {
+    children
+        .get(parent)
+        .into_iter()
+        .flatten()
+        .filter_map(|id| build_node(id, tweets, children))
+        .collect()
+}
+
+fn build_node(
+    id: &str,
+    tweets: &mut HashMap<String, TweetData>,
+    children: &HashMap<String, Vec<String>>,
+) -> Option<ReplyNode> {
+    let tweet = tweets.remove(id)?;
+    let replies = build_replies(id, tweets, children);
+    Some(ReplyNode { tweet, replies })
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchPage {
+    #[serde(default)]
+    data: Vec<TweetData>,
+    meta: Meta,
+}
+
+impl TweetyClient {
+    /// Fetches a conversation and rebuilds its reply tree.
+    ///
+    /// The root is looked up with `GET /2/tweets/:id`, and the replies are collected from
+    /// every page of a `conversation_id:` recent search, so only replies from the last
+    /// seven days are found.
+    /// [Docs](https://developer.x.com/en/docs/x-api/tweets/conversation-id)
+    pub async fn get_conversation(
+        &self,
+        conversation_id: impl Into<TweetId>,
+    ) -> Result<Conversation, TweetyError> {
+        let conversation_id: TweetId = conversation_id.into();
+        let mut root_query = Query::new();
+        root_query.push("tweet.fields", CONVERSATION_TWEET_FIELDS);
+        let value = self
+            .send_request::<()>(&Endpoint::Tweet(conversation_id.clone()), &root_query, None)
+            .await?;
+        let root = serde_json::from_value::<LookupResponse>(value)
+            .map_err(TweetyError::JsonParseError)?
+            .data;
+
+        let mut replies = Vec::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let mut query = Query::new();
+            query
+                .push("query", format!("conversation_id:{}", conversation_id))
+                .push("max_results", 100)
+                .push("tweet.fields", CONVERSATION_TWEET_FIELDS)
+                .push_opt("next_token", next_token.as_ref());
+
+            let value = self
+                .send_request::<()>(&Endpoint::RecentSearch, &query, None)
+                .await?;
+            let page =
+                serde_json::from_value::<SearchPage>(value).map_err(TweetyError::JsonParseError)?;
+
+            replies.extend(page.data);
+            match page.meta.next_token {
+                Some(token) => next_token = Some(token),
+                None => break,
+            }
+        }
+
+        Ok(Conversation::from_tweets(root, replies))
+    }
+}
@@ -0,0 +1,185 @@
+//! Publishing one post to several networks.
+//!
+//! A [`CrossPoster`] publishes text to one network and says how that network counts
+//! length. [`TweetyClient`] is one, posting a tweet; the `mastodon` and `bluesky`
+//! features add `Mastodon` and `Bluesky`. [`CrossPost`] takes a composed [`Post`]
+//! and publishes it through each of them at once, fitting the text to each network's
+//! limit: when it is too long, the text is cut at a word with an ellipsis, and the
+//! link, which some networks count at a fixed length, is kept whole.
+//!
+//! ```rust
+//! use tweety_rs::api::crosspost::Post;
+//! use tweety_rs::TweetyClient;
+//!
+//! let client = TweetyClient::from_bearer_token("token");
+//! let post = Post::new("Release notes: ".repeat(20)).link("https://crates.io/crates/tweety-rs");
+//! let tweet = post.fit_to(&client);
+//! assert!(tweet.ends_with("Release notes… https://crates.io/crates/tweety-rs"));
+//! ```
+//!
+//! See `tweety_rs::api::mastodon` for publishing to X and Mastodon together.
+
+use crate::api::client::TweetyClient;
+use crate::api::compose::{self, weighted_length};
+use crate::api::error::TweetyError;
+use crate::api::mentions::tweet_url;
+use futures_util::future::{self, BoxFuture};
+use std::fmt;
+use std::sync::Arc;
+
+/// The longest tweet, as [`weighted_length`] counts it.
+pub const TWEET_MAX_LENGTH: usize = 280;
+
+/// How many characters X and Mastodon count a link as, whatever its length.
+pub const LINK_LENGTH: usize = 23;
+
+/// Where a post was published.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossPosted {
+    /// The id the network gave the post.
+    pub id: String,
+    /// A link to the post, when the network says where it is.
+    pub url: Option<String>,
+}
+
+/// Publishes text to one network.
+pub trait CrossPoster: Send + Sync {
+    /// A short name for the network, such as `"x"`, used in [`CrossPost::publish`]'s
+    /// results.
+    fn network(&self) -> &str;
+
+    /// The longest text the network accepts, as [`CrossPoster::length`] counts it.
+    fn max_length(&self) -> usize;
+
+    /// How long the network counts `text`. Defaults to its characters.
+    fn length(&self, text: &str) -> usize {
+        text.chars().count()
+    }
+
+    /// Publishes `text`, which is already fitted to [`CrossPoster::max_length`].
+    fn publish<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<CrossPosted, TweetyError>>;
+}
+
+/// Posts tweets, counting their length with [`weighted_length`] like X does.
+impl CrossPoster for TweetyClient {
+    fn network(&self) -> &str {
+        "x"
+    }
+
+    fn max_length(&self) -> usize {
+        TWEET_MAX_LENGTH
+    }
+
+    fn length(&self, text: &str) -> usize {
+        weighted_length(text)
+    }
+
+    fn publish<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<CrossPosted, TweetyError>> {
+        Box::pin(async move {
+            let posted = self.post_tweet(text, None).await?;
+            Ok(CrossPosted {
+                url: Some(tweet_url(&posted.data.id)),
+                id: posted.data.id,
+            })
+        })
+    }
+}
+
+/// Text to publish, with an optional link placed after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Post {
+    pub text: String,
+    pub link: Option<String>,
+}
+
+impl Post {
+    pub fn new(text: impl Into<String>) -> Self {
+        Post {
+            text: text.into(),
+            link: None,
+        }
+    }
+
+    /// Places `url` after the text. It is never cut when the text is.
+    pub fn link(mut self, url: impl Into<String>) -> Self {
+        self.link = Some(url.into());
+        self
+    }
+
+    /// The text and link as `poster` publishes them. When they are longer than its
+    /// [`max_length`](CrossPoster::max_length), the text is cut after the last word
+    /// that fits and ends with an ellipsis; a single word too long to fit is cut
+    /// mid-word, but never inside an emoji, see [`text`](crate::api::text).
+    pub fn fit_to(&self, poster: &dyn CrossPoster) -> String {
+        let max_length = poster.max_length();
+        let full = self.join(&self.text);
+        if poster.length(&full) <= max_length {
+            return full;
+        }
+
+        compose::cut_at_word(&self.text, |cut| {
+            poster.length(&self.join(cut)) <= max_length
+        })
+        .map(|cut| self.join(&cut))
+        .unwrap_or_else(|| self.join(""))
+    }
+
+    fn join(&self, text: &str) -> String {
+        match (&self.link, text.is_empty()) {
+            (Some(link), true) => link.clone(),
+            (Some(link), false) => format!("{} {}", text.trim_end(), link),
+            (None, _) => text.to_string(),
+        }
+    }
+}
+
+/// Publishes a [`Post`] through several [`CrossPoster`]s at once.
+#[derive(Clone, Default)]
+pub struct CrossPost {
+    posters: Vec<Arc<dyn CrossPoster>>,
+}
+
+impl fmt::Debug for CrossPost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let networks: Vec<&str> = self.posters.iter().map(|poster| poster.network()).collect();
+        f.debug_struct("CrossPost")
+            .field("networks", &networks)
+            .finish()
+    }
+}
+
+impl CrossPost {
+    pub fn new() -> Self {
+        CrossPost::default()
+    }
+
+    /// Publishes through `poster` too.
+    pub fn to(mut self, poster: impl CrossPoster + 'static) -> Self {
+        self.posters.push(Arc::new(poster));
+        self
+    }
+
+    /// Publishes `post` through every poster at once, each fitted with
+    /// [`Post::fit_to`]. Returns each network's name and result, in the order the
+    /// posters were added; one failing doesn't stop the others.
+    pub async fn publish(&self, post: &Post) -> Vec<(String, Result<CrossPosted, TweetyError>)> {
+        let texts: Vec<String> = self
+            .posters
+            .iter()
+            .map(|poster| post.fit_to(poster.as_ref()))
+            .collect();
+        let results = future::join_all(
+            self.posters
+                .iter()
+                .zip(&texts)
+                .map(|(poster, text)| poster.publish(text)),
+        )
+        .await;
+
+        self.posters
+            .iter()
+            .map(|poster| poster.network().to_string())
+            .zip(results)
+            .collect()
+    }
+}
@@ -0,0 +1,147 @@
+//! Dropping tweets that were already delivered.
+//!
+//! Overlapping search windows, a stream reconnect that backfills the minutes it missed,
+//! or a page that shifted while it was being read all hand out some tweets twice.
+//! [`SeenIds`] remembers the most recent ids in a fixed-size ring buffer, so memory stays
+//! bounded however long the consumer runs.
+//!
+//! ```
+//! use tweety_rs::api::dedup::SeenIds;
+//!
+//! let mut seen = SeenIds::new(2);
+//! assert!(seen.insert("1"));
+//! assert!(!seen.insert("1"));
+//!
+//! seen.insert("2");
+//! seen.insert("3");
+//! // "1" was pushed out of the buffer.
+//! assert!(seen.insert("1"));
+//! ```
+
+use crate::api::error::TweetyError;
+use crate::types::borrowed::{StreamTweetRef, TweetRef};
+use futures_util::future;
+use futures_util::stream::{Stream, StreamExt};
+use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
+
+/// Ids [`SeenIds::default`] remembers.
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// The last `capacity` ids seen, oldest forgotten first.
+#[derive(Debug, Clone)]
+pub struct SeenIds {
+    capacity: usize,
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl Default for SeenIds {
+    fn default() -> Self {
+        SeenIds::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl SeenIds {
+    pub fn new(capacity: usize) -> Self {
+        SeenIds {
+            capacity,
+            ids: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Records `id`; `false` if it is still remembered from before.
+    pub fn insert(&mut self, id: &str) -> bool {
+        if !self.ids.insert(id.to_string()) {
+            return false;
+        }
+
+        self.order.push_back(id.to_string());
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.ids.contains(id)
+    }
+
+    /// Removes the items of a page that were seen before, and records the rest. Items
+    /// without an id are kept.
+    pub fn retain_new<T: TweetIdentity>(&mut self, items: &mut Vec<T>) {
+        items.retain(|item| item.tweet_id().is_none_or(|id| self.insert(id)));
+    }
+
+    /// The ids remembered, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.order.iter().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
+
+/// Something carrying the id of a tweet.
+pub trait TweetIdentity {
+    fn tweet_id(&self) -> Option<&str>;
+}
+
+impl TweetIdentity for crate::api::mentions::TweetData {
+    fn tweet_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
+
+impl TweetIdentity for crate::api::search::TweetData {
+    fn tweet_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
+
+impl TweetIdentity for TweetRef<'_> {
+    fn tweet_id(&self) -> Option<&str> {
+        Some(&self.id)
+    }
+}
+
+impl TweetIdentity for StreamTweetRef<'_> {
+    fn tweet_id(&self) -> Option<&str> {
+        Some(&self.data.id)
+    }
+}
+
+/// A raw tweet, or a stream message with the tweet under `data`.
+impl TweetIdentity for Value {
+    fn tweet_id(&self) -> Option<&str> {
+        self.get("data")
+            .filter(|data| data.is_object())
+            .unwrap_or(self)
+            .get("id")?
+            .as_str()
+    }
+}
+
+/// Passes on the tweets of `stream` whose id isn't in `seen` yet. Errors and items
+/// without an id always pass.
+pub fn dedupe<S, T>(stream: S, mut seen: SeenIds) -> impl Stream<Item = Result<T, TweetyError>>
+where
+    S: Stream<Item = Result<T, TweetyError>>,
+    T: TweetIdentity,
+{
+    stream.filter(move |item| {
+        let keep = match item {
+            Ok(item) => item.tweet_id().is_none_or(|id| seen.insert(id)),
+            Err(_) => true,
+        };
+        future::ready(keep)
+    })
+}
@@ -1,10 +1,16 @@
 use super::error::TweetyError;
+use crate::api::endpoint::Endpoint;
+use crate::api::query::{field_names, Query};
+pub use crate::types::dm::{DmAttachments, DmEvent, EventType, ReferencedTweet};
+use crate::types::ids::UserId;
+use crate::types::meta::{Meta, Paginated};
 use crate::TweetyClient;
-use reqwest::Method;
+use futures_util::stream::{self, Stream};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::VecDeque;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DMEventField {
     Id,
@@ -18,24 +24,18 @@ pub enum DMEventField {
     Attachments,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum EventType {
-    MessageCreate,
-    ParticipantsJoin,
-    ParticipantsLeave,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Expansion {
+    #[serde(rename = "attachments.media_keys")]
     AttachmentsMediaKeys,
+    #[serde(rename = "referenced_tweets.id")]
     ReferencedTweetsId,
     SenderId,
     ParticipantIds,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MediaField {
     DurationMs,
@@ -50,7 +50,7 @@ pub enum MediaField {
     Variants,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum TweetField {
     Attachments,
@@ -64,7 +64,11 @@ pub enum TweetField {
     Id,
     InReplyToUserId,
     Lang,
+    NonPublicMetrics,
+    NoteTweet,
     PublicMetrics,
+    OrganicMetrics,
+    PromotedMetrics,
     PossiblySensitive,
     ReferencedTweets,
     ReplySettings,
@@ -73,7 +77,7 @@ pub enum TweetField {
     Withheld,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum UserField {
     CreatedAt,
@@ -93,11 +97,21 @@ pub enum UserField {
     Withheld,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+field_names!(
+    DMEventField,
+    EventType,
+    Expansion,
+    MediaField,
+    TweetField,
+    UserField
+);
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct QueryParams {
     #[serde(rename = "dm_event.fields")]
     pub dm_event_fields: Option<Vec<DMEventField>>,
 
+    /// Only return events of these types. All types are returned when unset.
     #[serde(rename = "event_types")]
     pub event_types: Option<Vec<EventType>>,
 
@@ -126,71 +140,159 @@ pub struct QueryParams {
 ///
 impl QueryParams {
     pub fn to_query_string(&self) -> String {
-        let mut params = vec![];
-
-        if let Some(ref fields) = self.dm_event_fields {
-            let fields_str = fields
-                .iter()
-                .map(|f| serde_json::to_string(f).unwrap())
-                .collect::<Vec<_>>()
-                .join(",");
-            params.push(format!("dm_event.fields={}", fields_str));
-        }
+        self.to_query().to_query_string()
+    }
 
-        if let Some(ref types) = self.event_types {
-            let types_str = types
-                .iter()
-                .map(|t| serde_json::to_string(t).unwrap())
-                .collect::<Vec<_>>()
-                .join(",");
-            params.push(format!("event_types={}", types_str));
-        }
+    pub(crate) fn to_query(&self) -> Query {
+        let mut query = Query::new();
+        query
+            .push_fields(
+                "dm_event.fields",
+                self.dm_event_fields.as_deref().unwrap_or_default(),
+            )
+            .push_fields(
+                "event_types",
+                self.event_types.as_deref().unwrap_or_default(),
+            )
+            .push_fields("expansions", self.expansions.as_deref().unwrap_or_default())
+            .push_opt("max_results", self.max_results)
+            .push_fields(
+                "media.fields",
+                self.media_fields.as_deref().unwrap_or_default(),
+            )
+            .push_opt("pagination_token", self.pagination_token.as_ref())
+            .push_fields(
+                "tweet.fields",
+                self.tweet_fields.as_deref().unwrap_or_default(),
+            )
+            .push_fields(
+                "user.fields",
+                self.user_fields.as_deref().unwrap_or_default(),
+            );
+        query
+    }
+}
 
-        if let Some(ref exps) = self.expansions {
-            let exps_str = exps
-                .iter()
-                .map(|e| serde_json::to_string(e).unwrap())
-                .collect::<Vec<_>>()
-                .join(",");
-            params.push(format!("expansions={}", exps_str));
-        }
+impl Paginated for QueryParams {
+    fn set_page_token(&mut self, token: String) {
+        self.pagination_token = Some(token);
+    }
+}
 
-        if let Some(max_results) = self.max_results {
-            params.push(format!("max_results={}", max_results));
-        }
+/// One page of DM events.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DmEventsResponse {
+    /// Absent from the response when the page has no events.
+    #[serde(default)]
+    pub data: Vec<DmEvent>,
+    /// The objects asked for with `expansions`, as returned.
+    pub includes: Option<Value>,
+    pub meta: Meta,
+}
 
-        if let Some(ref media_fields) = self.media_fields {
-            let media_str = media_fields
-                .iter()
-                .map(|m| serde_json::to_string(m).unwrap())
-                .collect::<Vec<_>>()
-                .join(",");
-            params.push(format!("media.fields={}", media_str));
-        }
+/// Walks every page of a DM events endpoint, newest events first, passing each page's
+/// `next_token` back as `pagination_token`.
+///
+/// Pages are fetched as fast as the endpoint's rate limit allows: once the `x-rate-limit`
+/// headers show the window running low, fetches are spaced out, and an exhausted window
+/// is waited out instead of failing with a 429, see [`RateLimitStatus::pace`]. Turn it
+/// off with [`DmEventPaginator::pacing`].
+///
+/// ```rust,no_run
+/// use futures_util::StreamExt;
+/// use tweety_rs::api::direct_messages::{EventType, QueryParams};
+/// use tweety_rs::TweetyClient;
+///
+/// # async fn run(client: TweetyClient) {
+/// let params = QueryParams {
+///     event_types: Some(vec![EventType::MessageCreate]),
+///     max_results: Some(100),
+///     ..QueryParams::default()
+/// };
+///
+/// let mut events = Box::pin(client.paginate_dm_conversation("123-456", params).events());
+/// while let Some(event) = events.next().await {
+///     match event {
+///         Ok(event) => println!("{}: {:?}", event.id, event.text),
+///         Err(err) => eprintln!("stopped: {}", err),
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [`RateLimitStatus::pace`]: crate::api::rate_limit::RateLimitStatus::pace
+#[derive(Debug, Clone)]
+pub struct DmEventPaginator {
+    client: TweetyClient,
+    endpoint: Endpoint,
+    params: QueryParams,
+    paced: bool,
+    done: bool,
+}
 
-        if let Some(ref token) = self.pagination_token {
-            params.push(format!("pagination_token={}", token));
+impl DmEventPaginator {
+    fn new(client: &TweetyClient, endpoint: Endpoint, params: QueryParams) -> Self {
+        DmEventPaginator {
+            client: client.clone(),
+            endpoint,
+            params,
+            paced: true,
+            done: false,
         }
+    }
 
-        if let Some(ref tweet_fields) = self.tweet_fields {
-            let tweet_str = tweet_fields
-                .iter()
-                .map(|t| serde_json::to_string(t).unwrap())
-                .collect::<Vec<_>>()
-                .join(",");
-            params.push(format!("tweet.fields={}", tweet_str));
+    /// Whether to wait for the rate limit between pages. On by default.
+    pub fn pacing(mut self, paced: bool) -> Self {
+        self.paced = paced;
+        self
+    }
+
+    /// Fetches the next page, or `None` once the last one has been read.
+    pub async fn next_page(&mut self) -> Result<Option<DmEventsResponse>, TweetyError> {
+        if self.done {
+            return Ok(None);
+        }
+        let family = self.endpoint.rate_limit_family();
+        if let Some(status) = self.client.rate_limit(family).filter(|_| self.paced) {
+            self.client
+                .wait_for_rate_limit(Some(family), None, status.pace())
+                .await;
         }
 
-        if let Some(ref user_fields) = self.user_fields {
-            let user_str = user_fields
-                .iter()
-                .map(|u| serde_json::to_string(u).unwrap())
-                .collect::<Vec<_>>()
-                .join(",");
-            params.push(format!("user.fields={}", user_str));
+        let value = self
+            .client
+            .send_request::<()>(&self.endpoint, &self.params.to_query(), None)
+            .await?;
+        let page = serde_json::from_value::<DmEventsResponse>(value)
+            .map_err(TweetyError::JsonParseError)?;
+
+        match page.meta.next_token {
+            Some(ref token) => self.params.pagination_token = Some(token.clone()),
+            None => self.done = true,
         }
+        Ok(Some(page))
+    }
+
+    /// Every event of every remaining page. The stream ends after the first error,
+    /// which is yielded.
+    pub fn events(self) -> impl Stream<Item = Result<DmEvent, TweetyError>> + Send + 'static {
+        let state = (self, VecDeque::new());
 
-        params.join("&")
+        stream::unfold(state, |(mut pages, mut ready)| async move {
+            while ready.is_empty() {
+                match pages.next_page().await {
+                    Ok(Some(page)) => ready.extend(page.data),
+                    Ok(None) => return None,
+                    Err(err) => {
+                        pages.done = true;
+                        return Some((Err(err), (pages, ready)));
+                    }
+                }
+            }
+
+            let event = ready.pop_front()?;
+            Some((Ok(event), (pages, ready)))
+        })
     }
 }
 
@@ -201,9 +303,8 @@ impl TweetyClient {
     /// Authentication methods supported by this endpoint
     // OAuth 2.0 Authorization Code with PKCE
     pub async fn get_direct_messages(&self, params: QueryParams) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/dm_events?{}", params.to_query_string());
-        println!("Url: {}", url);
-        self.send_request::<()>(&url, Method::GET, None).await
+        self.send_request::<()>(&Endpoint::DmEvents, &params.to_query(), None)
+            .await
     }
     /// GET /2/dm_conversations/with/:participant_id/dm_events
     //Returns a list of Direct Messages (DM) events within a 1-1 conversation
@@ -211,16 +312,14 @@ impl TweetyClient {
     /// Messages are returned in reverse chronological order.
     pub async fn get_dm_events_with_participant(
         &self,
-        participant_id: &str,
+        participant_id: impl Into<UserId>,
         params: QueryParams,
     ) -> Result<Value, TweetyError> {
-        let url = format!(
-            "https://api.x.com/2/dm_conversations/with/{}/dm_events?{}",
-            participant_id,
-            params.to_query_string()
-        );
+        let participant_id: UserId = participant_id.into();
+        let endpoint = Endpoint::DmEventsWithParticipant(participant_id);
 
-        self.send_request::<()>(&url, Method::GET, None).await
+        self.send_request::<()>(&endpoint, &params.to_query(), None)
+            .await
     }
     /// Returns a list of Direct Messages within a conversation specified in the dm_conversation_id path parameter.
     /// Messages are returned in reverse chronological order.
@@ -229,12 +328,34 @@ impl TweetyClient {
         dm_conversation_id: &str,
         params: QueryParams,
     ) -> Result<Value, TweetyError> {
-        let url = format!(
-            "https://api.x.com/2/dm_conversations/{}/dm_events?{}",
-            dm_conversation_id,
-            params.to_query_string()
-        );
+        let endpoint = Endpoint::DmConversationEvents(dm_conversation_id.to_string());
+
+        self.send_request::<()>(&endpoint, &params.to_query(), None)
+            .await
+    }
+
+    /// Pages through the authenticated user's DM events, see [`DmEventPaginator`].
+    pub fn paginate_direct_messages(&self, params: QueryParams) -> DmEventPaginator {
+        DmEventPaginator::new(self, Endpoint::DmEvents, params)
+    }
+
+    /// Pages through the 1-1 conversation with `participant_id`, see [`DmEventPaginator`].
+    pub fn paginate_dm_events_with_participant(
+        &self,
+        participant_id: impl Into<UserId>,
+        params: QueryParams,
+    ) -> DmEventPaginator {
+        let endpoint = Endpoint::DmEventsWithParticipant(participant_id.into());
+        DmEventPaginator::new(self, endpoint, params)
+    }
 
-        self.send_request::<()>(&url, Method::GET, None).await
+    /// Pages through the conversation `dm_conversation_id`, see [`DmEventPaginator`].
+    pub fn paginate_dm_conversation(
+        &self,
+        dm_conversation_id: &str,
+        params: QueryParams,
+    ) -> DmEventPaginator {
+        let endpoint = Endpoint::DmConversationEvents(dm_conversation_id.to_string());
+        DmEventPaginator::new(self, endpoint, params)
     }
 }
@@ -0,0 +1,532 @@
+//! Every X API endpoint the client calls, in one table.
+//!
+//! An [`Endpoint`] knows its HTTP method, path template, host, the authentication it
+//! accepts and the rate-limit family it counts against, so URLs are built in one place
+//! and a client pointed at another base URL (see [`TweetyClient::with_base_url`]) sends
+//! every request there.
+//!
+//! ```
+//! use reqwest::Method;
+//! use tweety_rs::api::endpoint::Endpoint;
+//!
+//! let endpoint = Endpoint::Followers("2244994945".into());
+//! assert_eq!(endpoint.method(), Method::GET);
+//! assert_eq!(endpoint.path_template(), "/2/users/:id/followers");
+//! assert_eq!(endpoint.path(), "/2/users/2244994945/followers");
+//! ```
+//!
+//! [`TweetyClient::with_base_url`]: crate::api::client::TweetyClient::with_base_url
+
+use crate::api::client::AuthMode;
+use crate::api::oauth2::Scope;
+use crate::api::tier::Tier;
+use crate::types::ids::{ListId, TweetId, UserId};
+use reqwest::Method;
+
+/// Host of the v2 and v1.1 REST endpoints.
+pub const API_BASE_URL: &str = "https://api.x.com";
+/// Host of the v1.1 chunked media upload endpoint.
+pub const UPLOAD_BASE_URL: &str = "https://upload.twitter.com";
+/// Host of the Ads API.
+pub const ADS_BASE_URL: &str = "https://ads-api.twitter.com";
+
+/// Which host an [`Endpoint`] lives on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Host {
+    Api,
+    Upload,
+    Ads,
+}
+
+impl Host {
+    pub fn base_url(self) -> &'static str {
+        match self {
+            Host::Api => API_BASE_URL,
+            Host::Upload => UPLOAD_BASE_URL,
+            Host::Ads => ADS_BASE_URL,
+        }
+    }
+}
+
+/// The authentication an [`Endpoint`] accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointAuth {
+    /// Acting for a user: OAuth 1.0a or an OAuth 2.0 user token.
+    UserContext,
+    /// An app-only bearer token.
+    AppOnly,
+    /// User context or app-only.
+    Any,
+}
+
+impl EndpointAuth {
+    /// Whether a client authenticating with `mode` can call the endpoint.
+    pub fn allows(self, mode: AuthMode) -> bool {
+        match self {
+            EndpointAuth::Any => true,
+            EndpointAuth::UserContext => mode != AuthMode::AppOnly,
+            EndpointAuth::AppOnly => mode == AuthMode::AppOnly,
+        }
+    }
+}
+
+/// Endpoints X counts against the same rate-limit window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RateLimitFamily {
+    TweetLookup,
+    ManageTweets,
+    HideReplies,
+    RecentSearch,
+    FullArchiveSearch,
+    FilteredStream,
+    Compliance,
+    Timelines,
+    Retweets,
+    Likes,
+    Bookmarks,
+    Follows,
+    Blocks,
+    Mutes,
+    UserLookup,
+    Communities,
+    DirectMessages,
+    Lists,
+    Spaces,
+    Usage,
+    MediaUpload,
+    Account,
+    Geo,
+    Help,
+    Application,
+    Ads,
+}
+
+/// An X API endpoint together with the values for its path parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Endpoint {
+    /// `GET /2/tweets`
+    Tweets,
+    /// `GET /2/tweets/:id`
+    Tweet(TweetId),
+    /// `POST /2/tweets`
+    CreateTweet,
+    /// `DELETE /2/tweets/:id`
+    DeleteTweet(TweetId),
+    /// `PUT /2/tweets/:id/hidden`
+    HideReply(TweetId),
+    /// `GET /2/tweets/search/recent`
+    RecentSearch,
+    /// `GET /2/tweets/search/all`
+    FullArchiveSearch,
+    /// `GET /2/tweets/search/stream`
+    FilteredStream,
+    /// `GET /2/tweets/compliance/stream`
+    TweetsComplianceStream,
+    /// `GET /2/users/compliance/stream`
+    UsersComplianceStream,
+    /// `GET /2/likes/compliance/stream`
+    LikesComplianceStream,
+    /// `GET /2/users/:id/mentions`
+    UserMentions(UserId),
+    /// `GET /2/users/:id/tweets`
+    UserTweets(UserId),
+    /// `GET /2/tweets/:id/retweeted_by`
+    RetweetedBy(TweetId),
+    /// `GET /2/tweets/:id/retweets`
+    Retweets(TweetId),
+    /// `POST /2/users/:id/retweets`
+    Retweet(UserId),
+    /// `DELETE /2/users/:id/retweets/:source_tweet_id`
+    Unretweet { user_id: UserId, tweet_id: TweetId },
+    /// `GET /2/tweets/:id/liking_users`
+    LikingUsers(TweetId),
+    /// `GET /2/users/:id/liked_tweets`
+    LikedTweets(UserId),
+    /// `POST /2/users/:id/likes`
+    Like(UserId),
+    /// `DELETE /2/users/:id/likes/:tweet_id`
+    Unlike { user_id: UserId, tweet_id: TweetId },
+    /// `GET /2/users/:id/bookmarks`
+    Bookmarks(UserId),
+    /// `POST /2/users/:id/bookmarks`
+    Bookmark(UserId),
+    /// `DELETE /2/users/:id/bookmarks/:tweet_id`
+    RemoveBookmark { user_id: UserId, tweet_id: TweetId },
+    /// `GET /2/users/:id/following`
+    Following(UserId),
+    /// `POST /2/users/:id/following`
+    Follow(UserId),
+    /// `DELETE /2/users/:source_user_id/following/:target_user_id`
+    Unfollow {
+        user_id: UserId,
+        target_user_id: UserId,
+    },
+    /// `GET /2/users/:id/followers`
+    Followers(UserId),
+    /// `GET /2/users/:id/blocking`
+    Blocking(UserId),
+    /// `POST /2/users/:id/blocking`
+    Block(UserId),
+    /// `DELETE /2/users/:source_user_id/blocking/:target_user_id`
+    Unblock {
+        user_id: UserId,
+        target_user_id: UserId,
+    },
+    /// `GET /2/users/:id/muting`
+    Muting(UserId),
+    /// `POST /2/users/:id/muting`
+    Mute(UserId),
+    /// `DELETE /2/users/:source_user_id/muting/:target_user_id`
+    Unmute {
+        user_id: UserId,
+        target_user_id: UserId,
+    },
+    /// `GET /2/users/:id`
+    User(UserId),
+    /// `GET /2/users`
+    Users,
+    /// `GET /2/users/by/username/:username`
+    UserByUsername(String),
+    /// `GET /2/users/by`
+    UsersByUsernames,
+    /// `GET /2/users/me`
+    Me,
+    /// `GET /2/communities/:id`
+    Community(String),
+    /// `GET /2/communities/search`
+    SearchCommunities,
+    /// `GET /2/dm_events`
+    DmEvents,
+    /// `GET /2/dm_conversations/with/:participant_id/dm_events`
+    DmEventsWithParticipant(UserId),
+    /// `GET /2/dm_conversations/:dm_conversation_id/dm_events`
+    DmConversationEvents(String),
+    /// `GET /2/lists/:id/followers`
+    ListFollowers(ListId),
+    /// `GET /2/lists/:id/members`
+    ListMembers(ListId),
+    /// `GET /2/spaces/by/creator_ids`
+    SpacesByCreatorIds,
+    /// `GET /2/usage/tweets`
+    TweetUsage,
+    /// `POST /1.1/media/upload.json`, for the `INIT`, `APPEND` and `FINALIZE` commands
+    MediaUpload,
+    /// `GET /1.1/media/upload.json?command=STATUS`
+    MediaUploadStatus,
+    /// `GET /1.1/account/verify_credentials.json`
+    VerifyCredentials,
+    /// `POST /1.1/account/update_profile.json`
+    UpdateProfile,
+    /// `POST /1.1/account/update_profile_image.json`
+    UpdateProfileImage,
+    /// `POST /1.1/account/update_profile_banner.json`
+    UpdateProfileBanner,
+    /// `POST /1.1/account/pin_tweet.json`
+    PinTweet,
+    /// `POST /1.1/account/unpin_tweet.json`
+    UnpinTweet,
+    /// `GET /1.1/geo/search.json`
+    GeoSearch,
+    /// `GET /1.1/help/languages.json`
+    HelpLanguages,
+    /// `GET /1.1/application/rate_limit_status.json`
+    RateLimitStatus,
+    /// `GET /12/accounts` on the Ads API
+    AdAccounts,
+    /// `GET /12/accounts/:account_id/campaigns` on the Ads API
+    AdCampaigns(String),
+    /// `GET /12/accounts/:account_id/line_items` on the Ads API
+    AdLineItems(String),
+}
+
+impl Endpoint {
+    pub fn method(&self) -> Method {
+        match self {
+            Endpoint::CreateTweet
+            | Endpoint::Retweet(_)
+            | Endpoint::Like(_)
+            | Endpoint::Bookmark(_)
+            | Endpoint::Follow(_)
+            | Endpoint::Block(_)
+            | Endpoint::Mute(_)
+            | Endpoint::MediaUpload
+            | Endpoint::UpdateProfile
+            | Endpoint::UpdateProfileImage
+            | Endpoint::UpdateProfileBanner
+            | Endpoint::PinTweet
+            | Endpoint::UnpinTweet => Method::POST,
+            Endpoint::HideReply(_) => Method::PUT,
+            Endpoint::DeleteTweet(_)
+            | Endpoint::Unretweet { .. }
+            | Endpoint::Unlike { .. }
+            | Endpoint::RemoveBookmark { .. }
+            | Endpoint::Unfollow { .. }
+            | Endpoint::Unblock { .. }
+            | Endpoint::Unmute { .. } => Method::DELETE,
+            _ => Method::GET,
+        }
+    }
+
+    /// The path with its parameters as `:name` placeholders, as in the X API reference.
+    pub fn path_template(&self) -> &'static str {
+        match self {
+            Endpoint::Tweets | Endpoint::CreateTweet => "/2/tweets",
+            Endpoint::Tweet(_) | Endpoint::DeleteTweet(_) => "/2/tweets/:id",
+            Endpoint::HideReply(_) => "/2/tweets/:id/hidden",
+            Endpoint::RecentSearch => "/2/tweets/search/recent",
+            Endpoint::FullArchiveSearch => "/2/tweets/search/all",
+            Endpoint::FilteredStream => "/2/tweets/search/stream",
+            Endpoint::TweetsComplianceStream => "/2/tweets/compliance/stream",
+            Endpoint::UsersComplianceStream => "/2/users/compliance/stream",
+            Endpoint::LikesComplianceStream => "/2/likes/compliance/stream",
+            Endpoint::UserMentions(_) => "/2/users/:id/mentions",
+            Endpoint::UserTweets(_) => "/2/users/:id/tweets",
+            Endpoint::RetweetedBy(_) => "/2/tweets/:id/retweeted_by",
+            Endpoint::Retweets(_) => "/2/tweets/:id/retweets",
+            Endpoint::Retweet(_) => "/2/users/:id/retweets",
+            Endpoint::Unretweet { .. } => "/2/users/:id/retweets/:source_tweet_id",
+            Endpoint::LikingUsers(_) => "/2/tweets/:id/liking_users",
+            Endpoint::LikedTweets(_) => "/2/users/:id/liked_tweets",
+            Endpoint::Like(_) => "/2/users/:id/likes",
+            Endpoint::Unlike { .. } => "/2/users/:id/likes/:tweet_id",
+            Endpoint::Bookmarks(_) | Endpoint::Bookmark(_) => "/2/users/:id/bookmarks",
+            Endpoint::RemoveBookmark { .. } => "/2/users/:id/bookmarks/:tweet_id",
+            Endpoint::Following(_) | Endpoint::Follow(_) => "/2/users/:id/following",
+            Endpoint::Unfollow { .. } => "/2/users/:source_user_id/following/:target_user_id",
+            Endpoint::Followers(_) => "/2/users/:id/followers",
+            Endpoint::Blocking(_) | Endpoint::Block(_) => "/2/users/:id/blocking",
+            Endpoint::Unblock { .. } => "/2/users/:source_user_id/blocking/:target_user_id",
+            Endpoint::Muting(_) | Endpoint::Mute(_) => "/2/users/:id/muting",
+            Endpoint::Unmute { .. } => "/2/users/:source_user_id/muting/:target_user_id",
+            Endpoint::User(_) => "/2/users/:id",
+            Endpoint::Users => "/2/users",
+            Endpoint::UserByUsername(_) => "/2/users/by/username/:username",
+            Endpoint::UsersByUsernames => "/2/users/by",
+            Endpoint::Me => "/2/users/me",
+            Endpoint::Community(_) => "/2/communities/:id",
+            Endpoint::SearchCommunities => "/2/communities/search",
+            Endpoint::DmEvents => "/2/dm_events",
+            Endpoint::DmEventsWithParticipant(_) => {
+                "/2/dm_conversations/with/:participant_id/dm_events"
+            }
+            Endpoint::DmConversationEvents(_) => {
+                "/2/dm_conversations/:dm_conversation_id/dm_events"
+            }
+            Endpoint::ListFollowers(_) => "/2/lists/:id/followers",
+            Endpoint::ListMembers(_) => "/2/lists/:id/members",
+            Endpoint::SpacesByCreatorIds => "/2/spaces/by/creator_ids",
+            Endpoint::TweetUsage => "/2/usage/tweets",
+            Endpoint::MediaUpload | Endpoint::MediaUploadStatus => "/1.1/media/upload.json",
+            Endpoint::VerifyCredentials => "/1.1/account/verify_credentials.json",
+            Endpoint::UpdateProfile => "/1.1/account/update_profile.json",
+            Endpoint::UpdateProfileImage => "/1.1/account/update_profile_image.json",
+            Endpoint::UpdateProfileBanner => "/1.1/account/update_profile_banner.json",
+            Endpoint::PinTweet => "/1.1/account/pin_tweet.json",
+            Endpoint::UnpinTweet => "/1.1/account/unpin_tweet.json",
+            Endpoint::GeoSearch => "/1.1/geo/search.json",
+            Endpoint::HelpLanguages => "/1.1/help/languages.json",
+            Endpoint::RateLimitStatus => "/1.1/application/rate_limit_status.json",
+            Endpoint::AdAccounts => "/12/accounts",
+            Endpoint::AdCampaigns(_) => "/12/accounts/:account_id/campaigns",
+            Endpoint::AdLineItems(_) => "/12/accounts/:account_id/line_items",
+        }
+    }
+
+    /// The path with its parameters filled in.
+    pub fn path(&self) -> String {
+        let params: Vec<&str> = match self {
+            Endpoint::Tweet(id)
+            | Endpoint::DeleteTweet(id)
+            | Endpoint::HideReply(id)
+            | Endpoint::RetweetedBy(id)
+            | Endpoint::Retweets(id)
+            | Endpoint::LikingUsers(id) => vec![id.as_ref()],
+            Endpoint::UserMentions(id)
+            | Endpoint::UserTweets(id)
+            | Endpoint::Retweet(id)
+            | Endpoint::LikedTweets(id)
+            | Endpoint::Like(id)
+            | Endpoint::Bookmarks(id)
+            | Endpoint::Bookmark(id)
+            | Endpoint::Following(id)
+            | Endpoint::Follow(id)
+            | Endpoint::Followers(id)
+            | Endpoint::Blocking(id)
+            | Endpoint::Block(id)
+            | Endpoint::Muting(id)
+            | Endpoint::Mute(id)
+            | Endpoint::User(id)
+            | Endpoint::DmEventsWithParticipant(id) => vec![id.as_ref()],
+            Endpoint::Unretweet { user_id, tweet_id }
+            | Endpoint::Unlike { user_id, tweet_id }
+            | Endpoint::RemoveBookmark { user_id, tweet_id } => {
+                vec![user_id.as_ref(), tweet_id.as_ref()]
+            }
+            Endpoint::Unfollow {
+                user_id,
+                target_user_id,
+            }
+            | Endpoint::Unblock {
+                user_id,
+                target_user_id,
+            }
+            | Endpoint::Unmute {
+                user_id,
+                target_user_id,
+            } => vec![user_id.as_ref(), target_user_id.as_ref()],
+            Endpoint::UserByUsername(value)
+            | Endpoint::Community(value)
+            | Endpoint::DmConversationEvents(value)
+            | Endpoint::AdCampaigns(value)
+            | Endpoint::AdLineItems(value) => vec![value.as_str()],
+            Endpoint::ListFollowers(id) | Endpoint::ListMembers(id) => vec![id.as_ref()],
+            _ => Vec::new(),
+        };
+
+        let mut params = params.into_iter();
+        self.path_template()
+            .split('/')
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(_) => params.next().unwrap_or_default(),
+                None => segment,
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    pub fn host(&self) -> Host {
+        match self {
+            Endpoint::MediaUpload | Endpoint::MediaUploadStatus => Host::Upload,
+            Endpoint::AdAccounts | Endpoint::AdCampaigns(_) | Endpoint::AdLineItems(_) => Host::Ads,
+            _ => Host::Api,
+        }
+    }
+
+    /// The full URL on the endpoint's X host.
+    pub fn url(&self) -> String {
+        format!("{}{}", self.host().base_url(), self.path())
+    }
+
+    /// The authentication the endpoint accepts.
+    pub fn required_auth(&self) -> EndpointAuth {
+        match self {
+            Endpoint::Tweets
+            | Endpoint::Tweet(_)
+            | Endpoint::RecentSearch
+            | Endpoint::UserMentions(_)
+            | Endpoint::UserTweets(_)
+            | Endpoint::RetweetedBy(_)
+            | Endpoint::LikingUsers(_)
+            | Endpoint::LikedTweets(_)
+            | Endpoint::Following(_)
+            | Endpoint::Followers(_)
+            | Endpoint::User(_)
+            | Endpoint::Users
+            | Endpoint::UserByUsername(_)
+            | Endpoint::UsersByUsernames
+            | Endpoint::Community(_)
+            | Endpoint::SearchCommunities
+            | Endpoint::ListFollowers(_)
+            | Endpoint::ListMembers(_)
+            | Endpoint::SpacesByCreatorIds
+            | Endpoint::HelpLanguages
+            | Endpoint::RateLimitStatus => EndpointAuth::Any,
+            Endpoint::FullArchiveSearch
+            | Endpoint::FilteredStream
+            | Endpoint::TweetsComplianceStream
+            | Endpoint::UsersComplianceStream
+            | Endpoint::LikesComplianceStream
+            | Endpoint::TweetUsage => EndpointAuth::AppOnly,
+            _ => EndpointAuth::UserContext,
+        }
+    }
+
+    pub fn rate_limit_family(&self) -> RateLimitFamily {
+        match self {
+            Endpoint::Tweets | Endpoint::Tweet(_) => RateLimitFamily::TweetLookup,
+            Endpoint::CreateTweet | Endpoint::DeleteTweet(_) => RateLimitFamily::ManageTweets,
+            Endpoint::HideReply(_) => RateLimitFamily::HideReplies,
+            Endpoint::RecentSearch => RateLimitFamily::RecentSearch,
+            Endpoint::FullArchiveSearch => RateLimitFamily::FullArchiveSearch,
+            Endpoint::FilteredStream => RateLimitFamily::FilteredStream,
+            Endpoint::TweetsComplianceStream
+            | Endpoint::UsersComplianceStream
+            | Endpoint::LikesComplianceStream => RateLimitFamily::Compliance,
+            Endpoint::UserMentions(_) | Endpoint::UserTweets(_) => RateLimitFamily::Timelines,
+            Endpoint::RetweetedBy(_)
+            | Endpoint::Retweets(_)
+            | Endpoint::Retweet(_)
+            | Endpoint::Unretweet { .. } => RateLimitFamily::Retweets,
+            Endpoint::LikingUsers(_)
+            | Endpoint::LikedTweets(_)
+            | Endpoint::Like(_)
+            | Endpoint::Unlike { .. } => RateLimitFamily::Likes,
+            Endpoint::Bookmarks(_) | Endpoint::Bookmark(_) | Endpoint::RemoveBookmark { .. } => {
+                RateLimitFamily::Bookmarks
+            }
+            Endpoint::Following(_)
+            | Endpoint::Follow(_)
+            | Endpoint::Unfollow { .. }
+            | Endpoint::Followers(_) => RateLimitFamily::Follows,
+            Endpoint::Blocking(_) | Endpoint::Block(_) | Endpoint::Unblock { .. } => {
+                RateLimitFamily::Blocks
+            }
+            Endpoint::Muting(_) | Endpoint::Mute(_) | Endpoint::Unmute { .. } => {
+                RateLimitFamily::Mutes
+            }
+            Endpoint::User(_)
+            | Endpoint::Users
+            | Endpoint::UserByUsername(_)
+            | Endpoint::UsersByUsernames
+            | Endpoint::Me => RateLimitFamily::UserLookup,
+            Endpoint::Community(_) | Endpoint::SearchCommunities => RateLimitFamily::Communities,
+            Endpoint::DmEvents
+            | Endpoint::DmEventsWithParticipant(_)
+            | Endpoint::DmConversationEvents(_) => RateLimitFamily::DirectMessages,
+            Endpoint::ListFollowers(_) | Endpoint::ListMembers(_) => RateLimitFamily::Lists,
+            Endpoint::SpacesByCreatorIds => RateLimitFamily::Spaces,
+            Endpoint::TweetUsage => RateLimitFamily::Usage,
+            Endpoint::MediaUpload | Endpoint::MediaUploadStatus => RateLimitFamily::MediaUpload,
+            Endpoint::VerifyCredentials
+            | Endpoint::UpdateProfile
+            | Endpoint::UpdateProfileImage
+            | Endpoint::UpdateProfileBanner
+            | Endpoint::PinTweet
+            | Endpoint::UnpinTweet => RateLimitFamily::Account,
+            Endpoint::GeoSearch => RateLimitFamily::Geo,
+            Endpoint::HelpLanguages => RateLimitFamily::Help,
+            Endpoint::RateLimitStatus => RateLimitFamily::Application,
+            Endpoint::AdAccounts | Endpoint::AdCampaigns(_) | Endpoint::AdLineItems(_) => {
+                RateLimitFamily::Ads
+            }
+        }
+    }
+
+    /// The smallest access tier that can call the endpoint.
+    pub fn minimum_tier(&self) -> Tier {
+        match self {
+            Endpoint::CreateTweet
+            | Endpoint::DeleteTweet(_)
+            | Endpoint::Me
+            | Endpoint::TweetUsage
+            | Endpoint::MediaUpload
+            | Endpoint::MediaUploadStatus => Tier::Free,
+            // Ads API access is approved per app, independent of the tier.
+            Endpoint::AdAccounts | Endpoint::AdCampaigns(_) | Endpoint::AdLineItems(_) => {
+                Tier::Free
+            }
+            Endpoint::FullArchiveSearch | Endpoint::FilteredStream => Tier::Pro,
+            Endpoint::TweetsComplianceStream
+            | Endpoint::UsersComplianceStream
+            | Endpoint::LikesComplianceStream => Tier::Enterprise,
+            _ => Tier::Basic,
+        }
+    }
+
+    /// The OAuth 2.0 scopes a user token needs for the endpoint, see [`Scope::required_for`].
+    pub fn scopes(&self) -> Vec<Scope> {
+        Scope::required_for(&self.method(), self.path_template())
+    }
+}
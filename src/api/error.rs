@@ -1,30 +1,167 @@
+use crate::api::endpoint::RateLimitFamily;
+use crate::api::tier::Tier;
+use crate::api::usage::UsageKind;
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::time::Duration;
 use thiserror::Error;
 use url::ParseError;
 
+/// How much of an error response body [`TweetyError::ApiError`] keeps.
+const BODY_SNIPPET_LEN: usize = 512;
+
+/// The v1.1 error code for "Status is a duplicate."
+const DUPLICATE_STATUS_CODE: u64 = 187;
+
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum TweetyError {
     #[error("Error reading file: {0}")]
     FileIOError(String),
     #[error("Connection error occurred: {0}")]
-    NetworkError(String),
+    NetworkError(#[from] reqwest::Error),
     #[error("Authentication error occurred")]
     AuthError,
-    #[error("Request Error :{0}")]
-    RequestError(reqwest::Error),
-    #[error("API responded with an error: {0}")]
-    ApiError(String),
+    #[error("OAuth2 authorization failed: {0}")]
+    AuthFlowError(String),
+    /// A non-success response. `body` is the start of the response body, which for
+    /// the X API is usually a JSON problem description.
+    #[error("API responded with HTTP {status}: {body}")]
+    ApiError { status: StatusCode, body: String },
+    /// A response whose body isn't JSON, such as an HTML page from a proxy or a
+    /// plain-text rate limit message. `body` is the complete raw text.
+    #[error("Non-JSON response with HTTP {status}: {body}")]
+    NonJsonResponse { status: StatusCode, body: String },
     #[error("Failed to parse JSON response: {0}")]
-    JsonParseError(String),
+    JsonParseError(#[from] serde_json::Error),
+    /// A line of a stream that isn't valid JSON for the expected message. The stream
+    /// continues with the next line.
+    #[error("Malformed stream message: {source}")]
+    MalformedStreamLine {
+        line: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// A line of a stream longer than the parser's limit, which was skipped.
+    #[error("Stream message longer than {limit} bytes")]
+    StreamLineTooLong { limit: usize },
+    /// The response parsed, but lacked something the request should have returned.
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
     #[error("Parse Error: {0}")]
-    UrlParseError(ParseError),
+    UrlParseError(#[from] ParseError),
     #[error("Twitter consumer credentials are missing. Please set the 'consumer_key', 'consumer_secret', 'access_token', and 'access_token_secret' in your configuration:")]
     MissingCredentials,
+    #[error("Missing environment variables: {}", .0.join(", "))]
+    MissingEnvironmentVariables(Vec<String>),
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
     #[error("Failed to serialize query: {0}")]
     SerializeError(String),
+    #[error("Media upload failed: {0}")]
+    UploadError(String),
+    /// The webhook server couldn't start or stopped with an error, see
+    /// [`crate::api::webhook`].
+    #[error("Webhook server failed: {0}")]
+    WebhookServerError(String),
+    /// A request the client's access tier can't make, caught before it was sent.
+    #[error("Not possible on the {tier} tier: {reason}")]
+    TierLimit { tier: Tier, reason: String },
+    /// A request over the monthly budget of the client's
+    /// [`UsageGuard`](crate::api::usage::UsageGuard), caught before it was sent.
+    #[error("Monthly budget of {budget} posts {kind} used up, {used} so far")]
+    UsageBudgetExceeded {
+        kind: UsageKind,
+        used: u64,
+        budget: u64,
+    },
+    /// A 403 refusing to post a tweet identical to one the user posted recently.
+    /// Retrying won't help; `detail` is X's explanation.
+    #[error("Duplicate tweet: {detail}")]
+    DuplicateTweet { detail: String },
+    /// A 429. `retry_after` is how long X asked to wait, from the `Retry-After` or
+    /// `x-rate-limit-reset` header, and `body` the start of the response body.
+    #[error("Rate limited on {family:?}, retry in {retry_after:?}: {body}")]
+    RateLimited {
+        family: RateLimitFamily,
+        retry_after: Duration,
+        body: String,
+    },
+    /// Requests to `family` fail fast after repeated server errors, see
+    /// [`crate::api::circuit_breaker`].
+    #[error("Circuit open for {family:?} after repeated server errors, retry in {retry_after:?}")]
+    CircuitOpen {
+        family: RateLimitFamily,
+        retry_after: Duration,
+    },
+}
+
+impl TweetyError {
+    /// Builds an [`TweetyError::ApiError`], cutting `body` down to a snippet.
+    pub(crate) fn api(status: StatusCode, body: &str) -> Self {
+        TweetyError::ApiError {
+            status,
+            body: snippet(body),
+        }
+    }
+
+    /// Builds a [`TweetyError::RateLimited`], cutting `body` down to a snippet.
+    pub(crate) fn rate_limited(family: RateLimitFamily, retry_after: Duration, body: &str) -> Self {
+        TweetyError::RateLimited {
+            family,
+            retry_after,
+            body: snippet(body),
+        }
+    }
+
+    /// The HTTP status of an [`TweetyError::ApiError`] or [`TweetyError::NonJsonResponse`],
+    /// or of the failed request behind a [`TweetyError::NetworkError`].
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            TweetyError::ApiError { status, .. } | TweetyError::NonJsonResponse { status, .. } => {
+                Some(*status)
+            }
+            TweetyError::DuplicateTweet { .. } => Some(StatusCode::FORBIDDEN),
+            TweetyError::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            TweetyError::NetworkError(err) => err.status(),
+            _ => None,
+        }
+    }
+
+    /// The family whose rate limit a [`TweetyError::RateLimited`] hit.
+    pub fn rate_limit_family(&self) -> Option<RateLimitFamily> {
+        match self {
+            TweetyError::RateLimited { family, .. } => Some(*family),
+            _ => None,
+        }
+    }
+}
+
+/// X's explanation when a 403 `body` refuses a duplicate tweet: the v2 `detail`, or a
+/// v1.1 error with code 187.
+pub(crate) fn duplicate_detail(status: StatusCode, body: &Value) -> Option<String> {
+    if status != StatusCode::FORBIDDEN {
+        return None;
+    }
+    let is_duplicate = |message: &str| message.to_lowercase().contains("duplicate");
+
+    if let Some(detail) = body["detail"]
+        .as_str()
+        .filter(|detail| is_duplicate(detail))
+    {
+        return Some(detail.to_string());
+    }
+    body["errors"].as_array()?.iter().find_map(|error| {
+        let message = error["message"]
+            .as_str()
+            .unwrap_or("Status is a duplicate.");
+        (error["code"] == DUPLICATE_STATUS_CODE || is_duplicate(message))
+            .then(|| message.to_string())
+    })
 }
 
-impl From<reqwest::Error> for TweetyError {
-    fn from(err: reqwest::Error) -> TweetyError {
-        TweetyError::RequestError(err)
+fn snippet(body: &str) -> String {
+    match body.char_indices().nth(BODY_SNIPPET_LEN) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.to_string(),
     }
 }
@@ -0,0 +1,534 @@
+//! Posting the entries of an RSS or Atom feed.
+//!
+//! Enabled by the `feed` cargo feature. A [`FeedBridge`] polls a feed, formats each
+//! entry it hasn't posted yet with a hook, and posts it with
+//! [`TweetyClient::post_tweet`]. The ids of the entries already handled are kept in a
+//! [`StateStore`], so a restarted bridge doesn't post them again. A new bridge only
+//! posts the entries published after it first ran, unless
+//! [`FeedBridge::replay_backlog`] is set.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use tweety_rs::api::feed::FeedBridge;
+//! use tweety_rs::api::state::FileStateStore;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let mut bridge = FeedBridge::new(client, "https://blog.rust-lang.org/feed.xml")
+//!     .state_store(FileStateStore::new("feed.json"), "rust-blog")
+//!     .poll_interval(Duration::from_secs(15 * 60))
+//!     .format(|entry| Some(format!("New on the Rust blog: {} {}", entry.title, entry.link.as_deref()?)));
+//! bridge.run().await
+//! # }
+//! ```
+//!
+//! Feeds are read with `quick-xml`, leniently about mismatched end tags. The common
+//! children of each `item` or `entry` are picked, namespaced ones such as
+//! `content:encoded` or `dc:date` included, and the markup is stripped out of summaries.
+
+use crate::api::client::TweetyClient;
+use crate::api::dedup::SeenIds;
+use crate::api::error::TweetyError;
+use crate::api::poller::PollSchedule;
+use crate::api::retry::{RetryClassifier, RetryDecision};
+use crate::api::shutdown::Shutdown;
+use crate::api::state::StateStore;
+use quick_xml::events::Event;
+use quick_xml::name::QName;
+use quick_xml::Reader;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default time between two polls of the feed.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Default number of entries posted per poll, see [`FeedBridge::max_posts_per_poll`].
+pub const DEFAULT_MAX_POSTS_PER_POLL: usize = 5;
+
+/// How many handled entry ids are remembered. Feeds rarely list more than 100 entries.
+const SEEN_CAPACITY: usize = 1000;
+
+type Formatter = Arc<dyn Fn(&FeedEntry) -> Option<String> + Send + Sync>;
+
+/// An RSS `item` or Atom `entry`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedEntry {
+    /// The `guid` or `id`, else the link, else the title.
+    pub id: String,
+    pub title: String,
+    pub link: Option<String>,
+    /// The `description` or `summary` as plain text.
+    pub summary: Option<String>,
+    /// The `pubDate`, `published` or `updated` date, as written in the feed.
+    pub published: Option<String>,
+}
+
+/// Parses the entries of an RSS 2.0, RSS 1.0 or Atom feed, in the feed's order.
+///
+/// ```
+/// use tweety_rs::api::feed::parse_feed;
+///
+/// let entries = parse_feed(
+///     r#"<rss version="2.0"><channel><item>
+///         <title>Rust 1.80 &amp; more</title>
+///         <link>https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html</link>
+///     </item></channel></rss>"#,
+/// )?;
+/// assert_eq!(entries[0].title, "Rust 1.80 & more");
+/// assert_eq!(entries[0].id, "https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html");
+/// # Ok::<(), tweety_rs::api::error::TweetyError>(())
+/// ```
+pub fn parse_feed(xml: &str) -> Result<Vec<FeedEntry>, TweetyError> {
+    let not_a_feed = || TweetyError::UnexpectedResponse("not an RSS or Atom feed".to_string());
+    let mut reader = Reader::from_str(xml);
+    // Feeds in the wild aren't always well-formed.
+    reader.config_mut().check_end_names = false;
+    reader.config_mut().expand_empty_elements = true;
+
+    let mut root_seen = false;
+    let mut entries = Vec::new();
+    // The children of the entry being read, the child being read, and how deep in the
+    // entry the reader is.
+    let mut entry: Option<Vec<Child>> = None;
+    let mut child: Option<Child> = None;
+    let mut depth = 0;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|err| TweetyError::UnexpectedResponse(format!("invalid feed: {}", err)))?;
+        match event {
+            Event::Start(start) => {
+                let name = element_name(start.name());
+                if !root_seen {
+                    if !matches!(name.as_str(), "rss" | "feed" | "rdf:RDF") {
+                        return Err(not_a_feed());
+                    }
+                    root_seen = true;
+                }
+                match (&entry, &mut child) {
+                    (None, _) => {
+                        if name == "item" || name == "entry" {
+                            entry = Some(Vec::new());
+                            depth = 0;
+                        }
+                    }
+                    (Some(_), None) => {
+                        depth += 1;
+                        let attribute = |key: &[u8]| {
+                            start
+                                .attributes()
+                                .with_checks(false)
+                                .flatten()
+                                .find(|attribute| attribute.key.as_ref() == key)
+                                .map(|attribute| {
+                                    decode_entities(&String::from_utf8_lossy(&attribute.value))
+                                })
+                        };
+                        child = Some(Child {
+                            href: attribute(b"href"),
+                            rel: attribute(b"rel"),
+                            name,
+                            text: String::new(),
+                        });
+                    }
+                    (Some(_), Some(child)) => {
+                        depth += 1;
+                        if breaks_line(&name) {
+                            child.text.push(' ');
+                        }
+                    }
+                }
+            }
+            Event::End(end) => {
+                let Some(children) = &mut entry else {
+                    continue;
+                };
+                if depth == 0 {
+                    entries.push(to_entry(children));
+                    entry = None;
+                    continue;
+                }
+                depth -= 1;
+                match &mut child {
+                    Some(_) if depth == 0 => children.extend(child.take()),
+                    Some(child) if breaks_line(&element_name(end.name())) => child.text.push(' '),
+                    _ => {}
+                }
+            }
+            Event::Text(text) => {
+                if let Some(child) = &mut child {
+                    child
+                        .text
+                        .push_str(&decode_entities(&String::from_utf8_lossy(&text)));
+                }
+            }
+            Event::CData(cdata) => {
+                if let Some(child) = &mut child {
+                    child.text.push_str(&String::from_utf8_lossy(&cdata));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    match root_seen {
+        true => Ok(entries),
+        false => Err(not_a_feed()),
+    }
+}
+
+/// A direct child of an `item` or `entry`.
+struct Child {
+    name: String,
+    /// The contents with entities decoded and CDATA kept as is, markup not removed yet.
+    text: String,
+    href: Option<String>,
+    rel: Option<String>,
+}
+
+/// The name elements are matched by: Atom elements, whether in the default namespace or
+/// under the `atom` prefix, by their local name, others with their prefix, as in
+/// `content:encoded` or `dc:date`.
+fn element_name(name: QName) -> String {
+    let name = match name.prefix() {
+        Some(prefix) if prefix.as_ref() != b"atom" => name.into_inner(),
+        _ => name.local_name().into_inner(),
+    };
+    String::from_utf8_lossy(name).into_owned()
+}
+
+fn to_entry(children: &[Child]) -> FeedEntry {
+    let field = |names: &[&str]| {
+        names.iter().find_map(|name| {
+            children
+                .iter()
+                .filter(|child| child.name == *name)
+                .map(|child| text(&child.text))
+                .find(|text| !text.is_empty())
+        })
+    };
+    // Atom links are `<link rel="alternate" href="..."/>`, RSS links `<link>...</link>`.
+    let link = children
+        .iter()
+        .filter(|child| child.name == "link")
+        .find_map(|child| match &child.href {
+            Some(href) => {
+                matches!(child.rel.as_deref(), None | Some("alternate")).then(|| href.clone())
+            }
+            None => Some(text(&child.text)).filter(|link| !link.is_empty()),
+        });
+    let title = field(&["title"]).unwrap_or_default();
+    let id = field(&["guid", "id"])
+        .or_else(|| link.clone())
+        .unwrap_or_else(|| title.clone());
+
+    FeedEntry {
+        id,
+        title,
+        link,
+        summary: field(&["description", "summary", "content", "content:encoded"]),
+        published: field(&["pubDate", "published", "updated", "dc:date"]),
+    }
+}
+
+/// The plain text of an element's decoded contents: the markup of escaped or CDATA HTML
+/// removed and whitespace collapsed.
+fn text(decoded: &str) -> String {
+    strip_tags(decoded)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Removes the tags from `html`, leaving a space for those that break lines. A `<` not
+/// followed by a tag name, as in `1 < 2`, is kept.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let tag = &rest[start + 1..];
+        let is_tag = tag.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!');
+        match tag.find('>').filter(|_| is_tag) {
+            Some(end) => {
+                let name = tag[..end]
+                    .trim_start_matches('/')
+                    .split(|c: char| c.is_whitespace() || c == '/')
+                    .next()
+                    .unwrap_or_default();
+                if breaks_line(name) {
+                    text.push(' ');
+                }
+                rest = &tag[end + 1..];
+            }
+            None => {
+                text.push('<');
+                rest = tag;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Whether the HTML element `name` starts a new line.
+fn breaks_line(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "p" | "br"
+            | "div"
+            | "li"
+            | "ul"
+            | "ol"
+            | "tr"
+            | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+            | "blockquote"
+    )
+}
+
+/// Decodes the XML and common HTML entities of `text`, leaving unknown ones as written.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').map(|end| &rest[1..end + 1]);
+        let replacement = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                let code = entity.strip_prefix('#')?;
+                let code = match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+        match (entity, replacement) {
+            (Some(entity), Some(replacement)) => {
+                decoded.push(replacement);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// The default format: the title and the link.
+fn title_and_link(entry: &FeedEntry) -> Option<String> {
+    match &entry.link {
+        Some(link) => Some(format!("{} {}", entry.title, link)),
+        None => Some(entry.title.clone()),
+    }
+}
+
+/// Polls a feed and posts its new entries.
+pub struct FeedBridge {
+    client: TweetyClient,
+    url: String,
+    format: Formatter,
+    schedule: PollSchedule,
+    max_posts_per_poll: usize,
+    replay_backlog: bool,
+    /// Ids of the handled entries, once loaded.
+    seen: Option<SeenIds>,
+    state_store: Option<(Arc<dyn StateStore>, String)>,
+}
+
+impl fmt::Debug for FeedBridge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeedBridge")
+            .field("client", &self.client)
+            .field("url", &self.url)
+            .field("poll_interval", &self.schedule.interval)
+            .field("max_posts_per_poll", &self.max_posts_per_poll)
+            .field("replay_backlog", &self.replay_backlog)
+            .field("shutdown", &self.schedule.shutdown)
+            .finish()
+    }
+}
+
+impl FeedBridge {
+    /// Posts the entries of the feed at `url` with `client`.
+    pub fn new(client: TweetyClient, url: &str) -> Self {
+        FeedBridge {
+            client,
+            url: url.to_string(),
+            format: Arc::new(title_and_link),
+            schedule: PollSchedule::new(DEFAULT_POLL_INTERVAL),
+            max_posts_per_poll: DEFAULT_MAX_POSTS_PER_POLL,
+            replay_backlog: false,
+            seen: None,
+            state_store: None,
+        }
+    }
+
+    /// Writes the tweet for an entry, or skips the entry on `None`. Defaults to the
+    /// title followed by the link.
+    pub fn format(
+        mut self,
+        format: impl Fn(&FeedEntry) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.format = Arc::new(format);
+        self
+    }
+
+    /// Time between two polls, [`DEFAULT_POLL_INTERVAL`] by default.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.schedule.interval = interval;
+        self
+    }
+
+    /// Posts at most `max` entries per poll, leaving the rest for the next ones, so a
+    /// burst of entries doesn't flood the timeline. [`DEFAULT_MAX_POSTS_PER_POLL`] by
+    /// default.
+    pub fn max_posts_per_poll(mut self, max: usize) -> Self {
+        self.max_posts_per_poll = max.max(1);
+        self
+    }
+
+    /// Whether the first poll without saved state posts the entries already in the
+    /// feed. Off by default.
+    pub fn replay_backlog(mut self, replay: bool) -> Self {
+        self.replay_backlog = replay;
+        self
+    }
+
+    /// Keeps the ids of the handled entries under `key` in `store`, saved after each
+    /// one.
+    pub fn state_store(mut self, store: impl StateStore + 'static, key: &str) -> Self {
+        self.state_store = Some((Arc::new(store), key.to_string()));
+        self
+    }
+
+    /// Decides which errors [`FeedBridge::run`] keeps going after, and which failed
+    /// posts are skipped rather than tried again. Defaults to
+    /// [`DefaultRetryClassifier`](crate::api::retry::DefaultRetryClassifier).
+    pub fn retry_classifier(mut self, classifier: impl RetryClassifier + 'static) -> Self {
+        self.schedule.retry_classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Makes [`FeedBridge::run`] return once `shutdown` is requested, after the post in
+    /// flight is recorded.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.schedule.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// Polls until a shutdown is requested, see [`FeedBridge::shutdown_on`], or an error
+    /// the [`FeedBridge::retry_classifier`] deems permanent. Other errors, such as the
+    /// feed being unreachable, are logged and the bridge keeps going.
+    pub async fn run(&mut self) -> Result<(), TweetyError> {
+        let _running = self.schedule.running();
+
+        while !self.schedule.is_shutdown() {
+            let result = self.poll_once().await;
+            let what = format!("polling feed {}", self.url);
+            if self.schedule.wait(&self.client, result, &what).await? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the feed and posts the entries not handled yet, oldest first as feeds
+    /// list the newest first, returning how many were posted.
+    pub async fn poll_once(&mut self) -> Result<usize, TweetyError> {
+        let bytes = self.client.download(&self.url).await?;
+        let entries = parse_feed(&String::from_utf8_lossy(&bytes))?;
+
+        let first_run = self.load().await?;
+        if first_run && !self.replay_backlog {
+            for entry in &entries {
+                self.remember(&entry.id);
+            }
+            self.save().await?;
+            return Ok(0);
+        }
+
+        let mut posted = 0;
+        for entry in entries.iter().rev() {
+            if posted >= self.max_posts_per_poll || self.schedule.is_shutdown() {
+                break;
+            }
+            if self
+                .seen
+                .as_ref()
+                .is_some_and(|seen| seen.contains(&entry.id))
+            {
+                continue;
+            }
+
+            if let Some(text) = (self.format)(entry) {
+                match self.client.post_tweet(&text, None).await {
+                    Ok(_) => posted += 1,
+                    Err(err)
+                        if self.schedule.retry_classifier.classify(&err)
+                            == RetryDecision::Permanent =>
+                    {
+                        log::warn!("posting feed entry {} failed: {}", entry.id, err);
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            self.remember(&entry.id);
+            self.save().await?;
+        }
+        Ok(posted)
+    }
+
+    /// Reads the handled ids from the state store, once. `true` when nothing was saved.
+    async fn load(&mut self) -> Result<bool, TweetyError> {
+        if self.seen.is_some() {
+            return Ok(false);
+        }
+        let saved = match &self.state_store {
+            Some((store, key)) => store.get(key).await?,
+            None => None,
+        };
+        let first_run = saved.is_none();
+        let mut seen = SeenIds::new(SEEN_CAPACITY);
+        if let Some(value) = saved {
+            for id in serde_json::from_str::<Vec<String>>(&value)? {
+                seen.insert(&id);
+            }
+        }
+        self.seen = Some(seen);
+        Ok(first_run)
+    }
+
+    fn remember(&mut self, id: &str) {
+        self.seen
+            .get_or_insert_with(|| SeenIds::new(SEEN_CAPACITY))
+            .insert(id);
+    }
+
+    async fn save(&self) -> Result<(), TweetyError> {
+        let (Some((store, key)), Some(seen)) = (&self.state_store, &self.seen) else {
+            return Ok(());
+        };
+        let value = serde_json::to_string(&seen.iter().collect::<Vec<_>>())
+            .map_err(|err| TweetyError::SerializeError(err.to_string()))?;
+        store.set(key, &value).await
+    }
+}
@@ -0,0 +1,367 @@
+//! The filtered stream, with reconnects that don't lose tweets.
+//!
+//! After a short drop, the `backfill_minutes` parameter has X resend what was posted
+//! while the client was away, up to five minutes of it. For longer outages
+//! [`FilteredStream`] remembers the id of the last tweet it received and, given a
+//! [`gap_fill_query`](FilteredStream::gap_fill_query), runs a recent search for the
+//! tweets posted since before it connects again. Tweets that arrive both ways are
+//! delivered once.
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::filtered_stream::FilteredStream;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) {
+//! let mut stream = FilteredStream::new(&client)
+//!     .backfill_minutes(5)
+//!     .gap_fill_query("#rustlang -is:retweet");
+//!
+//! let result = stream
+//!     .run(|message| println!("{}", message["data"]["text"]))
+//!     .await;
+//! if let Err(err) = result {
+//!     eprintln!("stream stopped after {:?}: {}", stream.last_tweet_id(), err);
+//! }
+//! # }
+//! ```
+
+use crate::api::client::TweetyClient;
+use crate::api::conversation::compare_ids;
+use crate::api::dedup::{SeenIds, TweetIdentity};
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::query::{join_fields, Query};
+use crate::api::retry::{DefaultRetryClassifier, RetryClassifier, RetryDecision};
+use crate::api::search::{Expansion, TweetField};
+use crate::api::shutdown::Shutdown;
+use crate::api::state::StateStore;
+use crate::api::stream::{read_lines, LineParser};
+use serde_json::{json, Value};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Most minutes of missed tweets X resends on reconnect.
+pub const MAX_BACKFILL_MINUTES: u8 = 5;
+
+/// Wait before the first reconnect; it doubles after each failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(64);
+
+/// A connection to `GET /2/tweets/search/stream` that can be resumed.
+/// [Docs](https://developer.x.com/en/docs/x-api/tweets/filtered-stream/api-reference/get-tweets-search-stream)
+pub struct FilteredStream {
+    client: TweetyClient,
+    backfill_minutes: Option<u8>,
+    gap_fill_query: Option<String>,
+    tweet_fields: Option<String>,
+    expansions: Option<String>,
+    last_tweet_id: Option<String>,
+    last_received: Option<Instant>,
+    seen: SeenIds,
+    retry_classifier: Arc<dyn RetryClassifier>,
+    shutdown: Option<Shutdown>,
+    state_store: Option<(Arc<dyn StateStore>, String)>,
+    saved_tweet_id: Option<String>,
+}
+
+impl fmt::Debug for FilteredStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FilteredStream")
+            .field("client", &self.client)
+            .field("backfill_minutes", &self.backfill_minutes)
+            .field("gap_fill_query", &self.gap_fill_query)
+            .field("tweet_fields", &self.tweet_fields)
+            .field("expansions", &self.expansions)
+            .field("last_tweet_id", &self.last_tweet_id)
+            .field("last_received", &self.last_received)
+            .field("saved_tweet_id", &self.saved_tweet_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FilteredStream {
+    pub fn new(client: &TweetyClient) -> Self {
+        FilteredStream {
+            client: client.clone(),
+            backfill_minutes: None,
+            gap_fill_query: None,
+            tweet_fields: None,
+            expansions: None,
+            last_tweet_id: None,
+            last_received: None,
+            seen: SeenIds::default(),
+            retry_classifier: Arc::new(DefaultRetryClassifier),
+            shutdown: None,
+            state_store: None,
+            saved_tweet_id: None,
+        }
+    }
+
+    /// Minutes of missed tweets X resends on reconnect, between 1 and 5. Needs the Pro
+    /// tier or above.
+    pub fn backfill_minutes(mut self, minutes: u8) -> Self {
+        self.backfill_minutes = Some(minutes.clamp(1, MAX_BACKFILL_MINUTES));
+        self
+    }
+
+    /// Recent search query matching the stream's rules, run to fill outages longer
+    /// than the backfill window. Without one, such gaps stay unfilled.
+    pub fn gap_fill_query(mut self, query: &str) -> Self {
+        self.gap_fill_query = Some(query.to_string());
+        self
+    }
+
+    /// Asked for on the stream and the gap-fill searches alike.
+    pub fn tweet_fields(mut self, fields: &[TweetField]) -> Self {
+        self.tweet_fields = Some(join_fields(fields));
+        self
+    }
+
+    pub fn expansions(mut self, expansions: &[Expansion]) -> Self {
+        self.expansions = Some(join_fields(expansions));
+        self
+    }
+
+    /// Decides which errors [`run`](FilteredStream::run) reconnects after. Defaults to
+    /// [`DefaultRetryClassifier`].
+    pub fn retry_classifier(mut self, classifier: impl RetryClassifier + 'static) -> Self {
+        self.retry_classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Makes [`connect`](FilteredStream::connect) and [`run`](FilteredStream::run)
+    /// return once `shutdown` is requested. The message being delivered is finished, so
+    /// [`last_tweet_id`](FilteredStream::last_tweet_id) is where to resume.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// Resumes after the tweet with id `id`, from an earlier [`last_tweet_id`]. The
+    /// tweets posted since are gap-filled on the first connect.
+    ///
+    /// [`last_tweet_id`]: FilteredStream::last_tweet_id
+    pub fn since_id(mut self, id: &str) -> Self {
+        self.last_tweet_id = Some(id.to_string());
+        self
+    }
+
+    /// Resumes after the id stored under `key` in `store`, read on the first connect
+    /// unless [`since_id`](FilteredStream::since_id) was given, and stores
+    /// [`last_tweet_id`](FilteredStream::last_tweet_id) there after each gap fill and
+    /// each connection.
+    pub fn state_store(mut self, store: impl StateStore + 'static, key: &str) -> Self {
+        self.state_store = Some((Arc::new(store), key.to_string()));
+        self
+    }
+
+    /// Id of the newest tweet delivered so far.
+    pub fn last_tweet_id(&self) -> Option<&str> {
+        self.last_tweet_id.as_deref()
+    }
+
+    /// Connects once and calls `on_message` with every message until the connection
+    /// ends. Tweets missed since the last connection are gap-filled first when the
+    /// outage went beyond the backfill window.
+    ///
+    /// Lines that can't be parsed are logged and skipped.
+    pub async fn connect<F>(&mut self, mut on_message: F) -> Result<(), TweetyError>
+    where
+        F: FnMut(Value),
+    {
+        self.load_state().await?;
+        if self.needs_gap_fill() {
+            self.gap_fill(&mut on_message).await?;
+            self.save_state().await?;
+        }
+
+        let response = self
+            .client
+            .open_stream(&Endpoint::FilteredStream, &self.stream_query())
+            .await?;
+
+        let shutdown = self.shutdown.clone();
+        let read = read_lines(
+            response,
+            LineParser::<Value>::new(),
+            |message| self.deliver(message, &mut on_message),
+            |err| log::warn!("skipped a stream message: {}", err),
+        );
+        let result = match shutdown {
+            // Messages are delivered synchronously, so dropping the read between two
+            // of them loses nothing.
+            Some(shutdown) => tokio::select! {
+                result = read => result,
+                _ = shutdown.requested() => Ok(()),
+            },
+            None => read.await,
+        };
+
+        let saved = self.save_state().await;
+        result.and(saved)
+    }
+
+    /// Keeps the stream connected, reconnecting with exponential backoff after drops
+    /// and the errors the [`retry_classifier`](FilteredStream::retry_classifier) deems
+    /// retryable. Returns the first error it deems permanent, or `Ok(())` once a
+    /// shutdown is requested.
+    pub async fn run<F>(&mut self, mut on_message: F) -> Result<(), TweetyError>
+    where
+        F: FnMut(Value),
+    {
+        let _running = self.shutdown.as_ref().map(Shutdown::running);
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_requested) {
+                return Ok(());
+            }
+
+            let received_before = self.last_received;
+            let mut rate_limited = None;
+            let wait = match self.connect(&mut on_message).await {
+                Ok(()) => None,
+                Err(err) => {
+                    rate_limited = err.rate_limit_family();
+                    match self.retry_classifier.classify(&err) {
+                        RetryDecision::Retry => None,
+                        RetryDecision::RetryAfter(wait) => Some(wait),
+                        RetryDecision::Permanent => return Err(err),
+                    }
+                }
+            };
+
+            if self.last_received != received_before {
+                backoff = INITIAL_BACKOFF;
+            }
+            let wait = wait.map_or(backoff, |wait| wait.max(backoff));
+            let shutdown = self.shutdown.as_ref();
+            if self
+                .client
+                .wait_for_rate_limit(rate_limited, shutdown, wait)
+                .await
+            {
+                return Ok(());
+            }
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    /// Whether the tweets missed since the last one received are beyond what
+    /// `backfill_minutes` resends.
+    fn needs_gap_fill(&self) -> bool {
+        if self.gap_fill_query.is_none() || self.last_tweet_id.is_none() {
+            return false;
+        }
+
+        let window = Duration::from_secs(u64::from(self.backfill_minutes.unwrap_or(0)) * 60);
+        self.last_received
+            .is_none_or(|received| received.elapsed() > window)
+    }
+
+    /// Pages through the recent search for tweets newer than the last one delivered,
+    /// and delivers them oldest first.
+    async fn gap_fill<F>(&mut self, on_message: &mut F) -> Result<(), TweetyError>
+    where
+        F: FnMut(Value),
+    {
+        let mut tweets = Vec::new();
+        let mut next_token = None;
+        loop {
+            let query = self.gap_fill_search(next_token.as_ref());
+            let mut page = self
+                .client
+                .send_request::<()>(&Endpoint::RecentSearch, &query, None)
+                .await?;
+
+            if let Some(Value::Array(data)) = page.get_mut("data").map(Value::take) {
+                tweets.extend(data);
+            }
+            match page["meta"]["next_token"].as_str() {
+                Some(token) => next_token = Some(token.to_string()),
+                None => break,
+            }
+        }
+
+        tweets.sort_by(|a, b| compare_ids(tweet_id(a), tweet_id(b)));
+        for tweet in tweets {
+            self.deliver(json!({ "data": tweet }), on_message);
+        }
+        Ok(())
+    }
+
+    /// Reads the resume point from the state store on the first connect, unless one is
+    /// already set.
+    async fn load_state(&mut self) -> Result<(), TweetyError> {
+        if self.saved_tweet_id.is_some() || self.last_tweet_id.is_some() {
+            return Ok(());
+        }
+        if let Some((ref store, ref key)) = self.state_store {
+            self.last_tweet_id = store.get(key).await?.filter(|id| !id.is_empty());
+            self.saved_tweet_id = self.last_tweet_id.clone();
+        }
+        Ok(())
+    }
+
+    async fn save_state(&mut self) -> Result<(), TweetyError> {
+        let Some((ref store, ref key)) = self.state_store else {
+            return Ok(());
+        };
+        if self.last_tweet_id == self.saved_tweet_id {
+            return Ok(());
+        }
+        if let Some(ref id) = self.last_tweet_id {
+            store.set(key, id).await?;
+        }
+        self.saved_tweet_id = self.last_tweet_id.clone();
+        Ok(())
+    }
+
+    fn deliver<F>(&mut self, message: Value, on_message: &mut F)
+    where
+        F: FnMut(Value),
+    {
+        self.last_received = Some(Instant::now());
+
+        if let Some(id) = message.tweet_id() {
+            if !self.seen.insert(id) {
+                return;
+            }
+            let newer = self
+                .last_tweet_id
+                .as_deref()
+                .is_none_or(|last| compare_ids(id, last).is_gt());
+            if newer {
+                self.last_tweet_id = Some(id.to_string());
+            }
+        }
+
+        on_message(message);
+    }
+
+    fn stream_query(&self) -> Query {
+        let mut query = Query::new();
+        query
+            .push_opt("backfill_minutes", self.backfill_minutes)
+            .push_opt("tweet.fields", self.tweet_fields.as_ref())
+            .push_opt("expansions", self.expansions.as_ref());
+        query
+    }
+
+    fn gap_fill_search(&self, next_token: Option<&String>) -> Query {
+        let mut query = Query::new();
+        query
+            .push_opt("query", self.gap_fill_query.as_ref())
+            .push_opt("since_id", self.last_tweet_id.as_ref())
+            .push("max_results", 100)
+            .push_opt("tweet.fields", self.tweet_fields.as_ref())
+            .push_opt("expansions", self.expansions.as_ref())
+            .push_opt("next_token", next_token);
+        query
+    }
+}
+
+fn tweet_id(tweet: &Value) -> &str {
+    tweet.tweet_id().unwrap_or_default()
+}
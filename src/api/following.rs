@@ -1,8 +1,12 @@
 use super::user::UserQueryParams;
 use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
-use reqwest::Method;
+use crate::api::query::Query;
+use crate::types::ids::UserId;
+use crate::types::meta::Meta;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct FollowResponse {
@@ -38,17 +42,23 @@ pub struct UserFollowingResponse {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct User {
     pub pinned_tweet_id: Option<String>,
     pub id: String,
     pub username: String,
     pub name: String,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Includes {
     pub tweets: Vec<Tweet>,
-    pub meta: MetaData,
+    pub meta: Meta,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -78,33 +88,30 @@ pub struct Entity {
     pub description: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct MetaData {
-    pub result_count: u32,
-    pub next_token: String,
-}
-
 impl TweetyClient {
     /// Allows a user ID to follow another user.
     /// If the target user does not have public Tweets, this endpoint will send a follow request.
     /// [Docs](https://developer.x.com/en/docs/x-api/users/follows/api-reference/post-users-source_user_id-following)
     pub async fn follow_user(
         &self,
-        user_id: &str,
-        target_user_id: &str,
+        user_id: impl Into<UserId>,
+        target_user_id: impl Into<UserId>,
     ) -> Result<FollowResponse, TweetyError> {
-        let url = format!("https://api.x.com/2/users/:{}/following", user_id);
+        let user_id: UserId = user_id.into();
 
         let json_body = FollowBody {
-            target_user_id: target_user_id.to_string(),
+            target_user_id: target_user_id.into().into_inner(),
         };
 
-        match self.send_request(&url, Method::POST, Some(json_body)).await {
+        match self
+            .send_request(&Endpoint::Follow(user_id), &Query::new(), Some(json_body))
+            .await
+        {
             Ok(value) => match serde_json::from_value::<FollowResponse>(value) {
                 Ok(data) => Ok(data),
-                Err(err) => Err(TweetyError::JsonParseError(err.to_string())),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
             },
-            Err(err) => Err(TweetyError::ApiError(err.to_string())),
+            Err(err) => Err(err),
         }
     }
     /// Allows a user ID to unfollow another user.
@@ -113,20 +120,25 @@ impl TweetyClient {
     /// [Docs](https://developer.x.com/en/docs/x-api/users/follows/api-reference/delete-users-source_id-following)
     pub async fn unfollow_user(
         &self,
-        source_userid: &str,
-        target_userid: &str,
+        source_userid: impl Into<UserId>,
+        target_userid: impl Into<UserId>,
     ) -> Result<UnfollowResponse, TweetyError> {
-        let url = format!(
-            "https://api.x.com/2/users/:{}/following/:{}",
-            source_userid, target_userid
-        );
+        let (source_userid, target_userid): (UserId, UserId) =
+            (source_userid.into(), target_userid.into());
+        let endpoint = Endpoint::Unfollow {
+            user_id: source_userid,
+            target_user_id: target_userid,
+        };
 
-        match self.send_request::<()>(&url, Method::DELETE, None).await {
+        match self
+            .send_request::<()>(&endpoint, &Query::new(), None)
+            .await
+        {
             Ok(value) => match serde_json::from_value::<UnfollowResponse>(value) {
                 Ok(data) => Ok(data),
-                Err(err) => Err(TweetyError::JsonParseError(err.to_string())),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
             },
-            Err(err) => Err(TweetyError::ApiError(err.to_string())),
+            Err(err) => Err(err),
         }
     }
     /// GET /2/users/:id/following
@@ -134,22 +146,21 @@ impl TweetyClient {
     /// [Docs](https://developer.x.com/en/docs/x-api/users/follows/api-reference/get-users-id-following#tab0)
     pub async fn get_users_following(
         &self,
-        user_id: &str,
+        user_id: impl Into<UserId>,
         query: Option<UserQueryParams>,
     ) -> Result<UserFollowingResponse, TweetyError> {
-        let mut base_url = format!("https://api.x.com/2/users/{}/following", user_id);
-
-        if let Some(query) = query {
-            let query_string = query.construct_query_string();
-            base_url.push_str(&query_string);
-        }
+        let user_id: UserId = user_id.into();
+        let query = query.map(|query| query.to_query()).unwrap_or_default();
 
-        match self.send_request::<()>(&base_url, Method::GET, None).await {
+        match self
+            .send_request::<()>(&Endpoint::Following(user_id), &query, None)
+            .await
+        {
             Ok(value) => match serde_json::from_value::<UserFollowingResponse>(value) {
                 Ok(data) => Ok(data),
-                Err(err) => Err(TweetyError::JsonParseError(err.to_string())),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
             },
-            Err(err) => Err(TweetyError::ApiError(err.to_string())),
+            Err(err) => Err(err),
         }
     }
 }
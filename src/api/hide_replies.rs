@@ -1,8 +1,19 @@
 use crate::api::client::TweetyClient;
+use crate::api::conversation::{compare_ids, Conversation};
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
-use reqwest::Method;
+use crate::api::mentions::TweetData;
+use crate::api::query::Query;
+use crate::types::ids::TweetId;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Share of a conversation's replies that, once hidden, makes
+/// [`ModerationReport::suggested_reply_settings`] suggest limiting replies.
+pub const REPLY_SETTINGS_THRESHOLD: f64 = 0.25;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HideTweet {
@@ -14,11 +25,211 @@ pub struct HideTweet {
 /// Hides or unhides a reply to a Tweet.
 /// Endpoint URL
 /// https://api.x.com/2/tweets/:id/hidden
-/// hidden	boolean	Indicates if the Tweet was successfully hidden or unhidden.
+/// hidden boolean Indicates if the Tweet was successfully hidden or unhidden.
 impl TweetyClient {
-    pub async fn hide_tweet(self, tweet_id: &str) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/tweets/{}/hidden", tweet_id);
+    pub async fn hide_tweet(&self, tweet_id: impl Into<TweetId>) -> Result<Value, TweetyError> {
+        let tweet_id: TweetId = tweet_id.into();
         let json_body = HideTweet { hidden: true };
-        self.send_request(&url, Method::PUT, Some(json_body)).await
+        self.send_request(
+            &Endpoint::HideReply(tweet_id),
+            &Query::new(),
+            Some(json_body),
+        )
+        .await
+    }
+}
+
+type Predicate = Arc<dyn Fn(&TweetData) -> bool + Send + Sync>;
+
+/// Which replies [`TweetyClient::moderate_conversation`] hides. A reply is hidden when
+/// any rule matches it; replies by the conversation's author never are.
+///
+/// ```rust,no_run
+/// use tweety_rs::api::hide_replies::ModerationPolicy;
+/// use tweety_rs::TweetyClient;
+///
+/// # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+/// let policy = ModerationPolicy::new()
+///     .hide_words(["giveaway", "dm me"])
+///     .hide_if(|reply| reply.text.matches("https://").count() > 2);
+///
+/// let report = client.moderate_conversation("1828783668107559176", &policy).await?;
+/// println!("hid {} replies", report.hidden.len());
+/// if let Some(settings) = report.suggested_reply_settings {
+///     println!("consider posting with reply_settings={}", settings);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ModerationPolicy {
+    authors: HashSet<String>,
+    words: Vec<String>,
+    predicate: Option<Predicate>,
+    dry_run: bool,
+}
+
+impl fmt::Debug for ModerationPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModerationPolicy")
+            .field("authors", &self.authors)
+            .field("words", &self.words)
+            .field("predicate", &self.predicate.is_some())
+            .field("dry_run", &self.dry_run)
+            .finish()
+    }
+}
+
+impl ModerationPolicy {
+    /// A policy that hides nothing until rules are added.
+    pub fn new() -> Self {
+        ModerationPolicy::default()
+    }
+
+    /// Hides replies by these user ids. Needs `author_id`, which
+    /// [`TweetyClient::get_conversation`] requests.
+    pub fn hide_authors<I, S>(mut self, user_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.authors.extend(user_ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Hides replies whose full text contains one of `words`, ignoring case.
+    pub fn hide_words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.words
+            .extend(words.into_iter().map(|word| word.as_ref().to_lowercase()));
+        self
+    }
+
+    /// Hides replies `predicate` returns `true` for.
+    pub fn hide_if(
+        mut self,
+        predicate: impl Fn(&TweetData) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Only reports which replies would be hidden.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Returns `true` when a rule matches `reply`.
+    pub fn matches(&self, reply: &TweetData) -> bool {
+        let by_author = reply
+            .author_id
+            .as_ref()
+            .is_some_and(|author| self.authors.contains(author));
+        let text = reply.full_text().to_lowercase();
+        let by_word = self.words.iter().any(|word| text.contains(word.as_str()));
+        let by_predicate = self
+            .predicate
+            .as_ref()
+            .is_some_and(|predicate| predicate(reply));
+
+        by_author || by_word || by_predicate
+    }
+
+    /// The replies of `conversation` this policy hides, oldest first.
+    pub fn select<'a>(&self, conversation: &'a Conversation) -> Vec<&'a TweetData> {
+        let author = conversation.root.tweet.author_id.as_deref();
+        let mut selected = Vec::new();
+        for reply in replies(conversation) {
+            if reply.author_id.as_deref() != author && self.matches(reply) {
+                selected.push(reply);
+            }
+        }
+        selected
+    }
+}
+
+/// What [`TweetyClient::moderate_conversation`] did.
+#[derive(Debug)]
+pub struct ModerationReport {
+    /// Number of replies in the conversation.
+    pub replies: usize,
+    /// Ids of the replies the policy matched, oldest first.
+    pub matched: Vec<String>,
+    /// Ids of the matched replies that were hidden; none on a dry run.
+    pub hidden: Vec<String>,
+    /// The error that stopped hiding, e.g. the hide replies rate limit. Replies in
+    /// `matched` but not `hidden` are left to a later run.
+    pub error: Option<TweetyError>,
+    /// X can't limit who replies to a posted tweet, and hiding doesn't stop the
+    /// notifications. When at least [`REPLY_SETTINGS_THRESHOLD`] of the replies
+    /// matched, this is the `reply_settings` to post the next tweets with, see
+    /// [`PostTweetParamsBuilder::reply_settings`].
+    ///
+    /// [`PostTweetParamsBuilder::reply_settings`]: crate::types::tweet::PostTweetParamsBuilder::reply_settings
+    pub suggested_reply_settings: Option<&'static str>,
+}
+
+impl TweetyClient {
+    /// Hides the replies to one of the user's own tweets that `policy` selects.
+    ///
+    /// The conversation is fetched with [`TweetyClient::get_conversation`], so only
+    /// replies from the last seven days are seen. Hiding stops at the first error,
+    /// which is kept in the report rather than returned; only failing to fetch the
+    /// conversation is an `Err`.
+    pub async fn moderate_conversation(
+        &self,
+        tweet_id: impl Into<TweetId>,
+        policy: &ModerationPolicy,
+    ) -> Result<ModerationReport, TweetyError> {
+        let conversation = self.get_conversation(tweet_id).await?;
+        let replies = conversation.len() - 1;
+        let matched: Vec<String> = policy
+            .select(&conversation)
+            .into_iter()
+            .map(|reply| reply.id.clone())
+            .collect();
+
+        let mut hidden = Vec::new();
+        let mut error = None;
+        if !policy.dry_run {
+            for id in &matched {
+                match self.hide_tweet(id.as_str()).await {
+                    Ok(_) => hidden.push(id.clone()),
+                    Err(err) => {
+                        error = Some(err);
+                        break;
+                    }
+                }
+            }
+        }
+
+        let share = matched.len() as f64 / replies.max(1) as f64;
+        Ok(ModerationReport {
+            replies,
+            suggested_reply_settings: (!matched.is_empty() && share >= REPLY_SETTINGS_THRESHOLD)
+                .then_some("following"),
+            matched,
+            hidden,
+            error,
+        })
+    }
+}
+
+/// Every reply of `conversation`, orphans included, oldest first.
+fn replies(conversation: &Conversation) -> Vec<&TweetData> {
+    let mut replies = Vec::new();
+    conversation.root.walk(&mut |tweet, level| {
+        if level > 0 {
+            replies.push(tweet);
+        }
+    });
+    for orphan in &conversation.orphans {
+        orphan.walk(&mut |tweet, _| replies.push(tweet));
     }
+    replies.sort_by(|a, b| compare_ids(&a.id, &b.id));
+    replies
 }
@@ -0,0 +1,286 @@
+//! Connection tuning.
+//!
+//! A [`TweetyClient`] holds one connection pool, built once and shared by its clones,
+//! its [`TweetyClient::on_behalf_of`] handles and every endpoint call, so a long-running
+//! bot keeps a handful of sockets open rather than one per request. Build clients once
+//! and clone them; each [`TweetyClient::new`] opens a pool of its own.
+//!
+//! reqwest's defaults suit short requests. A client that holds a filtered stream open
+//! for days wants TCP keepalive so dead connections are noticed, and one that fires
+//! bursts of lookups wants idle connections kept around between bursts.
+//!
+//! ```
+//! use std::time::Duration;
+//! use tweety_rs::api::http::HttpConfig;
+//! use tweety_rs::TweetyClient;
+//!
+//! let client = TweetyClient::from_bearer_token("token").with_http_config(
+//!     HttpConfig::new()
+//!         .tcp_keepalive(Some(Duration::from_secs(30)))
+//!         .http2_adaptive_window(true),
+//! );
+//! ```
+//!
+//! Hosts can be pinned to addresses, e.g. to send `api.x.com` through a corporate
+//! egress proxy, or the whole DNS lookup replaced with a [`Resolve`] implementation.
+//!
+//! ```
+//! use tweety_rs::api::http::HttpConfig;
+//! use tweety_rs::TweetyClient;
+//!
+//! let client = TweetyClient::from_bearer_token("token")
+//!     .with_http_config(HttpConfig::new().resolve("api.x.com", "10.0.0.7:443".parse().unwrap()));
+//! ```
+//!
+//! Requests can also go through an HTTP CONNECT or, with the `socks` feature, a SOCKS5
+//! [`Proxy`]. It carries every request of the client, to the API and upload hosts alike.
+//!
+//! ```
+//! use tweety_rs::api::http::{HttpConfig, Proxy};
+//! use tweety_rs::TweetyClient;
+//!
+//! # fn main() -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let proxy = Proxy::http("http://proxy.internal:3128")?.basic_auth("bot", "hunter2");
+//! let client =
+//!     TweetyClient::from_bearer_token("token").with_http_config(HttpConfig::new().proxy(proxy));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`TweetyClient`]: crate::TweetyClient
+//! [`TweetyClient::on_behalf_of`]: crate::TweetyClient::on_behalf_of
+//! [`TweetyClient::new`]: crate::TweetyClient::new
+
+use crate::api::error::TweetyError;
+use crate::api::secret::SecretString;
+pub use hyper::client::connect::dns::Name;
+pub use reqwest::dns::{Addrs, Resolve, Resolving};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+/// How long an idle pooled connection is kept by [`HttpConfig::new`], as in reqwest.
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Settings for the client's connection pool, see [`TweetyClient::with_http_config`].
+///
+/// [`TweetyClient::with_http_config`]: crate::TweetyClient::with_http_config
+#[derive(Clone)]
+pub struct HttpConfig {
+    http2_adaptive_window: bool,
+    tcp_keepalive: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: usize,
+    dns_overrides: BTreeMap<String, Vec<SocketAddr>>,
+    dns_resolver: Option<Arc<dyn Resolve>>,
+    proxy: Option<Proxy>,
+}
+
+impl fmt::Debug for HttpConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpConfig")
+            .field("http2_adaptive_window", &self.http2_adaptive_window)
+            .field("tcp_keepalive", &self.tcp_keepalive)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("dns_overrides", &self.dns_overrides)
+            .field("dns_resolver", &self.dns_resolver.is_some())
+            .field("proxy", &self.proxy)
+            .finish()
+    }
+}
+
+/// Resolvers are equal when they are the same instance.
+impl PartialEq for HttpConfig {
+    fn eq(&self, other: &Self) -> bool {
+        let same_resolver = match (&self.dns_resolver, &other.dns_resolver) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        self.http2_adaptive_window == other.http2_adaptive_window
+            && self.tcp_keepalive == other.tcp_keepalive
+            && self.pool_idle_timeout == other.pool_idle_timeout
+            && self.pool_max_idle_per_host == other.pool_max_idle_per_host
+            && self.dns_overrides == other.dns_overrides
+            && self.proxy == other.proxy
+            && same_resolver
+    }
+}
+
+impl Eq for HttpConfig {}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig::new()
+    }
+}
+
+impl HttpConfig {
+    /// reqwest's defaults: no adaptive window, no TCP keepalive, idle connections
+    /// closed after [`DEFAULT_POOL_IDLE_TIMEOUT`].
+    pub fn new() -> Self {
+        HttpConfig {
+            http2_adaptive_window: false,
+            tcp_keepalive: None,
+            pool_idle_timeout: Some(DEFAULT_POOL_IDLE_TIMEOUT),
+            pool_max_idle_per_host: usize::MAX,
+            dns_overrides: BTreeMap::new(),
+            dns_resolver: None,
+            proxy: None,
+        }
+    }
+
+    /// For long-lived streams: the HTTP/2 window grows with the connection's
+    /// bandwidth, and TCP keepalive probes every 30 seconds.
+    pub fn streaming() -> Self {
+        HttpConfig::new()
+            .http2_adaptive_window(true)
+            .tcp_keepalive(Some(Duration::from_secs(30)))
+    }
+
+    /// For bursts of lookups with quiet spells between them: idle connections are
+    /// kept for 5 minutes so the next burst skips the TLS handshake.
+    pub fn bursty() -> Self {
+        HttpConfig::new().pool_idle_timeout(Some(Duration::from_secs(300)))
+    }
+
+    /// Sizes the HTTP/2 flow control window from the measured bandwidth-delay product
+    /// instead of a fixed window.
+    pub fn http2_adaptive_window(mut self, enabled: bool) -> Self {
+        self.http2_adaptive_window = enabled;
+        self
+    }
+
+    /// Sends TCP keepalive probes at this interval on idle connections, or none with
+    /// `None`.
+    pub fn tcp_keepalive(mut self, interval: Option<Duration>) -> Self {
+        self.tcp_keepalive = interval;
+        self
+    }
+
+    /// Closes pooled connections idle for this long, or keeps them with `None`.
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// Keeps at most this many idle connections per host. Unlimited by default.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Connects to `addr` for `host` instead of looking it up. Called again for the same
+    /// host, the addresses are tried in turn. TLS still checks the certificate against
+    /// `host`.
+    pub fn resolve(mut self, host: &str, addr: SocketAddr) -> Self {
+        self.dns_overrides
+            .entry(host.to_string())
+            .or_default()
+            .push(addr);
+        self
+    }
+
+    /// Looks up hosts without an override from [`HttpConfig::resolve`] with `resolver`
+    /// instead of the system resolver.
+    pub fn dns_resolver(mut self, resolver: impl Resolve + 'static) -> Self {
+        self.dns_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Sends every request through `proxy` instead of the proxy from the environment.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// A reqwest client with these settings.
+    pub(crate) fn build(&self) -> reqwest::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .http2_adaptive_window(self.http2_adaptive_window)
+            .tcp_keepalive(self.tcp_keepalive)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host);
+        if let Some(ref resolver) = self.dns_resolver {
+            builder = builder.dns_resolver(Arc::new(SharedResolver(resolver.clone())));
+        }
+        for (host, addrs) in &self.dns_overrides {
+            builder = builder.resolve_to_addrs(host, addrs);
+        }
+        if let Some(ref proxy) = self.proxy {
+            builder = builder.proxy(proxy.build()?);
+        }
+        builder.build()
+    }
+}
+
+/// A proxy for [`HttpConfig::proxy`]. `Debug` output masks the password.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proxy {
+    url: Url,
+    credentials: Option<(String, SecretString)>,
+}
+
+impl Proxy {
+    /// An HTTP proxy at an `http://` or `https://` URL. Requests to the `https` API
+    /// hosts are tunnelled with CONNECT.
+    pub fn http(url: &str) -> Result<Self, TweetyError> {
+        Proxy::parse(url, &["http", "https"])
+    }
+
+    /// A SOCKS5 proxy at a `socks5://` URL, or `socks5h://` to have the proxy resolve
+    /// host names.
+    #[cfg(feature = "socks")]
+    pub fn socks5(url: &str) -> Result<Self, TweetyError> {
+        Proxy::parse(url, &["socks5", "socks5h"])
+    }
+
+    fn parse(url: &str, schemes: &[&str]) -> Result<Self, TweetyError> {
+        let url = Url::parse(url)?;
+        if !schemes.contains(&url.scheme()) {
+            return Err(TweetyError::ConfigError(format!(
+                "proxy URL must start with {}://, got {}",
+                schemes.join(":// or "),
+                url.scheme()
+            )));
+        }
+        Ok(Proxy {
+            url,
+            credentials: None,
+        })
+    }
+
+    /// Authenticates with the proxy: Basic auth for HTTP proxies, username and password
+    /// for SOCKS5 ones.
+    pub fn basic_auth(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some((username.to_string(), password.into()));
+        self
+    }
+
+    /// The proxy's URL.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    fn build(&self) -> reqwest::Result<reqwest::Proxy> {
+        let proxy = reqwest::Proxy::all(self.url.clone())?;
+        Ok(match self.credentials {
+            Some((ref username, ref password)) => {
+                proxy.basic_auth(username, password.expose_secret())
+            }
+            None => proxy,
+        })
+    }
+}
+
+/// Lets a resolver shared by configs be handed to reqwest, which wants a sized one.
+struct SharedResolver(Arc<dyn Resolve>);
+
+impl Resolve for SharedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        self.0.resolve(name)
+    }
+}
@@ -0,0 +1,102 @@
+//! Rehydrating tweet ids: turning a list of ids, from a dataset or a compliance feed,
+//! back into tweets.
+//!
+//! [`TweetyClient::hydrate_tweets`] batches the ids into lookups of
+//! [`HYDRATION_BATCH_SIZE`], paces the lookups by the `x-rate-limit` headers and waits
+//! out 429s, reporting the waits to the client's
+//! [`Observer`](crate::api::observer::Observer). Each id comes back with its
+//! [`LookupOutcome`], so deleted, protected and suspended tweets can be counted rather
+//! than silently dropped:
+//!
+//! ```rust,no_run
+//! use futures_util::{stream, StreamExt};
+//! use tweety_rs::types::lookup::LookupOutcome;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient, dataset: Vec<String>) {
+//! let mut tweets = Box::pin(client.hydrate_tweets(stream::iter(dataset)));
+//! while let Some(result) = tweets.next().await {
+//!     match result {
+//!         Ok((_, LookupOutcome::Found(tweet))) => println!("{}", tweet.text),
+//!         Ok((id, outcome)) => eprintln!("{} unavailable: {:?}", id, outcome),
+//!         Err(err) => eprintln!("stopped: {}", err),
+//!     }
+//! }
+//! # }
+//! ```
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::mentions::TweetData;
+use crate::types::ids::TweetId;
+use crate::types::lookup::LookupOutcome;
+use futures_util::stream::{self, Stream, StreamExt};
+use std::collections::VecDeque;
+
+/// Ids per lookup, the most `GET /2/tweets` accepts.
+pub const HYDRATION_BATCH_SIZE: usize = 100;
+
+/// A hydrated id and what the lookup found for it.
+pub type Hydrated = (TweetId, LookupOutcome<TweetData>);
+
+impl TweetyClient {
+    /// Looks up every id of `ids`, [`HYDRATION_BATCH_SIZE`] at a time, see
+    /// [`crate::api::hydrate`]. Wrap an iterator with `futures_util::stream::iter`.
+    ///
+    /// A batch is sent once it is full or `ids` ends. Outcomes come in the order of
+    /// `ids`. The stream ends after the first error other than a rate limit, which is
+    /// yielded.
+    pub fn hydrate_tweets<S>(
+        &self,
+        ids: S,
+    ) -> impl Stream<Item = Result<Hydrated, TweetyError>> + Send + 'static
+    where
+        S: Stream + Send + 'static,
+        S::Item: Into<TweetId>,
+    {
+        let batches = ids.map(Into::into).chunks(HYDRATION_BATCH_SIZE).boxed();
+        let state = (self.clone(), batches, VecDeque::new(), false);
+
+        stream::unfold(
+            state,
+            |(client, mut batches, mut ready, mut done)| async move {
+                while ready.is_empty() {
+                    if done {
+                        return None;
+                    }
+                    let batch = batches.next().await?;
+                    match client.hydrate_batch(batch).await {
+                        Ok(outcomes) => ready.extend(outcomes.into_iter().map(Ok)),
+                        Err(err) => {
+                            done = true;
+                            ready.push_back(Err(err));
+                        }
+                    }
+                }
+
+                let item = ready.pop_front()?;
+                Some((item, (client, batches, ready, done)))
+            },
+        )
+    }
+
+    /// Looks up one batch once the rate limit allows, trying again after a 429.
+    async fn hydrate_batch(&self, ids: Vec<TweetId>) -> Result<Vec<Hydrated>, TweetyError> {
+        let family = Endpoint::Tweets.rate_limit_family();
+
+        loop {
+            if let Some(status) = self.rate_limit(family) {
+                self.wait_for_rate_limit(Some(family), None, status.pace())
+                    .await;
+            }
+            match self.lookup_tweets(ids.clone()).await {
+                Err(TweetyError::RateLimited { retry_after, .. }) => {
+                    self.wait_for_rate_limit(Some(family), None, retry_after)
+                        .await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
@@ -1,50 +1,77 @@
 use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
-use reqwest::Method;
+use crate::api::query::Query;
+use crate::types::ids::{TweetId, UserId};
 use serde_json::Value;
 
 impl TweetyClient {
-    /// DELETE https://api.x.com/2/tweets/:id/likes/:tweet_id
+    /// DELETE https://api.x.com/2/users/:id/likes/:tweet_id
     /// (unlike a Post)
     /// https://developer.x.com/en/docs/x-api/tweets/likes/migrate/manage-likes-standard-to-twitter-api-v2
-    pub async fn unlike_tweet(&self, user_id: u64, tweet_id: u64) -> Result<Value, TweetyError> {
-        let url = format!(
-            "https://api.x.com/2/tweets/:{}/likes/:{}",
-            user_id, tweet_id
-        );
+    pub async fn unlike_tweet(
+        &self,
+        user_id: impl Into<UserId>,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<Value, TweetyError> {
+        let (user_id, tweet_id): (UserId, TweetId) = (user_id.into(), tweet_id.into());
+        let endpoint = Endpoint::Unlike { user_id, tweet_id };
 
-        self.send_request::<()>(&url, Method::DELETE, None).await
+        self.send_request::<()>(&endpoint, &Query::new(), None)
+            .await
     }
     /// Users who have liked a Post
     /// https://developer.x.com/en/docs/x-api/tweets/likes/api-reference
-    pub async fn get_users_who_liked_a_post(&self, post_id: &str) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/tweets/{}/liking_users", post_id);
+    pub async fn get_users_who_liked_a_post(
+        &self,
+        post_id: impl Into<TweetId>,
+    ) -> Result<Value, TweetyError> {
+        let post_id: TweetId = post_id.into();
+        let endpoint = Endpoint::LikingUsers(post_id);
 
-        self.send_request::<()>(&url, Method::GET, None).await
+        self.send_request::<()>(&endpoint, &Query::new(), None)
+            .await
     }
 
     /// Posts liked by a user
     /// https://developer.x.com/en/docs/x-api/tweets/likes/api-reference
-    pub async fn get_posts_liked_by_a_user(&self, user_id: &str) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/users/{}/liked_tweets", user_id);
+    pub async fn get_posts_liked_by_a_user(
+        &self,
+        user_id: impl Into<UserId>,
+    ) -> Result<Value, TweetyError> {
+        let user_id: UserId = user_id.into();
+        let endpoint = Endpoint::LikedTweets(user_id);
 
-        self.send_request::<()>(&url, Method::GET, None).await
+        self.send_request::<()>(&endpoint, &Query::new(), None)
+            .await
     }
-    /// MANAGE LIKES
+    // MANAGE LIKES
 
     /// Allows a user ID to like a Post
     /// https://developer.x.com/en/docs/x-api/tweets/likes/api-reference
-    pub async fn like_a_post(&self, user_id: &str) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/users/{}/likes", user_id);
+    pub async fn like_a_post(
+        &self,
+        user_id: impl Into<UserId>,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<Value, TweetyError> {
+        let user_id: UserId = user_id.into();
+        let body = serde_json::json!({ "tweet_id": tweet_id.into() });
 
-        self.send_request::<()>(&url, Method::POST, None).await
+        self.send_request(&Endpoint::Like(user_id), &Query::new(), Some(body))
+            .await
     }
 
     /// Allows a user ID to unlike a Post
     /// https://developer.x.com/en/docs/x-api/tweets/likes/api-reference
-    pub async fn unlike_a_post(&self, user_id: &str, tweet_id: &str) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/users/{}/likes/{}", user_id, tweet_id);
+    pub async fn unlike_a_post(
+        &self,
+        user_id: impl Into<UserId>,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<Value, TweetyError> {
+        let (user_id, tweet_id): (UserId, TweetId) = (user_id.into(), tweet_id.into());
+        let endpoint = Endpoint::Unlike { user_id, tweet_id };
 
-        self.send_request::<()>(&url, Method::DELETE, None).await
+        self.send_request::<()>(&endpoint, &Query::new(), None)
+            .await
     }
 }
@@ -0,0 +1,67 @@
+//! Expanding t.co links.
+//!
+//! X wraps every link in a tweet's text in a `https://t.co/...` redirect. The URL
+//! entities (`tweet.fields=entities`) say where each one points;
+//! [`TweetyClient::expand_urls`] reads them, and follows the redirects itself for links
+//! the entities don't cover.
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::mentions::TweetData;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient, tweet: TweetData) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! for url in client.expand_urls(&tweet).await? {
+//!     println!("{}", url);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::error::TweetyError;
+use crate::api::mentions::TweetData;
+use crate::TweetyClient;
+use url::Url;
+
+/// The host of X's link shortener.
+pub const TCO_HOST: &str = "t.co";
+
+impl TweetyClient {
+    /// Where each link in `tweet`'s full text points, in order of appearance.
+    ///
+    /// Links with a URL entity are taken from it, see [`TweetData::expanded_urls`].
+    /// t.co links without one, e.g. when the entities weren't requested, are resolved
+    /// by following their redirects with [`TweetyClient::resolve_url`]. Fails with the
+    /// first link that can't be followed.
+    pub async fn expand_urls(&self, tweet: &TweetData) -> Result<Vec<String>, TweetyError> {
+        let entities = tweet.url_entities();
+        let mut urls = Vec::new();
+
+        for link in tco_links(tweet.full_text()) {
+            match entities.iter().find(|entity| entity.url == link) {
+                Some(entity) => urls.push(entity.target().to_string()),
+                None => urls.push(self.resolve_url(link).await?),
+            }
+        }
+        Ok(urls)
+    }
+
+    /// Follows the redirects from `url`, such as a t.co link, and returns where they
+    /// end. Only headers are requested and no credentials are sent.
+    pub async fn resolve_url(&self, url: &str) -> Result<String, TweetyError> {
+        Ok(self.follow_redirects(url).await?.to_string())
+    }
+}
+
+/// The t.co links in `text`, without punctuation around them.
+fn tco_links(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            let start = word.find("http")?;
+            Some(word[start..].trim_end_matches(|c: char| !c.is_ascii_alphanumeric()))
+        })
+        .filter(|link| {
+            Url::parse(link)
+                .map(|url| url.host_str() == Some(TCO_HOST))
+                .unwrap_or(false)
+        })
+}
@@ -0,0 +1,107 @@
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::query::Query;
+use crate::api::user::{Expansions, TweetFields, UserFields};
+use crate::types::ids::ListId;
+use crate::types::meta::{Meta, Paginated};
+use crate::types::user::UserResponse;
+use serde::{Deserialize, Serialize};
+
+/// Query parameters for `GET /2/lists/:id/followers` and `GET /2/lists/:id/members`.
+/// [Docs](https://developer.x.com/en/docs/x-api/lists/list-follows/api-reference/get-lists-id-followers)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ListFollowersParams {
+    pub expansions: Option<Expansions>,
+    /// The maximum number of results to be returned per page, between 1 and 100. The default is 100.
+    pub max_results: Option<u32>,
+    /// The `next_token` or `previous_token` of a previous page.
+    pub pagination_token: Option<String>,
+    pub tweet_fields: Option<Vec<TweetFields>>,
+    pub user_fields: Option<Vec<UserFields>>,
+}
+
+impl ListFollowersParams {
+    pub fn to_query_string(&self) -> String {
+        self.to_query().to_query_string()
+    }
+
+    pub(crate) fn to_query(&self) -> Query {
+        let mut query = Query::new();
+
+        if let Some(ref expansions) = self.expansions {
+            query.push_fields("expansions", std::slice::from_ref(expansions));
+        }
+        query
+            .push_opt("max_results", self.max_results)
+            .push_opt("pagination_token", self.pagination_token.as_ref());
+        if let Some(ref tweet_fields) = self.tweet_fields {
+            query.push_fields("tweet.fields", tweet_fields);
+        }
+        if let Some(ref user_fields) = self.user_fields {
+            query.push_fields("user.fields", user_fields);
+        }
+
+        query
+    }
+}
+
+impl Paginated for ListFollowersParams {
+    fn set_page_token(&mut self, token: String) {
+        self.pagination_token = Some(token);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListFollowersResponse {
+    /// Absent from the response when the list has no followers.
+    #[serde(default)]
+    pub data: Vec<UserResponse>,
+    pub meta: Meta,
+}
+
+/// Lists
+/// [Docs](https://developer.x.com/en/docs/x-api/lists)
+impl TweetyClient {
+    /// GET /2/lists/:id/followers
+    /// Returns a list of users who are followers of the specified List.
+    /// Pass `meta.next_token` back as `pagination_token` to fetch the next page.
+    /// [Docs](https://developer.x.com/en/docs/x-api/lists/list-follows/api-reference/get-lists-id-followers)
+    pub async fn get_list_followers(
+        &self,
+        list_id: impl Into<ListId>,
+        params: Option<ListFollowersParams>,
+    ) -> Result<ListFollowersResponse, TweetyError> {
+        let list_id: ListId = list_id.into();
+        let query = params.map(|params| params.to_query()).unwrap_or_default();
+
+        match self
+            .send_request::<()>(&Endpoint::ListFollowers(list_id), &query, None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<ListFollowersResponse>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// GET /2/lists/:id/members
+    /// Returns the users who are members of the specified List.
+    /// Pass `meta.next_token` back as `pagination_token` to fetch the next page.
+    /// [Docs](https://developer.x.com/en/docs/x-api/lists/list-members/api-reference/get-lists-id-members)
+    pub async fn get_list_members(
+        &self,
+        list_id: impl Into<ListId>,
+        params: Option<ListFollowersParams>,
+    ) -> Result<ListFollowersResponse, TweetyError> {
+        let list_id: ListId = list_id.into();
+        let query = params.map(|params| params.to_query()).unwrap_or_default();
+
+        let value = self
+            .send_request::<()>(&Endpoint::ListMembers(list_id), &query, None)
+            .await?;
+        serde_json::from_value(value).map_err(TweetyError::JsonParseError)
+    }
+}
@@ -0,0 +1,154 @@
+//! Publishing to Mastodon, as a [`CrossPoster`].
+//!
+//! Enabled by the `mastodon` cargo feature. [`Mastodon`] posts statuses to one
+//! instance with an access token that has the `write:statuses` scope. Add it to a
+//! [`CrossPost`] next to the [`TweetyClient`] to publish the same post to both:
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::crosspost::{CrossPost, Post};
+//! use tweety_rs::api::mastodon::{Mastodon, Visibility};
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let mastodon = Mastodon::new("https://fosstodon.org", "mastodon-token")?
+//!     .visibility(Visibility::Unlisted);
+//! let cross_post = CrossPost::new().to(client).to(mastodon);
+//!
+//! let post = Post::new("tweety-rs 1.4.0 is out!").link("https://crates.io/crates/tweety-rs");
+//! for (network, result) in cross_post.publish(&post).await {
+//!     match result {
+//!         Ok(posted) => println!("{}: {:?}", network, posted.url),
+//!         Err(err) => eprintln!("{}: {}", network, err),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`CrossPost`]: crate::api::crosspost::CrossPost
+//! [`TweetyClient`]: crate::TweetyClient
+
+use crate::api::crosspost::{CrossPosted, CrossPoster, LINK_LENGTH};
+use crate::api::error::TweetyError;
+use crate::api::secret::SecretString;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use url::Url;
+
+/// The status length of a stock Mastodon instance; some allow more, see
+/// [`Mastodon::max_length`].
+pub const DEFAULT_MAX_LENGTH: usize = 500;
+
+/// Who sees a status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    /// Everyone, on the public timelines too.
+    #[default]
+    Public,
+    /// Everyone, but kept off the public timelines.
+    Unlisted,
+    /// Followers only.
+    Private,
+    /// Only the mentioned users.
+    Direct,
+}
+
+#[derive(Debug, Deserialize)]
+struct Status {
+    id: String,
+    url: Option<String>,
+}
+
+/// Posts statuses to a Mastodon instance.
+#[derive(Debug, Clone)]
+pub struct Mastodon {
+    http: reqwest::Client,
+    instance: Url,
+    access_token: SecretString,
+    max_length: usize,
+    visibility: Visibility,
+}
+
+impl Mastodon {
+    /// Posts to the instance at `instance`, such as `https://mastodon.social`, with
+    /// `access_token`.
+    pub fn new(instance: &str, access_token: &str) -> Result<Self, TweetyError> {
+        Ok(Mastodon {
+            http: reqwest::Client::new(),
+            instance: Url::parse(instance)?,
+            access_token: SecretString::new(access_token),
+            max_length: DEFAULT_MAX_LENGTH,
+            visibility: Visibility::default(),
+        })
+    }
+
+    /// The longest status the instance accepts, when it isn't the stock 500
+    /// characters.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Who sees the statuses. Defaults to [`Visibility::Public`].
+    pub fn visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// POST /api/v1/statuses
+    /// Posts `text` as a status.
+    /// [Docs](https://docs.joinmastodon.org/methods/statuses/#create)
+    pub async fn post_status(&self, text: &str) -> Result<CrossPosted, TweetyError> {
+        let url = self.instance.join("/api/v1/statuses")?;
+        let response = self
+            .http
+            .post(url)
+            .bearer_auth(self.access_token.expose_secret())
+            .json(&json!({ "status": text, "visibility": self.visibility }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(TweetyError::api(status, &body));
+        }
+        let posted: Status = serde_json::from_str(&body)?;
+        Ok(CrossPosted {
+            id: posted.id,
+            url: posted.url,
+        })
+    }
+}
+
+/// Counts links as [`LINK_LENGTH`] characters like Mastodon does.
+impl CrossPoster for Mastodon {
+    fn network(&self) -> &str {
+        "mastodon"
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    fn length(&self, text: &str) -> usize {
+        length_with_links(text)
+    }
+
+    fn publish<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<CrossPosted, TweetyError>> {
+        Box::pin(self.post_status(text))
+    }
+}
+
+/// The characters in `text`, counting each `http://` or `https://` link as
+/// [`LINK_LENGTH`].
+fn length_with_links(text: &str) -> usize {
+    let links = text
+        .split_whitespace()
+        .filter(|word| word.starts_with("https://") || word.starts_with("http://"));
+    links.fold(text.chars().count(), |length, link| {
+        length - link.chars().count() + LINK_LENGTH
+    })
+}
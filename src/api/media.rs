@@ -0,0 +1,565 @@
+//! Media type detection and the upload limits X enforces.
+//!
+//! [`MediaInfo::from_path`] sniffs the file contents (not the extension) for the MIME
+//! type, `media_category`, dimensions and video duration, and [`MediaInfo::validate`]
+//! checks them against the documented limits so oversized files fail before any upload.
+
+use crate::api::client::TweetyClient;
+use crate::api::error::TweetyError;
+use crate::types::ids::MediaId;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Largest image accepted, 5 MB.
+pub const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+/// Largest animated GIF accepted, 15 MB.
+pub const MAX_GIF_BYTES: u64 = 15 * 1024 * 1024;
+/// Largest video accepted, 512 MB.
+pub const MAX_VIDEO_BYTES: u64 = 512 * 1024 * 1024;
+/// Largest width or height of an image, in pixels.
+pub const MAX_IMAGE_DIMENSION: u32 = 8192;
+/// Smallest width or height of a video, in pixels.
+pub const MIN_VIDEO_DIMENSION: u32 = 32;
+/// Shortest video accepted, in milliseconds.
+pub const MIN_VIDEO_DURATION_MS: u64 = 500;
+/// Longest video accepted as `tweet_video`; longer ones are uploaded as `amplify_video`.
+pub const MAX_TWEET_VIDEO_DURATION_MS: u64 = 140_000;
+
+/// The `media_category` of an upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaCategory {
+    TweetImage,
+    TweetGif,
+    TweetVideo,
+    AmplifyVideo,
+}
+
+impl MediaCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaCategory::TweetImage => "tweet_image",
+            MediaCategory::TweetGif => "tweet_gif",
+            MediaCategory::TweetVideo => "tweet_video",
+            MediaCategory::AmplifyVideo => "amplify_video",
+        }
+    }
+}
+
+/// What was detected about a media file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaInfo {
+    pub mime_type: &'static str,
+    pub category: MediaCategory,
+    /// File size in bytes.
+    pub size: u64,
+    /// Pixel dimensions, when they could be read from the file headers.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Video duration, when it could be read from the file headers.
+    pub duration_ms: Option<u64>,
+}
+
+impl MediaInfo {
+    /// Detects the media type of the file at `path` from its contents.
+    ///
+    /// Supports JPEG, PNG, GIF, WebP, MP4 and QuickTime files; anything else is an
+    /// `UploadError`.
+    pub fn from_path(path: &Path) -> Result<Self, TweetyError> {
+        let io_error = |err: std::io::Error| TweetyError::FileIOError(err.to_string());
+
+        let mut file = File::open(path).map_err(io_error)?;
+        let size = file.metadata().map_err(io_error)?.len();
+
+        let mut header = Vec::new();
+        (&mut file)
+            .take(64)
+            .read_to_end(&mut header)
+            .map_err(io_error)?;
+
+        if header.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+            let (width, height) = jpeg_dimensions(&mut file).map_err(io_error)?.unzip();
+            return Ok(MediaInfo::image("image/jpeg", size, width, height));
+        }
+        if header.starts_with(b"\x89PNG\r\n\x1a\n") && header.len() >= 24 {
+            let width = u32::from_be_bytes([header[16], header[17], header[18], header[19]]);
+            let height = u32::from_be_bytes([header[20], header[21], header[22], header[23]]);
+            return Ok(MediaInfo::image(
+                "image/png",
+                size,
+                Some(width),
+                Some(height),
+            ));
+        }
+        if (header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a")) && header.len() >= 10 {
+            let width = u16::from_le_bytes([header[6], header[7]]) as u32;
+            let height = u16::from_le_bytes([header[8], header[9]]) as u32;
+            return Ok(MediaInfo {
+                mime_type: "image/gif",
+                category: MediaCategory::TweetGif,
+                size,
+                width: Some(width),
+                height: Some(height),
+                duration_ms: None,
+            });
+        }
+        if header.len() >= 12 && &header[0..4] == b"RIFF" && &header[8..12] == b"WEBP" {
+            let (width, height) = webp_dimensions(&header).unzip();
+            return Ok(MediaInfo::image("image/webp", size, width, height));
+        }
+        if header.len() >= 12 && &header[4..8] == b"ftyp" {
+            let mime_type = if &header[8..12] == b"qt  " {
+                "video/quicktime"
+            } else {
+                "video/mp4"
+            };
+            let movie = mp4_movie_info(&mut file, size).map_err(io_error)?;
+            let category = match movie.duration_ms {
+                Some(duration) if duration > MAX_TWEET_VIDEO_DURATION_MS => {
+                    MediaCategory::AmplifyVideo
+                }
+                _ => MediaCategory::TweetVideo,
+            };
+            return Ok(MediaInfo {
+                mime_type,
+                category,
+                size,
+                width: movie.width,
+                height: movie.height,
+                duration_ms: movie.duration_ms,
+            });
+        }
+
+        Err(TweetyError::UploadError(format!(
+            "{} is not a supported media type (JPEG, PNG, GIF, WebP, MP4 or MOV)",
+            path.display()
+        )))
+    }
+
+    fn image(mime_type: &'static str, size: u64, width: Option<u32>, height: Option<u32>) -> Self {
+        MediaInfo {
+            mime_type,
+            category: MediaCategory::TweetImage,
+            size,
+            width,
+            height,
+            duration_ms: None,
+        }
+    }
+
+    /// Checks the size, dimension and duration limits for the detected category.
+    ///
+    /// Limits that couldn't be read from the file are not checked.
+    pub fn validate(&self) -> Result<(), TweetyError> {
+        let (max_bytes, kind) = match self.category {
+            MediaCategory::TweetImage => (MAX_IMAGE_BYTES, "images"),
+            MediaCategory::TweetGif => (MAX_GIF_BYTES, "GIFs"),
+            MediaCategory::TweetVideo | MediaCategory::AmplifyVideo => (MAX_VIDEO_BYTES, "videos"),
+        };
+        if self.size > max_bytes {
+            return Err(TweetyError::UploadError(format!(
+                "file is {}, {} must be at most {}",
+                format_megabytes(self.size),
+                kind,
+                format_megabytes(max_bytes)
+            )));
+        }
+
+        if let (Some(width), Some(height)) = (self.width, self.height) {
+            if self.category == MediaCategory::TweetImage
+                && (width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION)
+            {
+                return Err(TweetyError::UploadError(format!(
+                    "image is {}x{} pixels, images must be at most {}x{}",
+                    width, height, MAX_IMAGE_DIMENSION, MAX_IMAGE_DIMENSION
+                )));
+            }
+            if self.is_video() && (width < MIN_VIDEO_DIMENSION || height < MIN_VIDEO_DIMENSION) {
+                return Err(TweetyError::UploadError(format!(
+                    "video is {}x{} pixels, videos must be at least {}x{}",
+                    width, height, MIN_VIDEO_DIMENSION, MIN_VIDEO_DIMENSION
+                )));
+            }
+        }
+
+        if let Some(duration_ms) = self.duration_ms {
+            if self.is_video() && duration_ms < MIN_VIDEO_DURATION_MS {
+                return Err(TweetyError::UploadError(format!(
+                    "video is {} ms long, videos must be at least {} ms",
+                    duration_ms, MIN_VIDEO_DURATION_MS
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_video(&self) -> bool {
+        matches!(
+            self.category,
+            MediaCategory::TweetVideo | MediaCategory::AmplifyVideo
+        )
+    }
+}
+
+/// JPEG qualities tried, best first, before an image is downscaled further.
+#[cfg(feature = "image")]
+const JPEG_QUALITIES: [u8; 4] = [90, 80, 70, 60];
+
+/// An image made to fit the upload limits by [`prepare_image`].
+#[cfg(feature = "image")]
+#[derive(Debug, Clone)]
+pub struct PreparedImage {
+    pub bytes: Vec<u8>,
+    pub mime_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+    /// `false` when the original file already fit and is returned unchanged.
+    pub modified: bool,
+}
+
+#[cfg(feature = "image")]
+impl PreparedImage {
+    /// File extension matching `mime_type`.
+    pub fn extension(&self) -> &'static str {
+        match self.mime_type {
+            "image/png" => "png",
+            "image/webp" => "webp",
+            _ => "jpg",
+        }
+    }
+}
+
+/// Downscales and recompresses the image at `path` until it fits [`MAX_IMAGE_DIMENSION`]
+/// and [`MAX_IMAGE_BYTES`], so original photos can be posted as they are.
+///
+/// Images with transparency stay PNG when that fits, otherwise everything is re-encoded
+/// as JPEG. Images that already fit are returned untouched. Requires the `image` feature.
+#[cfg(feature = "image")]
+pub fn prepare_image(path: &Path) -> Result<PreparedImage, TweetyError> {
+    use image::imageops::FilterType;
+
+    let info = MediaInfo::from_path(path)?;
+    if info.category != MediaCategory::TweetImage {
+        return Err(TweetyError::UploadError(format!(
+            "{} is not a still image",
+            path.display()
+        )));
+    }
+    if info.validate().is_ok() {
+        if let (Some(width), Some(height)) = (info.width, info.height) {
+            let bytes =
+                std::fs::read(path).map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+            return Ok(PreparedImage {
+                bytes,
+                mime_type: info.mime_type,
+                width,
+                height,
+                modified: false,
+            });
+        }
+    }
+
+    let mut image = image::ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|err| TweetyError::FileIOError(err.to_string()))?
+        .decode()
+        .map_err(|err| {
+            TweetyError::UploadError(format!("could not decode {}: {}", path.display(), err))
+        })?;
+
+    if image.width() > MAX_IMAGE_DIMENSION || image.height() > MAX_IMAGE_DIMENSION {
+        image = image.resize(
+            MAX_IMAGE_DIMENSION,
+            MAX_IMAGE_DIMENSION,
+            FilterType::Lanczos3,
+        );
+    }
+
+    loop {
+        if image.color().has_alpha() {
+            let png = encode_image(&image, None)?;
+            if png.len() as u64 <= MAX_IMAGE_BYTES {
+                return Ok(prepared_image(png, "image/png", &image));
+            }
+        }
+        for quality in JPEG_QUALITIES {
+            let jpeg = encode_image(&image, Some(quality))?;
+            if jpeg.len() as u64 <= MAX_IMAGE_BYTES {
+                return Ok(prepared_image(jpeg, "image/jpeg", &image));
+            }
+        }
+
+        if image.width().max(image.height()) <= MIN_VIDEO_DIMENSION {
+            return Err(TweetyError::UploadError(format!(
+                "could not shrink {} under {}",
+                path.display(),
+                format_megabytes(MAX_IMAGE_BYTES)
+            )));
+        }
+        image = image.resize(
+            image.width() * 3 / 4,
+            image.height() * 3 / 4,
+            FilterType::Lanczos3,
+        );
+    }
+}
+
+/// Encodes as JPEG with the given quality, or as PNG when `jpeg_quality` is `None`.
+#[cfg(feature = "image")]
+fn encode_image(
+    image: &image::DynamicImage,
+    jpeg_quality: Option<u8>,
+) -> Result<Vec<u8>, TweetyError> {
+    let mut bytes = Vec::new();
+    let result = match jpeg_quality {
+        Some(quality) => image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, quality)
+            .encode_image(&image.to_rgb8()),
+        None => image.write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::Png,
+        ),
+    };
+
+    result.map_err(|err| TweetyError::UploadError(format!("could not encode image: {}", err)))?;
+    Ok(bytes)
+}
+
+#[cfg(feature = "image")]
+fn prepared_image(
+    bytes: Vec<u8>,
+    mime_type: &'static str,
+    image: &image::DynamicImage,
+) -> PreparedImage {
+    PreparedImage {
+        bytes,
+        mime_type,
+        width: image.width(),
+        height: image.height(),
+        modified: true,
+    }
+}
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// Scans the JPEG markers for the start-of-frame segment holding the dimensions.
+fn jpeg_dimensions(reader: &mut (impl Read + Seek)) -> std::io::Result<Option<(u32, u32)>> {
+    reader.seek(SeekFrom::Start(2))?;
+
+    loop {
+        let mut marker = [0u8; 4];
+        if reader.read_exact(&mut marker).is_err() || marker[0] != 0xFF {
+            return Ok(None);
+        }
+        let length = u16::from_be_bytes([marker[2], marker[3]]) as i64;
+
+        // SOF0-SOF15, except DHT (C4), JPG (C8) and DAC (CC).
+        if (0xC0..=0xCF).contains(&marker[1]) && ![0xC4, 0xC8, 0xCC].contains(&marker[1]) {
+            let mut frame = [0u8; 5];
+            reader.read_exact(&mut frame)?;
+            let height = u16::from_be_bytes([frame[1], frame[2]]) as u32;
+            let width = u16::from_be_bytes([frame[3], frame[4]]) as u32;
+            return Ok(Some((width, height)));
+        }
+
+        reader.seek(SeekFrom::Current(length - 2))?;
+    }
+}
+
+/// Reads the canvas size from the first chunk of a WebP file.
+fn webp_dimensions(header: &[u8]) -> Option<(u32, u32)> {
+    let byte = |index: usize| header.get(index).copied().map(u32::from);
+
+    match header.get(12..16)? {
+        b"VP8X" => {
+            let width = 1 + (byte(24)? | byte(25)? << 8 | byte(26)? << 16);
+            let height = 1 + (byte(27)? | byte(28)? << 8 | byte(29)? << 16);
+            Some((width, height))
+        }
+        b"VP8 " => {
+            let width = (byte(26)? | byte(27)? << 8) & 0x3FFF;
+            let height = (byte(28)? | byte(29)? << 8) & 0x3FFF;
+            Some((width, height))
+        }
+        b"VP8L" => {
+            let width = 1 + (byte(21)? | (byte(22)? & 0x3F) << 8);
+            let height = 1 + (byte(22)? >> 6 | byte(23)? << 2 | (byte(24)? & 0x0F) << 10);
+            Some((width, height))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Default)]
+struct MovieInfo {
+    width: Option<u32>,
+    height: Option<u32>,
+    duration_ms: Option<u64>,
+}
+
+/// Finds the `moov` box and reads the duration from `mvhd` and the size of the first
+/// visual track from `tkhd`, without reading the media data.
+fn mp4_movie_info(reader: &mut (impl Read + Seek), size: u64) -> std::io::Result<MovieInfo> {
+    let mut info = MovieInfo::default();
+
+    let Some((moov_start, moov_end)) = find_box(reader, 0, size, b"moov")? else {
+        return Ok(info);
+    };
+    let mut moov = vec![0u8; (moov_end - moov_start) as usize];
+    reader.seek(SeekFrom::Start(moov_start))?;
+    reader.read_exact(&mut moov)?;
+
+    let mut offset = 0;
+    while let Some((kind, content)) = next_box(&moov, &mut offset) {
+        match kind {
+            b"mvhd" => info.duration_ms = mvhd_duration_ms(content),
+            b"trak" if info.width.is_none() => {
+                let mut trak_offset = 0;
+                while let Some((kind, content)) = next_box(content, &mut trak_offset) {
+                    if kind == b"tkhd" {
+                        if let Some((width, height)) = tkhd_dimensions(content) {
+                            info.width = Some(width);
+                            info.height = Some(height);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(info)
+}
+
+/// Returns the content range of the first top-level box of type `kind`.
+fn find_box(
+    reader: &mut (impl Read + Seek),
+    mut position: u64,
+    end: u64,
+    kind: &[u8; 4],
+) -> std::io::Result<Option<(u64, u64)>> {
+    while position.saturating_add(8) <= end {
+        reader.seek(SeekFrom::Start(position))?;
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+
+        let mut header_len = 8;
+        let mut box_len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        if box_len == 1 {
+            let mut large = [0u8; 8];
+            reader.read_exact(&mut large)?;
+            box_len = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if box_len == 0 {
+            box_len = end - position;
+        }
+        if box_len < header_len {
+            return Ok(None);
+        }
+
+        // A length running past `u64::MAX` is garbage; stop rather than wrap around.
+        let Some(box_end) = position.checked_add(box_len) else {
+            return Ok(None);
+        };
+        if &header[4..8] == kind {
+            return Ok(Some((position + header_len, box_end.min(end))));
+        }
+        position = box_end;
+    }
+
+    Ok(None)
+}
+
+/// Iterates the boxes of an in-memory container, returning each type and content.
+fn next_box<'a>(data: &'a [u8], offset: &mut usize) -> Option<(&'a [u8], &'a [u8])> {
+    let header = data.get(*offset..*offset + 8)?;
+    let box_len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    if box_len < 8 {
+        return None;
+    }
+
+    let end = offset.checked_add(box_len)?;
+    let content = data.get(*offset + 8..end)?;
+    let kind = &header[4..8];
+    *offset = end;
+    Some((kind, content))
+}
+
+fn mvhd_duration_ms(content: &[u8]) -> Option<u64> {
+    let read_u32 = |at: usize| -> Option<u64> {
+        Some(u32::from_be_bytes(content.get(at..at + 4)?.try_into().ok()?) as u64)
+    };
+
+    let (timescale, duration) = if *content.first()? == 1 {
+        let duration = u64::from_be_bytes(content.get(24..32)?.try_into().ok()?);
+        (read_u32(20)?, duration)
+    } else {
+        (read_u32(12)?, read_u32(16)?)
+    };
+
+    (timescale > 0).then(|| duration * 1000 / timescale)
+}
+
+fn tkhd_dimensions(content: &[u8]) -> Option<(u32, u32)> {
+    // The 16.16 fixed-point width and height close the box; their offset depends on
+    // whether the times are 32 or 64 bit.
+    let at = if *content.first()? == 1 { 88 } else { 76 };
+    let width = u32::from_be_bytes(content.get(at..at + 4)?.try_into().ok()?) >> 16;
+    let height = u32::from_be_bytes(content.get(at + 4..at + 8)?.try_into().ok()?) >> 16;
+
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+impl TweetyClient {
+    /// Uploads an image, GIF or video, detecting its type and checking X's limits first.
+    ///
+    /// With the `image` feature, images over the limits are shrunk with `prepare_image`
+    /// instead of being rejected.
+    ///
+    /// Images are sent in one request; GIFs and videos use the chunked upload with the
+    /// detected `media_category`. Returns the media id to attach to a tweet.
+    pub async fn upload_media(&self, path: &Path) -> Result<MediaId, TweetyError> {
+        self.upload_media_with_progress(path, |_, _| {}).await
+    }
+
+    /// Same as [`TweetyClient::upload_media`], calling `on_progress(bytes_sent, total_bytes)`
+    /// as the file is sent.
+    pub async fn upload_media_with_progress<F>(
+        &self,
+        path: &Path,
+        on_progress: F,
+    ) -> Result<MediaId, TweetyError>
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        let info = MediaInfo::from_path(path)?;
+
+        #[cfg(feature = "image")]
+        if info.category == MediaCategory::TweetImage && info.validate().is_err() {
+            let prepared = prepare_image(path)?;
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+            let file_name = format!("{}.{}", stem, prepared.extension());
+            return self
+                .upload_bytes_with_progress(prepared.bytes, file_name, on_progress)
+                .await;
+        }
+
+        info.validate()?;
+
+        match info.category {
+            MediaCategory::TweetImage => self.upload_file_with_progress(path, on_progress).await,
+            category => {
+                self.upload_chunked_with_progress(
+                    path,
+                    info.mime_type,
+                    Some(category.as_str()),
+                    on_progress,
+                )
+                .await
+            }
+        }
+    }
+}
@@ -1,10 +1,19 @@
 use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
-use reqwest::Method;
+use crate::api::query::{field_names, Query};
+use crate::types::ids::UserId;
+use crate::types::lang::Lang;
+use crate::types::meta::{Meta, Paginated};
+use crate::types::tweet::{
+    Annotation, ContextAnnotation, ContextDomainKind, EditControls, EngagementMetrics,
+    NonPublicMetrics, TweetPublicMetrics,
+};
+use crate::types::user::Withheld;
 use serde::{Deserialize, Serialize};
-use yaup::to_string as convert_query_to_string;
+use serde_json::{Map, Value};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub struct QueryParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -44,6 +53,12 @@ pub struct QueryParams {
     pub user_fields: Option<Vec<UserField>>, // List of enum values
 }
 
+impl Paginated for QueryParams {
+    fn set_page_token(&mut self, token: String) {
+        self.pagination_token = Some(token);
+    }
+}
+
 // Enum for the `expansions` parameter
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -67,6 +82,7 @@ pub enum ExpansionType {
     InReplyToUserId,
     #[serde(rename = "entities.note.mentions.username")]
     EntitiesNoteMentionsUsername,
+    #[serde(rename = "referenced_tweets.id")]
     ReferencedTweetsId,
     #[serde(rename = "referenced_tweets.id.author_id")]
     ReferencedTweetsIdAuthorId,
@@ -132,6 +148,7 @@ pub enum TweetField {
     InReplyToUserId,
     Lang,
     NonPublicMetrics,
+    NoteTweet,
     PublicMetrics,
     OrganicMetrics,
     PromotedMetrics,
@@ -143,6 +160,47 @@ pub enum TweetField {
     Withheld,
 }
 
+impl TweetField {
+    /// Whether the field is only returned with user context (OAuth 1.0a or an OAuth 2.0
+    /// user token), and only for the authenticated user's own tweets.
+    ///
+    /// App-only (bearer token) requests for these fields fail with a
+    /// [`TweetyError::ConfigError`] before they are sent.
+    pub fn requires_user_context(&self) -> bool {
+        matches!(
+            self,
+            TweetField::NonPublicMetrics | TweetField::OrganicMetrics | TweetField::PromotedMetrics
+        )
+    }
+}
+
+/// Link to the tweet `id` on x.com, see [`TweetData::url`].
+pub(crate) fn tweet_url(id: &str) -> String {
+    format!("https://x.com/i/web/status/{}", id)
+}
+
+/// Fails with [`TweetyError::ConfigError`] when `query` asks for `tweet.fields` that
+/// need user context, which app-only requests get an opaque 403 for.
+pub(crate) fn check_app_only_fields(query: &Query) -> Result<(), TweetyError> {
+    let Some(fields) = query.get("tweet.fields") else {
+        return Ok(());
+    };
+    let user_only: Vec<&str> = fields
+        .split(',')
+        .filter(|name| {
+            name.parse::<TweetField>()
+                .is_ok_and(|field| field.requires_user_context())
+        })
+        .collect();
+    if user_only.is_empty() {
+        return Ok(());
+    }
+    Err(TweetyError::ConfigError(format!(
+        "tweet.fields {} need user context (OAuth 1.0a or an OAuth 2.0 user token), not an app-only bearer token",
+        user_only.join(", ")
+    )))
+}
+
 // Enum for the `user_fields` parameter
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -155,6 +213,7 @@ pub enum UserField {
     MostRecentTweetId,
     Name,
     PinnedTweetId,
+    ProfileBannerUrl,
     ProfileImageUrl,
     Protected,
     PublicMetrics,
@@ -165,25 +224,54 @@ pub enum UserField {
     Withheld,
 }
 
+field_names!(
+    ExpansionType,
+    MediaField,
+    PlaceField,
+    PollField,
+    TweetField,
+    UserField
+);
+
 // Root Response Struct
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MentionsResponse {
-    pub data: Vec<TweetData>,       // Required field for tweet data
+    #[serde(default)]
+    pub data: Vec<TweetData>, // Empty when there are no mentions in range
     pub includes: Option<Includes>, // Optional field for included related data
-    pub meta: MetaData,             // Required field for metadata
+    pub meta: Meta,                 // Required field for metadata
 }
 
 // Struct for each Tweet in "data"
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct TweetData {
-    pub id: String,                                      // Required field for tweet ID
+    pub id: String,                                          // Required field for tweet ID
     pub edit_history_tweet_ids: Vec<String>, // Required field for edit history tweet IDs
     pub text: String,                        // Required field for tweet text
     pub author_id: Option<String>,           // Optional field for author ID
-    pub lang: Option<String>,                // Optional field for language
+    pub lang: Option<Lang>,                  // Optional field for language
     pub conversation_id: Option<String>,     // Optional field for conversation ID
     pub in_reply_to_user_id: Option<String>, // Optional field for in reply to user ID
     pub referenced_tweets: Option<Vec<ReferencedTweet>>, // Optional field for referenced tweets
+    pub attachments: Option<Attachments>,    // Optional field for attached polls and media
+    pub entities: Option<TweetEntities>,     // Optional field for entities parsed from the text
+    pub context_annotations: Option<Vec<ContextAnnotation>>, // Optional field for inferred topics
+    pub note_tweet: Option<NoteTweet>,       // Optional field for the full text of long posts
+    pub edit_controls: Option<EditControls>, // Optional field for the remaining edits
+    pub withheld: Option<Withheld>, // Optional field for countries the tweet is withheld in
+    pub public_metrics: Option<TweetPublicMetrics>,
+    /// Requires user context, see [`TweetField::requires_user_context`].
+    pub non_public_metrics: Option<NonPublicMetrics>,
+    /// Requires user context, see [`TweetField::requires_user_context`].
+    pub organic_metrics: Option<EngagementMetrics>,
+    /// Requires user context and only present on promoted tweets.
+    pub promoted_metrics: Option<EngagementMetrics>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
 }
 
 // Struct for "referenced_tweets"
@@ -193,20 +281,195 @@ pub struct ReferencedTweet {
     pub r#type: String, // Required field for referenced tweet type
 }
 
+impl TweetData {
+    /// Guesses the language of [`TweetData::full_text`] locally, see [`Lang::detect`].
+    #[cfg(feature = "lang-detect")]
+    pub fn detect_lang(&self) -> Option<Lang> {
+        Lang::detect(self.full_text())
+    }
+
+    /// Finds this tweet's poll in the `includes` of the same response.
+    ///
+    /// Needs `expansions=attachments.poll_ids`; returns `None` when the tweet has no poll
+    /// or the poll wasn't expanded.
+    pub fn poll<'a>(&self, includes: &'a Includes) -> Option<&'a Poll> {
+        let poll_id = self.attachments.as_ref()?.poll_ids.as_ref()?.first()?;
+        includes.polls.iter().find(|poll| &poll.id == poll_id)
+    }
+
+    /// The complete text of the tweet.
+    ///
+    /// Posts longer than 280 characters have a truncated `text`; their full text is in
+    /// `note_tweet`, which needs `tweet.fields=note_tweet`.
+    pub fn full_text(&self) -> &str {
+        self.note_tweet
+            .as_ref()
+            .map(|note| note.text.as_str())
+            .unwrap_or(&self.text)
+    }
+
+    /// The URL entities of [`TweetData::full_text`], in order. Needs
+    /// `tweet.fields=entities`, plus `note_tweet` for posts longer than 280 characters.
+    pub fn url_entities(&self) -> &[UrlDetail] {
+        let entities = match self.note_tweet {
+            Some(ref note) => note.entities.as_ref(),
+            None => self.entities.as_ref(),
+        };
+        entities
+            .and_then(|entities| entities.urls.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Where the tweet's t.co links point, in order, from its URL entities. See
+    /// [`TweetyClient::expand_urls`] to also follow links the entities don't cover.
+    pub fn expanded_urls(&self) -> Vec<&str> {
+        self.url_entities().iter().map(UrlDetail::target).collect()
+    }
+
+    /// Returns `true` when one of the tweet's context annotations is in `domain`.
+    ///
+    /// Needs `tweet.fields=context_annotations`.
+    pub fn has_context_domain(&self, domain: ContextDomainKind) -> bool {
+        self.context_annotations
+            .iter()
+            .flatten()
+            .any(|annotation| annotation.domain.kind() == domain)
+    }
+
+    /// Link to the tweet on x.com, which redirects to the author's handle.
+    pub fn url(&self) -> String {
+        tweet_url(&self.id)
+    }
+
+    /// Returns `true` for retweets. Needs `tweet.fields=referenced_tweets`.
+    pub fn is_retweet(&self) -> bool {
+        self.referenced_tweet_id("retweeted").is_some()
+    }
+
+    /// Returns `true` for replies. Needs `tweet.fields=in_reply_to_user_id` or
+    /// `tweet.fields=referenced_tweets`.
+    pub fn is_reply(&self) -> bool {
+        self.in_reply_to_user_id.is_some() || self.referenced_tweet_id("replied_to").is_some()
+    }
+
+    /// Id of the tweet this one quotes. Needs `tweet.fields=referenced_tweets`.
+    pub fn quoted_tweet_id(&self) -> Option<&str> {
+        self.referenced_tweet_id("quoted")
+    }
+
+    /// Hashtags of the complete text, without the `#`. Needs `tweet.fields=entities`,
+    /// and `tweet.fields=note_tweet` for posts longer than 280 characters.
+    pub fn hashtags(&self) -> Vec<&str> {
+        let entities = self
+            .note_tweet
+            .as_ref()
+            .and_then(|note| note.entities.as_ref())
+            .or(self.entities.as_ref());
+        entities
+            .and_then(|entities| entities.hashtags.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|hashtag| hashtag.tag.as_str())
+            .collect()
+    }
+
+    /// Keys of the attached media, to look up in `includes.media`. Needs
+    /// `tweet.fields=attachments`.
+    pub fn media_keys(&self) -> &[String] {
+        self.attachments
+            .as_ref()
+            .and_then(|attachments| attachments.media_keys.as_deref())
+            .unwrap_or_default()
+    }
+
+    fn referenced_tweet_id(&self, kind: &str) -> Option<&str> {
+        self.referenced_tweets
+            .iter()
+            .flatten()
+            .find(|referenced| referenced.r#type == kind)
+            .map(|referenced| referenced.id.as_str())
+    }
+}
+
+// Struct for "note_tweet" in TweetData
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NoteTweet {
+    pub text: String,                    // Required field for the untruncated text
+    pub entities: Option<TweetEntities>, // Optional field for entities in the full text
+}
+
+// Struct for "entities" in TweetData
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TweetEntities {
+    pub annotations: Option<Vec<Annotation>>, // Optional field for named entities
+    pub cashtags: Option<Vec<Cashtag>>,       // Optional field for cashtags
+    pub hashtags: Option<Vec<Hashtag>>,       // Optional field for hashtags
+    pub mentions: Option<Vec<Mention>>,       // Optional field for mentions
+    pub urls: Option<Vec<UrlDetail>>,         // Optional field for URLs
+}
+
+// Struct for "attachments"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachments {
+    pub poll_ids: Option<Vec<String>>,
+    pub media_keys: Option<Vec<String>>,
+}
+
 // Struct for "includes"
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Includes {
+    #[serde(default)]
     pub users: Vec<UserData>, // Optional field for included users data
+    #[serde(default)]
+    pub polls: Vec<Poll>, // Optional field for included polls, see `TweetData::poll`
+}
+
+// Struct for each Poll in "includes.polls"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poll {
+    pub id: String,
+    pub options: Vec<PollOption>,
+    pub duration_minutes: Option<u32>,
+    pub end_datetime: Option<String>, // ISO 8601
+    pub voting_status: Option<VotingStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollOption {
+    pub position: u32,
+    pub label: String,
+    pub votes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingStatus {
+    Open,
+    Closed,
+}
+
+impl Poll {
+    /// Total votes across all options.
+    pub fn total_votes(&self) -> u64 {
+        self.options.iter().map(|option| option.votes).sum()
+    }
 }
 
 // Struct for each User in "includes.users"
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct UserData {
     pub name: String,                   // Required field for user name
     pub id: String,                     // Required field for user ID
     pub entities: Option<UserEntities>, // Optional field for user entities
     pub username: String,               // Required field for user username
     pub created_at: Option<String>,     // Optional field for user creation time (ISO 8601)
+    pub withheld: Option<Withheld>,     // Optional field for countries the user is withheld in
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
 }
 
 // Struct for "entities" in UserData
@@ -230,6 +493,15 @@ pub struct UrlDetail {
     pub url: String,          // Required field for URL
     pub expanded_url: String, // Required field for expanded URL
     pub display_url: String,  // Required field for display URL
+    /// The end of the redirect chain from `expanded_url`, when X resolved it.
+    pub unwound_url: Option<String>,
+}
+
+impl UrlDetail {
+    /// `unwound_url` when X resolved it, else `expanded_url`.
+    pub fn target(&self) -> &str {
+        self.unwound_url.as_deref().unwrap_or(&self.expanded_url)
+    }
 }
 
 // Struct for "description" in UserEntities
@@ -252,9 +524,10 @@ pub struct Hashtag {
 // Struct for each mention in DescriptionEntity
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Mention {
-    pub start: u32,       // Required field for start position
-    pub end: u32,         // Required field for end position
-    pub username: String, // Required field for username
+    pub start: u32,         // Required field for start position
+    pub end: u32,           // Required field for end position
+    pub username: String,   // Required field for username
+    pub id: Option<String>, // Optional field for the mentioned user ID, only set in tweets
 }
 
 // Struct for each cashtag in DescriptionEntity
@@ -265,15 +538,6 @@ pub struct Cashtag {
     pub tag: String, // Required field for cashtag
 }
 
-// Struct for "meta" in the response
-#[derive(Debug, Serialize, Deserialize)]
-pub struct MetaData {
-    pub oldest_id: String,          // Required field for oldest tweet ID
-    pub newest_id: String,          // Required field for newest tweet ID
-    pub result_count: u32,          // Required field for result count
-    pub next_token: Option<String>, // Optional field for pagination token
-}
-
 impl TweetyClient {
     /// Authentication methods
     /// supported by this endpoint
@@ -283,21 +547,48 @@ impl TweetyClient {
     /// [Docs](https://developer.x.com/en/docs/x-api/tweets/timelines/api-reference/get-users-id-mentions)
     pub async fn get_users_mentions(
         &self,
-        user_id: &str,
+        user_id: impl Into<UserId>,
         query_params: Option<QueryParams>,
     ) -> Result<MentionsResponse, TweetyError> {
-        let mut base_url = format!("https://api.x.com/2/users/{}/mentions", user_id);
-        if let Some(query) = query_params {
-            let query_params = convert_query_to_string(&query)
-                .map_err(|e| TweetyError::SerializeError(e.to_string()))?;
-            base_url = format!("{}{}", base_url, query_params);
+        let user_id: UserId = user_id.into();
+        let query = match query_params {
+            Some(query) => Query::from_serialize(&query)?,
+            None => Query::new(),
+        };
+        match self
+            .send_request::<()>(&Endpoint::UserMentions(user_id), &query, None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<MentionsResponse>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
         }
-        match self.send_request::<()>(&base_url, Method::GET, None).await {
+    }
+
+    /// Returns the tweets `user_id` posted, newest first, including their replies and
+    /// retweets.
+    /// [Docs](https://developer.x.com/en/docs/x-api/tweets/timelines/api-reference/get-users-id-tweets)
+    pub async fn get_users_tweets(
+        &self,
+        user_id: impl Into<UserId>,
+        query_params: Option<QueryParams>,
+    ) -> Result<MentionsResponse, TweetyError> {
+        let user_id: UserId = user_id.into();
+        let query = match query_params {
+            Some(query) => Query::from_serialize(&query)?,
+            None => Query::new(),
+        };
+        match self
+            .send_request::<()>(&Endpoint::UserTweets(user_id), &query, None)
+            .await
+        {
             Ok(value) => match serde_json::from_value::<MentionsResponse>(value) {
                 Ok(data) => Ok(data),
-                Err(err) => Err(TweetyError::JsonParseError(err.to_string())),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
             },
-            Err(err) => Err(TweetyError::ApiError(err.to_string())),
+            Err(err) => Err(err),
         }
     }
 }
@@ -0,0 +1,371 @@
+//! Mirroring another account's tweets to the authenticated account.
+//!
+//! Enabled by the `mirror` cargo feature. A [`Mirror`] polls the timeline of a source
+//! account and posts each new tweet again as the authenticated account, as a copy, a
+//! quote or a retweet, see [`MirrorMode`]. Hooks decide which tweets are mirrored and
+//! rewrite their text on the way: [`Mirror::filter`], [`Mirror::rewrite_links`],
+//! [`Mirror::transform`] and [`Mirror::prefix`]. Replies and retweets of the source are
+//! skipped unless included.
+//!
+//! With a [`StateStore`], the id of the last mirrored tweet is saved after each one, so
+//! a restarted mirror picks up where it left off. A new mirror starts from the source's
+//! next tweet unless [`Mirror::replay_backlog`] is set.
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::mirror::{Mirror, MirrorMode};
+//! use tweety_rs::api::state::FileStateStore;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let mut mirror = Mirror::new(client, "2244994945")
+//!     .mode(MirrorMode::Copy)
+//!     .state_store(FileStateStore::new("mirror.json"), "mirror.since_id")
+//!     .filter(|tweet| !tweet.full_text().contains("#ad"))
+//!     .rewrite_links(|url| Some(url.replace("twitter.com", "x.com")))
+//!     .prefix("From @XDevelopers: ");
+//! mirror.run().await
+//! # }
+//! ```
+//!
+//! Tweets from elsewhere, such as a [`FilteredStream`] with a `from:` rule, are mirrored
+//! with [`Mirror::mirror_tweet`].
+//!
+//! [`FilteredStream`]: crate::api::filtered_stream::FilteredStream
+
+use crate::api::client::TweetyClient;
+use crate::api::conversation::compare_ids;
+use crate::api::dedup::SeenIds;
+use crate::api::error::TweetyError;
+use crate::api::mentions::{QueryParams, TweetData, TweetField};
+use crate::api::poller::{Cursor, PollSchedule};
+use crate::api::retry::{RetryClassifier, RetryDecision};
+use crate::api::shutdown::Shutdown;
+use crate::api::state::StateStore;
+use crate::types::ids::UserId;
+use crate::types::tweet::PostTweetParams;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default time between two polls of the source's timeline.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How many mirrored tweet ids are remembered to skip duplicates.
+const SEEN_CAPACITY: usize = 1024;
+
+type Filter = Arc<dyn Fn(&TweetData) -> bool + Send + Sync>;
+type Transform = Arc<dyn Fn(String, &TweetData) -> Option<String> + Send + Sync>;
+type LinkRewriter = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// How a [`Mirror`] posts the source's tweets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MirrorMode {
+    /// A new tweet with the rewritten text.
+    #[default]
+    Copy,
+    /// A quote of the tweet, with the rewritten text as the comment.
+    Quote,
+    /// A retweet. The text hooks don't apply, but the filters do.
+    Retweet,
+}
+
+/// Reposts the tweets of a source account as the authenticated account.
+pub struct Mirror {
+    client: TweetyClient,
+    source_id: String,
+    mode: MirrorMode,
+    schedule: PollSchedule,
+    replay_backlog: bool,
+    include_replies: bool,
+    include_retweets: bool,
+    filters: Vec<Filter>,
+    link_rewriter: Option<LinkRewriter>,
+    transforms: Vec<Transform>,
+    prefix: String,
+    /// The authenticated account, looked up for retweets.
+    user_id: Option<String>,
+    cursor: Cursor,
+    seen: SeenIds,
+}
+
+impl fmt::Debug for Mirror {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mirror")
+            .field("client", &self.client)
+            .field("source_id", &self.source_id)
+            .field("mode", &self.mode)
+            .field("poll_interval", &self.schedule.interval)
+            .field("include_replies", &self.include_replies)
+            .field("include_retweets", &self.include_retweets)
+            .field("prefix", &self.prefix)
+            .field("last_seen_id", &self.cursor.last_seen_id())
+            .field("shutdown", &self.schedule.shutdown)
+            .finish()
+    }
+}
+
+impl Mirror {
+    /// Mirrors the tweets of `source_id` with `client`.
+    pub fn new(client: TweetyClient, source_id: impl Into<UserId>) -> Self {
+        Mirror {
+            client,
+            source_id: source_id.into().into_inner(),
+            mode: MirrorMode::default(),
+            schedule: PollSchedule::new(DEFAULT_POLL_INTERVAL),
+            replay_backlog: false,
+            include_replies: false,
+            include_retweets: false,
+            filters: Vec::new(),
+            link_rewriter: None,
+            transforms: Vec::new(),
+            prefix: String::new(),
+            user_id: None,
+            cursor: Cursor::default(),
+            seen: SeenIds::new(SEEN_CAPACITY),
+        }
+    }
+
+    /// How the tweets are posted, [`MirrorMode::Copy`] by default.
+    pub fn mode(mut self, mode: MirrorMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Time between two polls, [`DEFAULT_POLL_INTERVAL`] by default.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.schedule.interval = interval;
+        self
+    }
+
+    /// Whether the first poll without a saved cursor mirrors the tweets already on the
+    /// source's timeline. Off by default.
+    pub fn replay_backlog(mut self, replay: bool) -> Self {
+        self.replay_backlog = replay;
+        self
+    }
+
+    /// Also mirrors the source's replies.
+    pub fn include_replies(mut self) -> Self {
+        self.include_replies = true;
+        self
+    }
+
+    /// Also mirrors the source's retweets.
+    pub fn include_retweets(mut self) -> Self {
+        self.include_retweets = true;
+        self
+    }
+
+    /// Only mirrors tweets `filter` returns `true` for. Several filters must all agree.
+    pub fn filter(mut self, filter: impl Fn(&TweetData) -> bool + Send + Sync + 'static) -> Self {
+        self.filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Replaces each t.co link in the text with what `rewrite` returns for its target,
+    /// or removes it on `None`. Without it, links are left as t.co links.
+    pub fn rewrite_links(
+        mut self,
+        rewrite: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.link_rewriter = Some(Arc::new(rewrite));
+        self
+    }
+
+    /// Rewrites the text, after the links. Transforms run in the order they were added,
+    /// each on the text the one before returned; `None` skips the tweet.
+    pub fn transform(
+        mut self,
+        transform: impl Fn(String, &TweetData) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.transforms.push(Arc::new(transform));
+        self
+    }
+
+    /// Puts `prefix` in front of the text, after the transforms.
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    /// Saves the id of the last mirrored tweet under `key` in `store` after each
+    /// tweet, and resumes from the id stored there.
+    pub fn state_store(mut self, store: impl StateStore + 'static, key: &str) -> Self {
+        self.cursor.state_store(Arc::new(store), key);
+        self
+    }
+
+    /// Decides which polling errors [`Mirror::run`] keeps going after. Defaults to
+    /// [`DefaultRetryClassifier`](crate::api::retry::DefaultRetryClassifier).
+    pub fn retry_classifier(mut self, classifier: impl RetryClassifier + 'static) -> Self {
+        self.schedule.retry_classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Makes [`Mirror::run`] return once `shutdown` is requested, after the poll in
+    /// progress has been mirrored and its cursor saved.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.schedule.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// The id of the newest source tweet handled so far.
+    pub fn last_seen_id(&self) -> Option<&str> {
+        self.cursor.last_seen_id()
+    }
+
+    /// The text `tweet` would be mirrored with, or `None` when the hooks skip it.
+    /// Retweets use no text, but are skipped the same way.
+    pub fn render(&self, tweet: &TweetData) -> Option<String> {
+        let kind = |kind: &str| {
+            tweet
+                .referenced_tweets
+                .iter()
+                .flatten()
+                .any(|referenced| referenced.r#type == kind)
+        };
+        let reply = kind("replied_to") || tweet.in_reply_to_user_id.is_some();
+        if (reply && !self.include_replies) || (kind("retweeted") && !self.include_retweets) {
+            return None;
+        }
+        if !self.filters.iter().all(|filter| filter(tweet)) {
+            return None;
+        }
+
+        let mut text = tweet.full_text().to_string();
+        if let Some(rewrite) = &self.link_rewriter {
+            for url in tweet.url_entities() {
+                let replacement = rewrite(url.target()).unwrap_or_default();
+                text = text.replace(&url.url, &replacement);
+            }
+            text = text.trim().to_string();
+        }
+        for transform in &self.transforms {
+            text = transform(text, tweet)?;
+        }
+        Some(format!("{}{}", self.prefix, text))
+    }
+
+    /// Mirrors `tweet`, returning whether it was posted. Tweets already mirrored, or
+    /// older than the saved cursor, are skipped.
+    ///
+    /// A post failing with an error the [`Mirror::retry_classifier`] deems permanent,
+    /// such as X refusing a duplicate, is logged and the tweet skipped. Other errors are
+    /// returned, and the tweet is tried again on the next poll.
+    pub async fn mirror_tweet(&mut self, tweet: &TweetData) -> Result<bool, TweetyError> {
+        self.cursor.load().await?;
+        let old = self
+            .cursor
+            .last_seen_id()
+            .is_some_and(|last| compare_ids(&tweet.id, last).is_le());
+        if old || self.seen.contains(&tweet.id) {
+            return Ok(false);
+        }
+
+        let posted = match self.render(tweet) {
+            Some(text) => match self.post(tweet, &text).await {
+                Ok(()) => true,
+                Err(err)
+                    if self.schedule.retry_classifier.classify(&err)
+                        == RetryDecision::Permanent =>
+                {
+                    log::warn!("mirroring tweet {} failed: {}", tweet.id, err);
+                    false
+                }
+                Err(err) => return Err(err),
+            },
+            None => false,
+        };
+        self.seen.insert(&tweet.id);
+        self.cursor.advance(tweet.id.clone()).await?;
+        Ok(posted)
+    }
+
+    /// Polls until a shutdown is requested, see [`Mirror::shutdown_on`], or a request
+    /// fails with an error the [`Mirror::retry_classifier`] deems permanent. Other
+    /// errors are logged and the mirror keeps going.
+    pub async fn run(&mut self) -> Result<(), TweetyError> {
+        let _running = self.schedule.running();
+
+        while !self.schedule.is_shutdown() {
+            let result = self.poll_once().await;
+            if self
+                .schedule
+                .wait(&self.client, result, "mirroring")
+                .await?
+            {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches the source's tweets since the last poll and mirrors them oldest first,
+    /// returning how many were posted.
+    pub async fn poll_once(&mut self) -> Result<usize, TweetyError> {
+        let client = &self.client;
+        let source_id = self.source_id.as_str();
+        let (tweets, _) = self
+            .cursor
+            .fetch_new(self.replay_backlog, |since_id, pagination_token| {
+                client.get_users_tweets(source_id, Some(timeline_query(since_id, pagination_token)))
+            })
+            .await?;
+
+        let mut posted = 0;
+        for tweet in tweets {
+            if self.schedule.is_shutdown() {
+                break;
+            }
+            if self.mirror_tweet(&tweet).await? {
+                posted += 1;
+            }
+        }
+        Ok(posted)
+    }
+
+    async fn post(&mut self, tweet: &TweetData, text: &str) -> Result<(), TweetyError> {
+        match self.mode {
+            MirrorMode::Copy => {
+                self.client.post_tweet(text, None).await?;
+            }
+            MirrorMode::Quote => {
+                let params = PostTweetParams::builder().quote(tweet.id.as_str()).build();
+                self.client.post_tweet(text, Some(params)).await?;
+            }
+            MirrorMode::Retweet => {
+                let user_id = self.resolve_user_id().await?;
+                self.client.retweet(user_id, tweet.id.as_str()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn resolve_user_id(&mut self) -> Result<String, TweetyError> {
+        if let Some(ref id) = self.user_id {
+            return Ok(id.clone());
+        }
+
+        let verified = self.client.verify_credentials().await?;
+        let user = verified.user.ok_or_else(|| {
+            TweetyError::ConfigError("Mirror needs user context to retweet".to_string())
+        })?;
+        self.user_id = Some(user.id.clone());
+        Ok(user.id)
+    }
+}
+
+fn timeline_query(since_id: Option<String>, pagination_token: Option<String>) -> QueryParams {
+    QueryParams {
+        max_results: Some(100),
+        since_id,
+        pagination_token,
+        tweet_fields: Some(vec![
+            TweetField::Entities,
+            TweetField::InReplyToUserId,
+            TweetField::NoteTweet,
+            TweetField::ReferencedTweets,
+        ]),
+        ..Default::default()
+    }
+}
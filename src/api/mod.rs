@@ -1,15 +1,78 @@
+#[cfg(feature = "ads")]
+pub mod ads;
+#[cfg(feature = "analytics")]
+pub mod analytics;
+pub mod backfill;
+pub mod blocklist;
+#[cfg(feature = "bluesky")]
+pub mod bluesky;
 pub mod bookmark;
+pub mod bot;
+pub mod circuit_breaker;
 pub mod client;
+pub mod communities;
+#[cfg(feature = "streams")]
+pub mod compliance;
+pub mod compose;
+pub mod config;
+pub mod conversation;
+pub mod crosspost;
+pub mod dedup;
+#[cfg(feature = "dm")]
 pub mod direct_messages;
+pub mod endpoint;
 pub mod error;
 pub mod favourites;
+#[cfg(feature = "feed")]
+pub mod feed;
+#[cfg(feature = "streams")]
+pub mod filtered_stream;
 pub mod followers;
 pub mod following;
 pub mod hide_replies;
+pub mod http;
+pub mod hydrate;
 pub mod like;
+pub mod links;
+#[cfg(feature = "lists")]
+pub mod lists;
+#[cfg(feature = "mastodon")]
+pub mod mastodon;
+#[cfg(feature = "media-upload")]
+pub mod media;
 pub mod mentions;
+#[cfg(feature = "mirror")]
+pub mod mirror;
+pub mod oauth2;
+pub mod observer;
+#[cfg(feature = "outbox")]
+pub mod outbox;
+pub(crate) mod poller;
+pub(crate) mod query;
+pub mod rate_limit;
+#[cfg(feature = "request-log")]
+pub mod request_log;
+pub mod retry;
 pub mod retweets;
+#[cfg(feature = "schedule")]
+pub mod schedule;
 pub mod search;
+pub mod secret;
+pub mod shutdown;
+pub mod signing;
+#[cfg(feature = "spaces")]
+pub mod spaces;
+pub mod state;
+#[cfg(feature = "streams")]
+pub mod stream;
+pub mod text;
+pub mod tier;
 pub mod tweet;
+#[cfg(feature = "media-upload")]
 pub mod uploads;
+pub mod usage;
 pub mod user;
+#[cfg(feature = "v1")]
+pub mod v1;
+#[cfg(feature = "webhooks")]
+pub mod webhook;
@@ -0,0 +1,610 @@
+//! OAuth 2.0 tokens and scopes, and the Authorization Code flow with PKCE (`oauth2-flow`
+//! feature, on by default).
+//!
+//! [Docs](https://developer.x.com/en/docs/authentication/oauth-2-0/authorization-code)
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::oauth2::{OAuth2FlowBuilder, Scope};
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn example() -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let flow = OAuth2FlowBuilder::new("your_client_id", "https://example.com/callback")
+//!     .scopes([Scope::TweetRead, Scope::TweetWrite, Scope::UsersRead, Scope::OfflineAccess])
+//!     .build();
+//!
+//! let request = flow.authorization_request();
+//! println!("Visit {}", request.url);
+//!
+//! // ...after X redirects back with `?code=...&state=...`
+//! let token = flow.exchange_code("code_from_redirect", &request.code_verifier).await?;
+//! let client = TweetyClient::from_oauth2_token(token);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::client::{AuthMode, TweetyClient};
+use crate::api::error::TweetyError;
+use crate::api::secret::SecretString;
+#[cfg(feature = "oauth2-flow")]
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+#[cfg(feature = "oauth2-flow")]
+use base64::Engine;
+#[cfg(feature = "oauth2-flow")]
+use rand::distributions::Alphanumeric;
+#[cfg(feature = "oauth2-flow")]
+use rand::Rng;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "oauth2-flow")]
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+use url::Url;
+
+#[cfg(feature = "oauth2-flow")]
+const AUTHORIZE_URL: &str = "https://x.com/i/oauth2/authorize";
+#[cfg(feature = "oauth2-flow")]
+const TOKEN_URL: &str = "https://api.x.com/2/oauth2/token";
+
+/// OAuth 2.0 scopes understood by the X API.
+///
+/// [Docs](https://developer.x.com/en/docs/authentication/oauth-2-0/authorization-code)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+    #[serde(rename = "tweet.read")]
+    TweetRead,
+    #[serde(rename = "tweet.write")]
+    TweetWrite,
+    #[serde(rename = "tweet.moderate.write")]
+    TweetModerateWrite,
+    #[serde(rename = "users.read")]
+    UsersRead,
+    #[serde(rename = "follows.read")]
+    FollowsRead,
+    #[serde(rename = "follows.write")]
+    FollowsWrite,
+    #[serde(rename = "offline.access")]
+    OfflineAccess,
+    #[serde(rename = "space.read")]
+    SpaceRead,
+    #[serde(rename = "mute.read")]
+    MuteRead,
+    #[serde(rename = "mute.write")]
+    MuteWrite,
+    #[serde(rename = "like.read")]
+    LikeRead,
+    #[serde(rename = "like.write")]
+    LikeWrite,
+    #[serde(rename = "list.read")]
+    ListRead,
+    #[serde(rename = "list.write")]
+    ListWrite,
+    #[serde(rename = "block.read")]
+    BlockRead,
+    #[serde(rename = "block.write")]
+    BlockWrite,
+    #[serde(rename = "bookmark.read")]
+    BookmarkRead,
+    #[serde(rename = "bookmark.write")]
+    BookmarkWrite,
+    #[serde(rename = "dm.read")]
+    DmRead,
+    #[serde(rename = "dm.write")]
+    DmWrite,
+    #[serde(rename = "media.write")]
+    MediaWrite,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 21] = [
+        Scope::TweetRead,
+        Scope::TweetWrite,
+        Scope::TweetModerateWrite,
+        Scope::UsersRead,
+        Scope::FollowsRead,
+        Scope::FollowsWrite,
+        Scope::OfflineAccess,
+        Scope::SpaceRead,
+        Scope::MuteRead,
+        Scope::MuteWrite,
+        Scope::LikeRead,
+        Scope::LikeWrite,
+        Scope::ListRead,
+        Scope::ListWrite,
+        Scope::BlockRead,
+        Scope::BlockWrite,
+        Scope::BookmarkRead,
+        Scope::BookmarkWrite,
+        Scope::DmRead,
+        Scope::DmWrite,
+        Scope::MediaWrite,
+    ];
+
+    /// The scope as it appears in the `scope` parameter, e.g. `tweet.read`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::TweetRead => "tweet.read",
+            Scope::TweetWrite => "tweet.write",
+            Scope::TweetModerateWrite => "tweet.moderate.write",
+            Scope::UsersRead => "users.read",
+            Scope::FollowsRead => "follows.read",
+            Scope::FollowsWrite => "follows.write",
+            Scope::OfflineAccess => "offline.access",
+            Scope::SpaceRead => "space.read",
+            Scope::MuteRead => "mute.read",
+            Scope::MuteWrite => "mute.write",
+            Scope::LikeRead => "like.read",
+            Scope::LikeWrite => "like.write",
+            Scope::ListRead => "list.read",
+            Scope::ListWrite => "list.write",
+            Scope::BlockRead => "block.read",
+            Scope::BlockWrite => "block.write",
+            Scope::BookmarkRead => "bookmark.read",
+            Scope::BookmarkWrite => "bookmark.write",
+            Scope::DmRead => "dm.read",
+            Scope::DmWrite => "dm.write",
+            Scope::MediaWrite => "media.write",
+        }
+    }
+
+    /// Scopes an OAuth 2.0 user token needs to call `method` on the v2 API `path`.
+    ///
+    /// This mirrors the scope tables in the X API reference; unknown paths need no scopes.
+    pub fn required_for(method: &Method, path: &str) -> Vec<Scope> {
+        let segments: Vec<&str> = path
+            .trim_start_matches("/2/")
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let read = *method == Method::GET;
+        let has = |segment: &str| segments.contains(&segment);
+
+        let (read_scope, write_scope) = if has("bookmarks") {
+            (Scope::BookmarkRead, Scope::BookmarkWrite)
+        } else if has("likes") || has("liked_tweets") || has("liking_users") {
+            (Scope::LikeRead, Scope::LikeWrite)
+        } else if has("following") || has("followers") {
+            (Scope::FollowsRead, Scope::FollowsWrite)
+        } else if has("blocking") {
+            (Scope::BlockRead, Scope::BlockWrite)
+        } else if has("muting") {
+            (Scope::MuteRead, Scope::MuteWrite)
+        } else if has("lists") || has("followed_lists") || has("owned_lists") {
+            (Scope::ListRead, Scope::ListWrite)
+        } else if has("dm_events") || has("dm_conversations") {
+            (Scope::DmRead, Scope::DmWrite)
+        } else if has("spaces") {
+            return vec![Scope::SpaceRead, Scope::TweetRead, Scope::UsersRead];
+        } else if has("hidden") {
+            return vec![
+                Scope::TweetRead,
+                Scope::TweetModerateWrite,
+                Scope::UsersRead,
+            ];
+        } else if segments.first() == Some(&"tweets") || has("retweets") || has("mentions") {
+            (Scope::TweetRead, Scope::TweetWrite)
+        } else if matches!(segments.first(), Some(&"users") | Some(&"communities")) {
+            return vec![Scope::TweetRead, Scope::UsersRead];
+        } else {
+            return Vec::new();
+        };
+
+        // Every family also needs `tweet.read` and `users.read`.
+        let mut scopes = vec![Scope::TweetRead, Scope::UsersRead];
+        let family_scope = if read { read_scope } else { write_scope };
+        if !scopes.contains(&family_scope) {
+            scopes.push(family_scope);
+        }
+        scopes
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = TweetyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scope::ALL
+            .iter()
+            .find(|scope| scope.as_str() == s)
+            .copied()
+            .ok_or_else(|| TweetyError::ConfigError(format!("unknown OAuth2 scope `{}`", s)))
+    }
+}
+
+/// An OAuth 2.0 user access token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuth2Token {
+    pub access_token: SecretString,
+    pub refresh_token: Option<SecretString>,
+    /// Expiry as seconds since the Unix epoch.
+    pub expires_at: Option<u64>,
+    pub scopes: Vec<Scope>,
+}
+
+impl OAuth2Token {
+    pub fn new(access_token: &str, scopes: Vec<Scope>) -> Self {
+        OAuth2Token {
+            access_token: access_token.into(),
+            refresh_token: None,
+            expires_at: None,
+            scopes,
+        }
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// Returns the scopes from `required` that this token wasn't granted.
+    pub fn missing_scopes(&self, required: &[Scope]) -> Vec<Scope> {
+        required
+            .iter()
+            .filter(|scope| !self.has_scope(**scope))
+            .copied()
+            .collect()
+    }
+}
+
+/// Token endpoint response.
+#[cfg(feature = "oauth2-flow")]
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+    scope: Option<String>,
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "oauth2-flow")]
+impl From<TokenResponse> for OAuth2Token {
+    fn from(response: TokenResponse) -> Self {
+        let now = unix_now();
+
+        OAuth2Token {
+            access_token: response.access_token.into(),
+            refresh_token: response.refresh_token.map(SecretString::from),
+            expires_at: response.expires_in.map(|expires_in| now + expires_in),
+            scopes: response
+                .scope
+                .unwrap_or_default()
+                .split_whitespace()
+                .filter_map(|scope| scope.parse().ok())
+                .collect(),
+        }
+    }
+}
+
+/// The URL to send the user to, plus the values needed to finish the flow.
+///
+/// Persist `state` and `code_verifier` until X redirects the user back.
+#[cfg(feature = "oauth2-flow")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationRequest {
+    pub url: String,
+    pub state: String,
+    pub code_verifier: SecretString,
+}
+
+/// Builds an [`OAuth2Flow`].
+#[cfg(feature = "oauth2-flow")]
+#[derive(Debug)]
+pub struct OAuth2FlowBuilder {
+    client_id: String,
+    client_secret: Option<SecretString>,
+    redirect_uri: String,
+    scopes: Vec<Scope>,
+    http: Option<reqwest::Client>,
+}
+
+#[cfg(feature = "oauth2-flow")]
+impl OAuth2FlowBuilder {
+    pub fn new(client_id: &str, redirect_uri: &str) -> Self {
+        OAuth2FlowBuilder {
+            client_id: client_id.to_string(),
+            client_secret: None,
+            redirect_uri: redirect_uri.to_string(),
+            scopes: Vec::new(),
+            http: None,
+        }
+    }
+
+    /// Sets the client secret of a confidential client, sent as HTTP Basic auth.
+    pub fn client_secret(mut self, client_secret: &str) -> Self {
+        self.client_secret = Some(client_secret.into());
+        self
+    }
+
+    pub fn scope(mut self, scope: Scope) -> Self {
+        if !self.scopes.contains(&scope) {
+            self.scopes.push(scope);
+        }
+        self
+    }
+
+    pub fn scopes(mut self, scopes: impl IntoIterator<Item = Scope>) -> Self {
+        for scope in scopes {
+            self = self.scope(scope);
+        }
+        self
+    }
+
+    /// Sends token requests over `client`'s connection pool instead of a pool of the
+    /// flow's own.
+    pub fn connection_pool(mut self, client: &TweetyClient) -> Self {
+        self.http = Some(client.http.clone());
+        self
+    }
+
+    pub fn build(self) -> OAuth2Flow {
+        OAuth2Flow {
+            client_id: self.client_id,
+            client_secret: self.client_secret,
+            redirect_uri: self.redirect_uri,
+            scopes: self.scopes,
+            http: self.http.unwrap_or_default(),
+        }
+    }
+}
+
+/// A configured OAuth 2.0 Authorization Code flow with PKCE.
+#[cfg(feature = "oauth2-flow")]
+#[derive(Debug, Clone)]
+pub struct OAuth2Flow {
+    client_id: String,
+    client_secret: Option<SecretString>,
+    redirect_uri: String,
+    scopes: Vec<Scope>,
+    /// Built once, so refreshes reuse connections to the token endpoint.
+    http: reqwest::Client,
+}
+
+#[cfg(feature = "oauth2-flow")]
+impl OAuth2Flow {
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    /// Creates the authorization URL with a fresh `state` and S256 code challenge.
+    pub fn authorization_request(&self) -> AuthorizationRequest {
+        let state = random_string(32);
+        let code_verifier = random_string(64);
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+        let scope = self
+            .scopes
+            .iter()
+            .map(Scope::as_str)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut url = Url::parse(AUTHORIZE_URL).expect("authorize URL is valid");
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("scope", &scope)
+            .append_pair("state", &state)
+            .append_pair("code_challenge", &code_challenge)
+            .append_pair("code_challenge_method", "S256");
+
+        AuthorizationRequest {
+            url: url.to_string(),
+            state,
+            code_verifier: code_verifier.into(),
+        }
+    }
+
+    /// Exchanges the `code` from the redirect for a user access token.
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &SecretString,
+    ) -> Result<OAuth2Token, TweetyError> {
+        self.request_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.redirect_uri),
+            ("code_verifier", code_verifier.expose_secret()),
+        ])
+        .await
+    }
+
+    /// Trades a refresh token (granted with [`Scope::OfflineAccess`]) for a new access token.
+    pub async fn refresh(&self, refresh_token: &SecretString) -> Result<OAuth2Token, TweetyError> {
+        self.request_token(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.expose_secret()),
+        ])
+        .await
+    }
+
+    async fn request_token(&self, params: &[(&str, &str)]) -> Result<OAuth2Token, TweetyError> {
+        let mut form: Vec<(&str, &str)> = params.to_vec();
+        form.push(("client_id", &self.client_id));
+
+        let mut request = self.http.post(TOKEN_URL).form(&form);
+        if let Some(secret) = &self.client_secret {
+            request = request.basic_auth(&self.client_id, Some(secret.expose_secret()));
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(TweetyError::AuthFlowError(format!(
+                "HTTP {}: {}",
+                status, body
+            )));
+        }
+
+        let token = response.json::<TokenResponse>().await?;
+
+        Ok(token.into())
+    }
+}
+
+#[cfg(feature = "oauth2-flow")]
+fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Expiry state of the client's OAuth 2.0 token, published by
+/// [`TweetyClient::watch_token_expiry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenExpiry {
+    /// There is no token, it doesn't expire, or its expiry is further away than the lead time.
+    Valid,
+    /// The token expires within the lead time; refresh or rotate it now.
+    ExpiringSoon { expires_at: u64 },
+    /// The token has expired and requests will fail with 401 until it is replaced.
+    Expired { expires_at: u64 },
+}
+
+impl TokenExpiry {
+    fn at(expires_at: Option<u64>, lead: Duration, now: u64) -> Self {
+        match expires_at {
+            Some(expires_at) if now >= expires_at => TokenExpiry::Expired { expires_at },
+            Some(expires_at) if now + lead.as_secs() >= expires_at => {
+                TokenExpiry::ExpiringSoon { expires_at }
+            }
+            _ => TokenExpiry::Valid,
+        }
+    }
+}
+
+impl TweetyClient {
+    /// Returns a copy of the OAuth 2.0 user token, if the client has one.
+    pub fn oauth2_token(&self) -> Option<OAuth2Token> {
+        self.credentials.oauth2_token.borrow().clone()
+    }
+
+    /// Replaces the OAuth 2.0 user token for this client and all of its clones,
+    /// e.g. after [`OAuth2Flow::refresh`].
+    pub fn set_oauth2_token(&self, token: OAuth2Token) {
+        self.credentials.oauth2_token.send_replace(Some(token));
+    }
+
+    /// Publishes the token's [`TokenExpiry`] on a watch channel, switching to
+    /// `ExpiringSoon` once the token is within `lead` of expiring.
+    ///
+    /// The state is recomputed whenever [`TweetyClient::set_oauth2_token`] replaces the
+    /// token. The background task stops when every receiver or the client is dropped.
+    /// Must be called from within a Tokio runtime.
+    pub fn watch_token_expiry(&self, lead: Duration) -> watch::Receiver<TokenExpiry> {
+        let mut tokens = self.credentials.oauth2_token.subscribe();
+        let (sender, receiver) = watch::channel(TokenExpiry::Valid);
+
+        tokio::spawn(async move {
+            loop {
+                let expires_at = tokens
+                    .borrow_and_update()
+                    .as_ref()
+                    .and_then(|token| token.expires_at);
+                let now = unix_now();
+                let state = TokenExpiry::at(expires_at, lead, now);
+
+                sender.send_if_modified(|current| {
+                    let modified = *current != state;
+                    *current = state;
+                    modified
+                });
+
+                // Wake up at the next state transition, if there is one.
+                let wake_at = match (state, expires_at) {
+                    (TokenExpiry::Valid, Some(expires_at)) => {
+                        Some(expires_at.saturating_sub(lead.as_secs()))
+                    }
+                    (TokenExpiry::ExpiringSoon { expires_at }, _) => Some(expires_at),
+                    _ => None,
+                };
+                let sleep = async {
+                    match wake_at {
+                        Some(wake_at) => {
+                            tokio::time::sleep(Duration::from_secs(wake_at.saturating_sub(now)))
+                                .await
+                        }
+                        None => std::future::pending().await,
+                    }
+                };
+
+                tokio::select! {
+                    _ = sleep => {}
+                    changed = tokens.changed() => {
+                        if changed.is_err() {
+                            break;
+                        }
+                    }
+                    _ = sender.closed() => break,
+                }
+            }
+        });
+
+        receiver
+    }
+
+    /// Calls `callback` whenever the token becomes `ExpiringSoon` or `Expired`.
+    ///
+    /// See [`TweetyClient::watch_token_expiry`]; must be called from within a Tokio runtime.
+    pub fn on_token_expiry<F>(&self, lead: Duration, callback: F)
+    where
+        F: Fn(TokenExpiry) + Send + 'static,
+    {
+        let mut receiver = self.watch_token_expiry(lead);
+
+        tokio::spawn(async move {
+            while receiver.changed().await.is_ok() {
+                let state = *receiver.borrow_and_update();
+                if state != TokenExpiry::Valid {
+                    callback(state);
+                }
+            }
+        });
+    }
+
+    /// Returns the scopes the stored OAuth 2.0 token lacks for `method` on `path`.
+    ///
+    /// Always empty for OAuth 1.0a and app-only clients, which aren't scoped.
+    pub fn missing_scopes(&self, method: &Method, path: &str) -> Vec<Scope> {
+        if self.auth_mode() != Some(AuthMode::OAuth2User) {
+            return Vec::new();
+        }
+        match &*self.credentials.oauth2_token.borrow() {
+            Some(token) => token.missing_scopes(&Scope::required_for(method, path)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Logs a warning before calling an endpoint the OAuth 2.0 token isn't scoped for.
+    pub(crate) fn warn_missing_scopes(&self, method: &Method, url: &Url) {
+        let missing = self.missing_scopes(method, url.path());
+
+        if !missing.is_empty() {
+            let names: Vec<&str> = missing.iter().map(Scope::as_str).collect();
+            log::warn!(
+                "OAuth2 token is missing scopes [{}] for {} {}",
+                names.join(", "),
+                method,
+                url.path()
+            );
+        }
+    }
+}
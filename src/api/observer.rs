@@ -0,0 +1,123 @@
+//! Events the client reports while it works, for logs and UIs.
+//!
+//! Long waits are the main reason: once an endpoint family's rate limit runs out, a
+//! [`DmEventPaginator`] or a polling loop such as [`MentionListener::run`] can sit idle
+//! for up to 15 minutes. While it does, [`ClientEvent::RateLimitWait`] is reported when
+//! the wait starts and every [`WAIT_PROGRESS_INTERVAL`] after. Set an [`Observer`] with
+//! [`TweetyClient::with_observer`]; a closure works too:
+//!
+//! ```rust
+//! use tweety_rs::api::observer::ClientEvent;
+//! use tweety_rs::TweetyClient;
+//!
+//! let client = TweetyClient::from_bearer_token("token").with_observer(|event: &ClientEvent| {
+//!     if let ClientEvent::RateLimitWait { family, remaining } = event {
+//!         eprintln!("{:?} rate limited, resuming in {}s", family, remaining.as_secs());
+//!     }
+//! });
+//! # let _ = client;
+//! ```
+//!
+//! Every response is reported too, as [`ClientEvent::Response`], and the posts it counts
+//! against the monthly caps as [`ClientEvent::PostsConsumed`]. Both carry the tag of the
+//! [`TweetyClient::tagged`] handle that sent the request, so an application with several
+//! features on one client can tell which of them spends its rate limits and caps:
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use std::sync::Mutex;
+//! use tweety_rs::api::observer::ClientEvent;
+//! use tweety_rs::TweetyClient;
+//!
+//! let posts_by_tag = Mutex::new(HashMap::<String, u64>::new());
+//! let client = TweetyClient::from_bearer_token("token").with_observer(move |event: &ClientEvent| {
+//!     if let ClientEvent::PostsConsumed { tag, posts, .. } = event {
+//!         let tag = tag.clone().unwrap_or_default();
+//!         *posts_by_tag.lock().unwrap().entry(tag).or_default() += posts;
+//!     }
+//! });
+//! let search = client.tagged("search");
+//! # let _ = search;
+//! ```
+//!
+//! [`DmEventPaginator`]: crate::api::direct_messages::DmEventPaginator
+//! [`MentionListener::run`]: crate::api::bot::MentionListener::run
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::RateLimitFamily;
+use crate::api::rate_limit::RateLimitStatus;
+use crate::api::shutdown::{self, Shutdown};
+use crate::api::usage::UsageKind;
+use reqwest::StatusCode;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How often a wait reports its progress.
+pub const WAIT_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Something the client did that is worth surfacing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ClientEvent {
+    /// Waiting for `family`'s rate limit, with `remaining` left to wait.
+    RateLimitWait {
+        family: RateLimitFamily,
+        remaining: Duration,
+    },
+    /// A response from an endpoint of `family`, with the rate-limit window it reported.
+    /// `tag` is the tag of the handle that sent the request, see
+    /// [`TweetyClient::tagged`].
+    Response {
+        tag: Option<String>,
+        family: RateLimitFamily,
+        status: StatusCode,
+        rate_limit: Option<RateLimitStatus>,
+    },
+    /// A successful response that counts `posts` of `kind` against the monthly caps.
+    PostsConsumed {
+        tag: Option<String>,
+        kind: UsageKind,
+        posts: u64,
+    },
+}
+
+/// Receives the client's [`ClientEvent`]s. Called inline, so it should return quickly.
+pub trait Observer: Send + Sync {
+    fn on_event(&self, event: &ClientEvent);
+}
+
+impl<F> Observer for F
+where
+    F: Fn(&ClientEvent) + Send + Sync,
+{
+    fn on_event(&self, event: &ClientEvent) {
+        self(event)
+    }
+}
+
+impl TweetyClient {
+    /// Sleeps like [`shutdown::sleep`]. When the wait is for `family`'s rate limit, its
+    /// progress is reported as [`ClientEvent::RateLimitWait`].
+    pub(crate) async fn wait_for_rate_limit(
+        &self,
+        family: Option<RateLimitFamily>,
+        shutdown: Option<&Shutdown>,
+        wait: Duration,
+    ) -> bool {
+        let Some(family) = family else {
+            return shutdown::sleep(shutdown, wait).await;
+        };
+
+        let deadline = Instant::now() + wait;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return shutdown.is_some_and(Shutdown::is_requested);
+            }
+            self.emit(&ClientEvent::RateLimitWait { family, remaining });
+            if shutdown::sleep(shutdown, remaining.min(WAIT_PROGRESS_INTERVAL)).await {
+                return true;
+            }
+        }
+    }
+}
@@ -0,0 +1,615 @@
+//! A durable queue of tweets to post.
+//!
+//! Enabled by the `outbox` cargo feature. [`Outbox::enqueue_tweet`] writes the tweet to
+//! an [`OutboxStore`] in a single write before it returns, so a tweet the application
+//! decided to post is posted even if the process crashes right after. Entries have a
+//! key chosen by the caller, and enqueueing a key again returns the entry already
+//! there, so a step that crashed before noting it had enqueued can simply run again.
+//! [`Outbox::run`] posts the queued tweets in order, retrying failures as a
+//! [`RetryClassifier`] decides, with exponential backoff.
+//!
+//! Posting is exactly-once-ish: an entry is marked as sending before its request goes
+//! out, so after a crash or a timeout mid-request nobody knows whether X got it. The
+//! retry then either posts the tweet or gets X's duplicate-tweet refusal, which marks
+//! the entry as posted. Only a tweet deleted in between would be posted twice.
+//!
+//! [`Outbox::new`] keeps the queue as one JSON value in a [`StateStore`]. With a
+//! [`FileStateStore`] that is a file flushed to disk and replaced atomically on every
+//! change, which rewrites the whole queue each time and suits queues of up to a few
+//! hundred entries. `SqliteOutboxStore`, behind the `outbox-sqlite` feature, keeps
+//! one row per entry in a SQLite database instead; another database can be plugged in
+//! by implementing [`OutboxStore`]. Run one worker per store.
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::outbox::Outbox;
+//! use tweety_rs::api::state::FileStateStore;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let outbox = Outbox::new(client, FileStateStore::new("outbox.json"), "outbox");
+//! outbox
+//!     .enqueue_tweet("release-1.4.0", "tweety-rs 1.4.0 is out!", None)
+//!     .await?;
+//!
+//! let worker = outbox.clone();
+//! tokio::spawn(async move { worker.run().await });
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`FileStateStore`]: crate::api::state::FileStateStore
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::oauth2::unix_now;
+use crate::api::query::Query;
+use crate::api::retry::{DefaultRetryClassifier, RetryClassifier, RetryDecision};
+use crate::api::shutdown::{self, Shutdown};
+use crate::api::state::StateStore;
+use crate::types::tweet::PostTweetParams;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+#[cfg(feature = "outbox-sqlite")]
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Attempts an entry gets before it is marked as failed, unless changed with
+/// [`Outbox::max_attempts`].
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(15 * 60);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Where an [`OutboxEntry`] stands.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum OutboxStatus {
+    /// Waiting for its first attempt or a retry.
+    Pending,
+    /// A request is out, or was when the process stopped.
+    Sending,
+    /// Posted. `tweet_id` is `None` when a retry found X already had the tweet.
+    Posted { tweet_id: Option<String> },
+    /// Given up on, with the last error.
+    Failed { error: String },
+}
+
+/// A queued tweet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub key: String,
+    /// The `POST /2/tweets` body.
+    pub body: Value,
+    pub status: OutboxStatus,
+    /// Requests sent for the entry so far.
+    pub attempts: u32,
+    /// Unix seconds before which the entry isn't retried.
+    pub not_before: u64,
+}
+
+/// Where an [`Outbox`] keeps its entries.
+pub trait OutboxStore: Send + Sync {
+    /// Stores `entry` unless an entry with its key is stored already, and returns the
+    /// stored entry.
+    fn insert(&self, entry: OutboxEntry) -> BoxFuture<'_, Result<OutboxEntry, TweetyError>>;
+
+    /// The entry stored under `key`.
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<OutboxEntry>, TweetyError>>;
+
+    /// Every entry, in the order they were queued.
+    fn entries(&self) -> BoxFuture<'_, Result<Vec<OutboxEntry>, TweetyError>>;
+
+    /// Replaces the stored entry with `entry`'s key.
+    fn update<'a>(&'a self, entry: &'a OutboxEntry) -> BoxFuture<'a, Result<(), TweetyError>>;
+
+    /// Removes the posted entries, returning how many there were.
+    fn remove_posted(&self) -> BoxFuture<'_, Result<usize, TweetyError>>;
+}
+
+/// Keeps the whole queue as one JSON value under `key` in a [`StateStore`].
+struct StateStoreOutbox {
+    store: Arc<dyn StateStore>,
+    key: String,
+}
+
+impl StateStoreOutbox {
+    async fn load(&self) -> Result<Vec<OutboxEntry>, TweetyError> {
+        match self.store.get(&self.key).await? {
+            Some(value) => Ok(serde_json::from_str(&value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn save(&self, entries: &[OutboxEntry]) -> Result<(), TweetyError> {
+        let value = serde_json::to_string(entries)
+            .map_err(|err| TweetyError::SerializeError(err.to_string()))?;
+        self.store.set(&self.key, &value).await
+    }
+}
+
+impl OutboxStore for StateStoreOutbox {
+    fn insert(&self, entry: OutboxEntry) -> BoxFuture<'_, Result<OutboxEntry, TweetyError>> {
+        Box::pin(async move {
+            let mut entries = self.load().await?;
+            if let Some(stored) = entries.iter().find(|stored| stored.key == entry.key) {
+                return Ok(stored.clone());
+            }
+            entries.push(entry.clone());
+            self.save(&entries).await?;
+            Ok(entry)
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<OutboxEntry>, TweetyError>> {
+        Box::pin(async move {
+            let entries = self.load().await?;
+            Ok(entries.into_iter().find(|entry| entry.key == key))
+        })
+    }
+
+    fn entries(&self) -> BoxFuture<'_, Result<Vec<OutboxEntry>, TweetyError>> {
+        Box::pin(self.load())
+    }
+
+    fn update<'a>(&'a self, entry: &'a OutboxEntry) -> BoxFuture<'a, Result<(), TweetyError>> {
+        Box::pin(async move {
+            let mut entries = self.load().await?;
+            if let Some(stored) = entries.iter_mut().find(|stored| stored.key == entry.key) {
+                *stored = entry.clone();
+                self.save(&entries).await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn remove_posted(&self) -> BoxFuture<'_, Result<usize, TweetyError>> {
+        Box::pin(async move {
+            let mut entries = self.load().await?;
+            let before = entries.len();
+            entries.retain(|entry| !matches!(entry.status, OutboxStatus::Posted { .. }));
+            let removed = before - entries.len();
+            if removed > 0 {
+                self.save(&entries).await?;
+            }
+            Ok(removed)
+        })
+    }
+}
+
+/// Keeps the queue in a SQLite database, one row per entry, written with full
+/// durability. Enabled by the `outbox-sqlite` cargo feature.
+///
+/// Queries run on tokio's blocking thread pool. Clones share the connection.
+#[cfg(feature = "outbox-sqlite")]
+#[derive(Clone)]
+pub struct SqliteOutboxStore {
+    connection: Arc<std::sync::Mutex<rusqlite::Connection>>,
+}
+
+#[cfg(feature = "outbox-sqlite")]
+impl fmt::Debug for SqliteOutboxStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteOutboxStore").finish_non_exhaustive()
+    }
+}
+
+/// The columns of an entry: key, body, status, attempts and not_before.
+#[cfg(feature = "outbox-sqlite")]
+type SqliteRow = (String, String, String, u32, i64);
+
+#[cfg(feature = "outbox-sqlite")]
+const SELECT_ENTRIES: &str = "SELECT key, body, status, attempts, not_before FROM outbox";
+
+#[cfg(feature = "outbox-sqlite")]
+impl SqliteOutboxStore {
+    /// Opens the database at `path`, creating it and the `outbox` table when missing.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, TweetyError> {
+        let connection = rusqlite::Connection::open(path).map_err(sqlite_error)?;
+        Self::init(connection)
+    }
+
+    /// A database that lives in memory until the store is dropped, for tests.
+    pub fn open_in_memory() -> Result<Self, TweetyError> {
+        let connection = rusqlite::Connection::open_in_memory().map_err(sqlite_error)?;
+        Self::init(connection)
+    }
+
+    fn init(connection: rusqlite::Connection) -> Result<Self, TweetyError> {
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                 PRAGMA synchronous = FULL;
+                 CREATE TABLE IF NOT EXISTS outbox (
+                     seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                     key TEXT NOT NULL UNIQUE,
+                     body TEXT NOT NULL,
+                     status TEXT NOT NULL,
+                     attempts INTEGER NOT NULL,
+                     not_before INTEGER NOT NULL
+                 );",
+            )
+            .map_err(sqlite_error)?;
+        Ok(SqliteOutboxStore {
+            connection: Arc::new(std::sync::Mutex::new(connection)),
+        })
+    }
+
+    /// Runs `query` on the connection, off the async threads.
+    async fn call<T, F>(&self, query: F) -> Result<T, TweetyError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut rusqlite::Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let connection = Arc::clone(&self.connection);
+        tokio::task::spawn_blocking(move || {
+            let mut connection = connection
+                .lock()
+                .map_err(|_| TweetyError::FileIOError("outbox database poisoned".to_string()))?;
+            query(&mut connection).map_err(sqlite_error)
+        })
+        .await
+        .map_err(|err| TweetyError::FileIOError(err.to_string()))?
+    }
+}
+
+#[cfg(feature = "outbox-sqlite")]
+impl OutboxStore for SqliteOutboxStore {
+    fn insert(&self, entry: OutboxEntry) -> BoxFuture<'_, Result<OutboxEntry, TweetyError>> {
+        Box::pin(async move {
+            let (key, body, status, attempts, not_before) = to_row(&entry)?;
+            let row = self
+                .call(move |connection| {
+                    let transaction = connection.transaction()?;
+                    transaction.execute(
+                        "INSERT OR IGNORE INTO outbox (key, body, status, attempts, not_before)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        rusqlite::params![key, body, status, attempts, not_before],
+                    )?;
+                    let row = transaction.query_row(
+                        &format!("{} WHERE key = ?1", SELECT_ENTRIES),
+                        [&key],
+                        read_row,
+                    )?;
+                    transaction.commit()?;
+                    Ok(row)
+                })
+                .await?;
+            from_row(row)
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<OutboxEntry>, TweetyError>> {
+        let key = key.to_string();
+        Box::pin(async move {
+            let row = self
+                .call(move |connection| {
+                    let mut statement =
+                        connection.prepare(&format!("{} WHERE key = ?1", SELECT_ENTRIES))?;
+                    let mut rows = statement.query_map([&key], read_row)?;
+                    rows.next().transpose()
+                })
+                .await?;
+            row.map(from_row).transpose()
+        })
+    }
+
+    fn entries(&self) -> BoxFuture<'_, Result<Vec<OutboxEntry>, TweetyError>> {
+        Box::pin(async move {
+            let rows = self
+                .call(|connection| {
+                    let mut statement =
+                        connection.prepare(&format!("{} ORDER BY seq", SELECT_ENTRIES))?;
+                    let rows = statement.query_map([], read_row)?;
+                    rows.collect::<rusqlite::Result<Vec<SqliteRow>>>()
+                })
+                .await?;
+            rows.into_iter().map(from_row).collect()
+        })
+    }
+
+    fn update<'a>(&'a self, entry: &'a OutboxEntry) -> BoxFuture<'a, Result<(), TweetyError>> {
+        Box::pin(async move {
+            let (key, body, status, attempts, not_before) = to_row(entry)?;
+            self.call(move |connection| {
+                connection.execute(
+                    "UPDATE outbox SET body = ?2, status = ?3, attempts = ?4, not_before = ?5
+                     WHERE key = ?1",
+                    rusqlite::params![key, body, status, attempts, not_before],
+                )
+            })
+            .await?;
+            Ok(())
+        })
+    }
+
+    fn remove_posted(&self) -> BoxFuture<'_, Result<usize, TweetyError>> {
+        Box::pin(self.call(|connection| {
+            connection.execute(
+                "DELETE FROM outbox WHERE json_extract(status, '$.state') = 'posted'",
+                [],
+            )
+        }))
+    }
+}
+
+#[cfg(feature = "outbox-sqlite")]
+fn sqlite_error(err: rusqlite::Error) -> TweetyError {
+    TweetyError::FileIOError(format!("outbox database: {}", err))
+}
+
+#[cfg(feature = "outbox-sqlite")]
+fn read_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SqliteRow> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+    ))
+}
+
+#[cfg(feature = "outbox-sqlite")]
+fn to_row(entry: &OutboxEntry) -> Result<SqliteRow, TweetyError> {
+    Ok((
+        entry.key.clone(),
+        entry.body.to_string(),
+        serde_json::to_string(&entry.status)
+            .map_err(|err| TweetyError::SerializeError(err.to_string()))?,
+        entry.attempts,
+        entry.not_before as i64,
+    ))
+}
+
+#[cfg(feature = "outbox-sqlite")]
+fn from_row(
+    (key, body, status, attempts, not_before): SqliteRow,
+) -> Result<OutboxEntry, TweetyError> {
+    Ok(OutboxEntry {
+        key,
+        body: serde_json::from_str(&body)?,
+        status: serde_json::from_str(&status)?,
+        attempts,
+        not_before: not_before as u64,
+    })
+}
+
+/// Posts the tweets queued in an [`OutboxStore`]. Clones share the queue.
+#[derive(Clone)]
+pub struct Outbox {
+    client: TweetyClient,
+    store: Arc<dyn OutboxStore>,
+    /// Serializes this process's read-modify-write cycles on the queue.
+    lock: Arc<Mutex<()>>,
+    max_attempts: u32,
+    retry_backoff: Duration,
+    poll_interval: Duration,
+    retry_classifier: Arc<dyn RetryClassifier>,
+    shutdown: Option<Shutdown>,
+}
+
+impl fmt::Debug for Outbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Outbox")
+            .field("client", &self.client)
+            .field("max_attempts", &self.max_attempts)
+            .field("retry_backoff", &self.retry_backoff)
+            .field("poll_interval", &self.poll_interval)
+            .field("shutdown", &self.shutdown)
+            .finish()
+    }
+}
+
+impl Outbox {
+    /// Keeps the queue as one JSON value under `key` in `store` and posts it with
+    /// `client`.
+    pub fn new(client: TweetyClient, store: impl StateStore + 'static, key: &str) -> Self {
+        Outbox::with_store(
+            client,
+            StateStoreOutbox {
+                store: Arc::new(store),
+                key: key.to_string(),
+            },
+        )
+    }
+
+    /// Keeps the queue in `store`, such as a `SqliteOutboxStore`, and posts it with
+    /// `client`.
+    pub fn with_store(client: TweetyClient, store: impl OutboxStore + 'static) -> Self {
+        Outbox {
+            client,
+            store: Arc::new(store),
+            lock: Arc::default(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            retry_classifier: Arc::new(DefaultRetryClassifier),
+            shutdown: None,
+        }
+    }
+
+    /// Marks entries as failed after `max_attempts` requests, at least one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Waits `backoff` before the first retry of an entry, doubling for each one after,
+    /// up to 15 minutes.
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// How often [`Outbox::run`] looks for entries to post.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Decides which failed posts are retried. Defaults to [`DefaultRetryClassifier`].
+    pub fn retry_classifier(mut self, classifier: impl RetryClassifier + 'static) -> Self {
+        self.retry_classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Makes [`Outbox::run`] return once `shutdown` is requested, after the post in
+    /// flight is recorded.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// Queues `text`, with `params` as [`TweetyClient::post_tweet`] takes them, under
+    /// `key`, and returns the entry once it is stored. When `key` is queued already,
+    /// returns that entry and changes nothing.
+    pub async fn enqueue_tweet(
+        &self,
+        key: &str,
+        text: &str,
+        params: Option<PostTweetParams>,
+    ) -> Result<OutboxEntry, TweetyError> {
+        let body = match params {
+            Some(params) => params.to_json(text),
+            None => json!({ "text": text }),
+        };
+
+        let _guard = self.lock.lock().await;
+        self.store
+            .insert(OutboxEntry {
+                key: key.to_string(),
+                body,
+                status: OutboxStatus::Pending,
+                attempts: 0,
+                not_before: 0,
+            })
+            .await
+    }
+
+    /// Every entry, in the order they were queued.
+    pub async fn entries(&self) -> Result<Vec<OutboxEntry>, TweetyError> {
+        let _guard = self.lock.lock().await;
+        self.store.entries().await
+    }
+
+    /// Drops the posted entries from the store, returning how many there were. Their
+    /// keys can be queued again after.
+    pub async fn remove_posted(&self) -> Result<usize, TweetyError> {
+        let _guard = self.lock.lock().await;
+        self.store.remove_posted().await
+    }
+
+    /// Posts until a shutdown is requested, see [`Outbox::shutdown_on`]. Returns early
+    /// only when the store fails.
+    pub async fn run(&self) -> Result<(), TweetyError> {
+        let _running = self.shutdown.as_ref().map(Shutdown::running);
+
+        loop {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_requested) {
+                return Ok(());
+            }
+            self.process_once().await?;
+            if shutdown::sleep(self.shutdown.as_ref(), self.poll_interval).await {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Attempts every entry that is due once, returning how many were posted.
+    pub async fn process_once(&self) -> Result<usize, TweetyError> {
+        let now = unix_now();
+        let due: Vec<String> = self
+            .entries()
+            .await?
+            .into_iter()
+            .filter(|entry| {
+                matches!(entry.status, OutboxStatus::Pending | OutboxStatus::Sending)
+                    && entry.not_before <= now
+            })
+            .map(|entry| entry.key)
+            .collect();
+
+        let mut posted = 0;
+        for key in due {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_requested) {
+                break;
+            }
+            let Some(entry) = self
+                .update(&key, |entry| {
+                    entry.status = OutboxStatus::Sending;
+                    entry.attempts += 1;
+                })
+                .await?
+            else {
+                continue;
+            };
+
+            let result = self
+                .client
+                .send_request(&Endpoint::CreateTweet, &Query::new(), Some(entry.body))
+                .await;
+            let (status, not_before) = match result {
+                Ok(value) => {
+                    let tweet_id = value["data"]["id"].as_str().map(str::to_string);
+                    (OutboxStatus::Posted { tweet_id }, 0)
+                }
+                // An earlier attempt got through before its response was lost.
+                Err(TweetyError::DuplicateTweet { .. }) if entry.attempts > 1 => {
+                    (OutboxStatus::Posted { tweet_id: None }, 0)
+                }
+                Err(err) => self.after_failure(&err, entry.attempts),
+            };
+            if matches!(status, OutboxStatus::Posted { .. }) {
+                posted += 1;
+            }
+            self.update(&key, |entry| {
+                entry.status = status;
+                entry.not_before = not_before;
+            })
+            .await?;
+        }
+
+        Ok(posted)
+    }
+
+    /// The status and retry time of an entry whose `attempts`th request failed.
+    fn after_failure(&self, err: &TweetyError, attempts: u32) -> (OutboxStatus, u64) {
+        let failed = OutboxStatus::Failed {
+            error: err.to_string(),
+        };
+        let backoff = self
+            .retry_backoff
+            .saturating_mul(1 << attempts.saturating_sub(1).min(16))
+            .min(MAX_RETRY_BACKOFF);
+
+        let wait = match self.retry_classifier.classify(err) {
+            RetryDecision::Permanent => return (failed, 0),
+            RetryDecision::Retry => backoff,
+            RetryDecision::RetryAfter(retry_after) => retry_after.max(backoff),
+        };
+        if attempts >= self.max_attempts {
+            return (failed, 0);
+        }
+        log::warn!("posting a queued tweet failed: {}", err);
+        (OutboxStatus::Pending, unix_now() + wait.as_secs())
+    }
+
+    async fn update(
+        &self,
+        key: &str,
+        change: impl FnOnce(&mut OutboxEntry),
+    ) -> Result<Option<OutboxEntry>, TweetyError> {
+        let _guard = self.lock.lock().await;
+        let Some(mut entry) = self.store.get(key).await? else {
+            return Ok(None);
+        };
+        change(&mut entry);
+        self.store.update(&entry).await?;
+        Ok(Some(entry))
+    }
+}
@@ -0,0 +1,195 @@
+//! The parts shared by the loops that poll the API and act on what's new.
+//!
+//! A [`PollSchedule`] runs the loop: it waits the interval between two polls, longer
+//! when an error asks for it or hit a rate limit, gives up on the errors its
+//! [`RetryClassifier`] deems permanent and stops once a shutdown is requested. A
+//! [`Cursor`] keeps the id of the newest tweet handled, and fetches the tweets after it.
+
+use crate::api::client::TweetyClient;
+use crate::api::conversation::compare_ids;
+use crate::api::error::TweetyError;
+use crate::api::mentions::{MentionsResponse, TweetData, UserData};
+use crate::api::retry::{DefaultRetryClassifier, RetryClassifier, RetryDecision};
+use crate::api::shutdown::{RunningGuard, Shutdown};
+use crate::api::state::{self, StateStore};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// When a loop polls, which errors it keeps going after, and when it stops.
+pub(crate) struct PollSchedule {
+    pub(crate) interval: Duration,
+    pub(crate) retry_classifier: Arc<dyn RetryClassifier>,
+    pub(crate) shutdown: Option<Shutdown>,
+}
+
+impl PollSchedule {
+    pub(crate) fn new(interval: Duration) -> Self {
+        PollSchedule {
+            interval,
+            retry_classifier: Arc::new(DefaultRetryClassifier),
+            shutdown: None,
+        }
+    }
+
+    /// Counts the loop as running until the guard is dropped, see [`Shutdown::shutdown`].
+    pub(crate) fn running(&self) -> Option<RunningGuard> {
+        self.shutdown.as_ref().map(Shutdown::running)
+    }
+
+    pub(crate) fn is_shutdown(&self) -> bool {
+        self.shutdown.as_ref().is_some_and(Shutdown::is_requested)
+    }
+
+    /// Waits for the poll after one that ended with `result`, `true` when a shutdown was
+    /// requested meanwhile. An error the retry classifier deems permanent is returned;
+    /// others are logged as `what` failing and the wait is stretched to their retry
+    /// delay or rate limit.
+    pub(crate) async fn wait<T>(
+        &self,
+        client: &TweetyClient,
+        result: Result<T, TweetyError>,
+        what: &str,
+    ) -> Result<bool, TweetyError> {
+        let mut wait = self.interval;
+        let mut rate_limited = None;
+        if let Err(err) = result {
+            match self.retry_classifier.classify(&err) {
+                RetryDecision::Retry => {}
+                RetryDecision::RetryAfter(retry_after) => wait = wait.max(retry_after),
+                RetryDecision::Permanent => return Err(err),
+            }
+            rate_limited = err.rate_limit_family();
+            log::warn!("{} failed: {}", what, err);
+        }
+
+        Ok(client
+            .wait_for_rate_limit(rate_limited, self.shutdown.as_ref(), wait)
+            .await)
+    }
+}
+
+/// The id of the newest tweet handled, saved to a file or a [`StateStore`] as it moves.
+#[derive(Default)]
+pub(crate) struct Cursor {
+    last_seen_id: Option<String>,
+    file: Option<PathBuf>,
+    store: Option<(Arc<dyn StateStore>, String)>,
+    loaded: bool,
+}
+
+impl Cursor {
+    pub(crate) fn last_seen_id(&self) -> Option<&str> {
+        self.last_seen_id.as_deref()
+    }
+
+    /// Resumes from the id saved in `path`, if any, and saves the id there as it moves.
+    pub(crate) fn persist_to(&mut self, path: &Path) -> Result<(), TweetyError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                let id = contents.trim();
+                if !id.is_empty() {
+                    self.last_seen_id = Some(id.to_string());
+                }
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(TweetyError::FileIOError(err.to_string())),
+        }
+
+        self.file = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Saves the id under `key` in `store` as it moves, and resumes from the id stored
+    /// there when nothing else set one.
+    pub(crate) fn state_store(&mut self, store: Arc<dyn StateStore>, key: &str) {
+        self.store = Some((store, key.to_string()));
+    }
+
+    /// Reads the id from the state store, once, unless one is already set.
+    pub(crate) async fn load(&mut self) -> Result<(), TweetyError> {
+        if self.loaded {
+            return Ok(());
+        }
+        if let (None, Some((store, key))) = (&self.last_seen_id, &self.store) {
+            self.last_seen_id = store.get(key).await?.filter(|id| !id.is_empty());
+        }
+        self.loaded = true;
+        Ok(())
+    }
+
+    /// Moves to `id` and saves it, unless the cursor is already past it.
+    pub(crate) async fn advance(&mut self, id: String) -> Result<(), TweetyError> {
+        let newer = match self.last_seen_id {
+            Some(ref last) => compare_ids(&id, last).is_gt(),
+            None => true,
+        };
+        if !newer {
+            return Ok(());
+        }
+
+        if let Some(ref path) = self.file {
+            state::replace_synced(path, id.as_bytes())
+                .map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+        }
+        if let Some((ref store, ref key)) = self.store {
+            store.set(key, &id).await?;
+        }
+        self.last_seen_id = Some(id);
+        Ok(())
+    }
+
+    /// The tweets after the cursor, oldest first, and the users included with them.
+    /// `fetch` gets the `since_id` and pagination token of each page.
+    ///
+    /// Without a cursor, and unless `replay_backlog`, the first poll only moves the
+    /// cursor to the newest tweet and returns nothing, so a new loop starts with the
+    /// tweets posted after it.
+    pub(crate) async fn fetch_new<F, Fut>(
+        &mut self,
+        replay_backlog: bool,
+        mut fetch: F,
+    ) -> Result<(Vec<TweetData>, Vec<UserData>), TweetyError>
+    where
+        F: FnMut(Option<String>, Option<String>) -> Fut,
+        Fut: Future<Output = Result<MentionsResponse, TweetyError>>,
+    {
+        self.load().await?;
+
+        if self.last_seen_id.is_none() && !replay_backlog {
+            let response = fetch(None, None).await?;
+            if let Some(newest) = response
+                .data
+                .into_iter()
+                .map(|tweet| tweet.id)
+                .max_by(|a, b| compare_ids(a, b))
+            {
+                self.advance(newest).await?;
+            }
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let mut tweets = Vec::new();
+        let mut users = Vec::new();
+        let mut pagination_token = None;
+        loop {
+            let response = fetch(self.last_seen_id.clone(), pagination_token).await?;
+            tweets.extend(response.data);
+            users.extend(
+                response
+                    .includes
+                    .map(|includes| includes.users)
+                    .unwrap_or_default(),
+            );
+
+            match response.meta.next_token {
+                Some(token) => pagination_token = Some(token),
+                None => break,
+            }
+        }
+        tweets.sort_by(|a, b| compare_ids(&a.id, &b.id));
+        Ok((tweets, users))
+    }
+}
@@ -0,0 +1,166 @@
+//! Helpers shared by the endpoint query strings.
+//!
+//! Every endpoint builds its URL with [`build_url`], so list-valued parameters render
+//! the way the API expects: a single `tweet.fields=a,b,c`, with the commas left
+//! unencoded and no repeated keys.
+
+use crate::api::error::TweetyError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Query parameters, rendered in the order they were added.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Query(Vec<(String, String)>);
+
+impl Query {
+    pub(crate) fn new() -> Self {
+        Query::default()
+    }
+
+    pub(crate) fn push(&mut self, key: &str, value: impl ToString) -> &mut Self {
+        self.0.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub(crate) fn push_opt<V: ToString>(&mut self, key: &str, value: Option<V>) -> &mut Self {
+        if let Some(value) = value {
+            self.push(key, value);
+        }
+        self
+    }
+
+    /// Adds `key` with the API names of `fields` joined by commas; nothing when empty.
+    pub(crate) fn push_fields<T: Serialize>(&mut self, key: &str, fields: &[T]) -> &mut Self {
+        if !fields.is_empty() {
+            self.push(key, join_fields(fields));
+        }
+        self
+    }
+
+    /// Collects the fields of a params struct. Lists become comma-separated values and
+    /// `None`s are left out; field names come from the struct's serde attributes.
+    pub(crate) fn from_serialize<T: Serialize>(params: &T) -> Result<Self, TweetyError> {
+        let Value::Object(fields) = serde_json::to_value(params)? else {
+            return Err(TweetyError::SerializeError(
+                "query parameters must be a struct".to_string(),
+            ));
+        };
+
+        let mut query = Query::new();
+        for (key, value) in fields {
+            if let Some(value) = render_value(&value)? {
+                query.push(&key, value);
+            }
+        }
+
+        Ok(query)
+    }
+
+    pub(crate) fn extend(&mut self, other: Query) -> &mut Self {
+        self.0.extend(other.0);
+        self
+    }
+
+    /// The value of the first `key` added.
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// `key=value` pairs joined with `&`, without a leading `?`.
+    pub(crate) fn to_query_string(&self) -> String {
+        self.0
+            .iter()
+            .map(|(key, value)| format!("{}={}", encode_value(key), encode_list(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Appends `query` to `base`, adding the `?` only when there is something to append.
+pub(crate) fn build_url(base: &str, query: &Query) -> String {
+    if query.is_empty() {
+        base.to_string()
+    } else {
+        format!("{}?{}", base, query.to_query_string())
+    }
+}
+
+fn render_value(value: &Value) -> Result<Option<String>, TweetyError> {
+    match value {
+        Value::Null => Ok(None),
+        Value::String(value) => Ok(Some(value.clone())),
+        Value::Bool(_) | Value::Number(_) => Ok(Some(value.to_string())),
+        Value::Array(values) => {
+            let mut items = Vec::with_capacity(values.len());
+            for value in values {
+                items.extend(render_value(value)?);
+            }
+            Ok((!items.is_empty()).then(|| items.join(",")))
+        }
+        Value::Object(_) => Err(TweetyError::SerializeError(
+            "nested objects can't be sent as query parameters".to_string(),
+        )),
+    }
+}
+
+/// Serializes each field enum to its API name and joins them with commas.
+pub(crate) fn join_fields<T: Serialize>(fields: &[T]) -> String {
+    fields.iter().map(field_name).collect::<Vec<_>>().join(",")
+}
+
+/// The name a field or expansion enum is sent as.
+pub(crate) fn field_name<T: Serialize>(field: &T) -> String {
+    serde_json::to_string(field)
+        .unwrap()
+        .trim_matches('"')
+        .to_string()
+}
+
+/// Reads a field or expansion enum back from the name it is sent as.
+pub(crate) fn parse_field_name<T: DeserializeOwned>(
+    kind: &str,
+    name: &str,
+) -> Result<T, TweetyError> {
+    serde_json::from_value(Value::String(name.to_string()))
+        .map_err(|_| TweetyError::ConfigError(format!("unknown {} `{}`", kind, name)))
+}
+
+/// Implements `Display` and `FromStr` for field and expansion enums with the names
+/// their serde derives use, so a value read from a CLI flag or a config file is
+/// exactly the one sent in the query.
+macro_rules! field_names {
+    ($($name:ident),* $(,)?) => {$(
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&$crate::api::query::field_name(self))
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = $crate::api::error::TweetyError;
+
+            fn from_str(name: &str) -> Result<Self, Self::Err> {
+                $crate::api::query::parse_field_name(stringify!($name), name)
+            }
+        }
+    )*};
+}
+pub(crate) use field_names;
+
+/// Percent-encodes a free-form value such as a search query.
+pub(crate) fn encode_value(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// Like [`encode_value`], but keeps the commas that separate list items readable.
+fn encode_list(value: &str) -> String {
+    encode_value(value).replace("%2C", ",")
+}
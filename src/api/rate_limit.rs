@@ -0,0 +1,127 @@
+//! The rate-limit state X reports with every response.
+//!
+//! The client reads the `x-rate-limit-*` headers of each response and keeps the latest
+//! [`RateLimitStatus`] of every [`RateLimitFamily`] in a `tokio::sync::watch` channel.
+//! [`TweetyClient::rate_limits`] subscribes to it, so a dashboard or a scheduler can
+//! react as soon as a quota runs low:
+//!
+//! ```rust,no_run
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) {
+//! let mut rate_limits = client.rate_limits();
+//! tokio::spawn(async move {
+//!     while rate_limits.changed().await.is_ok() {
+//!         for (family, status) in rate_limits.borrow_and_update().iter() {
+//!             println!("{:?}: {}", family, status);
+//!         }
+//!     }
+//! });
+//! # }
+//! ```
+//!
+//! The state is shared by the clones of a client, and kept apart for the handles
+//! [`TweetyClient::on_behalf_of`] returns, which count against another user's limits.
+//!
+//! [`TweetyClient::rate_limits`]: crate::api::client::TweetyClient::rate_limits
+//! [`TweetyClient::on_behalf_of`]: crate::api::client::TweetyClient::on_behalf_of
+
+use crate::api::endpoint::RateLimitFamily;
+use crate::api::retry::RATE_LIMIT_WAIT;
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Share of a window that can be left before [`RateLimitStatus::pace`] starts spacing
+/// requests out.
+pub const PACING_THRESHOLD: f64 = 0.5;
+
+/// The latest status of every family the client has had a response from.
+pub type RateLimits = HashMap<RateLimitFamily, RateLimitStatus>;
+
+/// A rate-limit window, as of the last response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Requests allowed per window.
+    pub limit: u32,
+    /// Requests left in the current window.
+    pub remaining: u32,
+    /// When the window resets.
+    pub reset: SystemTime,
+}
+
+impl RateLimitStatus {
+    /// Reads the `x-rate-limit-limit`, `x-rate-limit-remaining` and
+    /// `x-rate-limit-reset` headers; `None` unless all three are present.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| -> Option<u64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+
+        Some(RateLimitStatus {
+            limit: u32::try_from(header("x-rate-limit-limit")?).ok()?,
+            remaining: u32::try_from(header("x-rate-limit-remaining")?).ok()?,
+            reset: UNIX_EPOCH + Duration::from_secs(header("x-rate-limit-reset")?),
+        })
+    }
+
+    /// Time left until the window resets, zero once it has.
+    pub fn resets_in(&self) -> Duration {
+        self.reset
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+    }
+
+    /// Whether the window is used up and hasn't reset yet.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == 0 && !self.resets_in().is_zero()
+    }
+
+    /// How long to wait before the next request so the window lasts until it resets.
+    ///
+    /// Nothing while more than [`PACING_THRESHOLD`] of the window is left, then the time
+    /// to the reset spread evenly over the requests left, and the whole time to the
+    /// reset once the window is used up.
+    pub fn pace(&self) -> Duration {
+        let resets_in = self.resets_in();
+        if self.remaining == 0 || resets_in.is_zero() {
+            return resets_in;
+        }
+        if f64::from(self.remaining) > f64::from(self.limit) * PACING_THRESHOLD {
+            return Duration::ZERO;
+        }
+        resets_in / self.remaining
+    }
+}
+
+impl fmt::Display for RateLimitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} remaining, resets in {}s",
+            self.remaining,
+            self.limit,
+            self.resets_in().as_secs()
+        )
+    }
+}
+
+/// How long a 429 asks to wait: the `Retry-After` header, in seconds or as an HTTP
+/// date, then the time to `x-rate-limit-reset`, then [`RATE_LIMIT_WAIT`].
+pub(crate) fn retry_after(headers: &HeaderMap) -> Duration {
+    let header = |name: &str| headers.get(name)?.to_str().ok().map(str::trim);
+
+    if let Some(value) = header("retry-after") {
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Duration::from_secs(seconds);
+        }
+        if let Ok(date) = httpdate::parse_http_date(value) {
+            return date.duration_since(SystemTime::now()).unwrap_or_default();
+        }
+    }
+    if let Some(reset) = header("x-rate-limit-reset").and_then(|value| value.parse().ok()) {
+        return (UNIX_EPOCH + Duration::from_secs(reset))
+            .duration_since(SystemTime::now())
+            .unwrap_or_default();
+    }
+    RATE_LIMIT_WAIT
+}
@@ -0,0 +1,278 @@
+//! Logging the requests the client sends, through the [`log`] crate, with credentials
+//! stripped.
+//!
+//! With [`TweetyClient::with_request_log`], every request and the status of its
+//! response are logged at `debug` level under the [`LOG_TARGET`] target. [`Verbosity`]
+//! picks how much of the request goes in. At any verbosity, `Authorization` and the
+//! other credential headers, and token parameters in the query or body, are replaced
+//! with [`REDACTED`], so logs from production can be passed around:
+//!
+//! ```rust
+//! use tweety_rs::api::request_log::{RequestLog, Verbosity};
+//! use tweety_rs::TweetyClient;
+//!
+//! let client = TweetyClient::from_bearer_token("token")
+//!     .with_request_log(RequestLog::new(Verbosity::Headers).redact_param("email"));
+//! # let _ = client;
+//! ```
+//!
+//! Response bodies are not logged; they are read by whoever made the request. Requests
+//! sent through a [`TweetyClient::tagged`] handle start with the tag in brackets.
+//!
+//! [`TweetyClient::with_request_log`]: crate::TweetyClient::with_request_log
+//! [`TweetyClient::tagged`]: crate::TweetyClient::tagged
+
+use crate::api::error::TweetyError;
+use reqwest::header::{HeaderMap, CONTENT_ENCODING, CONTENT_TYPE};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::time::Instant;
+use url::Url;
+
+/// The `log` target requests are logged under.
+pub const LOG_TARGET: &str = "tweety_rs::request";
+
+/// What redacted values are replaced with.
+pub const REDACTED: &str = "REDACTED";
+
+/// Headers redacted by [`RequestLog::new`].
+pub const DEFAULT_REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "proxy-authorization",
+    "set-cookie",
+    "x-csrf-token",
+];
+
+/// Query, form and JSON parameters redacted by [`RequestLog::new`].
+pub const DEFAULT_REDACTED_PARAMS: &[&str] = &[
+    "access_token",
+    "access_token_secret",
+    "bearer_token",
+    "client_secret",
+    "code",
+    "code_verifier",
+    "consumer_secret",
+    "oauth_token",
+    "oauth_token_secret",
+    "oauth_verifier",
+    "password",
+    "refresh_token",
+    "token",
+];
+
+/// Characters of a request body logged at most.
+pub const BODY_LOG_LEN: usize = 2048;
+
+/// How much of each request is logged. Each level includes the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// The method and URL, and the response status and time taken.
+    Url,
+    /// Also the request and response headers.
+    Headers,
+    /// Also the request body, up to [`BODY_LOG_LEN`] characters.
+    Body,
+}
+
+/// What to log of each request and what to strip from it, see
+/// [`crate::api::request_log`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestLog {
+    verbosity: Verbosity,
+    headers: BTreeSet<String>,
+    params: BTreeSet<String>,
+}
+
+impl RequestLog {
+    /// Logs at `verbosity`, redacting [`DEFAULT_REDACTED_HEADERS`] and
+    /// [`DEFAULT_REDACTED_PARAMS`].
+    pub fn new(verbosity: Verbosity) -> Self {
+        RequestLog {
+            verbosity,
+            headers: DEFAULT_REDACTED_HEADERS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            params: DEFAULT_REDACTED_PARAMS
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        self.verbosity
+    }
+
+    /// Also redacts the header `name`, in any case.
+    pub fn redact_header(mut self, name: &str) -> Self {
+        self.headers.insert(name.to_ascii_lowercase());
+        self
+    }
+
+    /// Also redacts the query, form and JSON parameter `name`, in any case. JSON keys
+    /// are redacted at any depth.
+    pub fn redact_param(mut self, name: &str) -> Self {
+        self.params.insert(name.to_ascii_lowercase());
+        self
+    }
+
+    /// `url` with the values of redacted query parameters replaced.
+    pub fn url(&self, url: &Url) -> String {
+        if !url
+            .query_pairs()
+            .any(|(key, _)| self.is_redacted_param(&key))
+        {
+            return url.to_string();
+        }
+
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(key, value)| {
+                let value = match self.is_redacted_param(&key) {
+                    true => REDACTED.to_string(),
+                    false => value.into_owned(),
+                };
+                (key.into_owned(), value)
+            })
+            .collect();
+        let mut url = url.clone();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+        url.to_string()
+    }
+
+    /// `headers` as `name: value` pairs, with redacted headers' values replaced.
+    pub fn headers(&self, headers: &HeaderMap) -> String {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.headers.contains(name.as_str()) {
+                    REDACTED
+                } else {
+                    value.to_str().unwrap_or("<binary>")
+                };
+                format!("{}: {}", name, value)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// A JSON or urlencoded form body with redacted parameters' values replaced,
+    /// cut to [`BODY_LOG_LEN`] characters. Other bodies are logged as text.
+    pub fn body(&self, content_type: Option<&str>, body: &[u8]) -> String {
+        let content_type = content_type.unwrap_or_default();
+        let text = if content_type.starts_with("application/json") {
+            match serde_json::from_slice::<Value>(body) {
+                Ok(mut value) => {
+                    self.redact_value(&mut value);
+                    value.to_string()
+                }
+                Err(_) => String::from_utf8_lossy(body).into_owned(),
+            }
+        } else if content_type.starts_with("application/x-www-form-urlencoded") {
+            let pairs = url::form_urlencoded::parse(body).map(|(key, value)| {
+                let value = match self.is_redacted_param(&key) {
+                    true => REDACTED.into(),
+                    false => value,
+                };
+                (key, value)
+            });
+            url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(pairs)
+                .finish()
+        } else {
+            String::from_utf8_lossy(body).into_owned()
+        };
+
+        match text.char_indices().nth(BODY_LOG_LEN) {
+            Some((end, _)) => format!("{}...", &text[..end]),
+            None => text,
+        }
+    }
+
+    /// Logs `request`, sends it and logs the response's status.
+    pub(crate) async fn send(
+        &self,
+        http: &reqwest::Client,
+        request: reqwest::Request,
+        tag: Option<&str>,
+    ) -> Result<reqwest::Response, TweetyError> {
+        let mut line = format!("{} {}", request.method(), self.url(request.url()));
+        if let Some(tag) = tag {
+            line = format!("[{}] {}", tag, line);
+        }
+        log::debug!(target: LOG_TARGET, "{}", self.describe_request(&line, &request));
+
+        let started = Instant::now();
+        let result = http.execute(request).await;
+        let elapsed = started.elapsed().as_millis();
+
+        match result {
+            Ok(response) => {
+                let mut message = format!("{} -> {} in {}ms", line, response.status(), elapsed);
+                if self.verbosity >= Verbosity::Headers {
+                    message.push_str(&format!(" [{}]", self.headers(response.headers())));
+                }
+                log::debug!(target: LOG_TARGET, "{}", message);
+                Ok(response)
+            }
+            Err(err) => {
+                log::debug!(target: LOG_TARGET, "{} failed after {}ms: {}", line, elapsed, err);
+                Err(err.into())
+            }
+        }
+    }
+
+    fn describe_request(&self, line: &str, request: &reqwest::Request) -> String {
+        let mut message = line.to_string();
+        if self.verbosity >= Verbosity::Headers {
+            message.push_str(&format!(" [{}]", self.headers(request.headers())));
+        }
+        if self.verbosity >= Verbosity::Body {
+            let headers = request.headers();
+            let body = request.body().map(|body| body.as_bytes());
+            let described = match (headers.get(CONTENT_ENCODING), body) {
+                (_, None) => None,
+                // Multipart uploads and other streamed bodies.
+                (_, Some(None)) => Some("<streamed body>".to_string()),
+                (Some(encoding), Some(Some(bytes))) => Some(format!(
+                    "<{} body, {} bytes>",
+                    encoding.to_str().unwrap_or_default(),
+                    bytes.len()
+                )),
+                (None, Some(Some(bytes))) => {
+                    let content_type = headers
+                        .get(CONTENT_TYPE)
+                        .and_then(|value| value.to_str().ok());
+                    Some(self.body(content_type, bytes))
+                }
+            };
+            if let Some(described) = described {
+                message.push(' ');
+                message.push_str(&described);
+            }
+        }
+        message
+    }
+
+    fn is_redacted_param(&self, name: &str) -> bool {
+        self.params.contains(&name.to_ascii_lowercase())
+    }
+
+    fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.is_redacted_param(key) {
+                        *value = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_value(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(|value| self.redact_value(value)),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Deciding which errors are worth retrying.
+//!
+//! The loops that keep going after failures, [`FilteredStream::run`] and
+//! [`MentionListener::run`], ask a [`RetryClassifier`] what to do with each error.
+//! [`DefaultRetryClassifier`] is used unless another one is set; a closure works as a
+//! classifier too:
+//!
+//! ```
+//! use tweety_rs::api::error::TweetyError;
+//! use tweety_rs::api::retry::{DefaultRetryClassifier, RetryClassifier, RetryDecision};
+//!
+//! // Keep retrying 403s, which a proxy in front of the API answers while it restarts.
+//! let classifier = |err: &TweetyError| match err.status().map(|status| status.as_u16()) {
+//!     Some(403) => RetryDecision::Retry,
+//!     _ => DefaultRetryClassifier.classify(err),
+//! };
+//! # let _ = classifier;
+//! ```
+//!
+//! [`FilteredStream::run`]: crate::api::filtered_stream::FilteredStream::run
+//! [`MentionListener::run`]: crate::api::bot::MentionListener::run
+
+use crate::api::error::TweetyError;
+use std::time::Duration;
+
+/// How long to wait after a 429 that doesn't say when the rate limit resets.
+pub const RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// What to do about a failed request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Try again after the usual backoff.
+    Retry,
+    /// Try again, but not before this long has passed, e.g. for a rate limit to reset.
+    RetryAfter(Duration),
+    /// Give up: retrying won't change the outcome.
+    Permanent,
+}
+
+/// Decides whether an error is worth retrying.
+pub trait RetryClassifier: Send + Sync {
+    fn classify(&self, err: &TweetyError) -> RetryDecision;
+}
+
+impl<F> RetryClassifier for F
+where
+    F: Fn(&TweetyError) -> RetryDecision + Send + Sync,
+{
+    fn classify(&self, err: &TweetyError) -> RetryDecision {
+        self(err)
+    }
+}
+
+/// The classification used when none is set.
+///
+/// - Missing or rejected credentials, bad configuration and tier limits are permanent.
+/// - So are duplicate tweets and other 4xx responses, except 408 and 429.
+/// - A 429 waits as long as X asked, or [`RATE_LIMIT_WAIT`] when it didn't say, and an
+///   open circuit waits out its cooldown.
+/// - Everything else, network failures and 5xx responses included, is retried.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultRetryClassifier;
+
+impl RetryClassifier for DefaultRetryClassifier {
+    fn classify(&self, err: &TweetyError) -> RetryDecision {
+        match err {
+            TweetyError::MissingCredentials
+            | TweetyError::MissingEnvironmentVariables(_)
+            | TweetyError::AuthError
+            | TweetyError::AuthFlowError(_)
+            | TweetyError::ConfigError(_)
+            | TweetyError::UrlParseError(_)
+            | TweetyError::SerializeError(_)
+            | TweetyError::DuplicateTweet { .. }
+            | TweetyError::TierLimit { .. } => return RetryDecision::Permanent,
+            TweetyError::RateLimited { retry_after, .. }
+            | TweetyError::CircuitOpen { retry_after, .. } => {
+                return RetryDecision::RetryAfter(*retry_after)
+            }
+            _ => {}
+        }
+
+        match err.status().map(|status| status.as_u16()) {
+            Some(429) => RetryDecision::RetryAfter(RATE_LIMIT_WAIT),
+            Some(408) => RetryDecision::Retry,
+            Some(400..=499) => RetryDecision::Permanent,
+            _ => RetryDecision::Retry,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
-use reqwest::Method;
+use crate::api::query::Query;
+use crate::types::ids::{TweetId, UserId};
 use serde_json::Value;
 
 /// Check required params
@@ -18,35 +20,22 @@ pub struct RetweetQueryParams {
 
 impl RetweetQueryParams {
     pub fn to_query_string(&self) -> String {
-        let mut params = vec![];
+        self.to_query().to_query_string()
+    }
 
-        if let Some(ref expansions) = self.expansions {
-            params.push(format!("expansions={}", expansions));
-        }
-        if let Some(max_results) = self.max_results {
-            params.push(format!("max_results={}", max_results));
-        }
-        if let Some(ref media_fields) = self.media_fields {
-            params.push(format!("media.fields={}", media_fields));
-        }
-        if let Some(ref place_fields) = self.place_fields {
-            params.push(format!("place.fields={}", place_fields));
-        }
-        if let Some(ref poll_fields) = self.poll_fields {
-            params.push(format!("poll.fields={}", poll_fields));
-        }
-        if let Some(ref tweet_fields) = self.tweet_fields {
-            params.push(format!("tweet.fields={}", tweet_fields));
-        }
-        if let Some(ref user_fields) = self.user_fields {
-            params.push(format!("user.fields={}", user_fields));
-        }
+    pub(crate) fn to_query(&self) -> Query {
+        let mut query = Query::new();
 
-        if !params.is_empty() {
-            return params.join("&");
-        }
+        query
+            .push_opt("expansions", self.expansions.as_ref())
+            .push_opt("max_results", self.max_results)
+            .push_opt("media.fields", self.media_fields.as_ref())
+            .push_opt("place.fields", self.place_fields.as_ref())
+            .push_opt("poll.fields", self.poll_fields.as_ref())
+            .push_opt("tweet.fields", self.tweet_fields.as_ref())
+            .push_opt("user.fields", self.user_fields.as_ref());
 
-        String::new()
+        query
     }
 }
 
@@ -54,17 +43,27 @@ impl RetweetQueryParams {
 /// API reference index
 /// For the complete API reference, select an endpoint from the list:
 /// [Docs](https://developer.x.com/en/docs/x-api/tweets/retweets/api-reference)
-
 impl TweetyClient {
     /// Users who have Retweeted a Post
-    pub async fn fetch_retweeters(self, tweet_id: &str) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/tweets/{}/retweeted_by", tweet_id);
-        self.send_request::<()>(&url, Method::GET, None).await
+    pub async fn fetch_retweeters(
+        &self,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<Value, TweetyError> {
+        let tweet_id: TweetId = tweet_id.into();
+        self.send_request::<()>(&Endpoint::RetweetedBy(tweet_id), &Query::new(), None)
+            .await
     }
     /// Causes the user ID identified in the path parameter to Retweet the target Tweet.
-    pub async fn retweet(&self, tweet_id: &str) -> Result<Value, TweetyError> {
-        let url = format!("https://api.x.com/2/users/{}/retweets", tweet_id);
-        self.send_request::<()>(&url, Method::POST, None).await
+    /// [Docs](https://developer.x.com/en/docs/x-api/tweets/retweets/api-reference/post-users-id-retweets)
+    pub async fn retweet(
+        &self,
+        user_id: impl Into<UserId>,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<Value, TweetyError> {
+        let user_id: UserId = user_id.into();
+        let body = serde_json::json!({ "tweet_id": tweet_id.into() });
+        self.send_request(&Endpoint::Retweet(user_id), &Query::new(), Some(body))
+            .await
     }
     /// Allows a user or authenticated user ID to remove the Retweet of a Tweet.
     /// The request succeeds with no action when the user sends a request to a user
@@ -72,33 +71,31 @@ impl TweetyClient {
     /// [Docs](https://developer.x.com/en/docs/x-api/tweets/retweets/api-reference/delete-users-id-retweets-tweet_id)
     pub async fn unretweet(
         &self,
-        user_id: &str,
-        source_tweet_id: &str,
+        user_id: impl Into<UserId>,
+        source_tweet_id: impl Into<TweetId>,
     ) -> Result<Value, TweetyError> {
-        let url = format!(
-            "https://api.x.com/2/users/{}/retweets/{}",
-            user_id, source_tweet_id
-        );
-        self.send_request::<()>(&url, Method::DELETE, None).await
+        let (user_id, source_tweet_id): (UserId, TweetId) =
+            (user_id.into(), source_tweet_id.into());
+        let endpoint = Endpoint::Unretweet {
+            user_id,
+            tweet_id: source_tweet_id,
+        };
+        self.send_request::<()>(&endpoint, &Query::new(), None)
+            .await
     }
 
     /// ## Query Parameters
     /// [Docs](https://developer.x.com/en/docs/x-api/tweets/retweets/api-reference/get-tweets-id-retweets)
     /// Returns the Retweets for a given Tweet ID.
     pub async fn fetch_retweets(
-        self,
-        user_id: &str,
+        &self,
+        tweet_id: impl Into<TweetId>,
         params: Option<RetweetQueryParams>,
     ) -> Result<Value, TweetyError> {
-        let mut url = format!("https://api.x.com/2/tweets/{}/retweets", user_id);
+        let tweet_id: TweetId = tweet_id.into();
+        let query = params.map(|params| params.to_query()).unwrap_or_default();
 
-        if let Some(query_params) = params {
-            let query_string = query_params.to_query_string();
-            if !query_string.is_empty() {
-                url.push_str("?");
-                url.push_str(&query_string);
-            }
-        }
-        self.send_request::<()>(&url, Method::GET, None).await
+        self.send_request::<()>(&Endpoint::Retweets(tweet_id), &query, None)
+            .await
     }
 }
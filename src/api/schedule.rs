@@ -0,0 +1,602 @@
+//! Posting on a recurring schedule.
+//!
+//! Enabled by the `schedule` cargo feature. A [`Scheduler`] runs named jobs, each on a
+//! [`Cron`] expression in UTC, and posts whatever text the job's callback returns when
+//! it comes due, e.g. a daily digest at 09:00. The callback is called at posting time,
+//! so the text can be filled in from fresh data; returning `None` skips the post.
+//!
+//! [`Scheduler::post_ephemeral_tweet`] posts a tweet that the scheduler deletes once
+//! its time to live has passed, e.g. a "live now" notice.
+//!
+//! With a [`StateStore`], the time each job last ran and the tweets waiting to be
+//! deleted are saved after every change. A restarted scheduler neither posts a run again
+//! nor, unless [`Scheduler::catch_up`] is set, posts the runs it missed while it was
+//! down, and it deletes the tweets that expired in the meantime as soon as it starts.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use tweety_rs::api::backfill::to_rfc3339;
+//! use tweety_rs::api::schedule::{Cron, Scheduler};
+//! use tweety_rs::api::state::FileStateStore;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let scheduler = Scheduler::new(client)
+//!     .state_store(FileStateStore::new("schedule.json"), "schedule")
+//!     .job("digest", "0 9 * * *".parse::<Cron>()?, |run| async move {
+//!         let date = &to_rfc3339(run.scheduled_at)[..10];
+//!         Ok(Some(format!("What happened in Rust on {}: ...", date)))
+//!     });
+//! let worker = scheduler.clone();
+//! tokio::spawn(async move { worker.run().await });
+//!
+//! scheduler
+//!     .post_ephemeral_tweet("Live now, for the next hour!", Duration::from_secs(3600))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::backfill::civil_from_days;
+use crate::api::client::TweetyClient;
+use crate::api::error::TweetyError;
+use crate::api::oauth2::unix_now;
+use crate::api::retry::{DefaultRetryClassifier, RetryClassifier, RetryDecision};
+use crate::api::shutdown::{self, Shutdown};
+use crate::api::state::StateStore;
+use crate::api::tweet::PostTweetResponseData;
+use crate::types::ids::TweetId;
+use futures_util::future::BoxFuture;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Notify;
+
+/// How far ahead [`Cron::next_after`] looks: long enough for any 29 February to come
+/// round on any weekday.
+const SEARCH_DAYS: i64 = 28 * 366;
+
+/// How long a failed deletion waits before it is tried again.
+const DELETE_RETRY_SECS: u64 = 60;
+
+/// A cron expression: minute, hour, day of month, month and day of week, in UTC.
+///
+/// Each field is `*`, a number, a range `a-b` or a comma-separated list of those, each
+/// optionally stepped with `/n`. Days of the week run from 0 (Sunday) to 6, with 7 also
+/// Sunday. As in cron, when both the day of month and the day of week are restricted, a
+/// day matching either runs. `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly`
+/// are accepted too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl Cron {
+    /// Parses `expression`, failing with [`TweetyError::ConfigError`].
+    pub fn parse(expression: &str) -> Result<Self, TweetyError> {
+        let fields = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let invalid =
+            |reason: &str| TweetyError::ConfigError(format!("cron {:?}: {}", expression, reason));
+
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(invalid("expected 5 fields"));
+        };
+        let mut days_of_week = parse_field(day_of_week, 0, 7).map_err(|err| invalid(&err))?;
+        if days_of_week & 1 << 7 != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Cron {
+            expression: expression.trim().to_string(),
+            minutes: parse_field(minute, 0, 59).map_err(|err| invalid(&err))?,
+            hours: parse_field(hour, 0, 23).map_err(|err| invalid(&err))?,
+            days_of_month: parse_field(day_of_month, 1, 31).map_err(|err| invalid(&err))?,
+            months: parse_field(month, 1, 12).map_err(|err| invalid(&err))?,
+            days_of_week,
+            any_day_of_month: day_of_month == "*",
+            any_day_of_week: day_of_week == "*",
+        })
+    }
+
+    /// The first minute matching the expression strictly after `time`, or `None` when
+    /// no date ever matches, e.g. `0 0 31 2 *`.
+    pub fn next_after(&self, time: SystemTime) -> Option<SystemTime> {
+        let secs = time.duration_since(UNIX_EPOCH).ok()?.as_secs();
+        let start = secs / 60 * 60 + 60;
+        let first_day = (start / 86_400) as i64;
+        let mut first_minute = start % 86_400 / 60;
+
+        for day in first_day..first_day + SEARCH_DAYS {
+            if self.matches_day(day) {
+                let minute_of_day = (first_minute..24 * 60).find(|minute_of_day| {
+                    self.hours & 1 << (minute_of_day / 60) != 0
+                        && self.minutes & 1 << (minute_of_day % 60) != 0
+                });
+                if let Some(minute_of_day) = minute_of_day {
+                    let secs = day as u64 * 86_400 + minute_of_day * 60;
+                    return Some(UNIX_EPOCH + Duration::from_secs(secs));
+                }
+            }
+            first_minute = 0;
+        }
+        None
+    }
+
+    fn matches_day(&self, days: i64) -> bool {
+        let (_, month, day) = civil_from_days(days);
+        // 1 January 1970 was a Thursday.
+        let weekday = (days + 4).rem_euclid(7);
+        if self.months & 1 << month == 0 {
+            return false;
+        }
+
+        let day_of_month = self.days_of_month & 1 << day != 0;
+        let day_of_week = self.days_of_week & 1 << weekday != 0;
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+impl FromStr for Cron {
+    type Err = TweetyError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        Cron::parse(expression)
+    }
+}
+
+impl fmt::Display for Cron {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// The values a cron field allows, as a bit set.
+fn parse_field(field: &str, min: u64, max: u64) -> Result<u64, String> {
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u64>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("invalid step in {:?}", part)),
+            },
+            None => (part, 1),
+        };
+        let number = |value: &str| match value.parse::<u64>() {
+            Ok(value) if (min..=max).contains(&value) => Ok(value),
+            _ => Err(format!("{:?} is not between {} and {}", value, min, max)),
+        };
+        let (from, to) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((from, to)) => (number(from)?, number(to)?),
+                // `5/15` runs from 5 to the end of the range.
+                None if step > 1 => (number(range)?, max),
+                None => (number(range)?, number(range)?),
+            },
+        };
+        if from > to {
+            return Err(format!("empty range {:?}", range));
+        }
+        for value in (from..=to).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+/// The run a job's callback is writing the post for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledRun {
+    /// The job's name.
+    pub name: String,
+    /// The time the run was due, which is before now when it was caught up on.
+    pub scheduled_at: SystemTime,
+}
+
+impl ScheduledRun {
+    /// [`ScheduledRun::scheduled_at`] in Unix seconds.
+    pub fn scheduled_at_secs(&self) -> u64 {
+        self.scheduled_at
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// A tweet waiting to be deleted, see [`Scheduler::post_ephemeral_tweet`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingDeletion {
+    pub tweet_id: TweetId,
+    /// Unix seconds from which the tweet is deleted.
+    pub delete_at: u64,
+}
+
+/// What the state store keeps.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Saved {
+    /// Unix seconds of each job's last run, by name.
+    #[serde(default)]
+    last_runs: HashMap<String, u64>,
+    #[serde(default)]
+    deletions: Vec<PendingDeletion>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    loaded: bool,
+    saved: Saved,
+    /// Unix seconds of each job's next run, by name, once the saved state is loaded.
+    next_runs: HashMap<String, u64>,
+}
+
+type ContentCallback = Arc<
+    dyn Fn(ScheduledRun) -> BoxFuture<'static, Result<Option<String>, TweetyError>> + Send + Sync,
+>;
+
+#[derive(Clone)]
+struct Job {
+    name: String,
+    cron: Cron,
+    content: ContentCallback,
+}
+
+/// Posts the text of named jobs on their [`Cron`] schedules, and deletes ephemeral
+/// tweets once they expire. Clones share the schedule, so one can register deletions
+/// while another runs.
+#[derive(Clone)]
+pub struct Scheduler {
+    client: TweetyClient,
+    jobs: Arc<Vec<Job>>,
+    state_store: Option<(Arc<dyn StateStore>, String)>,
+    state: Arc<Mutex<State>>,
+    /// Serializes saves, so a newer state is never overwritten by an older one.
+    save_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Wakes [`Scheduler::run`] when a deletion is registered.
+    changed: Arc<Notify>,
+    catch_up: bool,
+    shutdown: Option<Shutdown>,
+}
+
+impl fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let jobs: Vec<(&str, &Cron)> = self
+            .jobs
+            .iter()
+            .map(|job| (job.name.as_str(), &job.cron))
+            .collect();
+        f.debug_struct("Scheduler")
+            .field("client", &self.client)
+            .field("jobs", &jobs)
+            .field("state", &self.state)
+            .field("catch_up", &self.catch_up)
+            .field("shutdown", &self.shutdown)
+            .finish()
+    }
+}
+
+impl Scheduler {
+    /// Posts with `client`.
+    pub fn new(client: TweetyClient) -> Self {
+        Scheduler {
+            client,
+            jobs: Arc::default(),
+            state_store: None,
+            state: Arc::default(),
+            save_lock: Arc::default(),
+            changed: Arc::default(),
+            catch_up: false,
+            shutdown: None,
+        }
+    }
+
+    /// Adds a job posting the text `content` returns whenever `cron` comes due, or
+    /// nothing when it returns `None`. Adding a name twice replaces the earlier job.
+    pub fn job<F, Fut>(mut self, name: &str, cron: Cron, content: F) -> Self
+    where
+        F: Fn(ScheduledRun) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Option<String>, TweetyError>> + Send + 'static,
+    {
+        let jobs = Arc::make_mut(&mut self.jobs);
+        jobs.retain(|job| job.name != name);
+        jobs.push(Job {
+            name: name.to_string(),
+            cron,
+            content: Arc::new(move |run| Box::pin(content(run)) as BoxFuture<'static, _>),
+        });
+        self
+    }
+
+    /// Saves the time each job last ran and the tweets waiting to be deleted under
+    /// `key` in `store` after every change, and picks up from there on the first
+    /// [`Scheduler::run_due`].
+    pub fn state_store(mut self, store: impl StateStore + 'static, key: &str) -> Self {
+        self.state_store = Some((Arc::new(store), key.to_string()));
+        self
+    }
+
+    /// Posts once for a job that came due while the scheduler wasn't running, instead
+    /// of waiting for its next run. Only jobs with a saved last run are caught up on.
+    pub fn catch_up(mut self) -> Self {
+        self.catch_up = true;
+        self
+    }
+
+    /// Makes [`Scheduler::run`] return once `shutdown` is requested, after the post in
+    /// flight is recorded.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// When the job `name` runs next, once the scheduler has started.
+    pub fn next_run(&self, name: &str) -> Option<SystemTime> {
+        let secs = *self.state().next_runs.get(name)?;
+        Some(UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    /// When the job `name` last ran, as saved in the state store.
+    pub fn last_run(&self, name: &str) -> Option<SystemTime> {
+        let secs = *self.state().saved.last_runs.get(name)?;
+        Some(UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    /// The tweets waiting to be deleted, once the scheduler has started or a deletion
+    /// was registered.
+    pub fn pending_deletions(&self) -> Vec<PendingDeletion> {
+        self.state().saved.deletions.clone()
+    }
+
+    /// Posts `text` and deletes the tweet once `ttl` has passed. The deletion is saved
+    /// in the state store before this returns, so a tweet whose time ran out while the
+    /// process was down is deleted as soon as the scheduler runs again.
+    pub async fn post_ephemeral_tweet(
+        &self,
+        text: &str,
+        ttl: Duration,
+    ) -> Result<PostTweetResponseData, TweetyError> {
+        let response = self.client.post_tweet(text, None).await?;
+        self.delete_after(&response.data.id, ttl).await?;
+        Ok(response)
+    }
+
+    /// Deletes the tweet `tweet_id` once `ttl` has passed, see
+    /// [`Scheduler::post_ephemeral_tweet`].
+    pub async fn delete_after(
+        &self,
+        tweet_id: impl Into<TweetId>,
+        ttl: Duration,
+    ) -> Result<(), TweetyError> {
+        self.load(SystemTime::now()).await?;
+        self.state().saved.deletions.push(PendingDeletion {
+            tweet_id: tweet_id.into(),
+            delete_at: unix_now() + ttl.as_secs(),
+        });
+        self.save().await?;
+        self.changed.notify_one();
+        Ok(())
+    }
+
+    /// Posts and deletes on schedule until a shutdown is requested, see
+    /// [`Scheduler::shutdown_on`]. Returns early only when the state store fails.
+    pub async fn run(&self) -> Result<(), TweetyError> {
+        let _running = self.shutdown.as_ref().map(Shutdown::running);
+
+        loop {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_requested) {
+                return Ok(());
+            }
+            self.run_due(SystemTime::now()).await?;
+
+            let next = {
+                let state = self.state();
+                let deletions = state.saved.deletions.iter().map(|d| d.delete_at);
+                state.next_runs.values().copied().chain(deletions).min()
+            };
+            let wait = match next {
+                Some(next) => (UNIX_EPOCH + Duration::from_secs(next))
+                    .duration_since(SystemTime::now())
+                    .unwrap_or_default(),
+                // Nothing comes due until a deletion is registered.
+                None => Duration::MAX,
+            };
+            tokio::select! {
+                _ = self.changed.notified() => {}
+                stopped = shutdown::sleep(self.shutdown.as_ref(), wait) => {
+                    if stopped {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Deletes the tweets expired at `now`, then runs every job due at `now`, returning
+    /// how many posted. A job whose callback or post fails is skipped until its next
+    /// run, with a warning.
+    pub async fn run_due(&self, now: SystemTime) -> Result<usize, TweetyError> {
+        self.load(now).await?;
+        let now_secs = now
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        self.delete_expired(now_secs).await?;
+
+        let mut posted = 0;
+        for job in self.jobs.iter() {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_requested) {
+                break;
+            }
+            let due = self.state().next_runs.get(&job.name).copied();
+            let Some(due) = due.filter(|next| *next <= now_secs) else {
+                continue;
+            };
+
+            let run = ScheduledRun {
+                name: job.name.clone(),
+                scheduled_at: UNIX_EPOCH + Duration::from_secs(due),
+            };
+            let result = match (job.content)(run).await {
+                Ok(Some(text)) => self.client.post_tweet(&text, None).await.map(|_| true),
+                Ok(None) => Ok(false),
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(true) => posted += 1,
+                Ok(false) => {}
+                Err(err) => log::warn!("scheduled post {:?} failed: {}", job.name, err),
+            }
+
+            {
+                let mut state = self.state();
+                // Runs missed while this one was being written are skipped.
+                match next_secs(&job.cron, due.max(now_secs)) {
+                    Some(next) => state.next_runs.insert(job.name.clone(), next),
+                    None => state.next_runs.remove(&job.name),
+                };
+                state.saved.last_runs.insert(job.name.clone(), due);
+            }
+            self.save().await?;
+        }
+        Ok(posted)
+    }
+
+    /// Deletes the tweets whose time ran out by `now_secs`. Tweets already gone count
+    /// as deleted; other failures are retried a minute later unless they are permanent.
+    async fn delete_expired(&self, now_secs: u64) -> Result<(), TweetyError> {
+        let expired: Vec<PendingDeletion> = self
+            .state()
+            .saved
+            .deletions
+            .iter()
+            .filter(|deletion| deletion.delete_at <= now_secs)
+            .cloned()
+            .collect();
+
+        for deletion in expired {
+            let retry_at = match self.client.delete_tweet(&deletion.tweet_id).await {
+                Ok(_) => None,
+                Err(TweetyError::ApiError { status, .. }) if status == StatusCode::NOT_FOUND => {
+                    None
+                }
+                Err(err) => {
+                    log::warn!(
+                        "deleting expired tweet {} failed: {}",
+                        deletion.tweet_id,
+                        err
+                    );
+                    match DefaultRetryClassifier.classify(&err) {
+                        RetryDecision::Permanent => None,
+                        RetryDecision::Retry => Some(now_secs + DELETE_RETRY_SECS),
+                        RetryDecision::RetryAfter(wait) => {
+                            Some(now_secs + wait.as_secs().max(DELETE_RETRY_SECS))
+                        }
+                    }
+                }
+            };
+
+            {
+                let mut state = self.state();
+                let deletions = &mut state.saved.deletions;
+                match deletions.iter().position(|pending| *pending == deletion) {
+                    Some(index) => match retry_at {
+                        Some(retry_at) => deletions[index].delete_at = retry_at,
+                        None => {
+                            deletions.remove(index);
+                        }
+                    },
+                    None => continue,
+                }
+            }
+            self.save().await?;
+        }
+        Ok(())
+    }
+
+    /// Reads the saved state once, and works out the next run of each job without one.
+    async fn load(&self, now: SystemTime) -> Result<(), TweetyError> {
+        if !self.state().loaded {
+            let saved = match &self.state_store {
+                Some((store, key)) => match store.get(key).await? {
+                    Some(value) => Some(serde_json::from_str::<Saved>(&value)?),
+                    None => None,
+                },
+                None => None,
+            };
+            let mut state = self.state();
+            if !state.loaded {
+                if let Some(mut saved) = saved {
+                    // Keep deletions registered while the store was being read.
+                    saved.deletions.append(&mut state.saved.deletions);
+                    state.saved = saved;
+                }
+                state.loaded = true;
+            }
+        }
+
+        let now_secs = now
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        let mut state = self.state();
+        for job in self.jobs.iter() {
+            if state.next_runs.contains_key(&job.name) {
+                continue;
+            }
+            let next = match state.saved.last_runs.get(&job.name) {
+                Some(last_run) => match next_secs(&job.cron, *last_run) {
+                    Some(next) if next <= now_secs && !self.catch_up => {
+                        next_secs(&job.cron, now_secs)
+                    }
+                    next => next,
+                },
+                None => next_secs(&job.cron, now_secs),
+            };
+            if let Some(next) = next {
+                state.next_runs.insert(job.name.clone(), next);
+            }
+        }
+        Ok(())
+    }
+
+    async fn save(&self) -> Result<(), TweetyError> {
+        let Some((store, key)) = &self.state_store else {
+            return Ok(());
+        };
+        let _guard = self.save_lock.lock().await;
+        let value = serde_json::to_string(&self.state().saved)
+            .map_err(|err| TweetyError::SerializeError(err.to_string()))?;
+        store.set(key, &value).await
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+fn next_secs(cron: &Cron, after_secs: u64) -> Option<u64> {
+    let next = cron.next_after(UNIX_EPOCH + Duration::from_secs(after_secs))?;
+    Some(next.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
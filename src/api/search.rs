@@ -1,9 +1,14 @@
 use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
-use reqwest::Method;
+use crate::api::mentions::NoteTweet;
+use crate::api::query::{field_names, Query};
+use crate::types::lang::Lang;
+use crate::types::meta::{Meta, Paginated};
+use crate::types::tweet::EditControls;
+use crate::types::user::Withheld;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use yaup::to_string as convert_query_to_string;
+use serde_json::{Map, Value};
 
 #[derive(Debug, Serialize, Default)]
 pub struct QueryParams {
@@ -173,6 +178,8 @@ pub enum TweetField {
     Lang,
     #[serde(rename = "non_public_metrics")]
     NonPublicMetrics,
+    #[serde(rename = "note_tweet")]
+    NoteTweet,
     #[serde(rename = "public_metrics")]
     PublicMetrics,
     #[serde(rename = "organic_metrics")]
@@ -229,11 +236,17 @@ pub enum UserField {
     Withheld,
 }
 
+field_names!(Expansion, MediaField, PlaceField, PollField, TweetField, UserField);
+
+impl Paginated for QueryParams {
+    fn set_page_token(&mut self, token: String) {
+        self.next_token = Some(token);
+    }
+}
+
 impl QueryParams {
     pub fn to_query_string(&self) -> Result<String, TweetyError> {
-        let query_params = convert_query_to_string(&self)
-            .map_err(|e| TweetyError::SerializeError(e.to_string()))?;
-        Ok(query_params)
+        Ok(Query::from_serialize(self)?.to_query_string())
     }
 }
 
@@ -247,14 +260,40 @@ pub struct RecentSearchResponse {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct TweetData {
     pub text: String,
     pub author_id: String,
     pub id: String,
     pub edit_history_tweet_ids: Vec<String>,
-    pub lang: String,
+    pub lang: Lang,
     pub conversation_id: String,
     pub created_at: String,
+    /// The untruncated text of posts longer than 280 characters.
+    pub note_tweet: Option<NoteTweet>,
+    pub edit_controls: Option<EditControls>,
+    pub withheld: Option<Withheld>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+impl TweetData {
+    /// Guesses the language of [`TweetData::full_text`] locally, see [`Lang::detect`].
+    #[cfg(feature = "lang-detect")]
+    pub fn detect_lang(&self) -> Option<Lang> {
+        Lang::detect(self.full_text())
+    }
+
+    /// The complete text of the tweet, taken from `note_tweet` for long posts.
+    pub fn full_text(&self) -> &str {
+        self.note_tweet
+            .as_ref()
+            .map(|note| note.text.as_str())
+            .unwrap_or(&self.text)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -263,12 +302,19 @@ pub struct Includes {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct User {
     pub id: String,
     pub entities: Option<UserEntities>,
     pub created_at: String,
     pub username: String,
     pub name: String,
+    pub withheld: Option<Withheld>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -303,13 +349,6 @@ pub struct Hashtag {
     pub tag: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Meta {
-    pub newest_id: String,
-    pub oldest_id: String,
-    pub result_count: u32,
-}
-
 impl TweetyClient {
     /// Search for Posts published in the last 7 days
     /// The recent search endpoint returns Tweets from the last seven days that match a search query.
@@ -319,23 +358,19 @@ impl TweetyClient {
         query: &str,
         query_params: Option<QueryParams>,
     ) -> Result<RecentSearchResponse, TweetyError> {
-        let mut base_url = format!("https://api.x.com/2/tweets/search/recent?query={}", query);
-
-        if let Some(query) = query_params {
-            base_url = format!("{}{}", base_url, query.to_query_string()?);
-        }
-
-        match self.send_request::<()>(&base_url, Method::GET, None).await {
-            Ok(value) => {
-                let json_data = serde_json::from_value::<RecentSearchResponse>(value);
-
-                if let Ok(data) = json_data {
-                    Ok(data)
-                } else {
-                    Err(TweetyError::JsonParseError("Invalid JSON".to_string()))
-                }
-            }
-            Err(err) => Err(TweetyError::ApiError(err.to_string())),
+        let mut params = query_params.unwrap_or_default();
+        params.query = query.to_string();
+        let query = Query::from_serialize(&params)?;
+
+        match self
+            .send_request::<()>(&Endpoint::RecentSearch, &query, None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<RecentSearchResponse>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
         }
     }
     /// Only available to those with Pro and Enterprise access
@@ -348,15 +383,16 @@ impl TweetyClient {
         query: &str,
         query_params: Option<QueryParams>,
     ) -> Result<Value, TweetyError> {
-        let mut base_url = format!("https://api.x.com/2/tweets/search/all?query={}", query);
-
-        if let Some(queries) = query_params {
-            base_url = format!("{}&{}", base_url, queries.to_query_string()?);
-        }
-
-        match self.send_request::<()>(&base_url, Method::GET, None).await {
+        let mut params = query_params.unwrap_or_default();
+        params.query = query.to_string();
+        let query = Query::from_serialize(&params)?;
+
+        match self
+            .send_request::<()>(&Endpoint::FullArchiveSearch, &query, None)
+            .await
+        {
             Ok(value) => Ok(value),
-            Err(err) => Err(TweetyError::ApiError(err.to_string())),
+            Err(err) => Err(err),
         }
     }
 }
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Number of leading characters left visible when a secret is printed.
+const VISIBLE_CHARS: usize = 4;
+
+/// A string holding a credential, such as an access token or consumer secret.
+///
+/// `Debug` only shows the first four characters, so logging a client or profile with
+/// `{:?}` doesn't leak credentials. There is deliberately no `Display` implementation;
+/// use [`SecretString::expose_secret`] to get the raw value.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(secret: impl Into<String>) -> Self {
+        SecretString(secret.into())
+    }
+
+    /// Returns the raw secret.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the masked form used by `Debug`, e.g. `abcd****`.
+    ///
+    /// Secrets of four characters or fewer are masked entirely.
+    pub fn redacted(&self) -> String {
+        if self.0.is_empty() {
+            return String::new();
+        }
+        if self.0.chars().count() <= VISIBLE_CHARS {
+            return "****".to_string();
+        }
+
+        let visible: String = self.0.chars().take(VISIBLE_CHARS).collect();
+        format!("{}****", visible)
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.redacted())
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        SecretString(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        SecretString(secret.to_string())
+    }
+}
@@ -0,0 +1,114 @@
+//! Stopping background loops cleanly.
+//!
+//! A [`Shutdown`] is handed to each long-running component with its `shutdown_on`
+//! builder: [`MentionListener`], [`FilteredStream`], [`BlocklistImport`],
+//! [`SpacesWatcher`] and, with the `analytics`, `webhooks`, `outbox`, `schedule`,
+//! `mirror` and `feed` features, `MetricsTracker`, `WebhookHandler`, `Outbox`,
+//! `Scheduler`, `Mirror` and `FeedBridge`.
+//! [`Shutdown::shutdown`] asks all of them to stop and resolves
+//! once they have: each finishes the request it is making, saves its progress (the
+//! last handled mention, the import position) and returns `Ok(())` from its `run`
+//! instead of waiting for more work.
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use tweety_rs::api::bot::MentionListener;
+//! use tweety_rs::api::shutdown::Shutdown;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let shutdown = Shutdown::new();
+//! let mut listener = MentionListener::new(client)
+//!     .persist_to("mentions.cursor")?
+//!     .shutdown_on(&shutdown);
+//! let bot = tokio::spawn(async move { listener.run().await });
+//!
+//! // Stop after an hour, e.g. before a redeploy.
+//! tokio::time::sleep(Duration::from_secs(3600)).await;
+//! shutdown.shutdown().await;
+//! bot.await.ok();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`MentionListener`]: crate::api::bot::MentionListener
+//! [`FilteredStream`]: crate::api::filtered_stream::FilteredStream
+//! [`BlocklistImport`]: crate::api::blocklist::BlocklistImport
+//! [`SpacesWatcher`]: crate::api::spaces::SpacesWatcher
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// A shutdown signal shared by the components it was handed to. Clones share it.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    requested: Arc<watch::Sender<bool>>,
+    running: Arc<watch::Sender<usize>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Shutdown::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Shutdown {
+            requested: Arc::new(watch::Sender::new(false)),
+            running: Arc::new(watch::Sender::new(0)),
+        }
+    }
+
+    /// Asks every component to stop, and resolves once their `run` calls have returned.
+    pub async fn shutdown(&self) {
+        self.requested.send_replace(true);
+
+        let mut running = self.running.subscribe();
+        let _ = running.wait_for(|running| *running == 0).await;
+    }
+
+    pub fn is_requested(&self) -> bool {
+        *self.requested.borrow()
+    }
+
+    /// Resolves once a shutdown is requested.
+    pub async fn requested(&self) {
+        let mut requested = self.requested.subscribe();
+        let _ = requested.wait_for(|requested| *requested).await;
+    }
+
+    /// Counts a `run` call as in flight until the guard is dropped.
+    pub(crate) fn running(&self) -> RunningGuard {
+        self.running.send_modify(|running| *running += 1);
+        RunningGuard {
+            running: self.running.clone(),
+        }
+    }
+}
+
+pub(crate) struct RunningGuard {
+    running: Arc<watch::Sender<usize>>,
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        self.running.send_modify(|running| *running -= 1);
+    }
+}
+
+/// Sleeps for `duration`, or less if a shutdown is requested meanwhile. Returns `true`
+/// when the sleep was cut short or a shutdown had already been requested.
+pub(crate) async fn sleep(shutdown: Option<&Shutdown>, duration: Duration) -> bool {
+    match shutdown {
+        Some(shutdown) => tokio::select! {
+            _ = shutdown.requested() => true,
+            _ = tokio::time::sleep(duration) => shutdown.is_requested(),
+        },
+        None => {
+            tokio::time::sleep(duration).await;
+            false
+        }
+    }
+}
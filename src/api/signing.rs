@@ -0,0 +1,388 @@
+//! Making and inspecting OAuth 1.0a signatures.
+//!
+//! X answers a request whose signature doesn't match with a bare 401 `Could not
+//! authenticate you`. [`TweetyClient::inspect_signature`] shows what was signed, so it
+//! can be compared against another implementation or X's
+//! [signature guide](https://developer.x.com/en/docs/authentication/oauth-1-0a/creating-a-signature).
+//! Typical culprits are a parameter encoded twice, a stray `oauth_version`, the wrong
+//! host, or an access token from a different app than the consumer key. A wrong system
+//! clock is another, which the client corrects by itself, see
+//! [`TweetyClient::clock_skew`].
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::endpoint::Endpoint;
+//! use tweety_rs::TweetyClient;
+//!
+//! # fn main() -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let client = TweetyClient::from_env()?;
+//! let inspection = client.inspect_signature(&Endpoint::Me, &[("user.fields", "created_at")])?;
+//! println!("{}", inspection);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Requests to endpoints the client doesn't wrap, such as the Ads API, can be signed
+//! with [`sign_request`] and sent with any HTTP client. Form parameters of the body are
+//! signed along with the URL's query:
+//!
+//! ```rust
+//! use reqwest::Method;
+//! use tweety_rs::api::signing::{sign_request, OAuth1Credentials};
+//!
+//! # fn main() -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let credentials = OAuth1Credentials::new("key", "key_secret", "token", "token_secret");
+//! let authorization = sign_request(
+//!     &Method::POST,
+//!     "https://ads-api.x.com/12/accounts/18ce54d4x5t/campaigns",
+//!     &[("name", "launch"), ("funding_instrument_id", "lygyi")],
+//!     &credentials,
+//! )?;
+//! assert!(authorization.starts_with("OAuth "));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::query::Query;
+use crate::api::secret::SecretString;
+use crate::TweetyClient;
+use reqwest::header::{HeaderMap, AUTHORIZATION, DATE};
+use reqwest::Method;
+use reqwest_oauth1::{OAuthClientProvider, OAuthParameters, Secrets};
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+use url::{Position, Url};
+
+/// How far X's clock may be from the local one before signatures are made with X's
+/// time, see [`TweetyClient::clock_skew`].
+pub const CLOCK_SKEW_TOLERANCE: Duration = Duration::from_secs(30);
+
+/// The credentials [`sign_request`] signs with: the app's consumer key and secret, and
+/// the access token and secret of the user the request acts for, if any. `Debug`
+/// output masks them, see [`SecretString`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OAuth1Credentials {
+    pub consumer_key: SecretString,
+    pub consumer_secret: SecretString,
+    /// Empty to sign with the consumer key alone.
+    pub access_token: SecretString,
+    pub access_token_secret: SecretString,
+}
+
+impl OAuth1Credentials {
+    pub fn new(
+        consumer_key: &str,
+        consumer_secret: &str,
+        access_token: &str,
+        access_token_secret: &str,
+    ) -> Self {
+        OAuth1Credentials {
+            consumer_key: consumer_key.into(),
+            consumer_secret: consumer_secret.into(),
+            access_token: access_token.into(),
+            access_token_secret: access_token_secret.into(),
+        }
+    }
+
+    /// Credentials without a user, e.g. for requesting a request token.
+    pub fn consumer_only(consumer_key: &str, consumer_secret: &str) -> Self {
+        OAuth1Credentials::new(consumer_key, consumer_secret, "", "")
+    }
+}
+
+/// Signs a `method` request to `url` whose body is the urlencoded form `params` (empty
+/// for requests without one) and returns its `Authorization` header. The nonce is
+/// random and the timestamp the local clock's.
+pub fn sign_request(
+    method: &Method,
+    url: &str,
+    params: &[(&str, &str)],
+    credentials: &OAuth1Credentials,
+) -> Result<String, TweetyError> {
+    sign(method, url, params, credentials, None, None)
+}
+
+/// Like [`sign_request`], with a fixed nonce and timestamp, to reproduce a signature
+/// computed elsewhere or to correct for a wrong local clock.
+pub fn sign_request_at(
+    method: &Method,
+    url: &str,
+    params: &[(&str, &str)],
+    credentials: &OAuth1Credentials,
+    nonce: &str,
+    timestamp: u64,
+) -> Result<String, TweetyError> {
+    sign(
+        method,
+        url,
+        params,
+        credentials,
+        Some(nonce),
+        Some(timestamp),
+    )
+}
+
+/// Signs with `nonce` and `timestamp`, left for reqwest_oauth1 to make up when `None`:
+/// a random nonce and the local clock's time.
+fn sign(
+    method: &Method,
+    url: &str,
+    params: &[(&str, &str)],
+    credentials: &OAuth1Credentials,
+    nonce: Option<&str>,
+    timestamp: Option<u64>,
+) -> Result<String, TweetyError> {
+    if credentials.consumer_key.is_empty() || credentials.consumer_secret.is_empty() {
+        return Err(TweetyError::MissingCredentials);
+    }
+    // Form parameters go into the base string just like query parameters, so signing
+    // the URL with them added signs the request.
+    let mut url = Url::parse(url)?;
+    if !params.is_empty() {
+        url.query_pairs_mut().extend_pairs(params);
+    }
+    let secrets = Secrets::new(
+        credentials.consumer_key.expose_secret(),
+        credentials.consumer_secret.expose_secret(),
+    );
+    let secrets = match credentials.access_token.is_empty() {
+        true => secrets,
+        false => secrets.token(
+            credentials.access_token.expose_secret(),
+            credentials.access_token_secret.expose_secret(),
+        ),
+    };
+
+    let mut oauth = OAuthParameters::new();
+    if let Some(nonce) = nonce {
+        oauth = oauth.nonce(nonce);
+    }
+    if let Some(timestamp) = timestamp {
+        oauth = oauth.timestamp(timestamp);
+    }
+
+    let request = signing_client()
+        .clone()
+        .oauth1_with_params(secrets, oauth)
+        .request(method.clone(), url)
+        .generate_signature()
+        .map_err(|_| TweetyError::AuthError)?
+        .build()?;
+
+    Ok(authorization_of(&request))
+}
+
+/// The `Authorization` header of a signed `request`.
+fn authorization_of(request: &reqwest::Request) -> String {
+    request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The value of the `oauth_*` parameter `name` in an `Authorization` header.
+fn oauth_parameter<'a>(authorization: &'a str, name: &str) -> Option<&'a str> {
+    authorization
+        .strip_prefix("OAuth ")?
+        .split(',')
+        .find_map(|parameter| {
+            let (key, value) = parameter.trim().split_once('=')?;
+            (key == name).then(|| value.trim_matches('"'))
+        })
+}
+
+/// Builds the requests [`sign_request`] signs. Nothing is sent through it.
+fn signing_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// What signing a request produced. Holds no secrets: the consumer and token secrets
+/// only appear masked in `signing_key`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureInspection {
+    pub method: String,
+    /// The URL without its query, as it goes into the base string.
+    pub base_url: String,
+    /// The query and `oauth_*` parameters, percent-encoded, sorted and joined with `&`.
+    pub normalized_parameters: String,
+    /// `METHOD&base_url&normalized_parameters`, each part percent-encoded; the text the
+    /// HMAC-SHA1 signature is computed over.
+    pub base_string: String,
+    /// `consumer_secret&token_secret`, masked like [`SecretString`]'s `Debug`.
+    ///
+    /// [`SecretString`]: crate::api::secret::SecretString
+    pub signing_key: String,
+    /// The `Authorization` header the request would carry.
+    pub authorization: String,
+}
+
+impl fmt::Display for SignatureInspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "method:        {}", self.method)?;
+        writeln!(f, "base URL:      {}", self.base_url)?;
+        writeln!(f, "parameters:    {}", self.normalized_parameters)?;
+        writeln!(f, "base string:   {}", self.base_string)?;
+        writeln!(f, "signing key:   {}", self.signing_key)?;
+        write!(f, "authorization: {}", self.authorization)
+    }
+}
+
+impl TweetyClient {
+    /// Signs a request to `endpoint` with `query` the way it would be sent, without
+    /// sending it. Needs OAuth 1.0a user credentials. The timestamp is corrected by
+    /// [`TweetyClient::clock_skew`] like real requests.
+    pub fn inspect_signature(
+        &self,
+        endpoint: &Endpoint,
+        query: &[(&str, &str)],
+    ) -> Result<SignatureInspection, TweetyError> {
+        let mut params = Query::new();
+        for (key, value) in query {
+            params.push(key, value);
+        }
+        let url = self.endpoint_url(endpoint, &params);
+        let timestamp = self.oauth1_timestamp();
+
+        // Signing once has reqwest_oauth1 make up a random nonce, which the inspection
+        // then signs with.
+        let credentials = self
+            .oauth1_credentials()
+            .ok_or(TweetyError::MissingCredentials)?;
+        let authorization = sign(
+            &endpoint.method(),
+            &url,
+            &[],
+            &credentials,
+            None,
+            Some(timestamp),
+        )?;
+        let nonce = oauth_parameter(&authorization, "oauth_nonce").ok_or(TweetyError::AuthError)?;
+        self.inspect_signature_at(endpoint.method(), &url, nonce, timestamp)
+    }
+
+    /// The client's OAuth 1.0a credentials, for [`sign_request`]. `None` without a
+    /// consumer key and secret.
+    pub fn oauth1_credentials(&self) -> Option<OAuth1Credentials> {
+        let credentials = &self.credentials;
+        if credentials.consumer_key.is_empty() || credentials.consumer_key_secret.is_empty() {
+            return None;
+        }
+        Some(OAuth1Credentials {
+            consumer_key: credentials.consumer_key.clone(),
+            consumer_secret: credentials.consumer_key_secret.clone(),
+            access_token: credentials.access_token.clone(),
+            access_token_secret: credentials.access_token_secret.clone(),
+        })
+    }
+
+    /// Signs a `method` request to `url` with a fixed nonce and timestamp, to reproduce
+    /// a signature computed elsewhere.
+    pub fn inspect_signature_at(
+        &self,
+        method: Method,
+        url: &str,
+        nonce: &str,
+        timestamp: u64,
+    ) -> Result<SignatureInspection, TweetyError> {
+        let credentials = &self.credentials;
+        if credentials.consumer_key.is_empty() || credentials.consumer_key_secret.is_empty() {
+            return Err(TweetyError::MissingCredentials);
+        }
+        let url = Url::parse(url)?;
+
+        let mut parameters: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(key, value)| (encode(&key), encode(&value)))
+            .collect();
+        let timestamp_value = timestamp.to_string();
+        let mut oauth = vec![
+            (
+                "oauth_consumer_key",
+                credentials.consumer_key.expose_secret(),
+            ),
+            ("oauth_nonce", nonce),
+            ("oauth_signature_method", "HMAC-SHA1"),
+            ("oauth_timestamp", &timestamp_value),
+        ];
+        if !credentials.access_token.is_empty() {
+            oauth.push(("oauth_token", credentials.access_token.expose_secret()));
+        }
+        parameters.extend(
+            oauth
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), encode(value))),
+        );
+        parameters.sort();
+        let normalized_parameters = parameters
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let base_url = url[..Position::AfterPath].to_string();
+        let base_string = format!(
+            "{}&{}&{}",
+            method.as_str(),
+            encode(&base_url),
+            encode(&normalized_parameters)
+        );
+
+        let request = self
+            .http
+            .clone()
+            .oauth1_with_params(
+                self.oauth1_secrets(),
+                OAuthParameters::new().nonce(nonce).timestamp(timestamp),
+            )
+            .request(method.clone(), url)
+            .generate_signature()
+            .map_err(|_| TweetyError::AuthError)?
+            .build()?;
+        let authorization = authorization_of(&request);
+
+        Ok(SignatureInspection {
+            method: method.to_string(),
+            base_url,
+            normalized_parameters,
+            base_string,
+            signing_key: format!(
+                "{}&{}",
+                credentials.consumer_key_secret.redacted(),
+                credentials.access_token_secret.redacted()
+            ),
+            authorization,
+        })
+    }
+}
+
+/// Seconds the `Date` header of a response is ahead of the local clock.
+pub(crate) fn clock_skew_of(headers: &HeaderMap) -> Option<i64> {
+    let date = headers.get(DATE)?.to_str().ok()?;
+    let server = httpdate::parse_http_date(date).ok()?;
+    let local = SystemTime::now();
+
+    Some(match server.duration_since(local) {
+        Ok(ahead) => ahead.as_secs() as i64,
+        Err(behind) => -(behind.duration().as_secs() as i64),
+    })
+}
+
+/// Percent-encodes per RFC 3986, as OAuth 1.0a requires: everything but letters,
+/// digits and `-._~`.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
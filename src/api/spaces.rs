@@ -0,0 +1,292 @@
+//! Spaces, and a watcher that reports when they are scheduled, start and end.
+//!
+//! A [`SpacesWatcher`] polls `GET /2/spaces/by/creator_ids` for a set of hosts and
+//! compares each poll with the previous one, so a notification bot only handles the
+//! resulting [`SpaceEvent`]s:
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::spaces::{SpaceEvent, SpacesWatcher};
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let (events, mut received) = tokio::sync::mpsc::channel(16);
+//! let mut watcher = SpacesWatcher::new(client).host("2244994945");
+//! tokio::spawn(async move { watcher.run(events).await });
+//!
+//! while let Some(event) = received.recv().await {
+//!     if let SpaceEvent::Started(space) = event {
+//!         println!("Live now: {}", space.url());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::query::{field_names, Query};
+use crate::api::retry::{DefaultRetryClassifier, RetryClassifier, RetryDecision};
+use crate::api::shutdown::Shutdown;
+use crate::types::ids::UserId;
+use crate::types::meta::Meta;
+pub use crate::types::space::{Space, SpaceState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Default time between two polls.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Most creator ids `GET /2/spaces/by/creator_ids` takes at once.
+const CREATORS_PER_REQUEST: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpaceFields {
+    CreatedAt,
+    CreatorId,
+    EndedAt,
+    HostIds,
+    Id,
+    InvitedUserIds,
+    IsTicketed,
+    Lang,
+    ParticipantCount,
+    ScheduledStart,
+    SpeakerIds,
+    StartedAt,
+    State,
+    SubscriberCount,
+    Title,
+    TopicIds,
+    UpdatedAt,
+}
+
+field_names!(SpaceFields);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpacesResponse {
+    /// Absent from the response when none of the users has a live or scheduled Space.
+    #[serde(default)]
+    pub data: Vec<Space>,
+    #[serde(default)]
+    pub meta: Meta,
+}
+
+/// Spaces
+/// [Docs](https://developer.x.com/en/docs/x-api/spaces)
+impl TweetyClient {
+    /// GET /2/spaces/by/creator_ids
+    /// Returns the live and scheduled Spaces created by the given users, up to 100 ids.
+    /// [Docs](https://developer.x.com/en/docs/x-api/spaces/lookup/api-reference/get-spaces-by-creator-ids)
+    pub async fn get_spaces_by_creator_ids<S: AsRef<str>>(
+        &self,
+        user_ids: &[S],
+        space_fields: Option<Vec<SpaceFields>>,
+    ) -> Result<SpacesResponse, TweetyError> {
+        let user_ids: Vec<&str> = user_ids.iter().map(AsRef::as_ref).collect();
+        let mut query = Query::new();
+        query.push("user_ids", user_ids.join(","));
+        query.push_fields("space.fields", &space_fields.unwrap_or_default());
+
+        let value = self
+            .send_request::<()>(&Endpoint::SpacesByCreatorIds, &query, None)
+            .await?;
+        serde_json::from_value(value).map_err(TweetyError::JsonParseError)
+    }
+}
+
+/// A change a [`SpacesWatcher`] noticed between two polls.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpaceEvent {
+    /// A Space was scheduled for later.
+    Scheduled(Space),
+    /// A Space went live, scheduled or not.
+    Started(Space),
+    /// A live or scheduled Space ended. X stops returning Spaces once they end or are
+    /// canceled; those are reported as they were last seen, with their state set to
+    /// [`SpaceState::Ended`].
+    Ended(Space),
+}
+
+impl SpaceEvent {
+    /// The Space the event is about.
+    pub fn space(&self) -> &Space {
+        match self {
+            SpaceEvent::Scheduled(space)
+            | SpaceEvent::Started(space)
+            | SpaceEvent::Ended(space) => space,
+        }
+    }
+}
+
+/// Polls the Spaces of a set of hosts and reports each change as a [`SpaceEvent`].
+pub struct SpacesWatcher {
+    client: TweetyClient,
+    hosts: Vec<String>,
+    interval: Duration,
+    replay_existing: bool,
+    known: Option<HashMap<String, Space>>,
+    retry_classifier: Arc<dyn RetryClassifier>,
+    shutdown: Option<Shutdown>,
+}
+
+impl SpacesWatcher {
+    pub fn new(client: TweetyClient) -> Self {
+        SpacesWatcher {
+            client,
+            hosts: Vec::new(),
+            interval: DEFAULT_POLL_INTERVAL,
+            replay_existing: false,
+            known: None,
+            retry_classifier: Arc::new(DefaultRetryClassifier),
+            shutdown: None,
+        }
+    }
+
+    /// Adds a user whose Spaces to watch.
+    pub fn host(mut self, user_id: impl Into<UserId>) -> Self {
+        self.hosts.push(user_id.into().into_inner());
+        self
+    }
+
+    /// Time between two polls, [`DEFAULT_POLL_INTERVAL`] by default.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Whether the first poll reports the Spaces already scheduled or live. Off by
+    /// default, so a restarted bot doesn't announce the same Space twice.
+    pub fn replay_existing(mut self, replay: bool) -> Self {
+        self.replay_existing = replay;
+        self
+    }
+
+    /// Decides which polling errors [`run`](SpacesWatcher::run) keeps going after.
+    /// Defaults to [`DefaultRetryClassifier`].
+    pub fn retry_classifier(mut self, classifier: impl RetryClassifier + 'static) -> Self {
+        self.retry_classifier = Arc::new(classifier);
+        self
+    }
+
+    /// Makes [`run`](SpacesWatcher::run) return once `shutdown` is requested, after the
+    /// events of the poll in progress have been sent.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// Polls until a shutdown is requested, `events` is closed, or a request fails with
+    /// an error the [`retry_classifier`](SpacesWatcher::retry_classifier) deems
+    /// permanent. Other polling errors are logged and the watcher keeps going.
+    pub async fn run(&mut self, events: mpsc::Sender<SpaceEvent>) -> Result<(), TweetyError> {
+        let _running = self.shutdown.as_ref().map(Shutdown::running);
+
+        loop {
+            if self.shutdown.as_ref().is_some_and(Shutdown::is_requested) {
+                return Ok(());
+            }
+
+            let mut wait = self.interval;
+            let mut rate_limited = None;
+            match self.poll_once().await {
+                Ok(changes) => {
+                    for event in changes {
+                        if events.send(event).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+                Err(err) => {
+                    match self.retry_classifier.classify(&err) {
+                        RetryDecision::Retry => {}
+                        RetryDecision::RetryAfter(retry_after) => wait = wait.max(retry_after),
+                        RetryDecision::Permanent => return Err(err),
+                    }
+                    rate_limited = err.rate_limit_family();
+                    log::warn!("polling spaces failed: {}", err);
+                }
+            }
+
+            let shutdown = self.shutdown.as_ref();
+            if self
+                .client
+                .wait_for_rate_limit(rate_limited, shutdown, wait)
+                .await
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Fetches the hosts' Spaces and returns what changed since the last poll, in order
+    /// of Space id.
+    pub async fn poll_once(&mut self) -> Result<Vec<SpaceEvent>, TweetyError> {
+        let mut current = HashMap::new();
+        for hosts in self.hosts.chunks(CREATORS_PER_REQUEST) {
+            let response = self
+                .client
+                .get_spaces_by_creator_ids(hosts, Some(watched_fields()))
+                .await?;
+            for space in response.data {
+                current.insert(space.id.clone(), space);
+            }
+        }
+
+        let previous = match self.known.take() {
+            Some(previous) => previous,
+            None if !self.replay_existing => {
+                current.retain(|_, space| space.state != SpaceState::Ended);
+                self.known = Some(current);
+                return Ok(Vec::new());
+            }
+            None => HashMap::new(),
+        };
+
+        let mut events = Vec::new();
+        for space in current.values() {
+            let before = previous.get(&space.id).map(|space| space.state);
+            let event = match (before, space.state) {
+                (None, SpaceState::Scheduled) => SpaceEvent::Scheduled(space.clone()),
+                (None | Some(SpaceState::Scheduled), SpaceState::Live) => {
+                    SpaceEvent::Started(space.clone())
+                }
+                (Some(SpaceState::Scheduled | SpaceState::Live), SpaceState::Ended) => {
+                    SpaceEvent::Ended(space.clone())
+                }
+                _ => continue,
+            };
+            events.push(event);
+        }
+        for (id, space) in previous {
+            if !current.contains_key(&id) {
+                events.push(SpaceEvent::Ended(Space {
+                    state: SpaceState::Ended,
+                    ..space
+                }));
+            }
+        }
+        events.sort_by(|a, b| a.space().id.cmp(&b.space().id));
+
+        current.retain(|_, space| space.state != SpaceState::Ended);
+        self.known = Some(current);
+        Ok(events)
+    }
+}
+
+fn watched_fields() -> Vec<SpaceFields> {
+    vec![
+        SpaceFields::CreatorId,
+        SpaceFields::EndedAt,
+        SpaceFields::HostIds,
+        SpaceFields::ParticipantCount,
+        SpaceFields::ScheduledStart,
+        SpaceFields::StartedAt,
+        SpaceFields::State,
+        SpaceFields::Title,
+    ]
+}
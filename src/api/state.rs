@@ -0,0 +1,153 @@
+//! Remembering where pollers left off across restarts.
+//!
+//! A [`StateStore`] maps string keys to string values, such as the `since_id` of the
+//! last handled mention. [`MentionListener::state_store`] and
+//! [`FilteredStream::state_store`] read their position from one when they start and
+//! write it back as they go. [`FileStateStore`] keeps every key in one JSON file, and
+//! [`MemoryStateStore`] in memory, for tests; a database can be plugged in by
+//! implementing the trait.
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::bot::MentionListener;
+//! use tweety_rs::api::state::FileStateStore;
+//! use tweety_rs::TweetyClient;
+//!
+//! # fn build(client: TweetyClient) {
+//! let listener = MentionListener::new(client)
+//!     .state_store(FileStateStore::new("bot-state.json"), "mentions.since_id");
+//! # }
+//! ```
+//!
+//! [`MentionListener::state_store`]: crate::api::bot::MentionListener::state_store
+//! [`FilteredStream::state_store`]: crate::api::filtered_stream::FilteredStream::state_store
+
+use crate::api::error::TweetyError;
+use futures_util::future::BoxFuture;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// String values stored under string keys.
+pub trait StateStore: Send + Sync {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<String>, TweetyError>>;
+
+    fn set<'a>(&'a self, key: &'a str, value: &'a str) -> BoxFuture<'a, Result<(), TweetyError>>;
+}
+
+/// Keeps the values in memory; they are lost when the process exits.
+#[derive(Debug, Default)]
+pub struct MemoryStateStore {
+    values: Mutex<BTreeMap<String, String>>,
+}
+
+impl MemoryStateStore {
+    pub fn new() -> Self {
+        MemoryStateStore::default()
+    }
+}
+
+impl StateStore for MemoryStateStore {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<String>, TweetyError>> {
+        let value = self
+            .values
+            .lock()
+            .ok()
+            .and_then(|values| values.get(key).cloned());
+        Box::pin(async move { Ok(value) })
+    }
+
+    fn set<'a>(&'a self, key: &'a str, value: &'a str) -> BoxFuture<'a, Result<(), TweetyError>> {
+        if let Ok(mut values) = self.values.lock() {
+            values.insert(key.to_string(), value.to_string());
+        }
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Keeps every value in one JSON object file, rewritten on each change.
+///
+/// The file is replaced through a temporary file next to it, which is flushed to disk
+/// before the rename, and the rename is flushed too, so a crash or power loss mid-write
+/// leaves either the previous state or the new one.
+#[derive(Debug)]
+pub struct FileStateStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl FileStateStore {
+    /// Stores the values in `path`, which is created on the first write.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileStateStore {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>, TweetyError> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(err) => Err(TweetyError::FileIOError(err.to_string())),
+        }
+    }
+
+    fn write(&self, key: &str, value: &str) -> Result<(), TweetyError> {
+        let _guard = self.lock.lock();
+        let mut values = self.read()?;
+        values.insert(key.to_string(), value.to_string());
+
+        let contents = serde_json::to_string_pretty(&values)
+            .map_err(|err| TweetyError::SerializeError(err.to_string()))?;
+        replace_synced(&self.path, contents.as_bytes())
+            .map_err(|err| TweetyError::FileIOError(err.to_string()))
+    }
+}
+
+/// Replaces `path` with `contents` the way [`FileStateStore`] does, so a crash leaves
+/// either the previous contents or the new ones.
+pub(crate) fn replace_synced(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let temp_path = path.with_extension("tmp");
+    write_synced(&temp_path, contents)
+        .and_then(|()| fs::rename(&temp_path, path))
+        .and_then(|()| sync_parent(path))
+}
+
+/// Writes `contents` to `path` and waits until they are on disk.
+fn write_synced(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()
+}
+
+/// Flushes the directory entry of `path`, so a rename to it survives a power loss.
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> std::io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => File::open(parent)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
+
+/// Directories can't be opened to flush them outside Unix.
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+impl StateStore for FileStateStore {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<String>, TweetyError>> {
+        let value = {
+            let _guard = self.lock.lock();
+            self.read().map(|mut values| values.remove(key))
+        };
+        Box::pin(async move { value })
+    }
+
+    fn set<'a>(&'a self, key: &'a str, value: &'a str) -> BoxFuture<'a, Result<(), TweetyError>> {
+        let result = self.write(key, value);
+        Box::pin(async move { result })
+    }
+}
@@ -0,0 +1,260 @@
+//! Newline-delimited JSON, as sent by the streaming endpoints.
+//!
+//! [`LineParser`] is fed the response body chunk by chunk. A message split across
+//! TCP chunks is held until its newline arrives, the blank keep-alive lines X sends
+//! every few seconds are skipped, and a malformed or oversized line is reported as
+//! an error for that line only, so the stream carries on.
+//!
+//! With the `simd-json` feature, [`LineParser::push`] parses messages with simd-json
+//! instead of serde_json.
+
+use crate::api::error::TweetyError;
+use futures_util::stream::{self, Stream};
+use futures_util::StreamExt;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// Longest line [`LineParser`] buffers by default.
+pub const DEFAULT_MAX_LINE_BYTES: usize = 1024 * 1024;
+
+/// Incremental parser for newline-delimited JSON.
+///
+/// ```
+/// use serde_json::Value;
+/// use tweety_rs::api::stream::LineParser;
+///
+/// let mut parser = LineParser::<Value>::new();
+/// assert!(parser.push(b"{\"data\":").is_empty());
+///
+/// let items = parser.push(b"{\"id\":\"1\"}}\r\n\r\n");
+/// assert_eq!(items.len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct LineParser<T = Value> {
+    buffer: Vec<u8>,
+    max_line_bytes: usize,
+    /// Set while skipping the rest of a line that went over `max_line_bytes`.
+    discarding: bool,
+    item: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for LineParser<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> LineParser<T> {
+    pub fn new() -> Self {
+        LineParser {
+            buffer: Vec::new(),
+            max_line_bytes: DEFAULT_MAX_LINE_BYTES,
+            discarding: false,
+            item: PhantomData,
+        }
+    }
+
+    /// Lines longer than `max_line_bytes` are dropped and reported as
+    /// [`TweetyError::StreamLineTooLong`] rather than buffered without bound.
+    pub fn max_line_bytes(mut self, max_line_bytes: usize) -> Self {
+        self.max_line_bytes = max_line_bytes;
+        self
+    }
+
+    /// Feeds the next chunk of the body and calls `on_line` with each non-blank line it
+    /// completed, without parsing it.
+    ///
+    /// Lines that arrived whole are handed out straight from `chunk`, so together with
+    /// [`from_slice_borrowed`](crate::types::borrowed::from_slice_borrowed) a message can
+    /// be read without copying its strings.
+    pub fn push_raw<F>(&mut self, mut chunk: &[u8], mut on_line: F)
+    where
+        F: FnMut(Result<&[u8], TweetyError>),
+    {
+        while let Some(end) = chunk.iter().position(|&byte| byte == b'\n') {
+            let line = &chunk[..end];
+            chunk = &chunk[end + 1..];
+
+            if self.discarding {
+                self.discarding = false;
+                continue;
+            }
+            if self.buffer.len() + line.len() > self.max_line_bytes {
+                self.buffer.clear();
+                on_line(Err(self.too_long()));
+                continue;
+            }
+
+            if self.buffer.is_empty() {
+                emit_line(line, &mut on_line);
+            } else {
+                self.buffer.extend_from_slice(line);
+                emit_line(&self.buffer, &mut on_line);
+                self.buffer.clear();
+            }
+        }
+
+        if !self.discarding {
+            if self.buffer.len() + chunk.len() > self.max_line_bytes {
+                self.buffer.clear();
+                self.discarding = true;
+                on_line(Err(self.too_long()));
+            } else {
+                self.buffer.extend_from_slice(chunk);
+            }
+        }
+    }
+
+    /// Hands out whatever is left once the body has ended without a final newline.
+    pub fn finish_raw<F>(&mut self, mut on_line: F)
+    where
+        F: FnMut(Result<&[u8], TweetyError>),
+    {
+        self.discarding = false;
+        emit_line(&self.buffer, &mut on_line);
+        self.buffer.clear();
+    }
+
+    fn too_long(&self) -> TweetyError {
+        TweetyError::StreamLineTooLong {
+            limit: self.max_line_bytes,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> LineParser<T> {
+    /// Feeds the next chunk of the body and returns the lines it completed, in order.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Result<T, TweetyError>> {
+        let mut items = Vec::new();
+        self.push_raw(chunk, |line| items.push(line.and_then(parse_line)));
+        items
+    }
+
+    /// Parses whatever is left once the body has ended without a final newline.
+    pub fn finish(&mut self) -> Option<Result<T, TweetyError>> {
+        let mut item = None;
+        self.finish_raw(|line| item = Some(line.and_then(parse_line)));
+        item
+    }
+}
+
+fn emit_line<F>(line: &[u8], on_line: &mut F)
+where
+    F: FnMut(Result<&[u8], TweetyError>),
+{
+    let line = line.trim_ascii();
+    if !line.is_empty() {
+        on_line(Ok(line));
+    }
+}
+
+#[cfg(not(feature = "simd-json"))]
+fn parse_line<T: DeserializeOwned>(line: &[u8]) -> Result<T, TweetyError> {
+    serde_json::from_slice(line).map_err(|source| malformed(line, source.into()))
+}
+
+/// simd-json parses in place, so the line is copied into a scratch buffer first.
+#[cfg(feature = "simd-json")]
+fn parse_line<T: DeserializeOwned>(line: &[u8]) -> Result<T, TweetyError> {
+    let mut scratch = line.to_vec();
+    simd_json::serde::from_slice(&mut scratch).map_err(|source| malformed(line, source.into()))
+}
+
+fn malformed(line: &[u8], source: Box<dyn std::error::Error + Send + Sync>) -> TweetyError {
+    TweetyError::MalformedStreamLine {
+        line: String::from_utf8_lossy(line).into_owned(),
+        source,
+    }
+}
+
+/// Reads a streaming response to the end, calling `on_item` for each message and
+/// `on_error` for each line that couldn't be parsed.
+///
+/// Only a failure of the connection itself ends the stream early.
+pub async fn read_lines<T, F, E>(
+    response: reqwest::Response,
+    mut parser: LineParser<T>,
+    mut on_item: F,
+    mut on_error: E,
+) -> Result<(), TweetyError>
+where
+    T: DeserializeOwned,
+    F: FnMut(T),
+    E: FnMut(TweetyError),
+{
+    let mut body = response.bytes_stream();
+
+    while let Some(chunk) = body.next().await {
+        for item in parser.push(&chunk?) {
+            match item {
+                Ok(item) => on_item(item),
+                Err(err) => on_error(err),
+            }
+        }
+    }
+
+    match parser.finish() {
+        Some(Ok(item)) => on_item(item),
+        Some(Err(err)) => on_error(err),
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Like [`read_lines`], but hands each raw line to `on_line` for borrowed parsing.
+pub async fn read_raw_lines<T, F>(
+    response: reqwest::Response,
+    mut parser: LineParser<T>,
+    mut on_line: F,
+) -> Result<(), TweetyError>
+where
+    F: FnMut(Result<&[u8], TweetyError>),
+{
+    let mut body = response.bytes_stream();
+
+    while let Some(chunk) = body.next().await {
+        parser.push_raw(&chunk?, &mut on_line);
+    }
+    parser.finish_raw(&mut on_line);
+
+    Ok(())
+}
+
+/// Turns a streaming response into a [`Stream`] of its messages.
+///
+/// A line that couldn't be parsed comes out as an error and the stream carries on; a
+/// failure of the connection itself is the last item.
+pub fn messages<T>(
+    response: reqwest::Response,
+    parser: LineParser<T>,
+) -> impl Stream<Item = Result<T, TweetyError>> + Send + 'static
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let body = Box::pin(response.bytes_stream());
+    let state = (Some(body), parser, VecDeque::new());
+
+    stream::unfold(state, |(mut body, mut parser, mut ready)| async move {
+        while ready.is_empty() {
+            let chunk = body.as_mut()?.next().await;
+            match chunk {
+                Some(Ok(chunk)) => ready.extend(parser.push(&chunk)),
+                Some(Err(err)) => {
+                    body = None;
+                    ready.push_back(Err(err.into()));
+                }
+                None => {
+                    body = None;
+                    ready.extend(parser.finish());
+                    break;
+                }
+            }
+        }
+
+        let item = ready.pop_front()?;
+        Some((item, (body, parser, ready)))
+    })
+}
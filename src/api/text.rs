@@ -0,0 +1,127 @@
+//! Counting, slicing and truncating text by what readers see as one character.
+//!
+//! Cutting a string at a `char` can split an emoji: 👩‍👩‍👧 is five `char`s joined by
+//! zero-width joiners, a flag is two regional indicators and 👍🏽 is a thumb and a skin
+//! tone. These helpers work on grapheme clusters instead, so emoji sequences, flags,
+//! keycaps, Hangul syllables spelled with jamo and letters with combining accents or
+//! vowel signs are kept whole. [`Composer`] and [`Post::fit_to`] cut text with them.
+//!
+//! Clusters are the extended grapheme clusters of Unicode Standard Annex #29, as
+//! segmented by the `unicode-segmentation` crate.
+//!
+//! ```rust
+//! use tweety_rs::api::text;
+//!
+//! let text = "Go 🇺🇦! 👩‍👩‍👧";
+//! assert_eq!(text::grapheme_count(text), 7);
+//! assert_eq!(text::truncate(text, 4), "Go 🇺🇦");
+//! assert_eq!(text::truncate_with_ellipsis(text, 6), "Go 🇺🇦!…");
+//! assert_eq!(text::slice(text, 3..), "🇺🇦! 👩‍👩‍👧");
+//! ```
+//!
+//! [`Composer`]: crate::api::compose::Composer
+//! [`Post::fit_to`]: crate::api::crosspost::Post::fit_to
+
+use std::borrow::Cow;
+use std::ops::{Bound, RangeBounds};
+use unicode_segmentation::UnicodeSegmentation;
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+const ELLIPSIS: char = '…';
+
+/// The grapheme clusters of a string, see [`graphemes`].
+#[derive(Debug, Clone)]
+pub struct Graphemes<'a> {
+    inner: unicode_segmentation::Graphemes<'a>,
+}
+
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.inner.next()
+    }
+}
+
+/// The grapheme clusters of `text`, in order.
+pub fn graphemes(text: &str) -> Graphemes<'_> {
+    Graphemes {
+        inner: text.graphemes(true),
+    }
+}
+
+/// How many grapheme clusters `text` has.
+pub fn grapheme_count(text: &str) -> usize {
+    graphemes(text).count()
+}
+
+/// The first `max` grapheme clusters of `text`.
+pub fn truncate(text: &str, max: usize) -> &str {
+    slice(text, ..max)
+}
+
+/// `text` when it has at most `max` grapheme clusters, otherwise its first `max - 1`
+/// followed by an ellipsis.
+pub fn truncate_with_ellipsis(text: &str, max: usize) -> Cow<'_, str> {
+    let ends: Vec<usize> = boundaries(text).take(max.saturating_add(1)).collect();
+    if ends.len() <= max {
+        return Cow::Borrowed(text);
+    }
+    match max {
+        0 => Cow::Borrowed(""),
+        1 => Cow::Owned(ELLIPSIS.to_string()),
+        max => Cow::Owned(format!("{}{}", &text[..ends[max - 2]], ELLIPSIS)),
+    }
+}
+
+/// The grapheme clusters of `text` in `range`, which counts clusters. Ends past the
+/// last cluster are clamped.
+pub fn slice(text: &str, range: impl RangeBounds<usize>) -> &str {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => Some(end.saturating_add(1)),
+        Bound::Excluded(&end) => Some(end),
+        Bound::Unbounded => None,
+    };
+
+    let offset = |index: usize| match index {
+        0 => 0,
+        index => boundaries(text).nth(index - 1).unwrap_or(text.len()),
+    };
+    let start = offset(start);
+    let end = end.map_or(text.len(), offset).max(start);
+    &text[start..end]
+}
+
+/// The byte offsets where each grapheme cluster of `text` ends.
+pub(crate) fn boundaries(text: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut end = 0;
+    graphemes(text).map(move |cluster| {
+        end += cluster.len();
+        end
+    })
+}
+
+/// Whether `cluster` is an emoji made of several `char`s, such as a flag, a keycap or
+/// an emoji with a skin tone, or a single emoji from the supplementary planes.
+pub(crate) fn is_emoji(cluster: &str) -> bool {
+    let mut chars = cluster.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if chars.next().is_none() {
+        return matches!(first as u32, 0x1F000..=0x1FAFF);
+    }
+    is_regional_indicator(first)
+        || cluster.chars().any(|c| {
+            c == ZERO_WIDTH_JOINER || matches!(c as u32, 0xFE0F | 0x20E3 | 0x1F3FB..=0x1F3FF)
+        })
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
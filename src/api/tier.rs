@@ -0,0 +1,322 @@
+//! X API access tiers.
+//!
+//! Set the tier of your developer account with [`TweetyClient::with_tier`] and requests
+//! the tier can't make fail before they are sent, with a [`TweetyError::TierLimit`]
+//! naming what is wrong instead of the API's generic 403. The client also counts the
+//! posts it sends and reads, and logs a warning as the tier's monthly caps come near.
+//! The counts start over with each monthly period, see [`cap_period_start`].
+//!
+//! ```
+//! use tweety_rs::api::endpoint::Endpoint;
+//! use tweety_rs::api::tier::Tier;
+//!
+//! assert!(!Tier::Basic.allows(&Endpoint::FullArchiveSearch));
+//! assert_eq!(Tier::Basic.max_query_length(&Endpoint::RecentSearch), Some(512));
+//! ```
+//!
+//! [`TweetyClient::with_tier`]: crate::api::client::TweetyClient::with_tier
+//! [`TweetyError::TierLimit`]: crate::api::error::TweetyError::TierLimit
+
+use crate::api::backfill::civil_from_days;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::oauth2::unix_now;
+use crate::api::query::Query;
+use serde::de::{Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Day of the month the monthly caps are taken to reset on until the project's is
+/// known. X resets them on the `cap_reset_day` reported by `GET /2/usage/tweets`, see
+/// [`UsageGuard::cap_reset_day`].
+///
+/// [`UsageGuard::cap_reset_day`]: crate::api::usage::UsageGuard::cap_reset_day
+pub const DEFAULT_CAP_RESET_DAY: u8 = 1;
+
+/// Share of a monthly cap at which the first warning is logged.
+const WARN_AT_PERCENT: u64 = 80;
+
+/// The access tier of an X developer account, from the smallest to the largest.
+/// [Docs](https://developer.x.com/en/docs/x-api/getting-started/about-x-api)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tier {
+    Free,
+    Basic,
+    Pro,
+    Enterprise,
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Tier::Free => "Free",
+            Tier::Basic => "Basic",
+            Tier::Pro => "Pro",
+            Tier::Enterprise => "Enterprise",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Tier {
+    /// Whether the tier can call `endpoint` at all.
+    pub fn allows(self, endpoint: &Endpoint) -> bool {
+        self >= endpoint.minimum_tier()
+    }
+
+    /// Longest search query the tier accepts for `endpoint`, or `None` for endpoints
+    /// without a query.
+    pub fn max_query_length(self, endpoint: &Endpoint) -> Option<usize> {
+        match (endpoint, self) {
+            (Endpoint::RecentSearch, Tier::Pro | Tier::Enterprise) => Some(4096),
+            (Endpoint::RecentSearch, _) => Some(512),
+            (Endpoint::FullArchiveSearch, Tier::Enterprise) => Some(4096),
+            (Endpoint::FullArchiveSearch, _) => Some(1024),
+            _ => None,
+        }
+    }
+
+    /// Longest filtered stream rule the tier accepts.
+    pub fn max_rule_length(self) -> usize {
+        match self {
+            Tier::Free | Tier::Basic => 512,
+            Tier::Pro => 1024,
+            Tier::Enterprise => 2048,
+        }
+    }
+
+    /// Largest `max_results` the tier may ask `endpoint` for, or `None` when the tier
+    /// doesn't change the API's own limit.
+    pub fn max_results(self, endpoint: &Endpoint) -> Option<u32> {
+        match endpoint {
+            Endpoint::RecentSearch => Some(100),
+            Endpoint::FullArchiveSearch => Some(500),
+            _ => None,
+        }
+    }
+
+    /// Posts the tier may create per month, or `None` when the cap is negotiated.
+    pub fn monthly_post_cap(self) -> Option<u64> {
+        match self {
+            Tier::Free => Some(500),
+            Tier::Basic => Some(3_000),
+            Tier::Pro => Some(300_000),
+            Tier::Enterprise => None,
+        }
+    }
+
+    /// Posts the tier may read per month, or `None` when the cap is negotiated.
+    pub fn monthly_read_cap(self) -> Option<u64> {
+        match self {
+            Tier::Free => Some(100),
+            Tier::Basic => Some(10_000),
+            Tier::Pro => Some(1_000_000),
+            Tier::Enterprise => None,
+        }
+    }
+
+    /// Checks a request against the tier before it is sent.
+    pub(crate) fn check(self, endpoint: &Endpoint, query: &Query) -> Result<(), TweetyError> {
+        let limit = |reason: String| TweetyError::TierLimit { tier: self, reason };
+
+        if !self.allows(endpoint) {
+            return Err(limit(format!(
+                "{} {} needs the {} tier or above",
+                endpoint.method(),
+                endpoint.path_template(),
+                endpoint.minimum_tier()
+            )));
+        }
+
+        if let (Some(max), Some(search)) = (self.max_query_length(endpoint), query.get("query")) {
+            let length = search.chars().count();
+            if length > max {
+                return Err(limit(format!(
+                    "the query is {} characters long, the limit is {}",
+                    length, max
+                )));
+            }
+        }
+
+        if let (Some(max), Some(requested)) = (self.max_results(endpoint), query.get("max_results"))
+        {
+            if requested
+                .parse::<u32>()
+                .is_ok_and(|requested| requested > max)
+            {
+                return Err(limit(format!(
+                    "max_results is {}, the limit is {}",
+                    requested, max
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Start of the monthly cap period `now` falls in: midnight UTC on the latest
+/// `reset_day` of a month, or on the month's last day when it has fewer days. Days
+/// outside 1 to 31 are clamped.
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use tweety_rs::api::backfill::to_rfc3339;
+/// use tweety_rs::api::tier::cap_period_start;
+///
+/// // 2024-03-10T12:00:00Z
+/// let now = UNIX_EPOCH + Duration::from_secs(1_710_072_000);
+/// assert_eq!(to_rfc3339(cap_period_start(now, 19)), "2024-02-19T00:00:00Z");
+/// assert_eq!(to_rfc3339(cap_period_start(now, 10)), "2024-03-10T00:00:00Z");
+/// // February 2024 has 29 days.
+/// assert_eq!(to_rfc3339(cap_period_start(now, 31)), "2024-02-29T00:00:00Z");
+/// ```
+pub fn cap_period_start(now: SystemTime, reset_day: u8) -> SystemTime {
+    let secs = now
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    UNIX_EPOCH + Duration::from_secs(cap_period_start_secs(secs, reset_day))
+}
+
+/// [`cap_period_start`] in seconds since the epoch.
+pub(crate) fn cap_period_start_secs(now_secs: u64, reset_day: u8) -> u64 {
+    let reset_day = u32::from(reset_day.clamp(1, 31));
+    let resets_on = |days: i64| {
+        let (_, _, day) = civil_from_days(days);
+        day == reset_day || (day < reset_day && civil_from_days(days + 1).2 == 1)
+    };
+    let today = (now_secs / 86_400) as i64;
+    let start = (today - 31..=today)
+        .rev()
+        .find(|days| resets_on(*days))
+        .unwrap_or(today);
+    start as u64 * 86_400
+}
+
+/// Posts sent and read by a client, counted against its tier's monthly caps.
+#[derive(Debug, Default)]
+pub(crate) struct Usage {
+    period_start: u64,
+    posts_created: u64,
+    posts_read: u64,
+}
+
+impl Usage {
+    /// Counts a successful response and returns a warning when it brings the client
+    /// near or to one of the tier's monthly caps, which reset on `reset_day`.
+    pub(crate) fn record(
+        &mut self,
+        tier: Tier,
+        endpoint: &Endpoint,
+        reset_day: u8,
+        posts: impl FnOnce() -> u64,
+    ) -> Option<String> {
+        let period_start = cap_period_start_secs(unix_now(), reset_day);
+        if period_start != self.period_start {
+            *self = Usage {
+                period_start,
+                ..Usage::default()
+            };
+        }
+
+        let (kind, count, cap) = match endpoint {
+            Endpoint::CreateTweet => ("sent", &mut self.posts_created, tier.monthly_post_cap()),
+            _ if reads_posts(endpoint) => ("read", &mut self.posts_read, tier.monthly_read_cap()),
+            _ => return None,
+        };
+
+        let before = *count;
+        *count += posts();
+
+        let cap = cap?;
+        let warn_at = cap * WARN_AT_PERCENT / 100;
+        let crossed = |threshold: u64| before < threshold && *count >= threshold;
+
+        if crossed(cap) {
+            Some(format!(
+                "{} posts {} this month, the {} tier's cap is {}",
+                *count, kind, tier, cap
+            ))
+        } else if crossed(warn_at) {
+            Some(format!(
+                "{} posts {} this month, {}% of the {} tier's cap of {}",
+                *count, kind, WARN_AT_PERCENT, tier, cap
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// The number of posts in a response's `data`: its length when it is a list, else one.
+pub(crate) fn posts_in_value(value: &Value) -> u64 {
+    match value.get("data") {
+        Some(Value::Array(posts)) => posts.len() as u64,
+        Some(_) => 1,
+        None => 0,
+    }
+}
+
+/// Like [`posts_in_value`], skimming a response body without building a `Value`.
+pub(crate) fn posts_in_body(text: &str) -> u64 {
+    #[derive(Deserialize)]
+    struct Body {
+        data: Option<PostCount>,
+    }
+
+    struct PostCount(u64);
+
+    impl<'de> Deserialize<'de> for PostCount {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(PostCountVisitor)
+        }
+    }
+
+    struct PostCountVisitor;
+
+    impl<'de> Visitor<'de> for PostCountVisitor {
+        type Value = PostCount;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a post or a list of posts")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<PostCount, A::Error> {
+            let mut count = 0;
+            while seq.next_element::<IgnoredAny>()?.is_some() {
+                count += 1;
+            }
+            Ok(PostCount(count))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<PostCount, A::Error> {
+            while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+            Ok(PostCount(1))
+        }
+    }
+
+    serde_json::from_str::<Body>(text)
+        .ok()
+        .and_then(|body| body.data)
+        .map_or(0, |PostCount(count)| count)
+}
+
+/// Endpoints whose responses count as posts read.
+pub(crate) fn reads_posts(endpoint: &Endpoint) -> bool {
+    matches!(
+        endpoint,
+        Endpoint::Tweets
+            | Endpoint::Tweet(_)
+            | Endpoint::RecentSearch
+            | Endpoint::FullArchiveSearch
+            | Endpoint::UserMentions(_)
+            | Endpoint::UserTweets(_)
+            | Endpoint::Retweets(_)
+            | Endpoint::LikedTweets(_)
+            | Endpoint::Bookmarks(_)
+    )
+}
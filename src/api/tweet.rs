@@ -1,14 +1,16 @@
 use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
 use crate::api::mentions::{
-    ExpansionType, MediaField, PlaceField, PollField, TweetData, TweetField, UserField,
+    ExpansionType, Includes, MediaField, PlaceField, PollField, TweetData, TweetField, UserField,
 };
+use crate::api::query::Query;
+use crate::types::ids::TweetId;
+use crate::types::lookup::LookupOutcome;
 use crate::types::tweet::PostTweetParams;
-use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt;
-use yaup::to_string as convert_query_to_string;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TweetRequest {
@@ -33,15 +35,18 @@ pub struct DeleteData {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Ids {
-    Single(String),
-    Multiple(Vec<String>),
+    Single(TweetId),
+    Multiple(Vec<TweetId>),
 }
 
 impl fmt::Display for Ids {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Ids::Single(id) => write!(f, "{}", id),
-            Ids::Multiple(ids) => write!(f, "{}", ids.join(",")),
+            Ids::Multiple(ids) => {
+                let ids: Vec<&str> = ids.iter().map(TweetId::as_str).collect();
+                write!(f, "{}", ids.join(","))
+            }
         }
     }
 }
@@ -72,7 +77,26 @@ pub struct QueryParams {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LookupResponse {
     pub data: TweetData,
-    // TODO: impl includes field
+    pub includes: Option<Includes>,
+}
+
+/// The response of `GET /2/tweets`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TweetsResponse {
+    /// Absent from the response when none of the ids could be returned.
+    #[serde(default)]
+    pub data: Vec<TweetData>,
+    pub includes: Option<Includes>,
+    /// One entry per id that couldn't be returned, e.g. because the tweet was deleted.
+    #[serde(default)]
+    pub errors: Vec<Value>,
+}
+
+impl TweetsResponse {
+    /// Pairs each of `ids` with what the response holds for it, in the order given.
+    pub fn outcomes(self, ids: &[TweetId]) -> Vec<(TweetId, LookupOutcome<TweetData>)> {
+        LookupOutcome::collect(ids, self.data, &self.errors, |tweet| &tweet.id)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -93,18 +117,51 @@ impl TweetyClient {
     /// GET /2/tweets
     /// Returns a variety of information about the Tweet specified by the requested ID or list of IDs.
     /// [Docs](https://developer.x.com/en/docs/x-api/tweets/lookup/api-reference/get-tweets)
-    pub async fn get_tweet(&self, tweet_id: Ids) -> Result<Value, TweetyError> {
-        let base_url = format!("https://api.x.com/2/tweets/?ids={}", tweet_id);
+    pub async fn get_tweet(&self, tweet_id: Ids) -> Result<TweetsResponse, TweetyError> {
+        let value = self.get_tweet_raw(tweet_id).await?;
+        serde_json::from_value(value).map_err(TweetyError::JsonParseError)
+    }
 
-        self.send_request::<()>(&base_url, Method::GET, None).await
+    /// GET /2/tweets
+    /// Looks up `ids` and tells apart the tweets that came back from the deleted,
+    /// protected and suspended ones, see [`LookupOutcome`]. Up to 100 ids per call.
+    pub async fn lookup_tweets(
+        &self,
+        ids: Vec<TweetId>,
+    ) -> Result<Vec<(TweetId, LookupOutcome<TweetData>)>, TweetyError> {
+        let response = self.get_tweet(Ids::Multiple(ids.clone())).await?;
+        Ok(response.outcomes(&ids))
     }
+
+    /// Like [`get_tweet`](TweetyClient::get_tweet), returning the response as it came.
+    pub async fn get_tweet_raw(&self, tweet_id: Ids) -> Result<Value, TweetyError> {
+        let mut query = Query::new();
+        query.push("ids", tweet_id);
+
+        self.send_request::<()>(&Endpoint::Tweets, &query, None)
+            .await
+    }
+
     /// GET /2/tweets/:id
     /// Returns a variety of information about a single Tweet specified by the requested ID.
     /// [Docs](https://developer.x.com/en/docs/x-api/tweets/lookup/api-reference/get-tweets-id)
-    pub async fn get_tweet_info(&self, tweet_id: &str) -> Result<Value, TweetyError> {
-        let base_url = format!("https://api.x.com/2/tweets/{}", tweet_id);
+    pub async fn get_tweet_info(
+        &self,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<LookupResponse, TweetyError> {
+        self.get_tweet_info_with_params(tweet_id, None).await
+    }
+
+    /// Like [`get_tweet_info`](TweetyClient::get_tweet_info), returning the response as
+    /// it came.
+    pub async fn get_tweet_info_raw(
+        &self,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<Value, TweetyError> {
+        let tweet_id: TweetId = tweet_id.into();
 
-        self.send_request::<()>(&base_url, Method::GET, None).await
+        self.send_request::<()>(&Endpoint::Tweet(tweet_id), &Query::new(), None)
+            .await
     }
 
     /// GET /2/tweets/:id
@@ -112,23 +169,24 @@ impl TweetyClient {
     /// [Docs](https://developer.x.com/en/docs/x-api/tweets/lookup/api-reference/get-tweets-id)
     pub async fn get_tweet_info_with_params(
         &self,
-        tweet_id: &str,
+        tweet_id: impl Into<TweetId>,
         params: Option<QueryParams>,
     ) -> Result<LookupResponse, TweetyError> {
-        let mut base_url = format!("https://api.x.com/2/tweets/{}", tweet_id);
-
-        if let Some(query) = params {
-            let query_params = convert_query_to_string(&query)
-                .map_err(|e| TweetyError::SerializeError(e.to_string()))?;
-            base_url = format!("{}{}", base_url, query_params);
-        }
+        let tweet_id: TweetId = tweet_id.into();
+        let query = match params {
+            Some(query) => Query::from_serialize(&query)?,
+            None => Query::new(),
+        };
 
-        match self.send_request::<()>(&base_url, Method::GET, None).await {
+        match self
+            .send_request::<()>(&Endpoint::Tweet(tweet_id), &query, None)
+            .await
+        {
             Ok(value) => match serde_json::from_value::<LookupResponse>(value) {
                 Ok(data) => Ok(data),
-                Err(err) => Err(TweetyError::JsonParseError(err.to_string())),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
             },
-            Err(err) => Err(TweetyError::ApiError(err.to_string())),
+            Err(err) => Err(err),
         }
     }
 
@@ -140,8 +198,6 @@ impl TweetyClient {
         message: &str,
         body_params: Option<PostTweetParams>,
     ) -> Result<PostTweetResponseData, TweetyError> {
-        let base_url = "https://api.twitter.com/2/tweets";
-
         let json_body = if let Some(body) = body_params {
             body.to_json(message)
         } else {
@@ -150,38 +206,51 @@ impl TweetyClient {
         };
 
         match self
-            .send_request(base_url, Method::POST, Some(json_body))
+            .send_request(&Endpoint::CreateTweet, &Query::new(), Some(json_body))
             .await
         {
             Ok(value) => match serde_json::from_value::<PostTweetResponseData>(value) {
                 Ok(res) => Ok(res),
-                Err(e) => Err(TweetyError::JsonParseError(e.to_string())),
+                Err(e) => Err(TweetyError::JsonParseError(e)),
             },
-            Err(err) => Err(TweetyError::ApiError(err.to_string())),
+            Err(err) => Err(err),
         }
     }
     /// UPDATE/EDIT TWEET
-    pub async fn edit_tweet(self, message: &str, media_id: &str) -> Result<Value, TweetyError> {
-        let base_url = format!("https://api.twitter.com/2/tweets/{}", media_id);
-
+    /// Posts `message` as a new version of `tweet_id`, which must still be editable,
+    /// see [`EditControls`](crate::types::tweet::EditControls).
+    /// [Docs](https://developer.x.com/en/docs/x-api/tweets/manage-tweets/api-reference/post-tweets)
+    pub async fn edit_tweet(
+        &self,
+        message: &str,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<Value, TweetyError> {
         let body = serde_json::json!({
             "text": message,
+            "edit_options": { "previous_post_id": tweet_id.into() },
         });
 
-        self.send_request(&base_url, Method::PATCH, Some(body))
+        self.send_request(&Endpoint::CreateTweet, &Query::new(), Some(body))
             .await
     }
 
     /// DELETE TWEET
     /// Path parameter, pass The Tweet ID you are deleting.
     /// [Delete Docs](https://developer.x.com/en/docs/x-api/tweets/manage-tweets/api-reference/delete-tweets-id)
-    pub async fn delete_tweet(&self, tweet_id: &str) -> Result<DeleteResponse, TweetyError> {
-        let url = format!("https://api.x.com/2/tweets/{}", tweet_id);
+    pub async fn delete_tweet(
+        &self,
+        tweet_id: impl Into<TweetId>,
+    ) -> Result<DeleteResponse, TweetyError> {
+        let tweet_id: TweetId = tweet_id.into();
+        let endpoint = Endpoint::DeleteTweet(tweet_id);
 
-        match self.send_request::<()>(&url, Method::DELETE, None).await {
+        match self
+            .send_request::<()>(&endpoint, &Query::new(), None)
+            .await
+        {
             Ok(value) => match serde_json::from_value::<DeleteResponse>(value) {
                 Ok(res) => Ok(res),
-                Err(err) => Err(TweetyError::JsonParseError(err.to_string())),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
             },
             Err(err) => Err(err),
         }
@@ -1,67 +1,495 @@
 use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
+use crate::api::oauth2::unix_now;
+use crate::api::query::Query;
+use crate::types::ids::MediaId;
+use futures_util::{stream, StreamExt};
 use reqwest::multipart;
-use reqwest_oauth1::OAuthClientProvider;
 use serde::{Deserialize, Serialize};
-use std::{fs::File, io::Read, path::Path};
+use std::collections::BTreeSet;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::{fs::File, time::Duration};
+use tokio::task::JoinSet;
 
 #[derive(Serialize, Deserialize)]
 pub struct Media {
-    pub media_id: u64,
+    pub media_id: MediaId,
 }
 
 impl TweetyClient {
     /// Create a media from a file<br/>
     /// Will fail if `consumer_key`, `consumer_secret`, `access_token` and `access_token_secret` are not set
     /// Will take a path as a parameter and return the media id of the uploadeded file is sucess and TweetyError incase of failure
-    pub async fn upload_file(&self, path: &Path) -> Result<u64, TweetyError> {
+    pub async fn upload_file(&self, path: &Path) -> Result<MediaId, TweetyError> {
+        self.upload_file_with_progress(path, |_, _| {}).await
+    }
+
+    /// Same as [`TweetyClient::upload_file`], calling `on_progress(bytes_sent, total_bytes)`
+    /// as the file is sent, e.g. to drive a progress bar.
+    pub async fn upload_file_with_progress<F>(
+        &self,
+        path: &Path,
+        on_progress: F,
+    ) -> Result<MediaId, TweetyError>
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
         if !self.is_initialized() {
             return Err(TweetyError::MissingCredentials);
         }
 
-        let mut file = match File::open(path) {
-            Ok(value) => value,
-            Err(err) => {
-                println!("Error opening file: {:?}", err);
-                return Err(TweetyError::FileIOError(err.to_string()));
-            }
-        };
+        let mut file = File::open(path).map_err(|err| TweetyError::FileIOError(err.to_string()))?;
 
         let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer).unwrap();
+        file.read_to_end(&mut buffer)
+            .map_err(|err| TweetyError::FileIOError(err.to_string()))?;
 
-        let part = multipart::Part::bytes(buffer)
-            .file_name(path.file_name().unwrap().to_string_lossy().to_string());
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        self.upload_bytes_with_progress(buffer, file_name, on_progress)
+            .await
+    }
+
+    /// Uploads in-memory media in a single request, e.g. an image re-encoded to fit the limits.
+    pub(crate) async fn upload_bytes_with_progress<F>(
+        &self,
+        buffer: Vec<u8>,
+        file_name: String,
+        on_progress: F,
+    ) -> Result<MediaId, TweetyError>
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        if !self.is_initialized() {
+            return Err(TweetyError::MissingCredentials);
+        }
+
+        let total_bytes = buffer.len() as u64;
+        let part = multipart::Part::stream_with_length(
+            progress_body(buffer, total_bytes, on_progress),
+            total_bytes,
+        )
+        .file_name(file_name);
 
         let form = multipart::Form::new().part("media", part);
 
-        let secrets = reqwest_oauth1::Secrets::new(&self.consumer_key, &self.consumer_key_secret)
-            .token(&self.access_token, &self.access_token_secret);
-        let client = reqwest::Client::new();
-
-        let response = client
-            .oauth1(secrets)
-            .post("https://upload.twitter.com/1.1/media/upload.json")
-            .multipart(form)
-            .send()
-            .await;
-
-        match response {
-            Ok(res) => {
-                if res.status().is_success() {
-                    let res = res.json::<Media>().await?;
-                    Ok(res.media_id)
-                } else {
-                    let err = res.json().await;
-                    if let Ok(value) = err {
-                        return Ok(value);
-                    }
-                    Err(TweetyError::JsonParseError(
-                        "Error parsing multipart JSON".to_string(),
-                    ))
+        let value = self
+            .send_multipart(&Endpoint::MediaUpload, &Query::new(), form)
+            .await?;
+        let media = serde_json::from_value::<Media>(value)?;
+
+        Ok(media.media_id)
+    }
+}
+
+/// Bytes handed to the connection between two progress reports of a single-request upload.
+const PROGRESS_STEP: usize = 64 * 1024;
+
+/// Streams `buffer` as a request body, reporting each piece as it is read.
+fn progress_body<F>(buffer: Vec<u8>, total_bytes: u64, on_progress: F) -> reqwest::Body
+where
+    F: Fn(u64, u64) + Send + Sync + 'static,
+{
+    let pieces: Vec<Result<Vec<u8>, std::io::Error>> = buffer
+        .chunks(PROGRESS_STEP)
+        .map(|piece| Ok(piece.to_vec()))
+        .collect();
+
+    let mut bytes_sent = 0;
+    let stream = stream::iter(pieces).inspect(move |piece| {
+        if let Ok(piece) = piece {
+            bytes_sent += piece.len() as u64;
+            on_progress(bytes_sent, total_bytes);
+        }
+    });
+
+    reqwest::Body::wrap_stream(stream)
+}
+
+/// Bytes sent per APPEND segment unless changed with
+/// [`TweetyClient::with_upload_chunk_size`].
+pub const DEFAULT_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Largest APPEND segment X accepts.
+pub const MAX_CHUNK_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Most APPEND segments an upload can have; `segment_index` runs from 0 to 999.
+const MAX_SEGMENTS: u64 = 1000;
+
+/// Number of APPEND segments uploaded at the same time.
+pub const DEFAULT_UPLOAD_CONCURRENCY: usize = 4;
+
+fn default_concurrency() -> usize {
+    DEFAULT_UPLOAD_CONCURRENCY
+}
+
+/// Progress of a chunked upload, returned by [`TweetyClient::init_chunked_upload`].
+///
+/// Save it with [`UploadState::persist_to`] and an interrupted upload can be continued
+/// with [`TweetyClient::resume_upload`] — even from another process — instead of
+/// re-sending the segments X already has.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadState {
+    pub media_id: MediaId,
+    /// The file being uploaded.
+    pub path: PathBuf,
+    pub media_type: String,
+    pub total_bytes: u64,
+    pub chunk_size: u64,
+    /// Number of segments already appended, counted from the first one. Segments are
+    /// sent concurrently, so some after this index may have been appended too; they are
+    /// sent again on resume.
+    pub segments_appended: u32,
+    /// Unix time after which X discards the unfinished upload.
+    pub expires_at: Option<u64>,
+    #[serde(skip)]
+    state_file: Option<PathBuf>,
+    #[serde(skip, default = "default_concurrency")]
+    concurrency: usize,
+}
+
+impl UploadState {
+    /// Total number of APPEND segments for the file.
+    pub fn total_segments(&self) -> u32 {
+        self.total_bytes.div_ceil(self.chunk_size.max(1)) as u32
+    }
+
+    /// Returns `true` once every segment has been appended.
+    pub fn is_complete(&self) -> bool {
+        self.segments_appended >= self.total_segments()
+    }
+
+    /// Sets how many segments are uploaded in parallel, [`DEFAULT_UPLOAD_CONCURRENCY`]
+    /// unless changed. Not persisted.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Writes the state to `path` now and after every appended segment.
+    ///
+    /// The file is removed once the upload is finalized.
+    pub fn persist_to(mut self, path: impl Into<PathBuf>) -> Result<Self, TweetyError> {
+        self.state_file = Some(path.into());
+        self.save()?;
+        Ok(self)
+    }
+
+    /// Reads a state written by [`UploadState::persist_to`]; it keeps saving to the same file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TweetyError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+        let mut state: UploadState =
+            serde_json::from_str(&contents).map_err(TweetyError::JsonParseError)?;
+        state.state_file = Some(path.to_path_buf());
+        Ok(state)
+    }
+
+    fn save(&self) -> Result<(), TweetyError> {
+        if let Some(ref state_file) = self.state_file {
+            let contents = serde_json::to_string_pretty(self)
+                .map_err(|err| TweetyError::SerializeError(err.to_string()))?;
+            std::fs::write(state_file, contents)
+                .map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn remove_state_file(&self) {
+        if let Some(ref state_file) = self.state_file {
+            let _ = std::fs::remove_file(state_file);
+        }
+    }
+
+    /// Reads the bytes of segment `index` from the file.
+    fn read_segment(&self, index: u32) -> Result<Vec<u8>, TweetyError> {
+        let mut file =
+            File::open(&self.path).map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+        file.seek(SeekFrom::Start(index as u64 * self.chunk_size))
+            .map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+
+        let mut buffer = Vec::new();
+        file.take(self.chunk_size)
+            .read_to_end(&mut buffer)
+            .map_err(|err| TweetyError::FileIOError(err.to_string()))?;
+        Ok(buffer)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct InitResponse {
+    media_id: MediaId,
+    expires_after_secs: Option<u64>,
+}
+
+/// Response of the FINALIZE and STATUS commands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaStatus {
+    pub media_id: MediaId,
+    /// Only present for media that X processes after upload, such as videos.
+    pub processing_info: Option<ProcessingInfo>,
+}
+
+/// Server-side processing of an uploaded video or GIF, reported by FINALIZE and STATUS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingInfo {
+    pub state: ProcessingState,
+    pub check_after_secs: Option<u64>,
+    pub progress_percent: Option<u8>,
+    pub error: Option<ProcessingError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingState {
+    Pending,
+    InProgress,
+    Failed,
+    Succeeded,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingError {
+    pub code: Option<u32>,
+    pub name: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Chunked media upload
+/// [Docs](https://developer.x.com/en/docs/twitter-api/v1/media/upload-media/uploading-media/chunked-media-upload)
+impl TweetyClient {
+    /// Uploads a large file, such as a video, in segments and returns its media id once
+    /// X has finished processing it.
+    ///
+    /// `media_category` is e.g. `tweet_video` or `tweet_gif`. For uploads that should
+    /// survive an interruption, call [`TweetyClient::init_chunked_upload`] and
+    /// [`TweetyClient::resume_upload`] yourself and persist the state in between.
+    pub async fn upload_chunked(
+        &self,
+        path: &Path,
+        media_type: &str,
+        media_category: Option<&str>,
+    ) -> Result<MediaId, TweetyError> {
+        self.upload_chunked_with_progress(path, media_type, media_category, |_, _| {})
+            .await
+    }
+
+    /// Same as [`TweetyClient::upload_chunked`], calling `on_progress(bytes_sent, total_bytes)`
+    /// each time a segment has been appended.
+    pub async fn upload_chunked_with_progress<F>(
+        &self,
+        path: &Path,
+        media_type: &str,
+        media_category: Option<&str>,
+        on_progress: F,
+    ) -> Result<MediaId, TweetyError>
+    where
+        F: Fn(u64, u64) + Send + Sync,
+    {
+        let mut state = self
+            .init_chunked_upload(path, media_type, media_category)
+            .await?;
+        self.resume_upload_with_progress(&mut state, on_progress)
+            .await
+    }
+
+    /// POST /1.1/media/upload.json?command=INIT
+    /// Starts a chunked upload of the file at `path`; nothing is appended yet.
+    pub async fn init_chunked_upload(
+        &self,
+        path: &Path,
+        media_type: &str,
+        media_category: Option<&str>,
+    ) -> Result<UploadState, TweetyError> {
+        let total_bytes = std::fs::metadata(path)
+            .map_err(|err| TweetyError::FileIOError(err.to_string()))?
+            .len();
+
+        let mut query = Query::new();
+        query
+            .push("command", "INIT")
+            .push("total_bytes", total_bytes)
+            .push("media_type", media_type)
+            .push_opt("media_category", media_category);
+
+        let value = self
+            .send_request::<()>(&Endpoint::MediaUpload, &query, None)
+            .await?;
+        let init =
+            serde_json::from_value::<InitResponse>(value).map_err(TweetyError::JsonParseError)?;
+
+        Ok(UploadState {
+            media_id: init.media_id,
+            path: path.to_path_buf(),
+            media_type: media_type.to_string(),
+            total_bytes,
+            chunk_size: self
+                .upload_chunk_size
+                .max(total_bytes.div_ceil(MAX_SEGMENTS)),
+            segments_appended: 0,
+            expires_at: init.expires_after_secs.map(|secs| unix_now() + secs),
+            state_file: None,
+            concurrency: DEFAULT_UPLOAD_CONCURRENCY,
+        })
+    }
+
+    /// Appends the segments after `state.segments_appended`, then finalizes the upload
+    /// and waits for processing. Returns the media id.
+    ///
+    /// Up to [`UploadState::concurrency`] segments are in flight at once; FINALIZE is only
+    /// sent after all of them succeeded. `state` is updated (and saved, if persisted) as
+    /// segments complete, so on error it can be passed back in to pick up where the upload
+    /// stopped.
+    pub async fn resume_upload(&self, state: &mut UploadState) -> Result<MediaId, TweetyError> {
+        self.resume_upload_with_progress(state, |_, _| {}).await
+    }
+
+    /// Same as [`TweetyClient::resume_upload`], calling `on_progress(bytes_sent, total_bytes)`
+    /// each time a segment has been appended. Segments appended before the resume count as
+    /// already sent.
+    pub async fn resume_upload_with_progress<F>(
+        &self,
+        state: &mut UploadState,
+        on_progress: F,
+    ) -> Result<MediaId, TweetyError>
+    where
+        F: Fn(u64, u64) + Send + Sync,
+    {
+        if state
+            .expires_at
+            .is_some_and(|expires_at| unix_now() >= expires_at)
+        {
+            return Err(TweetyError::UploadError(format!(
+                "upload of media {} has expired, start a new one",
+                state.media_id
+            )));
+        }
+
+        let mut remaining = state.segments_appended..state.total_segments();
+        let mut completed = BTreeSet::new();
+        let mut in_flight = JoinSet::new();
+        let mut bytes_sent =
+            (state.segments_appended as u64 * state.chunk_size).min(state.total_bytes);
+
+        loop {
+            while in_flight.len() < state.concurrency.max(1) {
+                let Some(segment_index) = remaining.next() else {
+                    break;
+                };
+                let bytes = state.read_segment(segment_index)?;
+                let length = bytes.len() as u64;
+                let client = self.clone();
+                let media_id = state.media_id;
+
+                in_flight.spawn(async move {
+                    let result = client.append_segment(media_id, segment_index, bytes).await;
+                    (segment_index, length, result)
+                });
+            }
+
+            let Some(joined) = in_flight.join_next().await else {
+                break;
+            };
+            let (segment_index, length, result) =
+                joined.map_err(|err| TweetyError::UploadError(err.to_string()))?;
+            // Dropping `in_flight` on error aborts the other segments.
+            result?;
+
+            bytes_sent += length;
+            on_progress(bytes_sent, state.total_bytes);
+
+            completed.insert(segment_index);
+            while completed.remove(&state.segments_appended) {
+                state.segments_appended += 1;
+            }
+            state.save()?;
+        }
+
+        let media_id = self.finalize_upload(state.media_id).await?;
+        state.remove_state_file();
+
+        Ok(media_id)
+    }
+
+    /// GET /1.1/media/upload.json?command=STATUS
+    /// Returns the processing state of a finalized upload.
+    pub async fn upload_status(
+        &self,
+        media_id: impl Into<MediaId>,
+    ) -> Result<MediaStatus, TweetyError> {
+        let media_id: MediaId = media_id.into();
+        let mut query = Query::new();
+        query.push("command", "STATUS").push("media_id", media_id);
+
+        match self
+            .send_request::<()>(&Endpoint::MediaUploadStatus, &query, None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<MediaStatus>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// APPEND, which answers with an empty body.
+    async fn append_segment(
+        &self,
+        media_id: MediaId,
+        segment_index: u32,
+        bytes: Vec<u8>,
+    ) -> Result<(), TweetyError> {
+        let form = multipart::Form::new()
+            .text("command", "APPEND")
+            .text("media_id", media_id.to_string())
+            .text("segment_index", segment_index.to_string())
+            .part("media", multipart::Part::bytes(bytes));
+
+        self.send_multipart(&Endpoint::MediaUpload, &Query::new(), form)
+            .await?;
+
+        Ok(())
+    }
+
+    /// FINALIZE, then poll STATUS until processing has finished.
+    async fn finalize_upload(&self, media_id: MediaId) -> Result<MediaId, TweetyError> {
+        let mut query = Query::new();
+        query.push("command", "FINALIZE").push("media_id", media_id);
+
+        let value = self
+            .send_request::<()>(&Endpoint::MediaUpload, &query, None)
+            .await?;
+        let mut status =
+            serde_json::from_value::<MediaStatus>(value).map_err(TweetyError::JsonParseError)?;
+
+        while let Some(info) = status.processing_info {
+            match info.state {
+                ProcessingState::Succeeded => break,
+                ProcessingState::Failed => {
+                    let message = info
+                        .error
+                        .and_then(|error| error.message)
+                        .unwrap_or_else(|| "no details given".to_string());
+                    return Err(TweetyError::UploadError(format!(
+                        "processing of media {} failed: {}",
+                        media_id, message
+                    )));
+                }
+                ProcessingState::Pending | ProcessingState::InProgress => {
+                    let wait = info.check_after_secs.unwrap_or(1);
+                    tokio::time::sleep(Duration::from_secs(wait)).await;
+                    status = self.upload_status(media_id).await?;
                 }
             }
-            Err(err) => Err(TweetyError::NetworkError(err.to_string())),
         }
+
+        Ok(status.media_id)
     }
 }
@@ -0,0 +1,439 @@
+//! Keeping a month's post consumption within budget.
+//!
+//! X caps the posts a project may send and read each month, and once a cap is reached
+//! requests fail until it resets. A [`UsageGuard`] counts the posts the client sends and
+//! reads, and once a configured share of a cap is used up, refuses further requests of
+//! that kind with [`TweetyError::UsageBudgetExceeded`] before they are sent, or only
+//! logs a warning. The caps come from the client's [`Tier`] unless set on the guard.
+//!
+//! The counts start over on the day of the month the caps reset on, the 1st until
+//! [`TweetyClient::sync_usage`] reports the project's, see [`cap_period_start`]. Local
+//! counts only see this process: [`UsageGuard::state_store`] keeps the count of posts
+//! sent across restarts, and [`TweetyClient::sync_usage`] replaces the read count and
+//! cap with what `GET /2/usage/tweets` reports for the whole project.
+//!
+//! ```rust,no_run
+//! use tweety_rs::api::tier::Tier;
+//! use tweety_rs::api::usage::UsageGuard;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run() -> Result<(), tweety_rs::api::error::TweetyError> {
+//! let client = TweetyClient::from_bearer_token("token")
+//!     .with_tier(Tier::Basic)
+//!     .with_usage_guard(UsageGuard::new(0.9));
+//! let usage = client.sync_usage().await?;
+//! println!("{} of {} posts read", usage.project_usage, usage.project_cap);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`TweetyError::UsageBudgetExceeded`]: crate::api::error::TweetyError::UsageBudgetExceeded
+//! [`cap_period_start`]: crate::api::tier::cap_period_start
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::TweetyError;
+use crate::api::oauth2::unix_now;
+use crate::api::query::Query;
+use crate::api::state::StateStore;
+use crate::api::tier::{cap_period_start_secs, reads_posts, Tier, DEFAULT_CAP_RESET_DAY};
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// What a request consumes of the monthly caps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UsageKind {
+    /// Posts created.
+    Sent,
+    /// Posts returned by lookups, timelines and searches.
+    Read,
+}
+
+impl UsageKind {
+    /// The kind of posts `endpoint` consumes, or `None` when it doesn't count.
+    pub fn of(endpoint: &Endpoint) -> Option<Self> {
+        match endpoint {
+            Endpoint::CreateTweet => Some(UsageKind::Sent),
+            _ if reads_posts(endpoint) => Some(UsageKind::Read),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for UsageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UsageKind::Sent => "sent",
+            UsageKind::Read => "read",
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    period_start: u64,
+    reset_day: u8,
+    sent: u64,
+    read: u64,
+    /// The project's read cap, from the last [`UsageGuard::sync`].
+    synced_read_cap: Option<u64>,
+    /// Whether the count saved in the state store was read.
+    restored: bool,
+    warned_sent: bool,
+    warned_read: bool,
+}
+
+impl Counts {
+    /// Starts over once a new monthly period began.
+    fn roll(&mut self) {
+        let period_start = cap_period_start_secs(unix_now(), self.reset_day);
+        if period_start != self.period_start {
+            *self = Counts {
+                period_start,
+                reset_day: self.reset_day,
+                synced_read_cap: self.synced_read_cap,
+                restored: self.restored,
+                ..Counts::default()
+            };
+        }
+    }
+
+    fn used(&mut self, kind: UsageKind) -> (&mut u64, &mut bool) {
+        match kind {
+            UsageKind::Sent => (&mut self.sent, &mut self.warned_sent),
+            UsageKind::Read => (&mut self.read, &mut self.warned_read),
+        }
+    }
+}
+
+/// The count of posts sent, as saved in the state store.
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedCount {
+    period_start: u64,
+    sent: u64,
+}
+
+/// A budget on the monthly caps, shared by every clone of the client it is set on.
+pub struct UsageGuard {
+    budget: f64,
+    refuse: bool,
+    post_cap: Option<u64>,
+    read_cap: Option<u64>,
+    counts: Mutex<Counts>,
+    state_store: Option<(Arc<dyn StateStore>, String)>,
+    /// Serializes saves, so a newer count is never overwritten by an older one.
+    save_lock: tokio::sync::Mutex<()>,
+}
+
+impl fmt::Debug for UsageGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageGuard")
+            .field("budget", &self.budget)
+            .field("refuse", &self.refuse)
+            .field("post_cap", &self.post_cap)
+            .field("read_cap", &self.read_cap)
+            .field("counts", &self.counts)
+            .finish()
+    }
+}
+
+impl UsageGuard {
+    /// Refuses requests once `budget`, a share of a cap between 0 and 1, is used up.
+    pub fn new(budget: f64) -> Self {
+        UsageGuard {
+            budget: budget.clamp(0.0, 1.0),
+            refuse: true,
+            post_cap: None,
+            read_cap: None,
+            counts: Mutex::new(Counts {
+                reset_day: DEFAULT_CAP_RESET_DAY,
+                ..Counts::default()
+            }),
+            state_store: None,
+            save_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Logs a warning once per month and kind instead of refusing requests.
+    pub fn warn_only(mut self) -> Self {
+        self.refuse = false;
+        self
+    }
+
+    /// Posts the project may send per month, instead of the tier's cap.
+    pub fn post_cap(mut self, cap: u64) -> Self {
+        self.post_cap = Some(cap);
+        self
+    }
+
+    /// Posts the project may read per month, instead of the tier's cap. Replaced by
+    /// the cap reported to [`UsageGuard::sync`].
+    pub fn read_cap(mut self, cap: u64) -> Self {
+        self.read_cap = Some(cap);
+        self
+    }
+
+    /// Starts the counts over on `day` of each month, or on its last day in months
+    /// too short for it, until [`UsageGuard::sync`] reports the project's reset day.
+    /// [`DEFAULT_CAP_RESET_DAY`] unless set.
+    pub fn cap_reset_day(mut self, day: u8) -> Self {
+        if let Ok(counts) = self.counts.get_mut() {
+            counts.reset_day = day;
+        }
+        self
+    }
+
+    /// Saves the count of posts sent under `key` in `store` after each post, and
+    /// starts from the count saved there for the current period, so a restart doesn't
+    /// forget what was already posted.
+    pub fn state_store(mut self, store: impl StateStore + 'static, key: &str) -> Self {
+        self.state_store = Some((Arc::new(store), key.to_string()));
+        self
+    }
+
+    /// The day of the month the caps reset on.
+    pub fn reset_day(&self) -> u8 {
+        self.counts
+            .lock()
+            .map_or(DEFAULT_CAP_RESET_DAY, |counts| counts.reset_day)
+    }
+
+    /// Posts of `kind` counted this month.
+    pub fn used(&self, kind: UsageKind) -> u64 {
+        match self.counts.lock() {
+            Ok(mut counts) => {
+                counts.roll();
+                *counts.used(kind).0
+            }
+            Err(_) => 0,
+        }
+    }
+
+    /// How many posts of `kind` the budget allows per month, or `None` without a cap
+    /// to take a share of.
+    pub fn budget(&self, kind: UsageKind, tier: Option<Tier>) -> Option<u64> {
+        let cap = match kind {
+            UsageKind::Sent => self.post_cap.or_else(|| tier?.monthly_post_cap()),
+            UsageKind::Read => {
+                let synced = self
+                    .counts
+                    .lock()
+                    .ok()
+                    .and_then(|counts| counts.synced_read_cap);
+                synced
+                    .or(self.read_cap)
+                    .or_else(|| tier?.monthly_read_cap())
+            }
+        }?;
+        Some((cap as f64 * self.budget) as u64)
+    }
+
+    /// Replaces the read count, cap and reset day with the project's, as reported by
+    /// `GET /2/usage/tweets`.
+    pub fn sync(&self, usage: &TweetUsage) {
+        if let Ok(mut counts) = self.counts.lock() {
+            counts.roll();
+            // The posts sent so far were sent in the project's current period too.
+            counts.reset_day = usage.cap_reset_day;
+            counts.period_start = cap_period_start_secs(unix_now(), usage.cap_reset_day);
+            counts.read = usage.project_usage;
+            counts.synced_read_cap = Some(usage.project_cap);
+        }
+    }
+
+    /// Reads the count of posts sent from the state store, once.
+    pub(crate) async fn restore(&self) -> Result<(), TweetyError> {
+        let Some((ref store, ref key)) = self.state_store else {
+            return Ok(());
+        };
+        if self.counts.lock().map_or(true, |counts| counts.restored) {
+            return Ok(());
+        }
+
+        let saved = match store.get(key).await? {
+            Some(value) => Some(serde_json::from_str::<SavedCount>(&value)?),
+            None => None,
+        };
+        if let Ok(mut counts) = self.counts.lock() {
+            counts.roll();
+            if let Some(saved) = saved.filter(|saved| saved.period_start == counts.period_start) {
+                counts.sent = counts.sent.max(saved.sent);
+            }
+            counts.restored = true;
+        }
+        Ok(())
+    }
+
+    /// Saves the count of posts sent to the state store, after a request to `endpoint`
+    /// that sent some.
+    pub(crate) async fn save(&self, endpoint: &Endpoint) -> Result<(), TweetyError> {
+        let Some((ref store, ref key)) = self.state_store else {
+            return Ok(());
+        };
+        if UsageKind::of(endpoint) != Some(UsageKind::Sent) {
+            return Ok(());
+        }
+
+        let _guard = self.save_lock.lock().await;
+        let saved = match self.counts.lock() {
+            Ok(mut counts) => {
+                counts.roll();
+                SavedCount {
+                    period_start: counts.period_start,
+                    sent: counts.sent,
+                }
+            }
+            Err(_) => return Ok(()),
+        };
+        let value = serde_json::to_string(&saved)
+            .map_err(|err| TweetyError::SerializeError(err.to_string()))?;
+        store.set(key, &value).await
+    }
+
+    /// Fails with [`TweetyError::UsageBudgetExceeded`] when the budget for what
+    /// `endpoint` consumes is used up, or warns instead with [`UsageGuard::warn_only`].
+    pub(crate) fn check(&self, endpoint: &Endpoint, tier: Option<Tier>) -> Result<(), TweetyError> {
+        let Some(kind) = UsageKind::of(endpoint) else {
+            return Ok(());
+        };
+        let Some(budget) = self.budget(kind, tier) else {
+            return Ok(());
+        };
+        let Ok(mut counts) = self.counts.lock() else {
+            return Ok(());
+        };
+        counts.roll();
+        let (used, warned) = counts.used(kind);
+        if *used < budget {
+            return Ok(());
+        }
+
+        let err = TweetyError::UsageBudgetExceeded {
+            kind,
+            used: *used,
+            budget,
+        };
+        if self.refuse {
+            return Err(err);
+        }
+        if !*warned {
+            *warned = true;
+            log::warn!("{}", err);
+        }
+        Ok(())
+    }
+
+    /// Counts `posts` of what `endpoint` consumes.
+    pub(crate) fn record(&self, endpoint: &Endpoint, posts: u64) {
+        let Some(kind) = UsageKind::of(endpoint) else {
+            return;
+        };
+        if let Ok(mut counts) = self.counts.lock() {
+            counts.roll();
+            *counts.used(kind).0 += posts;
+        }
+    }
+}
+
+/// The project's post consumption, from `GET /2/usage/tweets`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct TweetUsage {
+    pub project_id: String,
+    /// Posts the project may read per month.
+    #[serde(deserialize_with = "count")]
+    pub project_cap: u64,
+    /// Posts the project has read since the cap last reset.
+    #[serde(deserialize_with = "count")]
+    pub project_usage: u64,
+    /// Day of the month the cap resets on.
+    pub cap_reset_day: u8,
+    /// The project's reads per day since the cap last reset.
+    pub daily_project_usage: Option<DailyProjectUsage>,
+    /// The reads per day of each of the project's apps.
+    #[serde(default)]
+    pub daily_client_app_usage: Vec<DailyClientAppUsage>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+/// A project's reads per day, in [`TweetUsage::daily_project_usage`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct DailyProjectUsage {
+    pub project_id: String,
+    #[serde(default)]
+    pub usage: Vec<DailyUsage>,
+}
+
+/// An app's reads per day, in [`TweetUsage::daily_client_app_usage`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct DailyClientAppUsage {
+    pub client_app_id: String,
+    #[serde(default)]
+    pub usage: Vec<DailyUsage>,
+    /// The number of days in `usage`.
+    pub usage_result_count: Option<u32>,
+}
+
+/// Posts read on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct DailyUsage {
+    /// Midnight UTC of the day, in ISO 8601.
+    pub date: String,
+    #[serde(deserialize_with = "count")]
+    pub usage: u64,
+}
+
+/// X sends the counts as strings.
+fn count<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(count) => count.parse().map_err(de::Error::custom),
+        Value::Number(count) => count
+            .as_u64()
+            .ok_or_else(|| de::Error::custom("expected a non-negative count")),
+        other => Err(de::Error::custom(format!(
+            "expected a count, got {}",
+            other
+        ))),
+    }
+}
+
+/// Usage
+/// [Docs](https://developer.x.com/en/docs/x-api/usage/tweets/introduction)
+impl TweetyClient {
+    /// GET /2/usage/tweets
+    /// Returns the project's post consumption this month. Needs app-only
+    /// authentication.
+    /// [Docs](https://developer.x.com/en/docs/x-api/usage/tweets/api-reference/get-usage-tweets)
+    pub async fn get_tweet_usage(&self) -> Result<TweetUsage, TweetyError> {
+        match self
+            .send_request::<()>(&Endpoint::TweetUsage, &Query::new(), None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<TweetUsage>(value["data"].clone()) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fetches the project's consumption with [`TweetyClient::get_tweet_usage`] and
+    /// passes it to the [`UsageGuard`] set with [`TweetyClient::with_usage_guard`].
+    pub async fn sync_usage(&self) -> Result<TweetUsage, TweetyError> {
+        let usage = self.get_tweet_usage().await?;
+        if let Some(guard) = self.usage_guard() {
+            guard.sync(&usage);
+        }
+        Ok(usage)
+    }
+}
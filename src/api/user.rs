@@ -6,18 +6,71 @@
 //!
 //!
 
-use crate::api::client::TweetyClient;
+use crate::api::client::{AuthMode, TweetyClient};
+use crate::api::endpoint::Endpoint;
 use crate::api::error::TweetyError;
-use crate::types::user::UserResponse;
-use reqwest::Method;
+use crate::api::mentions::TweetData;
+use crate::api::query::{field_names, Query};
+use crate::types::ids::UserId;
+use crate::types::lookup::LookupOutcome;
+use crate::types::user::{BannerSize, UserResponse};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::path::Path;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct UserInfo {
     pub data: UserResponse,
 }
 
+/// The response of `GET /2/users`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UsersResponse {
+    /// Absent from the response when none of the ids could be returned.
+    #[serde(default)]
+    pub data: Vec<UserResponse>,
+    /// The objects asked for with `expansions`, as returned.
+    pub includes: Option<Value>,
+    /// One entry per id that couldn't be returned, e.g. because the user is suspended.
+    #[serde(default)]
+    pub errors: Vec<Value>,
+}
+
+impl UsersResponse {
+    /// Pairs each of `ids` with what the response holds for it, in the order given.
+    pub fn outcomes(self, ids: &[UserId]) -> Vec<(UserId, LookupOutcome<UserResponse>)> {
+        LookupOutcome::collect(ids, self.data, &self.errors, |user| &user.id)
+    }
+}
+
+/// Result of [`TweetyClient::verify_credentials`].
+#[derive(Debug)]
+pub struct VerifiedCredentials {
+    pub auth_mode: AuthMode,
+    /// The authenticated user; `None` for app-only clients, which have no user context.
+    pub user: Option<UserResponse>,
+}
+
+/// A user and their pinned tweet, returned by [`TweetyClient::get_pinned_tweet`].
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PinnedTweet {
+    pub user: UserResponse,
+    /// `None` when nothing is pinned, or the pinned tweet was deleted or can't be seen.
+    pub tweet: Option<TweetData>,
+}
+
+#[derive(Deserialize)]
+struct PinnedTweetResponse {
+    data: UserResponse,
+    includes: Option<PinnedTweetIncludes>,
+}
+
+#[derive(Deserialize)]
+struct PinnedTweetIncludes {
+    #[serde(default)]
+    tweets: Vec<TweetData>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Expansions {
@@ -39,6 +92,7 @@ pub enum TweetFields {
     InReplyToUserId,
     Lang,
     NonPublicMetrics,
+    NoteTweet,
     PublicMetrics,
     OrganicMetrics,
     PromotedMetrics,
@@ -61,6 +115,7 @@ pub enum UserFields {
     MostRecentTweetId,
     Name,
     PinnedTweetId,
+    ProfileBannerUrl,
     ProfileImageUrl,
     Protected,
     PublicMetrics,
@@ -71,7 +126,9 @@ pub enum UserFields {
     Withheld,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+field_names!(Expansions, TweetFields, UserFields);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct UserQueryParams {
     pub expansions: Option<Expansions>,
     pub tweet_fields: Option<Vec<TweetFields>>,
@@ -81,49 +138,27 @@ pub struct UserQueryParams {
 impl UserQueryParams {
     /// Construct querys params from the provided optional parameters
     pub fn construct_query_string(&self) -> String {
-        let mut query = vec![];
+        self.to_query().to_query_string()
+    }
+
+    pub(crate) fn to_query(&self) -> Query {
+        let mut query = Query::new();
 
         if let Some(expansion) = &self.expansions {
-            let serialized_expansion = serde_json::to_string(expansion)
-                .unwrap()
-                .trim_matches('"')
-                .to_string();
-            query.push(format!("expansions={}", serialized_expansion));
+            query.push_fields("expansions", std::slice::from_ref(expansion));
         }
-
         if let Some(tweet_fields) = &self.tweet_fields {
-            let fields = tweet_fields
-                .iter()
-                .map(|f| {
-                    serde_json::to_string(f)
-                        .unwrap()
-                        .trim_matches('"')
-                        .to_string()
-                })
-                .collect::<Vec<_>>()
-                .join(",");
-            query.push(format!("tweet.fields={}", fields));
+            query.push_fields("tweet.fields", tweet_fields);
         }
-
         if let Some(user_fields) = &self.user_fields {
-            let fields = user_fields
-                .iter()
-                .map(|f| {
-                    serde_json::to_string(f)
-                        .unwrap()
-                        .trim_matches('"')
-                        .to_string()
-                })
-                .collect::<Vec<_>>()
-                .join(",");
-            query.push(format!("user.fields={}", fields));
+            query.push_fields("user.fields", user_fields);
         }
-        query.join("&")
+
+        query
     }
 }
 /// Users lookup
 /// API reference index
-
 impl TweetyClient {
     /// Fetches detailed information about a user specified by their ID.
     ///
@@ -155,16 +190,44 @@ impl TweetyClient {
     /// ```
     pub async fn get_user_by_id(
         &self,
-        user_id: &str,
+        user_id: impl Into<UserId>,
         params: Option<UserQueryParams>,
     ) -> Result<Value, TweetyError> {
-        let query_string = if let Some(params) = params {
-            params.construct_query_string()
-        } else {
-            String::new()
+        let user_id: UserId = user_id.into();
+        let query = params.map(|params| params.to_query()).unwrap_or_default();
+        self.send_request::<()>(&Endpoint::User(user_id), &query, None)
+            .await
+    }
+
+    /// GET /2/users/:id with `expansions=pinned_tweet_id`
+    /// Fetches the user and hydrates their pinned tweet from `includes.tweets`. The
+    /// expansion is always requested; `params` adds tweet and user fields.
+    pub async fn get_pinned_tweet(
+        &self,
+        user_id: impl Into<UserId>,
+        params: Option<UserQueryParams>,
+    ) -> Result<PinnedTweet, TweetyError> {
+        let user_id: UserId = user_id.into();
+        let params = UserQueryParams {
+            expansions: Some(Expansions::PinnedTweetId),
+            ..params.unwrap_or_default()
+        };
+
+        let value = self
+            .send_request::<()>(&Endpoint::User(user_id), &params.to_query(), None)
+            .await?;
+        let response = serde_json::from_value::<PinnedTweetResponse>(value)
+            .map_err(TweetyError::JsonParseError)?;
+
+        let tweets = response.includes.map(|includes| includes.tweets);
+        let tweet = match (&response.data.pinned_tweet_id, tweets) {
+            (Some(id), Some(tweets)) => tweets.into_iter().find(|tweet| &tweet.id == id),
+            _ => None,
         };
-        let url = format!("https://api.x.com/2/users/{}?{}", user_id, query_string);
-        self.send_request::<()>(&url, Method::GET, None).await
+        Ok(PinnedTweet {
+            user: response.data,
+            tweet,
+        })
     }
 
     /// <https://developer.x.com/en/docs/x-api/users/lookup/api-reference/get-users#tab1>
@@ -172,66 +235,148 @@ impl TweetyClient {
     /// Fetches detailed information about one or more users specified by their IDs.
     pub async fn get_users(
         &self,
-        ids: Vec<String>,
+        ids: Vec<UserId>,
         params: Option<UserQueryParams>,
     ) -> Result<Value, TweetyError> {
-        let ids_string = ids.join(",");
-        let query_string = if let Some(params) = params {
-            params.construct_query_string()
-        } else {
-            String::new()
-        };
+        let mut query = Query::new();
+        let ids: Vec<&str> = ids.iter().map(UserId::as_str).collect();
+        query.push("ids", ids.join(","));
+        if let Some(params) = params {
+            query.extend(params.to_query());
+        }
 
-        let url = format!(
-            "https://api.x.com/2/users?ids={}&{}",
-            ids_string, query_string
-        );
-        self.send_request::<()>(&url, Method::GET, None).await
+        self.send_request::<()>(&Endpoint::Users, &query, None)
+            .await
+    }
+
+    /// GET /2/users
+    /// Looks up `ids` and tells apart the users that came back from the suspended,
+    /// deleted and protected ones, see [`LookupOutcome`]. Up to 100 ids per call.
+    pub async fn lookup_users(
+        &self,
+        ids: Vec<UserId>,
+        params: Option<UserQueryParams>,
+    ) -> Result<Vec<(UserId, LookupOutcome<UserResponse>)>, TweetyError> {
+        let value = self.get_users(ids.clone(), params).await?;
+        let response =
+            serde_json::from_value::<UsersResponse>(value).map_err(TweetyError::JsonParseError)?;
+        Ok(response.outcomes(&ids))
     }
     ///  Returns a variety of information about one or more users specified by their usernames.
-    ///  Required 	string	A comma separated list of user IDs. Up to 100 are allowed in a single request.
+    ///  Required string A comma separated list of user IDs. Up to 100 are allowed in a single request.
     /// Make sure to not include a space between commas and fields.
     pub async fn get_users_by_username(&self, username: &[&str]) -> Result<Value, TweetyError> {
-        let url = format!(
-            "https://api.x.com/2/users/by/username/{}",
-            username.join(",")
-        );
-        self.send_request::<()>(&url, Method::GET, None).await
+        let endpoint = Endpoint::UserByUsername(username.join(","));
+        self.send_request::<()>(&endpoint, &Query::new(), None)
+            .await
     }
     /// GET /2/users/by
     /// Returns a variety of information about one or more users specified by their usernames.
     /// usernames
-    ///  Required 	string A comma separated list of Twitter usernames (handles).
+    ///  Required string A comma separated list of Twitter usernames (handles).
     /// Up to 100 are allowed in a single request. Make sure to not include a space between commas and fields.
     pub async fn get_users_by_usernames(
         &self,
         user_names: &[&str],
         params: Option<UserQueryParams>,
     ) -> Result<Value, TweetyError> {
-        let query_string = if let Some(params) = params {
-            params.construct_query_string()
-        } else {
-            String::new()
-        };
-
-        let url = format!(
-            "https://api.x.com/2/users/by/?username={}&{}",
-            user_names.join(","),
-            query_string
-        );
+        let mut query = Query::new();
+        query.push("usernames", user_names.join(","));
+        if let Some(params) = params {
+            query.extend(params.to_query());
+        }
 
-        self.send_request::<()>(&url, Method::GET, None).await
+        self.send_request::<()>(&Endpoint::UsersByUsernames, &query, None)
+            .await
     }
     /// Returns information about an authorized user.
     /// <https://developer.x.com/en/docs/x-api/users/lookup/api-reference/get-users-me#>
     pub async fn get_user_me(&self, params: Option<UserQueryParams>) -> Result<Value, TweetyError> {
-        let query_string = if let Some(params) = params {
-            params.construct_query_string()
-        } else {
-            String::new()
+        let query = params.map(|params| params.to_query()).unwrap_or_default();
+        self.send_request::<()>(&Endpoint::Me, &query, None).await
+    }
+    /// Checks that the client's credentials are accepted, e.g. as a startup health check.
+    ///
+    /// User contexts call `GET /2/users/me` and return the authenticated user. App-only
+    /// clients have no user, so the bearer token is checked with a lookup of the `@X`
+    /// account instead and `user` is `None`.
+    pub async fn verify_credentials(&self) -> Result<VerifiedCredentials, TweetyError> {
+        let auth_mode = self.auth_mode().ok_or(TweetyError::MissingCredentials)?;
+
+        let endpoint = match auth_mode {
+            AuthMode::OAuth1User | AuthMode::OAuth2User => Endpoint::Me,
+            AuthMode::AppOnly => Endpoint::User("783214".into()),
         };
-        let url = format!("https://api.x.com/2/users/me?{}", query_string);
 
-        self.send_request::<()>(&url, Method::GET, None).await
+        match self
+            .send_request::<()>(&endpoint, &Query::new(), None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<UserInfo>(value) {
+                Ok(info) => Ok(VerifiedCredentials {
+                    auth_mode,
+                    user: (auth_mode != AuthMode::AppOnly).then_some(info.data),
+                }),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Downloads the user's avatar at its uploaded size, see
+    /// [`UserResponse::original_profile_image_url`].
+    ///
+    /// Fails with a `ConfigError` when the user was fetched without
+    /// `user.fields=profile_image_url`.
+    pub async fn download_profile_image(
+        &self,
+        user: &UserResponse,
+    ) -> Result<Vec<u8>, TweetyError> {
+        let url = user.original_profile_image_url().ok_or_else(|| {
+            TweetyError::ConfigError(format!(
+                "@{} has no profile_image_url, request user.fields=profile_image_url",
+                user.username
+            ))
+        })?;
+        self.download(&url).await
+    }
+
+    /// Downloads the user's profile banner at `size`.
+    ///
+    /// Fails with a `ConfigError` when the user has no banner or was fetched without
+    /// `user.fields=profile_banner_url`.
+    pub async fn download_profile_banner(
+        &self,
+        user: &UserResponse,
+        size: BannerSize,
+    ) -> Result<Vec<u8>, TweetyError> {
+        let url = user.profile_banner_url_at(size).ok_or_else(|| {
+            TweetyError::ConfigError(format!(
+                "@{} has no profile_banner_url, request user.fields=profile_banner_url",
+                user.username
+            ))
+        })?;
+        self.download(&url).await
+    }
+
+    /// Downloads the user's avatar at its uploaded size and writes it to `path`.
+    pub async fn save_profile_image(
+        &self,
+        user: &UserResponse,
+        path: &Path,
+    ) -> Result<(), TweetyError> {
+        let bytes = self.download_profile_image(user).await?;
+        std::fs::write(path, bytes).map_err(|err| TweetyError::FileIOError(err.to_string()))
+    }
+
+    /// Downloads the user's profile banner at `size` and writes it to `path`.
+    pub async fn save_profile_banner(
+        &self,
+        user: &UserResponse,
+        size: BannerSize,
+        path: &Path,
+    ) -> Result<(), TweetyError> {
+        let bytes = self.download_profile_banner(user, size).await?;
+        std::fs::write(path, bytes).map_err(|err| TweetyError::FileIOError(err.to_string()))
     }
 }
@@ -0,0 +1,437 @@
+//! v1.1 endpoints that still have no v2 equivalent.
+//!
+//! Enabled by the `v1` cargo feature. The endpoints go through the same client,
+//! credentials and [`TweetyError`] as the v2 API; reach them with [`TweetyClient::v1`].
+
+use crate::api::client::TweetyClient;
+use crate::api::endpoint::{Endpoint, RateLimitFamily};
+use crate::api::error::TweetyError;
+use crate::api::query::{join_fields, Query};
+use crate::api::rate_limit::{RateLimitStatus, RateLimits};
+#[cfg(feature = "media-upload")]
+use crate::types::ids::MediaId;
+use crate::types::ids::TweetId;
+use crate::types::types::User;
+#[cfg(feature = "media-upload")]
+use reqwest::multipart;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "media-upload")]
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Borrowed handle for the v1.1 endpoints, returned by [`TweetyClient::v1`].
+#[derive(Debug, Clone, Copy)]
+pub struct V1<'a> {
+    client: &'a TweetyClient,
+}
+
+/// How precise the places returned by [`V1::geo_search`] should be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    Poi,
+    Neighborhood,
+    City,
+    Admin,
+    Country,
+}
+
+/// Query parameters for `GET /1.1/geo/search.json`.
+///
+/// At least one of `query`, `lat`/`long` or `ip` must be set.
+/// [Docs](https://developer.x.com/en/docs/twitter-api/v1/geo/places-near-location/api-reference/get-geo-search)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GeoSearchParams {
+    /// Free-form text to match against place names, e.g. `Toronto`.
+    pub query: Option<String>,
+    pub lat: Option<f64>,
+    pub long: Option<f64>,
+    /// An IP address to geolocate.
+    pub ip: Option<String>,
+    pub granularity: Option<Granularity>,
+    pub max_results: Option<u32>,
+}
+
+impl GeoSearchParams {
+    pub fn to_query_string(&self) -> String {
+        self.to_query().to_query_string()
+    }
+
+    pub(crate) fn to_query(&self) -> Query {
+        let mut query = Query::new();
+        query
+            .push_opt("query", self.query.as_ref())
+            .push_opt("lat", self.lat)
+            .push_opt("long", self.long)
+            .push_opt("ip", self.ip.as_ref())
+            .push_opt("granularity", self.granularity.map(|g| join_fields(&[g])))
+            .push_opt("max_results", self.max_results);
+        query
+    }
+}
+
+/// The profile fields `POST /1.1/account/update_profile.json` can change. Fields left
+/// unset are kept; an empty string clears the field.
+/// [Docs](https://developer.x.com/en/docs/twitter-api/v1/accounts-and-users/manage-account-settings/api-reference/post-account-update_profile)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProfileUpdate {
+    /// Display name, up to 50 characters.
+    pub name: Option<String>,
+    /// The bio, up to 160 characters.
+    pub description: Option<String>,
+    /// Up to 30 characters.
+    pub location: Option<String>,
+    /// Website shown on the profile, up to 100 characters.
+    pub url: Option<String>,
+}
+
+impl ProfileUpdate {
+    pub fn to_query_string(&self) -> String {
+        self.to_query().to_query_string()
+    }
+
+    pub(crate) fn to_query(&self) -> Query {
+        let mut query = Query::new();
+        query
+            .push_opt("name", self.name.as_ref())
+            .push_opt("description", self.description.as_ref())
+            .push_opt("location", self.location.as_ref())
+            .push_opt("url", self.url.as_ref())
+            .push("skip_status", true);
+        query
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeoSearchResponse {
+    pub result: GeoSearchResult,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeoSearchResult {
+    #[serde(default)]
+    pub places: Vec<Place>,
+}
+
+/// A place, as returned by the v1.1 geo endpoints.
+///
+/// The `id` can be used as `geo.place_id` when posting a tweet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Place {
+    pub id: String,
+    pub name: String,
+    pub full_name: String,
+    pub place_type: String,
+    pub country: Option<String>,
+    pub country_code: Option<String>,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub contained_within: Vec<Place>,
+}
+
+/// Quotas from `GET /1.1/application/rate_limit_status.json` merged with the ones the
+/// client has read from response headers, see [`V1::get_rate_limit_status`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimitReport {
+    /// Each requested family that anything is known about. The server's numbers win for
+    /// the families it reports; the others are the last headers seen.
+    pub families: RateLimits,
+    /// Every window the server reported, keyed by v1.1 path, e.g. `/geo/search`.
+    pub resources: BTreeMap<String, RateLimitStatus>,
+}
+
+impl RateLimitReport {
+    /// The requested families nothing is known about yet.
+    pub fn unknown<'a>(
+        &'a self,
+        families: &'a [RateLimitFamily],
+    ) -> impl Iterator<Item = RateLimitFamily> + 'a {
+        families
+            .iter()
+            .copied()
+            .filter(|family| !self.families.contains_key(family))
+    }
+}
+
+#[derive(Deserialize)]
+struct RateLimitStatusResponse {
+    #[serde(default)]
+    resources: BTreeMap<String, BTreeMap<String, RateLimitWindow>>,
+}
+
+#[derive(Deserialize)]
+struct RateLimitWindow {
+    limit: u32,
+    remaining: u32,
+    reset: u64,
+}
+
+impl From<RateLimitWindow> for RateLimitStatus {
+    fn from(window: RateLimitWindow) -> Self {
+        RateLimitStatus {
+            limit: window.limit,
+            remaining: window.remaining,
+            reset: UNIX_EPOCH + Duration::from_secs(window.reset),
+        }
+    }
+}
+
+/// The `resources` group and path `GET /1.1/application/rate_limit_status.json` reports
+/// `family` under. Only the families of v1.1 endpoints are listed there.
+fn v1_resource(family: RateLimitFamily) -> Option<(&'static str, &'static str)> {
+    match family {
+        RateLimitFamily::Account => Some(("account", "/account/verify_credentials")),
+        RateLimitFamily::Geo => Some(("geo", "/geo/search")),
+        RateLimitFamily::Help => Some(("help", "/help/languages")),
+        RateLimitFamily::Application => Some(("application", "/application/rate_limit_status")),
+        _ => None,
+    }
+}
+
+/// A language supported by X, from `GET /1.1/help/languages.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Language {
+    /// BCP 47 code, e.g. `en` or `pt`.
+    pub code: String,
+    pub name: String,
+    pub local_name: Option<String>,
+    pub status: Option<String>,
+}
+
+impl TweetyClient {
+    /// Access the v1.1 endpoints that have no v2 replacement.
+    pub fn v1(&self) -> V1<'_> {
+        V1 { client: self }
+    }
+}
+
+impl V1<'_> {
+    /// POST /1.1/media/upload.json
+    /// Same as [`TweetyClient::upload_file`]; returns the media id to attach to a tweet.
+    /// [Docs](https://developer.x.com/en/docs/twitter-api/v1/media/upload-media/api-reference/post-media-upload)
+    #[cfg(feature = "media-upload")]
+    pub async fn upload_media(&self, path: &Path) -> Result<MediaId, TweetyError> {
+        self.client.upload_file(path).await
+    }
+
+    /// GET /1.1/account/verify_credentials.json
+    /// Returns the user the OAuth 1.0a credentials belong to.
+    /// [Docs](https://developer.x.com/en/docs/twitter-api/v1/accounts-and-users/manage-account-settings/api-reference/get-account-verify_credentials)
+    pub async fn verify_credentials(&self) -> Result<User, TweetyError> {
+        let mut query = Query::new();
+        query.push("skip_status", true);
+
+        match self
+            .client
+            .send_request::<()>(&Endpoint::VerifyCredentials, &query, None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<User>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// POST /1.1/account/pin_tweet.json
+    /// Pins `tweet_id` to the authenticated user's profile, replacing any pinned tweet.
+    /// Read it back with [`TweetyClient::get_pinned_tweet`].
+    ///
+    /// The endpoint is not part of the documented v1.1 reference; apps without access to
+    /// it get an `ApiError`.
+    pub async fn pin_tweet(&self, tweet_id: impl Into<TweetId>) -> Result<(), TweetyError> {
+        self.set_pinned(Endpoint::PinTweet, tweet_id.into()).await
+    }
+
+    /// POST /1.1/account/unpin_tweet.json
+    /// Unpins `tweet_id` from the authenticated user's profile. Same caveats as
+    /// [`V1::pin_tweet`].
+    pub async fn unpin_tweet(&self, tweet_id: impl Into<TweetId>) -> Result<(), TweetyError> {
+        self.set_pinned(Endpoint::UnpinTweet, tweet_id.into()).await
+    }
+
+    async fn set_pinned(&self, endpoint: Endpoint, tweet_id: TweetId) -> Result<(), TweetyError> {
+        let mut query = Query::new();
+        query.push("id", tweet_id.as_ref());
+
+        self.client
+            .send_request::<()>(&endpoint, &query, None)
+            .await
+            .map(|_| ())
+    }
+
+    /// POST /1.1/account/update_profile.json
+    /// Changes the authenticated user's name, bio, location or website; returns the
+    /// updated user.
+    /// [Docs](https://developer.x.com/en/docs/twitter-api/v1/accounts-and-users/manage-account-settings/api-reference/post-account-update_profile)
+    pub async fn update_profile(&self, update: ProfileUpdate) -> Result<User, TweetyError> {
+        let value = self
+            .client
+            .send_request::<()>(&Endpoint::UpdateProfile, &update.to_query(), None)
+            .await?;
+        serde_json::from_value::<User>(value).map_err(TweetyError::JsonParseError)
+    }
+
+    /// POST /1.1/account/update_profile_image.json
+    /// Replaces the authenticated user's avatar with `image`, a GIF, JPEG or PNG under
+    /// 700KB; returns the updated user. The new image can take a few seconds to appear.
+    /// [Docs](https://developer.x.com/en/docs/twitter-api/v1/accounts-and-users/manage-account-settings/api-reference/post-account-update_profile_image)
+    #[cfg(feature = "media-upload")]
+    pub async fn update_profile_image(&self, image: Vec<u8>) -> Result<User, TweetyError> {
+        let mut query = Query::new();
+        query.push("skip_status", true);
+        let form = multipart::Form::new().part("image", multipart::Part::bytes(image));
+
+        let value = self
+            .client
+            .send_multipart(&Endpoint::UpdateProfileImage, &query, form)
+            .await?;
+        serde_json::from_value::<User>(value).map_err(TweetyError::JsonParseError)
+    }
+
+    /// POST /1.1/account/update_profile_banner.json
+    /// Replaces the authenticated user's profile banner with `banner`, a GIF, JPEG or
+    /// PNG under 5MB. X crops it to 1500x500, see [`BannerSize`].
+    /// [Docs](https://developer.x.com/en/docs/twitter-api/v1/accounts-and-users/manage-account-settings/api-reference/post-account-update_profile_banner)
+    ///
+    /// [`BannerSize`]: crate::types::user::BannerSize
+    #[cfg(feature = "media-upload")]
+    pub async fn update_profile_banner(&self, banner: Vec<u8>) -> Result<(), TweetyError> {
+        let form = multipart::Form::new().part("banner", multipart::Part::bytes(banner));
+
+        self.client
+            .send_multipart(&Endpoint::UpdateProfileBanner, &Query::new(), form)
+            .await
+            .map(|_| ())
+    }
+
+    /// GET /1.1/geo/search.json
+    /// Searches for places that can be attached to a tweet.
+    /// [Docs](https://developer.x.com/en/docs/twitter-api/v1/geo/places-near-location/api-reference/get-geo-search)
+    pub async fn geo_search(
+        &self,
+        params: GeoSearchParams,
+    ) -> Result<GeoSearchResponse, TweetyError> {
+        match self
+            .client
+            .send_request::<()>(&Endpoint::GeoSearch, &params.to_query(), None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<GeoSearchResponse>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// GET /1.1/geo/search.json
+    /// Places matching `query`, optionally near `(lat, long)`. Pass a place's `id` to
+    /// [`PostTweetParamsBuilder::place_id`] to tag a tweet with it.
+    ///
+    /// ```rust,no_run
+    /// use tweety_rs::types::tweet::PostTweetParams;
+    /// use tweety_rs::TweetyClient;
+    ///
+    /// # async fn run(client: TweetyClient) -> Result<(), tweety_rs::api::error::TweetyError> {
+    /// let places = client
+    ///     .v1()
+    ///     .search_places("Golden Gate Park", Some((37.7694, -122.4862)))
+    ///     .await?;
+    /// if let Some(place) = places.first() {
+    ///     let params = PostTweetParams::builder().place_id(&place.id).build();
+    ///     client.post_tweet("Picnic time", Some(params)).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`PostTweetParamsBuilder::place_id`]: crate::types::tweet::PostTweetParamsBuilder::place_id
+    pub async fn search_places(
+        &self,
+        query: &str,
+        near: Option<(f64, f64)>,
+    ) -> Result<Vec<Place>, TweetyError> {
+        let params = GeoSearchParams {
+            query: Some(query.to_string()),
+            lat: near.map(|(lat, _)| lat),
+            long: near.map(|(_, long)| long),
+            ..GeoSearchParams::default()
+        };
+
+        let response = self.geo_search(params).await?;
+        Ok(response.result.places)
+    }
+
+    /// GET /1.1/application/rate_limit_status.json
+    /// A pre-flight picture of `families`. The server's report is recorded like response
+    /// headers are, so [`TweetyClient::rate_limits`] subscribers see it too; families it
+    /// doesn't cover, such as v2 ones, come from the headers seen so far.
+    /// [Docs](https://developer.x.com/en/docs/twitter-api/v1/developer-utilities/rate-limit-status/api-reference/get-application-rate_limit_status)
+    pub async fn get_rate_limit_status(
+        &self,
+        families: &[RateLimitFamily],
+    ) -> Result<RateLimitReport, TweetyError> {
+        let mut groups: Vec<&str> = families
+            .iter()
+            .filter_map(|family| v1_resource(*family))
+            .map(|(group, _)| group)
+            .collect();
+        groups.sort_unstable();
+        groups.dedup();
+
+        let mut query = Query::new();
+        if !groups.is_empty() {
+            query.push("resources", groups.join(","));
+        }
+        let value = self
+            .client
+            .send_request::<()>(&Endpoint::RateLimitStatus, &query, None)
+            .await?;
+        let response = serde_json::from_value::<RateLimitStatusResponse>(value)
+            .map_err(TweetyError::JsonParseError)?;
+
+        let resources: BTreeMap<String, RateLimitStatus> = response
+            .resources
+            .into_values()
+            .flatten()
+            .map(|(path, window)| (path, window.into()))
+            .collect();
+        let reported: RateLimits = families
+            .iter()
+            .filter_map(|family| {
+                let (_, path) = v1_resource(*family)?;
+                Some((*family, *resources.get(path)?))
+            })
+            .collect();
+        self.client.merge_rate_limits(&reported);
+
+        let families = families
+            .iter()
+            .filter_map(|family| Some((*family, self.client.rate_limit(*family)?)))
+            .collect();
+        Ok(RateLimitReport {
+            families,
+            resources,
+        })
+    }
+
+    /// GET /1.1/help/languages.json
+    /// Returns the languages supported by X.
+    /// [Docs](https://developer.x.com/en/docs/twitter-api/v1/developer-utilities/supported-languages/api-reference/get-help-languages)
+    pub async fn help_languages(&self) -> Result<Vec<Language>, TweetyError> {
+        match self
+            .client
+            .send_request::<()>(&Endpoint::HelpLanguages, &Query::new(), None)
+            .await
+        {
+            Ok(value) => match serde_json::from_value::<Vec<Language>>(value) {
+                Ok(data) => Ok(data),
+                Err(err) => Err(TweetyError::JsonParseError(err)),
+            },
+            Err(err) => Err(err),
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! The receiving end of an Account Activity API webhook.
+//!
+//! X checks a webhook with a challenge-response check (CRC) when it is registered and
+//! about every hour after: a `GET` with a `crc_token`, answered with the token's
+//! HMAC-SHA256 under the app's consumer secret. Account activity then arrives as JSON
+//! `POST`s, signed in the [`SIGNATURE_HEADER`] header. [`WebhookHandler`] answers both,
+//! passing each delivery with a valid signature to a callback as a [`WebhookPayload`].
+//!
+//! Run it standalone for local development and expose the port with a tunnel such as
+//! `ngrok http 8080`, then register the tunnel's URL as the webhook:
+//!
+//! ```rust,no_run
+//! use std::net::TcpListener;
+//! use tweety_rs::types::webhook::WebhookEvent;
+//! use tweety_rs::TweetyClient;
+//!
+//! # async fn run(client: TweetyClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let handler = client.webhook_handler(|payload| {
+//!     for event in payload.events {
+//!         if let WebhookEvent::DirectMessage(message) = event {
+//!             println!("{} wrote: {}", message.sender_id(), message.text());
+//!         }
+//!     }
+//! });
+//! handler.serve(TcpListener::bind("127.0.0.1:8080")?).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Or mount [`WebhookHandler::handle`] at a route of your own hyper server. With another
+//! web framework, check deliveries with [`verify_signature`] before trusting them:
+//!
+//! ```rust
+//! use tweety_rs::api::webhook::verify_signature;
+//!
+//! let body = br#"{"for_user_id":"2244994945"}"#;
+//! // The value of the request's `x-twitter-webhooks-signature` header.
+//! let signature = "sha256=u4lZKKrBfHwAe+776eHnVf2VV1ggheRb1fS3xokQxlA=";
+//! assert!(verify_signature("consumer_secret", body, signature));
+//! ```
+
+use crate::api::client::TweetyClient;
+use crate::api::error::TweetyError;
+use crate::api::secret::SecretString;
+use crate::api::shutdown::Shutdown;
+use crate::types::webhook::WebhookPayload;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use hyper::body::HttpBody;
+use hyper::header::CONTENT_LENGTH;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::convert::Infallible;
+use std::fmt;
+use std::net::TcpListener;
+use std::sync::Arc;
+use url::form_urlencoded;
+
+/// The header X signs webhook deliveries in.
+pub const SIGNATURE_HEADER: &str = "x-twitter-webhooks-signature";
+
+/// The largest delivery body read before its signature is checked, unless changed with
+/// [`WebhookHandler::max_body_size`]. Account activity deliveries are a few kilobytes.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+type EventCallback = Arc<dyn Fn(WebhookPayload) + Send + Sync>;
+
+/// Answers CRC checks and passes account activity events to a callback.
+#[derive(Clone)]
+pub struct WebhookHandler {
+    consumer_secret: SecretString,
+    on_event: EventCallback,
+    max_body_size: usize,
+    shutdown: Option<Shutdown>,
+}
+
+impl fmt::Debug for WebhookHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookHandler")
+            .field("consumer_secret", &self.consumer_secret)
+            .field("max_body_size", &self.max_body_size)
+            .field("shutdown", &self.shutdown)
+            .finish()
+    }
+}
+
+impl WebhookHandler {
+    /// Signs CRC responses with `consumer_secret` and calls `on_event` with every event
+    /// delivery. `on_event` is called before X gets its response, so it
+    /// should return quickly: X retries deliveries not answered within 3 seconds.
+    pub fn new(
+        consumer_secret: &str,
+        on_event: impl Fn(WebhookPayload) + Send + Sync + 'static,
+    ) -> Self {
+        WebhookHandler {
+            consumer_secret: consumer_secret.into(),
+            on_event: Arc::new(on_event),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            shutdown: None,
+        }
+    }
+
+    /// Answers `POST`s with bodies over `bytes` with a 413, without reading the rest.
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = bytes;
+        self
+    }
+
+    /// Stops [`WebhookHandler::serve`] when `shutdown` is requested, after the requests
+    /// in flight are answered.
+    pub fn shutdown_on(mut self, shutdown: &Shutdown) -> Self {
+        self.shutdown = Some(shutdown.clone());
+        self
+    }
+
+    /// The `response_token` answering the CRC check for `crc_token`.
+    pub fn crc_response(&self, crc_token: &str) -> String {
+        let mac = mac(self.consumer_secret.expose_secret(), crc_token.as_bytes());
+        format!("sha256={}", STANDARD.encode(mac.finalize().into_bytes()))
+    }
+
+    /// Answers a request to the webhook URL: a `GET` with a `crc_token` gets the CRC
+    /// response, a `POST` of a JSON object is passed to the callback and gets an empty
+    /// 200. `POST`s without a valid [`SIGNATURE_HEADER`] get a 401, and those with a body
+    /// over [`WebhookHandler::max_body_size`] a 413.
+    pub async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        match *request.method() {
+            Method::GET => {
+                let crc_token = request.uri().query().and_then(|query| {
+                    form_urlencoded::parse(query.as_bytes())
+                        .find(|(key, _)| key == "crc_token")
+                        .map(|(_, value)| value.into_owned())
+                });
+                match crc_token {
+                    Some(crc_token) => {
+                        let body = json!({ "response_token": self.crc_response(&crc_token) });
+                        Response::builder()
+                            .header("Content-Type", "application/json")
+                            .body(Body::from(body.to_string()))
+                            .expect("response is valid")
+                    }
+                    None => status_response(StatusCode::BAD_REQUEST),
+                }
+            }
+            Method::POST => {
+                let signature = request
+                    .headers()
+                    .get(SIGNATURE_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                if signature.is_empty() {
+                    return status_response(StatusCode::UNAUTHORIZED);
+                }
+                let body = match read_body(request, self.max_body_size).await {
+                    Ok(body) => body,
+                    Err(status) => return status_response(status),
+                };
+                if !verify_signature(self.consumer_secret.expose_secret(), &body, &signature) {
+                    return status_response(StatusCode::UNAUTHORIZED);
+                }
+                let payload = serde_json::from_slice::<Value>(&body)
+                    .ok()
+                    .and_then(WebhookPayload::from_value);
+                match payload {
+                    Some(payload) => {
+                        (self.on_event)(payload);
+                        status_response(StatusCode::OK)
+                    }
+                    None => status_response(StatusCode::BAD_REQUEST),
+                }
+            }
+            _ => status_response(StatusCode::METHOD_NOT_ALLOWED),
+        }
+    }
+
+    /// Serves [`WebhookHandler::handle`] on every path of `listener` until the
+    /// [`Shutdown`] set with [`WebhookHandler::shutdown_on`] is requested.
+    pub async fn serve(&self, listener: TcpListener) -> Result<(), TweetyError> {
+        let _running = self.shutdown.as_ref().map(Shutdown::running);
+        let server_error = |err: hyper::Error| TweetyError::WebhookServerError(err.to_string());
+
+        listener
+            .set_nonblocking(true)
+            .map_err(|err| TweetyError::WebhookServerError(err.to_string()))?;
+        let handler = self.clone();
+        let make_service = make_service_fn(move |_| {
+            let handler = handler.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let handler = handler.clone();
+                    async move { Ok::<_, Infallible>(handler.handle(request).await) }
+                }))
+            }
+        });
+        let server = Server::from_tcp(listener)
+            .map_err(server_error)?
+            .serve(make_service);
+
+        match self.shutdown.clone() {
+            Some(shutdown) => server
+                .with_graceful_shutdown(async move { shutdown.requested().await })
+                .await
+                .map_err(server_error),
+            None => server.await.map_err(server_error),
+        }
+    }
+}
+
+/// Whether `signature`, the value of a delivery's [`SIGNATURE_HEADER`], is the
+/// HMAC-SHA256 of `body` under `consumer_secret`. Compared in constant time.
+pub fn verify_signature(consumer_secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(signature) = signature.strip_prefix("sha256=") else {
+        return false;
+    };
+    let Ok(signature) = STANDARD.decode(signature) else {
+        return false;
+    };
+    mac(consumer_secret, body).verify_slice(&signature).is_ok()
+}
+
+/// Reads the body of `request`, failing with a 413 as soon as its `Content-Length` or
+/// the bytes received go over `limit`.
+async fn read_body(request: Request<Body>, limit: usize) -> Result<Vec<u8>, StatusCode> {
+    let content_length = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if content_length.is_some_and(|length| length > limit) {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    let mut body = request.into_body();
+    let mut bytes = Vec::with_capacity(content_length.unwrap_or_default());
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if bytes.len() + chunk.len() > limit {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn mac(consumer_secret: &str, message: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(consumer_secret.as_bytes())
+        .expect("HMAC takes keys of any length");
+    mac.update(message);
+    mac
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .expect("response is valid")
+}
+
+impl TweetyClient {
+    /// A [`WebhookHandler`] signing CRC responses with the client's consumer secret.
+    pub fn webhook_handler(
+        &self,
+        on_event: impl Fn(WebhookPayload) + Send + Sync + 'static,
+    ) -> WebhookHandler {
+        WebhookHandler::new(
+            self.credentials.consumer_key_secret.expose_secret(),
+            on_event,
+        )
+    }
+}
@@ -27,6 +27,42 @@
 //! use tweety_rs;
 //! ```
 //!
+//! ### Cargo features
+//!
+//! All features but `simd-json`, `image`, `strict-schema`, `analytics`, `compression`, `socks`, `lang-detect`, `request-log`, `webhooks`, `ads`, `outbox`, `outbox-sqlite`, `schedule`, `mirror`, `feed`, `mastodon` and `bluesky` are on by default. A bot that only posts can turn off the parts it doesn't use:
+//!
+//! ```bash
+//! cargo add tweety-rs --no-default-features
+//! ```
+//!
+//! - `v1` - v1.1 endpoints with no v2 equivalent
+//! - `streams` - streaming endpoints
+//! - `media-upload` - the `media` and `uploads` modules
+//! - `oauth2-flow` - the OAuth 2.0 Authorization Code flow; OAuth 2.0 tokens work without it
+//! - `dm` - the `direct_messages` module
+//! - `lists` - the `lists` module
+//! - `spaces` - the `spaces` module
+//! - `simd-json` - parse stream messages with simd-json
+//! - `image` - shrink oversized images before upload
+//! - `strict-schema` - fail on response fields the typed models don't know, instead of keeping them in `extra`
+//! - `analytics` - the `analytics` module
+//! - `compression` - gzip large JSON request bodies and accept gzip or brotli responses
+//! - `socks` - SOCKS5 proxies
+//! - `lang-detect` - guess the language of tweet text locally
+//! - `request-log` - log requests with credentials redacted
+//! - `webhooks` - answer Account Activity API webhooks
+//! - `ads` - the `ads` module
+//! - `outbox` - the `outbox` module
+//! - `outbox-sqlite` - keep the outbox in SQLite
+//! - `schedule` - the `schedule` module
+//! - `mirror` - the `mirror` module
+//! - `feed` - the `feed` module
+//! - `mastodon` - the `mastodon` module
+//! - `bluesky` - the `bluesky` module
+//!
+//! Warnings, such as a poll that failed and will be retried, are logged at the `warn` level
+//! through the `log` crate. Install a logger, e.g. `env_logger`, to see them.
+//!
 //! ## Authentication
 //!
 //! To authenticate with the Twitter API, you will need the following credentials:
@@ -38,24 +74,29 @@
 //!
 //! You can obtain these from the [Twitter Developer portal](https://developer.x.com/en/portal/projects-and-apps).
 //!
+//! `TweetyClient::from_env()` reads them from the `CONSUMER_API_KEY`, `CONSUMER_API_SECRET`,
+//! `ACCESS_TOKEN` and `ACCESS_TOKEN_SECRET` environment variables (or an app-only `BEARER_TOKEN`),
+//! loading a `.env` file first if one is present.
+//!
 //! ## Usage
 //! To get started, you'll need to create a `TweetyClient` with your Twitter API credentials.
 //!
 //! ### Example: How to post a tweet
 //!
-//! ```rust
-//! use tweety_rs::client::TweetyClient;
+//! ```rust,no_run
+//! use tweety_rs::TweetyClient;
 //!
-//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     let client = TweetyClient::new(
 //!         "your_consumer_key",
-//!         "your_consumer_key_secret",
 //!         "your_access_token",
+//!         "your_consumer_key_secret",
 //!         "your_access_token_secret",
 //!     );
 //!
 //!     // Post a tweet
-//!     client.post_tweet("Hello, Twitter!", None).unwrap();
+//!     client.post_tweet("Hello, Twitter!", None).await?;
 //!
 //!     Ok(())
 //! }
@@ -65,44 +106,30 @@
 //!
 //! Make a tweet with an image appended to your tweet.
 //!
-//! ```rust
-//! use tweety_rs::{
-//!     types::tweet::{Media, PostTweetParams},
-//!     TweetyClient,
-//! };
-//! use tokio;
+//! ```rust,no_run
+//! use std::path::Path;
+//! use tweety_rs::{types::tweet::PostTweetParams, TweetyClient};
 //!
 //! #[tokio::main]
 //! async fn main() {
 //!     let client = TweetyClient::new(
 //!         "your_consumer_key",
-//!         "your_consumer_key_secret",
 //!         "your_access_token",
+//!         "your_consumer_key_secret",
 //!         "your_access_token_secret",
 //!     );
 //!
-//!     let path = Path::new(&file_path); // path of the image to be uploaded
-//!
-//!     match client.upload_file(&path).await {
-//!         Ok(value) => {
-//!             let media_string = value.to_string();
-//!             let message = format!("#{}", self.file_content.1);
-//!
-//!             let params = PostTweetParams {
-//!                 direct_message_deep_link: None,
-//!                 for_super_followers_only: None,
-//!                 geo: None,
-//!                 media: Some(Media {
-//!                     media_ids: vec![media_string].into(),
-//!                     tagged_user_ids: None,
-//!                 }),
-//!                 poll: None,
-//!                 quote_tweet_id: None,
-//!                 reply: None,
-//!                 reply_settings: None,
-//!             };
-//!
-//!             match client.post_tweet(&message, Some(params)).await {
+//!     let path = Path::new("ferris.png"); // path of the image to be uploaded
+//!
+//!     match client.upload_file(path).await {
+//!         Ok(media_id) => {
+//!             let message = "#rustlang";
+//!
+//!             let params = PostTweetParams::builder()
+//!                 .media(vec![media_id])
+//!                 .build();
+//!
+//!             match client.post_tweet(message, Some(params)).await {
 //!                 Ok(status_code) => {
 //!                     println!("Posted tweet: {:?}", status_code);
 //!                 }
@@ -120,19 +147,20 @@
 //!
 //! ### Example: Retweeting
 //!
-//! ```rust
-//! use tweety_rs::client::TweetyClient;
+//! ```rust,no_run
+//! use tweety_rs::TweetyClient;
 //!
-//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //!     let client = TweetyClient::new(
 //!         "your_consumer_key",
-//!         "your_consumer_key_secret",
 //!         "your_access_token",
+//!         "your_consumer_key_secret",
 //!         "your_access_token_secret",
 //!     );
 //!
-//!     // Retweet a tweet by ID
-//!     client.retweet("1234567890")?;
+//!     // Retweet a tweet by ID as the given user
+//!     client.retweet("2244994945", "1234567890").await?;
 //!
 //!     Ok(())
 //! }
@@ -142,17 +170,20 @@
 //!
 //! Here’s an example of how to use the `get_direct_messages` function:
 //!
-//! ```rust
-//! use tweety_rs::{client::TweetyClient,error::TweetyError,direct_messages::QueryParams};
-//! use serde_json::Value;
+//! ```rust,no_run
+//! use tweety_rs::api::direct_messages::{
+//!     DMEventField, EventType, Expansion, MediaField, QueryParams, TweetField, UserField,
+//! };
+//! use tweety_rs::api::error::TweetyError;
+//! use tweety_rs::TweetyClient;
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), TweetyError> {
 //!     // Create an instance of TweetyClient
 //!     let client = TweetyClient::new(
 //!         "your_consumer_key",
-//!         "your_consumer_key_secret",
 //!         "your_access_token",
+//!         "your_consumer_key_secret",
 //!         "your_access_token_secret",
 //!     );
 //!
@@ -163,6 +194,7 @@
 //!         expansions: Some(vec![Expansion::SenderId]),
 //!         max_results: Some(50),
 //!         media_fields: Some(vec![MediaField::Url, MediaField::Type]),
+//!         pagination_token: None,
 //!         tweet_fields: Some(vec![TweetField::CreatedAt, TweetField::Text]),
 //!         user_fields: Some(vec![UserField::Username, UserField::Verified]),
 //!     };
@@ -181,6 +213,36 @@
 //! }
 //! ```
 //!
+//! ### Example: Sharing a client between tasks
+//!
+//! `TweetyClient` is cheap to clone, so every task can own a handle to the same client.
+//!
+//! ```rust,no_run
+//! use tweety_rs::TweetyClient;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let client = TweetyClient::new(
+//!         "your_consumer_key",
+//!         "your_access_token",
+//!         "your_consumer_key_secret",
+//!         "your_access_token_secret",
+//!     );
+//!
+//!     let handles: Vec<_> = ["1234567890", "1234567891"]
+//!         .into_iter()
+//!         .map(|tweet_id| {
+//!             let client = client.clone();
+//!             tokio::spawn(async move { client.get_tweet_info(tweet_id).await })
+//!         })
+//!         .collect();
+//!
+//!     for handle in handles {
+//!         println!("{:?}", handle.await);
+//!     }
+//! }
+//! ```
+//!
 //! ## Twitter API Rate Limits
 //!
 //! Be aware of Twitter's rate limits:
@@ -190,20 +252,61 @@
 //!
 //! ## Modules
 //!
+//! - ads - Read ad accounts, campaigns and line items from the Ads API; behind the `ads` feature
+//! - analytics - Sample follower counts into a pluggable store and query their history, and summarize tweet engagement; behind the `analytics` feature
+//! - backfill - Split a date range into windows and page through the full-archive search
+//! - blocklist - Block and mute accounts, export block and mute lists, import them with throttling and resume, and block the members of a List
+//! - bluesky - Publish posts to Bluesky, with their links marked up, as a cross-poster; behind the `bluesky` feature
 //! - bookmark - Manage bookmarks
+//! - bot - Mention listener, command router and throttled replies for reply bots
+//! - circuit_breaker - Fail fast for a cooldown after repeated server errors from an endpoint family
 //! - client - Main client for interacting with the Twitter API
+//! - communities - Look up and search Communities
+//! - compliance - Typed events of the compliance streams; behind the `streams` feature
+//! - compose - Write tweets and threads from named templates, with mentions, hashtags and links, fitted to X's weighted length
+//! - config - Load credentials from profile-based config files
+//! - conversation - Rebuild the reply tree of a conversation
+//! - crosspost - Publish one post to X and other networks at once, fitted to each network's length limit
+//! - dedup - Drop tweets already delivered by overlapping pages, windows or reconnects
 //! - direct_messages - Handle sending and receiving direct messages
+//! - endpoint - Method, path, auth and rate-limit family of every endpoint the client calls
 //! - favourites - Manage favourites (likes)
+//! - feed - Post the new entries of an RSS or Atom feed, formatted by a hook and remembered across restarts; behind the `feed` feature
+//! - filtered_stream - Filtered stream connection with reconnect backoff, backfill and gap-fill by recent search; behind the `streams` feature
 //! - followers - Manage followers
 //! - following - Manage followings
-//! - hide_replies - Hide replies to tweets
+//! - hide_replies - Hide replies to tweets, or every reply a moderation policy matches
+//! - http - Connection pool and keepalive tuning
+//! - hydrate - Rehydrate tweet ids in batches of 100, paced by the rate limit, with per-id outcomes
 //! - like - Like tweets
-//! - mentions - Manage mentions
+//! - links - Expand t.co links to where they point
+//! - lists - Look up Lists, their followers and their members
+//! - mastodon - Publish statuses to a Mastodon instance as a cross-poster; behind the `mastodon` feature
+//! - media - Detect media types and check upload limits before uploading; the optional `image` feature shrinks oversized images
+//! - mentions - Manage mentions and read the tweets a user posted
+//! - mirror - Repost, quote or retweet another account's new tweets, with filters, link rewriting and text transforms; behind the `mirror` feature
+//! - oauth2 - OAuth 2.0 Authorization Code flow with PKCE and typed scopes
+//! - observer - Hook for events such as waits on a rate limit, with their progress
+//! - outbox - Queue tweets durably and post them from a background worker with retries, without losing or duplicating them across crashes, in a state store or SQLite; behind the `outbox` feature
+//! - rate_limit - Rate-limit state of every endpoint family, published on a watch channel as responses arrive
+//! - request_log - Log requests and response statuses with credentials redacted; behind the `request-log` feature
+//! - retry - Pluggable classification of which errors are worth retrying
 //! - retweets - Retweet tweets
+//! - schedule - Post on recurring cron schedules, such as a daily digest at 09:00 UTC, with text written by a callback at posting time, and post tweets that delete themselves after a time to live, all saved across restarts; behind the `schedule` feature
 //! - search - Search tweets and users
+//! - shutdown - Stop the mention listener, filtered stream and other background loops cleanly, saving their progress
+//! - signing - Sign requests to endpoints the client doesn't wrap with OAuth 1.0a, and inspect signatures to debug 401 responses
+//! - spaces - Look up Spaces and watch hosts for Spaces being scheduled, starting and ending; behind the `spaces` feature
+//! - state - Persist where the mention listener and filtered stream left off, in a file or a store of your own
+//! - stream - Parse the newline-delimited JSON of streaming endpoints; behind the `streams` feature
+//! - text - Count, slice and truncate text by grapheme clusters, so emoji, flags and accented letters are never split
+//! - tier - Access tiers, checking requests against their limits and monthly caps
 //! - tweet - Post and manage tweets
-//! - uploads - Upload media files
+//! - uploads - Upload media files, including resumable chunked uploads for large videos
+//! - usage - Budget the monthly post caps, refusing or warning about requests past a share of them, synced with the usage endpoint
 //! - user - Manage user information
+//! - v1 - v1.1 endpoints with no v2 equivalent (media upload, verify_credentials, geo search, languages); enabled by the default `v1` feature
+//! - webhook - Answer the CRC checks and signed events of Account Activity API webhooks, standalone or in your own server; behind the `webhooks` feature
 //!
 //! ## Common Issues
 //!
@@ -0,0 +1,126 @@
+//! Tweet models that borrow their strings from the response buffer.
+//!
+//! Meant for hot paths such as stream processing, where thousands of tweets a second
+//! would otherwise each allocate a `String` per field. Strings without JSON escapes
+//! point into the buffer; escaped ones fall back to an owned copy, so every valid
+//! response still parses.
+//!
+//! ```
+//! use tweety_rs::types::borrowed::{from_slice_borrowed, StreamTweetRef};
+//!
+//! let line = br#"{"data":{"id":"1","text":"hello","edit_history_tweet_ids":["1"]}}"#;
+//! let tweet: StreamTweetRef = from_slice_borrowed(line).unwrap();
+//! assert_eq!(tweet.data.text, "hello");
+//! ```
+
+use crate::api::error::TweetyError;
+use crate::types::lang::Lang;
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+
+/// Deserializes `T` borrowing from `bytes`, e.g. a line handed out by
+/// [`LineParser::push_raw`](crate::api::stream::LineParser::push_raw).
+pub fn from_slice_borrowed<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, TweetyError> {
+    Ok(serde_json::from_slice(bytes)?)
+}
+
+/// Borrowed counterpart of [`TweetData`](crate::api::mentions::TweetData), with the
+/// fields stream consumers usually need. Unknown fields are ignored.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TweetRef<'a> {
+    #[serde(borrow)]
+    pub id: Cow<'a, str>,
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+    #[serde(borrow, default, deserialize_with = "borrow_vec")]
+    pub edit_history_tweet_ids: Vec<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_option")]
+    pub author_id: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_option")]
+    pub lang: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_option")]
+    pub conversation_id: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_option")]
+    pub in_reply_to_user_id: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_option")]
+    pub created_at: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    pub referenced_tweets: Vec<ReferencedTweetRef<'a>>,
+    #[serde(borrow, default)]
+    pub note_tweet: Option<NoteTweetRef<'a>>,
+}
+
+impl TweetRef<'_> {
+    /// The complete text of the tweet, see
+    /// [`TweetData::full_text`](crate::api::mentions::TweetData::full_text).
+    pub fn full_text(&self) -> &str {
+        self.note_tweet
+            .as_ref()
+            .map(|note| note.text.as_ref())
+            .unwrap_or(&self.text)
+    }
+
+    /// `lang` as a [`Lang`].
+    pub fn language(&self) -> Option<Lang> {
+        self.lang.as_deref().map(Lang::from_code)
+    }
+
+    /// Guesses the language of [`TweetRef::full_text`] locally, see [`Lang::detect`].
+    #[cfg(feature = "lang-detect")]
+    pub fn detect_lang(&self) -> Option<Lang> {
+        Lang::detect(self.full_text())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReferencedTweetRef<'a> {
+    #[serde(borrow)]
+    pub id: Cow<'a, str>,
+    #[serde(borrow, rename = "type")]
+    pub kind: Cow<'a, str>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NoteTweetRef<'a> {
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+}
+
+/// A filtered stream rule a tweet matched.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MatchingRuleRef<'a> {
+    #[serde(borrow)]
+    pub id: Cow<'a, str>,
+    #[serde(borrow, default, deserialize_with = "borrow_option")]
+    pub tag: Option<Cow<'a, str>>,
+}
+
+/// One message of a tweet stream, `{"data": {...}, "matching_rules": [...]}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StreamTweetRef<'a> {
+    #[serde(borrow)]
+    pub data: TweetRef<'a>,
+    #[serde(borrow, default)]
+    pub matching_rules: Vec<MatchingRuleRef<'a>>,
+}
+
+/// A string that borrows when it can. Serde only borrows a `Cow<str>` that is a field
+/// by itself, not one inside an `Option` or `Vec`.
+#[derive(Deserialize)]
+struct BorrowedStr<'a>(#[serde(borrow)] Cow<'a, str>);
+
+fn borrow_option<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<BorrowedStr<'a>>::deserialize(deserializer)?;
+    Ok(value.map(|value| value.0))
+}
+
+fn borrow_vec<'de: 'a, 'a, D>(deserializer: D) -> Result<Vec<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Vec::<BorrowedStr<'a>>::deserialize(deserializer)?;
+    Ok(values.into_iter().map(|value| value.0).collect())
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Kinds of DM event, for the `event_types` filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventType {
+    MessageCreate,
+    ParticipantsJoin,
+    ParticipantsLeave,
+}
+
+/// A Direct Message event: a message, or a participant joining or leaving a group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct DmEvent {
+    pub id: String,
+    pub event_type: EventType,
+    /// Set on `MessageCreate` events.
+    pub text: Option<String>,
+    pub sender_id: Option<String>,
+    pub dm_conversation_id: Option<String>,
+    pub created_at: Option<String>,
+    /// Set on `ParticipantsJoin` and `ParticipantsLeave` events.
+    pub participant_ids: Option<Vec<String>>,
+    pub referenced_tweets: Option<Vec<ReferencedTweet>>,
+    pub attachments: Option<DmAttachments>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReferencedTweet {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DmAttachments {
+    #[serde(default)]
+    pub media_keys: Vec<String>,
+    #[serde(default)]
+    pub card_ids: Vec<String>,
+}
@@ -0,0 +1,276 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+macro_rules! languages {
+    ($($code:literal => $variant:ident, $name:literal;)*) => {
+        /// A language code as X returns it in `lang` and accepts in the `lang:` search
+        /// operator, mostly two-letter BCP 47 codes. Codes missing from the list are kept
+        /// in [`Lang::Other`].
+        /// [Docs](https://docs.x.com/x-api/posts/search/integrate/build-a-query#operators)
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum Lang {
+            $($variant,)*
+            /// A code missing from the list, lowercased.
+            Other(String),
+        }
+
+        impl Lang {
+            /// The language for `code`, ignoring case.
+            pub fn from_code(code: &str) -> Self {
+                match code.to_ascii_lowercase().as_str() {
+                    $($code => Lang::$variant,)*
+                    other => Lang::Other(other.to_string()),
+                }
+            }
+
+            /// The code X uses, e.g. `en`.
+            pub fn code(&self) -> &str {
+                match self {
+                    $(Lang::$variant => $code,)*
+                    Lang::Other(code) => code,
+                }
+            }
+
+            /// The English name, e.g. `Portuguese`.
+            pub fn name(&self) -> Option<&'static str> {
+                match self {
+                    $(Lang::$variant => Some($name),)*
+                    Lang::Other(_) => None,
+                }
+            }
+        }
+    };
+}
+
+languages! {
+    "am" => Amharic, "Amharic";
+    "ar" => Arabic, "Arabic";
+    "bg" => Bulgarian, "Bulgarian";
+    "bn" => Bengali, "Bengali";
+    "bo" => Tibetan, "Tibetan";
+    "ca" => Catalan, "Catalan";
+    "chr" => Cherokee, "Cherokee";
+    "cs" => Czech, "Czech";
+    "cy" => Welsh, "Welsh";
+    "da" => Danish, "Danish";
+    "de" => German, "German";
+    "dv" => Maldivian, "Maldivian";
+    "el" => Greek, "Greek";
+    "en" => English, "English";
+    "es" => Spanish, "Spanish";
+    "et" => Estonian, "Estonian";
+    "eu" => Basque, "Basque";
+    "fa" => Persian, "Persian";
+    "fi" => Finnish, "Finnish";
+    "fr" => French, "French";
+    "ga" => Irish, "Irish";
+    "gu" => Gujarati, "Gujarati";
+    "he" => Hebrew, "Hebrew";
+    "hi" => Hindi, "Hindi";
+    "hr" => Croatian, "Croatian";
+    "ht" => HaitianCreole, "Haitian Creole";
+    "hu" => Hungarian, "Hungarian";
+    "hy" => Armenian, "Armenian";
+    "id" => Indonesian, "Indonesian";
+    "is" => Icelandic, "Icelandic";
+    "it" => Italian, "Italian";
+    "iu" => Inuktitut, "Inuktitut";
+    "ja" => Japanese, "Japanese";
+    "ka" => Georgian, "Georgian";
+    "km" => Khmer, "Khmer";
+    "kn" => Kannada, "Kannada";
+    "ko" => Korean, "Korean";
+    "lo" => Lao, "Lao";
+    "lt" => Lithuanian, "Lithuanian";
+    "lv" => Latvian, "Latvian";
+    "ml" => Malayalam, "Malayalam";
+    "mr" => Marathi, "Marathi";
+    "ms" => Malay, "Malay";
+    "my" => Burmese, "Burmese";
+    "ne" => Nepali, "Nepali";
+    "nl" => Dutch, "Dutch";
+    "no" => Norwegian, "Norwegian";
+    "or" => Oriya, "Oriya";
+    "pa" => Panjabi, "Panjabi";
+    "pl" => Polish, "Polish";
+    "ps" => Pashto, "Pashto";
+    "pt" => Portuguese, "Portuguese";
+    "ro" => Romanian, "Romanian";
+    "ru" => Russian, "Russian";
+    "si" => Sinhala, "Sinhala";
+    "sk" => Slovak, "Slovak";
+    "sl" => Slovenian, "Slovenian";
+    "sr" => Serbian, "Serbian";
+    "sv" => Swedish, "Swedish";
+    "ta" => Tamil, "Tamil";
+    "te" => Telugu, "Telugu";
+    "th" => Thai, "Thai";
+    "tl" => Tagalog, "Tagalog";
+    "tr" => Turkish, "Turkish";
+    "uk" => Ukrainian, "Ukrainian";
+    "ur" => Urdu, "Urdu";
+    "vi" => Vietnamese, "Vietnamese";
+    "zh" => Chinese, "Chinese";
+    "qam" => MentionsOnly, "Mentions only";
+    "qct" => CashtagsOnly, "Cashtags only";
+    "qht" => HashtagsOnly, "Hashtags only";
+    "qme" => MediaLinksOnly, "Media links only";
+    "qst" => VeryShortText, "Very short text";
+    "und" => Undetermined, "Undetermined";
+    "zxx" => NoLinguisticContent, "No linguistic content";
+}
+
+impl Lang {
+    /// The `lang:` search and filtered stream rule operator for this language, e.g.
+    /// `lang:en`.
+    ///
+    /// ```
+    /// use tweety_rs::types::lang::Lang;
+    ///
+    /// let query = format!("#rustlang {} -is:retweet", Lang::German.operator());
+    /// assert_eq!(query, "#rustlang lang:de -is:retweet");
+    /// ```
+    pub fn operator(&self) -> String {
+        format!("lang:{}", self.code())
+    }
+}
+
+#[cfg(feature = "lang-detect")]
+impl Lang {
+    /// Guesses the language of `text` locally, for tweets whose `lang` is missing or
+    /// `und`. Mentions, hashtags, cashtags and links are left out, as X does. `None`
+    /// when nothing is left or no language stands out.
+    ///
+    /// ```
+    /// use tweety_rs::types::lang::Lang;
+    ///
+    /// let text = "@rustlang Das Wetter ist heute wirklich schön, wir gehen spazieren";
+    /// assert_eq!(Lang::detect(text), Some(Lang::German));
+    /// ```
+    pub fn detect(text: &str) -> Option<Lang> {
+        let words: Vec<&str> = text
+            .split_whitespace()
+            .filter(|word| !word.starts_with(['@', '#', '$']) && !word.starts_with("http"))
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+
+        let info = whatlang::detect(&words.join(" "))?;
+        Some(Lang::from_code(iso_639_1(info.lang())))
+    }
+}
+
+/// The two-letter code of a whatlang language.
+#[cfg(feature = "lang-detect")]
+fn iso_639_1(lang: whatlang::Lang) -> &'static str {
+    use whatlang::Lang::*;
+
+    match lang {
+        Afr => "af",
+        Aka => "ak",
+        Amh => "am",
+        Ara => "ar",
+        Aze => "az",
+        Bel => "be",
+        Ben => "bn",
+        Bul => "bg",
+        Cat => "ca",
+        Ces => "cs",
+        Cmn => "zh",
+        Dan => "da",
+        Deu => "de",
+        Ell => "el",
+        Eng => "en",
+        Epo => "eo",
+        Est => "et",
+        Fin => "fi",
+        Fra => "fr",
+        Guj => "gu",
+        Heb => "he",
+        Hin => "hi",
+        Hrv => "hr",
+        Hun => "hu",
+        Hye => "hy",
+        Ind => "id",
+        Ita => "it",
+        Jav => "jv",
+        Jpn => "ja",
+        Kan => "kn",
+        Kat => "ka",
+        Khm => "km",
+        Kor => "ko",
+        Lat => "la",
+        Lav => "lv",
+        Lit => "lt",
+        Mal => "ml",
+        Mar => "mr",
+        Mkd => "mk",
+        Mya => "my",
+        Nep => "ne",
+        Nld => "nl",
+        Nob => "no",
+        Ori => "or",
+        Pan => "pa",
+        Pes => "fa",
+        Pol => "pl",
+        Por => "pt",
+        Ron => "ro",
+        Rus => "ru",
+        Sin => "si",
+        Slk => "sk",
+        Slv => "sl",
+        Sna => "sn",
+        Spa => "es",
+        Srp => "sr",
+        Swe => "sv",
+        Tam => "ta",
+        Tel => "te",
+        Tgl => "tl",
+        Tha => "th",
+        Tuk => "tk",
+        Tur => "tr",
+        Ukr => "uk",
+        Urd => "ur",
+        Uzb => "uz",
+        Vie => "vi",
+        Yid => "yi",
+        Zul => "zu",
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Lang {
+    type Err = Infallible;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Ok(Lang::from_code(code))
+    }
+}
+
+impl From<&str> for Lang {
+    fn from(code: &str) -> Self {
+        Lang::from_code(code)
+    }
+}
+
+impl Serialize for Lang {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Lang {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = Cow::<str>::deserialize(deserializer)?;
+        Ok(Lang::from_code(&code))
+    }
+}
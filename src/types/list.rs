@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A List, as returned by the `/2/lists` endpoints.
+///
+/// Only `id` and `name` are returned by default; request the rest through
+/// `list.fields`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct List {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner_id: Option<String>,
+    pub private: Option<bool>,
+    pub follower_count: Option<u64>,
+    pub member_count: Option<u64>,
+    pub created_at: Option<String>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+impl List {
+    /// Link to the List on x.com.
+    pub fn url(&self) -> String {
+        format!("https://x.com/i/lists/{}", self.id)
+    }
+}
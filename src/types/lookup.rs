@@ -0,0 +1,77 @@
+use serde_json::Value;
+
+/// What a batch lookup returned for one requested id.
+///
+/// `GET /2/tweets` and `GET /2/users` answer ids they can't return with an entry in
+/// `errors` rather than failing the request; this sorts those entries by cause.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LookupOutcome<T> {
+    Found(T),
+    /// The user is suspended, or the tweet's author is.
+    Suspended,
+    /// Deleted, or never existed.
+    NotFound,
+    /// Protected: only the account's approved followers can see it.
+    Unauthorized,
+    /// A resource error of another kind, as returned.
+    Other(Value),
+}
+
+impl<T> LookupOutcome<T> {
+    /// Sorts an entry of a response's `errors` array by its `detail` and problem `type`.
+    pub fn from_error(error: &Value) -> Self {
+        let detail = error["detail"].as_str().unwrap_or_default();
+        let kind = error["type"].as_str().unwrap_or_default();
+
+        if detail.contains("suspended") {
+            LookupOutcome::Suspended
+        } else if kind.ends_with("/not-authorized-for-resource") {
+            LookupOutcome::Unauthorized
+        } else if kind.ends_with("/resource-not-found") {
+            LookupOutcome::NotFound
+        } else {
+            LookupOutcome::Other(error.clone())
+        }
+    }
+
+    /// Pairs each of `ids` with its outcome, in the order requested. Ids missing from
+    /// both `data` and `errors` are [`LookupOutcome::NotFound`].
+    pub fn collect<I: AsRef<str> + Clone>(
+        ids: &[I],
+        data: Vec<T>,
+        errors: &[Value],
+        id_of: impl Fn(&T) -> &str,
+    ) -> Vec<(I, LookupOutcome<T>)> {
+        let mut found: Vec<Option<T>> = data.into_iter().map(Some).collect();
+
+        ids.iter()
+            .map(|id| {
+                let wanted = id.as_ref();
+                let item = found
+                    .iter_mut()
+                    .find(|item| item.as_ref().is_some_and(|item| id_of(item) == wanted))
+                    .and_then(Option::take);
+                let outcome = match item {
+                    Some(item) => LookupOutcome::Found(item),
+                    None => errors
+                        .iter()
+                        .find(|error| error["resource_id"] == wanted)
+                        .map_or(LookupOutcome::NotFound, LookupOutcome::from_error),
+                };
+                (id.clone(), outcome)
+            })
+            .collect()
+    }
+
+    /// The item, if it was found.
+    pub fn found(self) -> Option<T> {
+        match self {
+            LookupOutcome::Found(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn is_found(&self) -> bool {
+        matches!(self, LookupOutcome::Found(_))
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// The `meta` object of a paginated response. Which fields are set depends on the
+/// endpoint: tweet timelines and searches report the `newest_id` and `oldest_id` of the
+/// page, and only some endpoints page backwards with `previous_token`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Meta {
+    #[serde(default)]
+    pub result_count: u32,
+    pub next_token: Option<String>,
+    pub previous_token: Option<String>,
+    pub newest_id: Option<String>,
+    pub oldest_id: Option<String>,
+}
+
+impl Meta {
+    /// Whether there is a page after this one.
+    pub fn has_next(&self) -> bool {
+        self.next_token.is_some()
+    }
+
+    /// `params` set up to fetch the next page, or `None` on the last page.
+    ///
+    /// ```rust
+    /// use tweety_rs::api::mentions::QueryParams;
+    /// use tweety_rs::types::meta::Meta;
+    ///
+    /// let meta = Meta {
+    ///     next_token: Some("7140dibdnow9c7btw3z2vwioavpvutgzrzm9icis4ndix".to_string()),
+    ///     ..Meta::default()
+    /// };
+    /// let params = meta.next_request(QueryParams::default()).unwrap();
+    /// assert_eq!(params.pagination_token, meta.next_token);
+    /// ```
+    pub fn next_request<P: Paginated>(&self, mut params: P) -> Option<P> {
+        let token = self.next_token.clone()?;
+        params.set_page_token(token);
+        Some(params)
+    }
+}
+
+/// Query parameters of a paginated endpoint, see [`Meta::next_request`].
+pub trait Paginated {
+    /// Sets the parameter the endpoint takes a `next_token` in, `pagination_token` or
+    /// `next_token` depending on the endpoint.
+    fn set_page_token(&mut self, token: String);
+}
@@ -1,3 +1,13 @@
+pub mod borrowed;
+pub mod dm;
+pub mod ids;
+pub mod lang;
+pub mod list;
+pub mod lookup;
+pub mod meta;
+pub mod space;
 pub mod tweet;
+#[allow(clippy::module_inception)]
 pub mod types;
 pub mod user;
+pub mod webhook;
@@ -0,0 +1,53 @@
+use crate::types::lang::Lang;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpaceState {
+    Scheduled,
+    Live,
+    Ended,
+}
+
+/// A Space, as returned by the `/2/spaces` endpoints.
+///
+/// Only `id` and `state` are returned by default; request the rest through
+/// `space_fields`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
+pub struct Space {
+    pub id: String,
+    pub state: SpaceState,
+    pub title: Option<String>,
+    pub creator_id: Option<String>,
+    #[serde(default)]
+    pub host_ids: Vec<String>,
+    #[serde(default)]
+    pub speaker_ids: Vec<String>,
+    #[serde(default)]
+    pub invited_user_ids: Vec<String>,
+    #[serde(default)]
+    pub topic_ids: Vec<String>,
+    pub lang: Option<Lang>,
+    pub is_ticketed: Option<bool>,
+    pub participant_count: Option<u64>,
+    pub subscriber_count: Option<u64>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub scheduled_start: Option<String>,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+impl Space {
+    /// Link to the Space on x.com.
+    pub fn url(&self) -> String {
+        format!("https://x.com/i/spaces/{}", self.id)
+    }
+}
@@ -1,8 +1,15 @@
+use crate::types::ids::{MediaId, TweetId, UserId};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::marker::PhantomData;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct PostTweetParams {
+    /// Posts the tweet to a Community. Look up valid targets with
+    /// [`TweetyClient::get_community`](crate::TweetyClient::get_community).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub community_id: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub direct_message_deep_link: Option<String>,
 
@@ -19,7 +26,7 @@ pub struct PostTweetParams {
     pub poll: Option<Poll>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub quote_tweet_id: Option<String>,
+    pub quote_tweet_id: Option<TweetId>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reply: Option<Reply>,
@@ -31,11 +38,168 @@ pub struct PostTweetParams {
 impl PostTweetParams {
     /// takes the message and appends it to the body json
     pub fn to_json(&self, message: &str) -> Value {
-        let mut json_payload = serde_json::to_value(&self).unwrap();
+        let mut json_payload = serde_json::to_value(self).unwrap();
         json_payload["text"] = serde_json::Value::String(message.to_string());
 
         json_payload
     }
+
+    /// Starts a [`PostTweetParamsBuilder`], which only allows combinations the API accepts.
+    ///
+    /// ```
+    /// use tweety_rs::types::tweet::PostTweetParams;
+    ///
+    /// let params = PostTweetParams::builder()
+    ///     .media(vec![1234567890u64.into()])
+    ///     .tagged_users(vec!["2244994945".into()])
+    ///     .in_reply_to("1828783668107559176")
+    ///     .build();
+    /// ```
+    ///
+    /// Media, a poll and a quoted tweet exclude each other, so a second attachment
+    /// doesn't compile:
+    ///
+    /// ```compile_fail
+    /// use tweety_rs::types::tweet::PostTweetParams;
+    ///
+    /// let params = PostTweetParams::builder()
+    ///     .poll(vec!["yes".into(), "no".into()], 60)
+    ///     .quote("1828783668107559176")
+    ///     .build();
+    /// ```
+    pub fn builder() -> PostTweetParamsBuilder<NoAttachment> {
+        PostTweetParamsBuilder {
+            params: PostTweetParams::default(),
+            attachment: PhantomData,
+        }
+    }
+}
+
+/// Typestate of a [`PostTweetParamsBuilder`] that has no media, poll or quoted tweet yet.
+#[derive(Debug)]
+pub struct NoAttachment;
+
+/// Typestate of a [`PostTweetParamsBuilder`] with media attached.
+#[derive(Debug)]
+pub struct WithMedia;
+
+/// Typestate of a [`PostTweetParamsBuilder`] with a poll attached.
+#[derive(Debug)]
+pub struct WithPoll;
+
+/// Typestate of a [`PostTweetParamsBuilder`] quoting a tweet.
+#[derive(Debug)]
+pub struct WithQuote;
+
+/// Builds [`PostTweetParams`], see [`PostTweetParams::builder`].
+///
+/// `A` tracks the attachment: media, a poll and a quoted tweet can only be set on a
+/// builder without one, and tagged users only once media is attached.
+#[derive(Debug)]
+pub struct PostTweetParamsBuilder<A> {
+    params: PostTweetParams,
+    attachment: PhantomData<A>,
+}
+
+impl<A> PostTweetParamsBuilder<A> {
+    fn attach<B>(self) -> PostTweetParamsBuilder<B> {
+        PostTweetParamsBuilder {
+            params: self.params,
+            attachment: PhantomData,
+        }
+    }
+
+    /// Posts the tweet to a Community.
+    pub fn community_id(mut self, community_id: &str) -> Self {
+        self.params.community_id = Some(community_id.to_string());
+        self
+    }
+
+    pub fn direct_message_deep_link(mut self, link: &str) -> Self {
+        self.params.direct_message_deep_link = Some(link.to_string());
+        self
+    }
+
+    pub fn for_super_followers_only(mut self, super_followers_only: bool) -> Self {
+        self.params.for_super_followers_only = Some(super_followers_only);
+        self
+    }
+
+    pub fn place_id(mut self, place_id: &str) -> Self {
+        self.params.geo = Some(Geo {
+            place_id: Some(place_id.to_string()),
+        });
+        self
+    }
+
+    /// Posts the tweet as a reply to `tweet_id`.
+    pub fn in_reply_to(mut self, tweet_id: impl Into<TweetId>) -> Self {
+        self.reply_mut().in_reply_to_tweet_id = Some(tweet_id.into());
+        self
+    }
+
+    /// Leaves these users out of the reply's mentions.
+    pub fn exclude_reply_users(mut self, user_ids: Vec<UserId>) -> Self {
+        self.reply_mut().exclude_reply_user_ids = Some(user_ids);
+        self
+    }
+
+    /// Who can reply, `following`, `mentionedUsers` or `subscribers`.
+    pub fn reply_settings(mut self, reply_settings: &str) -> Self {
+        self.params.reply_settings = Some(reply_settings.to_string());
+        self
+    }
+
+    pub fn build(self) -> PostTweetParams {
+        self.params
+    }
+
+    fn reply_mut(&mut self) -> &mut Reply {
+        self.params.reply.get_or_insert(Reply {
+            in_reply_to_tweet_id: None,
+            exclude_reply_user_ids: None,
+        })
+    }
+}
+
+impl PostTweetParamsBuilder<NoAttachment> {
+    /// Attaches uploaded media, see [`TweetyClient::upload_media`](crate::TweetyClient::upload_media).
+    pub fn media(mut self, media_ids: Vec<MediaId>) -> PostTweetParamsBuilder<WithMedia> {
+        self.params.media = Some(Media {
+            media_ids: Some(media_ids),
+            tagged_user_ids: None,
+        });
+        self.attach()
+    }
+
+    /// Attaches a poll with `options` that is open for `duration_minutes`.
+    pub fn poll(
+        mut self,
+        options: Vec<String>,
+        duration_minutes: u32,
+    ) -> PostTweetParamsBuilder<WithPoll> {
+        self.params.poll = Some(Poll {
+            duration_minutes: Some(duration_minutes),
+            options: Some(options),
+        });
+        self.attach()
+    }
+
+    /// Quotes `tweet_id`.
+    pub fn quote(mut self, tweet_id: impl Into<TweetId>) -> PostTweetParamsBuilder<WithQuote> {
+        self.params.quote_tweet_id = Some(tweet_id.into());
+        self.attach()
+    }
+}
+
+impl PostTweetParamsBuilder<WithMedia> {
+    /// Tags users in the attached media.
+    pub fn tagged_users(mut self, user_ids: Vec<UserId>) -> Self {
+        if let Some(media) = self.params.media.as_mut() {
+            media.tagged_user_ids = Some(user_ids);
+        }
+        self
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,9 +211,9 @@ pub struct Geo {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Media {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub media_ids: Option<Vec<String>>,
+    pub media_ids: Option<Vec<MediaId>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tagged_user_ids: Option<Vec<String>>,
+    pub tagged_user_ids: Option<Vec<UserId>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -63,7 +227,240 @@ pub struct Poll {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Reply {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub in_reply_to_tweet_id: Option<String>,
+    pub in_reply_to_tweet_id: Option<TweetId>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub exclude_reply_user_ids: Option<Vec<String>>,
+    pub exclude_reply_user_ids: Option<Vec<UserId>>,
+}
+
+/// Whether and until when a tweet can still be edited, `tweet.fields=edit_controls`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditControls {
+    pub edits_remaining: u32,
+    pub is_edit_eligible: bool,
+    /// ISO 8601 time after which the tweet can no longer be edited.
+    pub editable_until: String,
+}
+
+impl EditControls {
+    /// Returns `true` when the tweet is eligible for edits and has edits left. The
+    /// `editable_until` deadline is not checked.
+    pub fn can_edit(&self) -> bool {
+        self.is_edit_eligible && self.edits_remaining > 0
+    }
+}
+
+/// Engagement counts anyone can request with `tweet.fields=public_metrics`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TweetPublicMetrics {
+    pub retweet_count: Option<u64>,
+    pub reply_count: Option<u64>,
+    pub like_count: Option<u64>,
+    pub quote_count: Option<u64>,
+    pub bookmark_count: Option<u64>,
+    pub impression_count: Option<u64>,
+}
+
+/// Private engagement counts, `tweet.fields=non_public_metrics`.
+///
+/// Only returned with user context (OAuth 1.0a or an OAuth 2.0 user token) for
+/// tweets the authenticated user authored, and only for the last 30 days.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NonPublicMetrics {
+    pub impression_count: Option<u64>,
+    pub url_link_clicks: Option<u64>,
+    pub user_profile_clicks: Option<u64>,
+}
+
+/// Engagement counts from organic (non-promoted) views, `tweet.fields=organic_metrics`
+/// or `tweet.fields=promoted_metrics`.
+///
+/// Like [`NonPublicMetrics`], these need user context and the authenticated
+/// user's own tweets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EngagementMetrics {
+    pub impression_count: Option<u64>,
+    pub like_count: Option<u64>,
+    pub reply_count: Option<u64>,
+    pub retweet_count: Option<u64>,
+    pub url_link_clicks: Option<u64>,
+    pub user_profile_clicks: Option<u64>,
+}
+
+/// A topic X inferred for a tweet, `tweet.fields=context_annotations`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextAnnotation {
+    pub domain: ContextDomain,
+    pub entity: ContextEntity,
+}
+
+/// The kind of topic of a [`ContextAnnotation`], e.g. "Brand" or "Sports Team".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextDomain {
+    pub id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+impl ContextDomain {
+    /// The documented domain matching `id`.
+    pub fn kind(&self) -> ContextDomainKind {
+        ContextDomainKind::from_id(&self.id)
+    }
+}
+
+/// The specific topic of a [`ContextAnnotation`], e.g. a particular brand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextEntity {
+    pub id: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+macro_rules! context_domains {
+    ($($id:literal => $variant:ident, $name:literal;)*) => {
+        /// The context annotation domains listed in the X API documentation.
+        /// [Docs](https://docs.x.com/x-api/fundamentals/post-annotations)
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ContextDomainKind {
+            $($variant,)*
+            /// A domain id missing from the documented list.
+            Other(u32),
+        }
+
+        impl ContextDomainKind {
+            pub fn from_id(id: &str) -> Self {
+                match id.parse::<u32>() {
+                    $(Ok($id) => ContextDomainKind::$variant,)*
+                    Ok(other) => ContextDomainKind::Other(other),
+                    Err(_) => ContextDomainKind::Other(0),
+                }
+            }
+
+            pub fn id(&self) -> u32 {
+                match self {
+                    $(ContextDomainKind::$variant => $id,)*
+                    ContextDomainKind::Other(id) => *id,
+                }
+            }
+
+            /// The documented name, e.g. `Sports Team`.
+            pub fn name(&self) -> Option<&'static str> {
+                match self {
+                    $(ContextDomainKind::$variant => Some($name),)*
+                    ContextDomainKind::Other(_) => None,
+                }
+            }
+        }
+    };
+}
+
+context_domains! {
+    3 => TvShows, "TV Shows";
+    4 => TvEpisodes, "TV Episodes";
+    6 => SportsEvents, "Sports Events";
+    10 => Person, "Person";
+    11 => Sport, "Sport";
+    12 => SportsTeam, "Sports Team";
+    13 => Place, "Place";
+    22 => TvGenres, "TV Genres";
+    23 => TvChannels, "TV Channels";
+    26 => SportsLeague, "Sports League";
+    27 => AmericanFootballGame, "American Football Game";
+    28 => NflFootballGame, "NFL Football Game";
+    29 => Events, "Events";
+    31 => Community, "Community";
+    35 => Politicians, "Politicians";
+    38 => PoliticalRace, "Political Race";
+    39 => BasketballGame, "Basketball Game";
+    40 => SportsSeries, "Sports Series";
+    43 => SoccerMatch, "Soccer Match";
+    44 => BaseballGame, "Baseball Game";
+    45 => BrandVertical, "Brand Vertical";
+    46 => BrandCategory, "Brand Category";
+    47 => Brand, "Brand";
+    48 => Product, "Product";
+    54 => Musician, "Musician";
+    55 => MusicGenre, "Music Genre";
+    56 => Actor, "Actor";
+    58 => EntertainmentPersonality, "Entertainment Personality";
+    60 => Athlete, "Athlete";
+    65 => InterestsAndHobbiesVertical, "Interests and Hobbies Vertical";
+    66 => InterestsAndHobbiesCategory, "Interests and Hobbies Category";
+    67 => InterestsAndHobbies, "Interests and Hobbies";
+    68 => HockeyGame, "Hockey Game";
+    71 => VideoGame, "Video Game";
+    78 => VideoGamePublisher, "Video Game Publisher";
+    79 => VideoGameHardware, "Video Game Hardware";
+    83 => CricketMatch, "Cricket Match";
+    84 => Book, "Book";
+    85 => BookGenre, "Book Genre";
+    86 => Movie, "Movie";
+    87 => MovieGenre, "Movie Genre";
+    88 => PoliticalBody, "Political Body";
+    89 => MusicAlbum, "Music Album";
+    90 => RadioStation, "Radio Station";
+    91 => Podcast, "Podcast";
+    92 => SportsPersonality, "Sports Personality";
+    93 => Coach, "Coach";
+    94 => Journalist, "Journalist";
+    95 => TvChannelEntityService, "TV Channel [Entity Service]";
+    109 => ReoccurringTrends, "Reoccurring Trends";
+    110 => ViralAccounts, "Viral Accounts";
+    114 => Concert, "Concert";
+    115 => VideoGameConference, "Video Game Conference";
+    116 => VideoGameTournament, "Video Game Tournament";
+    117 => MovieFestival, "Movie Festival";
+    118 => AwardShow, "Award Show";
+    119 => Holiday, "Holiday";
+    120 => DigitalCreator, "Digital Creator";
+    122 => FictionalCharacter, "Fictional Character";
+    130 => MultimediaFranchise, "Multimedia Franchise";
+    131 => UnifiedTwitterTaxonomy, "Unified Twitter Taxonomy";
+    136 => VideoGamePersonality, "Video Game Personality";
+    137 => EsportsTeam, "eSports Team";
+    138 => EsportsPlayer, "eSports Player";
+    139 => FanCommunity, "Fan Community";
+    149 => EsportsLeague, "Esports League";
+    152 => Food, "Food";
+    155 => Weather, "Weather";
+    156 => Cities, "Cities";
+    157 => CollegesAndUniversities, "Colleges & Universities";
+    158 => PointsOfInterest, "Points of Interest";
+    159 => States, "States";
+    160 => Countries, "Countries";
+    162 => ExerciseAndFitness, "Exercise & fitness";
+    163 => Travel, "Travel";
+    164 => FieldsOfStudy, "Fields of study";
+    165 => Technology, "Technology";
+    166 => Stocks, "Stocks";
+    167 => Animals, "Animals";
+    171 => LocalNews, "Local News";
+    172 => GlobalTvShow, "Global TV Show";
+    173 => GoogleProductTaxonomy, "Google Product Taxonomy";
+    174 => DigitalAssetsAndCrypto, "Digital Assets & Crypto";
+    175 => EmergencyEvents, "Emergency Events";
+}
+
+/// A named entity X recognised in the tweet text, from `entities.annotations`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub start: u32,
+    pub end: u32,
+    /// Confidence between 0 and 1.
+    pub probability: f64,
+    pub r#type: AnnotationType,
+    /// The annotated text, e.g. `Barack Obama`.
+    pub normalized_text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnnotationType {
+    Person,
+    Place,
+    Product,
+    Organization,
+    Other,
+    /// A type missing from the documented list.
+    #[serde(other)]
+    Unknown,
 }
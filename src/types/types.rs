@@ -89,6 +89,10 @@ impl User {
     }
 
     pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn screen_name(&self) -> &str {
         &self.screen_name
     }
 }
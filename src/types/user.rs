@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 #[derive(Deserialize)]
 pub struct ApiResponse {
@@ -19,6 +20,7 @@ pub struct Includes {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "strict-schema", serde(deny_unknown_fields))]
 pub struct UserResponse {
     pub id: String,
     pub name: String,
@@ -34,8 +36,64 @@ pub struct UserResponse {
     pub verified_type: Option<VerifiedType>, // Enum could be used here
     pub entities: Option<Entities>,
     pub profile_image_url: Option<String>,
+    pub profile_banner_url: Option<String>,
     pub public_metrics: Option<PublicMetrics>,
     pub pinned_tweet_id: Option<String>,
+    /// Fields returned by the API that this struct doesn't model. Always empty with the
+    /// `strict-schema` feature, which rejects them instead.
+    #[cfg_attr(not(feature = "strict-schema"), serde(flatten))]
+    #[cfg_attr(feature = "strict-schema", serde(skip))]
+    pub extra: Map<String, Value>,
+}
+
+impl UserResponse {
+    /// The avatar at its uploaded size. `profile_image_url` points to a 48x48 thumbnail.
+    ///
+    /// Needs `user.fields=profile_image_url`.
+    pub fn original_profile_image_url(&self) -> Option<String> {
+        let url = self.profile_image_url.as_ref()?;
+        Some(url.replacen("_normal", "", 1))
+    }
+
+    /// The profile banner at `size`. Needs `user.fields=profile_banner_url`; `None` when
+    /// the user has no banner.
+    pub fn profile_banner_url_at(&self, size: BannerSize) -> Option<String> {
+        let url = self.profile_banner_url.as_ref()?;
+        Some(format!("{}/{}", url.trim_end_matches('/'), size.as_str()))
+    }
+}
+
+/// Resolutions profile banners are served in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BannerSize {
+    /// 300x100
+    Small,
+    /// 600x200
+    Medium,
+    /// 1500x500, the size banners are uploaded at.
+    Large,
+    /// 520x260
+    Web,
+    /// 1040x520
+    WebRetina,
+    /// 320x160
+    Mobile,
+    /// 640x320
+    MobileRetina,
+}
+
+impl BannerSize {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BannerSize::Small => "300x100",
+            BannerSize::Medium => "600x200",
+            BannerSize::Large => "1500x500",
+            BannerSize::Web => "web",
+            BannerSize::WebRetina => "web_retina",
+            BannerSize::Mobile => "mobile",
+            BannerSize::MobileRetina => "mobile_retina",
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,6 +107,8 @@ pub enum VerifiedType {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Withheld {
+    /// Set on tweets withheld because of a DMCA (copyright) complaint.
+    pub copyright: Option<bool>,
     pub country_codes: Option<Vec<String>>,
     pub scope: Option<WithheldScope>, // Enum could be used here
 }
@@ -0,0 +1,198 @@
+use crate::types::types::{Tweet, User};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Keys of an Account Activity payload that describe the events rather than being
+/// events themselves.
+const PAYLOAD_METADATA: &[&str] = &["for_user_id", "user_has_blocked", "users", "apps"];
+
+/// One delivery to an Account Activity webhook: the subscribed user it is for and the
+/// events it carries, grouped by kind.
+#[derive(Debug)]
+pub struct WebhookPayload {
+    pub for_user_id: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+impl WebhookPayload {
+    /// Sorts the `*_events` arrays of `payload` into [`WebhookEvent`]s. Events of kinds
+    /// not modeled here, or that don't match their model, come back as
+    /// [`WebhookEvent::Other`]. `None` when `payload` is not a JSON object.
+    pub fn from_value(payload: Value) -> Option<Self> {
+        let Value::Object(payload) = payload else {
+            return None;
+        };
+        let for_user_id = payload
+            .get("for_user_id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        let mut events = Vec::new();
+        for (kind, value) in payload {
+            if PAYLOAD_METADATA.contains(&kind.as_str()) {
+                continue;
+            }
+            match value {
+                Value::Array(items) => events.extend(
+                    items
+                        .into_iter()
+                        .map(|event| WebhookEvent::from_event(&kind, event)),
+                ),
+                event => events.push(WebhookEvent::Other { kind, event }),
+            }
+        }
+
+        Some(WebhookPayload {
+            for_user_id,
+            events,
+        })
+    }
+}
+
+/// An event delivered to an Account Activity webhook.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WebhookEvent {
+    /// A tweet by, mentioning, replying to or retweeting the subscribed user.
+    TweetCreate(Tweet),
+    /// The subscribed user liked a tweet, or one of their tweets was liked.
+    Favorite(FavoriteEvent),
+    /// A follow or unfollow by or of the subscribed user.
+    Follow(RelationshipEvent),
+    /// A block or unblock by the subscribed user.
+    Block(RelationshipEvent),
+    /// A mute or unmute by the subscribed user.
+    Mute(RelationshipEvent),
+    /// A Direct Message sent or received by the subscribed user.
+    DirectMessage(DirectMessageEvent),
+    /// Someone is typing a Direct Message to the subscribed user.
+    DirectMessageIndicateTyping(TypingEvent),
+    /// A tweet of the subscribed user was deleted.
+    TweetDelete(TweetDeleteEvent),
+    /// An event under `kind`, the payload key it came in, as delivered.
+    Other { kind: String, event: Value },
+}
+
+impl WebhookEvent {
+    fn from_event(kind: &str, event: Value) -> Self {
+        fn parse<T: DeserializeOwned>(
+            event: &Value,
+            variant: fn(T) -> WebhookEvent,
+        ) -> Option<WebhookEvent> {
+            T::deserialize(event).ok().map(variant)
+        }
+
+        let parsed = match kind {
+            "tweet_create_events" => parse(&event, WebhookEvent::TweetCreate),
+            "favorite_events" => parse(&event, WebhookEvent::Favorite),
+            "follow_events" => parse(&event, WebhookEvent::Follow),
+            "block_events" => parse(&event, WebhookEvent::Block),
+            "mute_events" => parse(&event, WebhookEvent::Mute),
+            "direct_message_events" => parse(&event, WebhookEvent::DirectMessage),
+            "direct_message_indicate_typing_events" => {
+                parse(&event, WebhookEvent::DirectMessageIndicateTyping)
+            }
+            "tweet_delete_events" => parse(&event, WebhookEvent::TweetDelete),
+            _ => None,
+        };
+        parsed.unwrap_or_else(|| WebhookEvent::Other {
+            kind: kind.to_string(),
+            event,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FavoriteEvent {
+    pub id: String,
+    pub created_at: String,
+    pub favorited_status: Tweet,
+    /// Who liked the tweet.
+    pub user: User,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationshipAction {
+    Follow,
+    Unfollow,
+    Block,
+    Unblock,
+    Mute,
+    Unmute,
+}
+
+/// A follow, block or mute, or its undoing, by `source` of `target`.
+#[derive(Debug, Deserialize)]
+pub struct RelationshipEvent {
+    #[serde(rename = "type")]
+    pub action: RelationshipAction,
+    /// Milliseconds since the epoch.
+    pub created_timestamp: String,
+    pub source: User,
+    pub target: User,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DirectMessageEvent {
+    pub id: String,
+    /// Milliseconds since the epoch.
+    pub created_timestamp: String,
+    pub message_create: MessageCreate,
+}
+
+impl DirectMessageEvent {
+    pub fn sender_id(&self) -> &str {
+        &self.message_create.sender_id
+    }
+
+    pub fn recipient_id(&self) -> &str {
+        &self.message_create.target.recipient_id
+    }
+
+    pub fn text(&self) -> &str {
+        &self.message_create.message_data.text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MessageCreate {
+    pub target: MessageTarget,
+    pub sender_id: String,
+    pub source_app_id: Option<String>,
+    pub message_data: MessageData,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MessageTarget {
+    pub recipient_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MessageData {
+    pub text: String,
+    pub entities: Option<Value>,
+    /// Media attached to the message, as delivered.
+    pub attachment: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TypingEvent {
+    /// Milliseconds since the epoch.
+    pub created_timestamp: String,
+    pub sender_id: String,
+    pub target: MessageTarget,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TweetDeleteEvent {
+    pub status: DeletedStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeletedStatus {
+    pub id: String,
+    pub user_id: String,
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use common::serve_status;
+use tweety_rs::api::v1::ProfileUpdate;
+use tweety_rs::TweetyClient;
+
+const USER: &str = r#"{"id":2244994945,"id_str":"2244994945","name":"Developers","screen_name":"XDevelopers","location":"127.0.0.1","description":"bio","url":null,"followers_count":1,"friends_count":2,"listed_count":3,"favourites_count":4,"statuses_count":5}"#;
+
+fn client(base_url: &str) -> TweetyClient {
+    TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_update_only_sends_set_fields() {
+        let update = ProfileUpdate {
+            description: Some("Rust & X".to_string()),
+            url: Some(String::new()),
+            ..ProfileUpdate::default()
+        };
+
+        assert_eq!(
+            update.to_query_string(),
+            "description=Rust+%26+X&url=&skip_status=true"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_profile() {
+        let (base_url, requests) = serve_status("200 OK", USER);
+
+        let update = ProfileUpdate {
+            name: Some("Developers".to_string()),
+            location: Some("127.0.0.1".to_string()),
+            ..ProfileUpdate::default()
+        };
+        client(&base_url).v1().update_profile(update).await.unwrap();
+
+        let request = requests.recv().unwrap().head();
+        assert!(request.starts_with(
+            "post /1.1/account/update_profile.json?name=developers&location=127.0.0.1&skip_status=true "
+        ));
+        assert!(request.contains("authorization: oauth "));
+    }
+
+    #[cfg(feature = "media-upload")]
+    #[tokio::test]
+    async fn test_update_profile_image_and_banner_are_multipart() {
+        let (base_url, requests) = serve_status("200 OK", USER);
+        let client = client(&base_url);
+
+        client
+            .v1()
+            .update_profile_image(b"png".to_vec())
+            .await
+            .unwrap();
+        let request = requests.recv().unwrap().head();
+        assert!(
+            request.starts_with("post /1.1/account/update_profile_image.json?skip_status=true ")
+        );
+        assert!(request.contains("content-type: multipart/form-data"));
+
+        let (base_url, requests) = serve_status("201 Created", "");
+        client
+            .with_base_url(&base_url)
+            .v1()
+            .update_profile_banner(b"png".to_vec())
+            .await
+            .unwrap();
+        let request = requests.recv().unwrap().head();
+        assert!(request.starts_with("post /1.1/account/update_profile_banner.json "));
+        assert!(request.contains("content-type: multipart/form-data"));
+    }
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "ads")]
+
+mod common;
+
+use common::serve_status;
+use tweety_rs::api::ads::AdsParams;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::TweetyClient;
+
+const ACCOUNTS: &str = r#"{"request":{"params":{}},"next_cursor":"c-1","data":[{"name":"Sandbox","business_name":null,"timezone":"America/Los_Angeles","timezone_switch_at":"2016-07-21T07:00:00Z","id":"18ce54d4x5t","created_at":"2016-07-21T22:42:09Z","salt":"f6c7d8b4a1e9","updated_at":"2017-11-30T19:24:38Z","business_id":null,"approval_status":"ACCEPTED","deleted":false}],"total_count":3}"#;
+
+const CAMPAIGNS: &str = r#"{"request":{"params":{"account_id":"18ce54d4x5t"}},"next_cursor":null,"data":[{"name":"launch","start_time":"2023-05-01T00:00:00Z","servable":false,"reasons_not_servable":["PAUSED_BY_ADVERTISER"],"daily_budget_amount_local_micro":140000000,"funding_instrument_id":"lygyi","id":"8wku2","entity_status":"PAUSED","currency":"USD","created_at":"2023-04-28T17:05:12Z","updated_at":"2023-05-02T09:11:40Z","deleted":false}]}"#;
+
+const LINE_ITEMS: &str = r#"{"request":{"params":{}},"next_cursor":null,"data":[{"placements":["ALL_ON_TWITTER"],"bid_strategy":"AUTO","campaign_id":"8wku2","objective":"ENGAGEMENTS","product_type":"PROMOTED_TWEETS","id":"bjw1q","name":"engagement","entity_status":"ACTIVE","currency":"USD","deleted":false}]}"#;
+
+fn client(base_url: &str) -> TweetyClient {
+    TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_ad_accounts_is_signed_and_paged() {
+        let (base_url, requests) = serve_status("200 OK", ACCOUNTS);
+        let params = AdsParams {
+            count: Some(1),
+            with_total_count: Some(true),
+            ..AdsParams::default()
+        };
+
+        let accounts = client(&base_url)
+            .get_ad_accounts(Some(params.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(accounts.data.len(), 1);
+        assert_eq!(accounts.data[0].id, "18ce54d4x5t");
+        assert_eq!(
+            accounts.data[0].approval_status.as_deref(),
+            Some("ACCEPTED")
+        );
+        assert_eq!(accounts.data[0].salt.as_deref(), Some("f6c7d8b4a1e9"));
+        assert_eq!(accounts.total_count, Some(3));
+        let request = requests.recv().unwrap();
+        assert_eq!(
+            request.line,
+            "GET /12/accounts?count=1&with_total_count=true HTTP/1.1"
+        );
+        assert!(request
+            .head()
+            .contains("authorization: oauth oauth_consumer_key=\"key\""));
+
+        let next = accounts.next_request(params).unwrap();
+        assert_eq!(next.cursor.as_deref(), Some("c-1"));
+    }
+
+    #[tokio::test]
+    async fn test_get_campaigns_and_line_items() {
+        let (base_url, requests) = serve_status("200 OK", CAMPAIGNS);
+        let campaigns = client(&base_url)
+            .get_campaigns("18ce54d4x5t", None)
+            .await
+            .unwrap();
+
+        let campaign = &campaigns.data[0];
+        assert_eq!(campaign.entity_status, "PAUSED");
+        assert_eq!(campaign.daily_budget_amount_local_micro, Some(140000000));
+        assert_eq!(campaign.reasons_not_servable, ["PAUSED_BY_ADVERTISER"]);
+        assert_eq!(campaign.start_time.as_deref(), Some("2023-05-01T00:00:00Z"));
+        assert!(campaigns.next_request(AdsParams::default()).is_none());
+        assert!(requests
+            .recv()
+            .unwrap()
+            .line
+            .starts_with("GET /12/accounts/18ce54d4x5t/campaigns "));
+
+        let (base_url, requests) = serve_status("200 OK", LINE_ITEMS);
+        let line_items = client(&base_url)
+            .get_line_items("18ce54d4x5t", &["8wku2", "9xyz"], None)
+            .await
+            .unwrap();
+
+        assert_eq!(line_items.data[0].campaign_id, "8wku2");
+        assert_eq!(line_items.data[0].placements, ["ALL_ON_TWITTER"]);
+        assert_eq!(line_items.data[0].bid_strategy.as_deref(), Some("AUTO"));
+        // OAuth 1.0a signing percent-encodes the commas.
+        assert!(requests
+            .recv()
+            .unwrap()
+            .line
+            .starts_with("GET /12/accounts/18ce54d4x5t/line_items?campaign_ids=8wku2%2C9xyz "));
+    }
+
+    #[tokio::test]
+    async fn test_ads_errors_surface_as_api_errors() {
+        let (base_url, _requests) = serve_status(
+            "404 Not Found",
+            r#"{"errors":[{"code":"NOT_FOUND","message":"Account not found"}],"request":{"params":{}}}"#,
+        );
+
+        let err = client(&base_url)
+            .get_campaigns("missing", None)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, TweetyError::ApiError { ref body, .. } if body.contains("NOT_FOUND"))
+        );
+        assert_eq!(err.status(), Some(reqwest::StatusCode::NOT_FOUND));
+    }
+}
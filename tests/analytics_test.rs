@@ -0,0 +1,139 @@
+#![cfg(feature = "analytics")]
+
+use serde_json::json;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tweety_rs::api::analytics::{
+    summarize_engagement, JsonLinesStore, MemoryStore, MetricsSample, MetricsStore, MetricsTracker,
+    TopTweet,
+};
+use tweety_rs::api::mentions::TweetData;
+use tweety_rs::TweetyClient;
+
+fn sample(user_id: &str, hours: u64, followers_count: u32) -> MetricsSample {
+    MetricsSample {
+        user_id: user_id.into(),
+        at: UNIX_EPOCH + Duration::from_secs(hours * 3600),
+        followers_count,
+        following_count: 10,
+        tweet_count: 100,
+        listed_count: 1,
+    }
+}
+
+fn hours(hours: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(hours * 3600)
+}
+
+fn tweet(id: &str, likes: u64, retweets: u64, impressions: u64) -> TweetData {
+    serde_json::from_value(json!({
+        "id": id,
+        "edit_history_tweet_ids": [id],
+        "text": "hello",
+        "public_metrics": {
+            "retweet_count": retweets,
+            "reply_count": 1,
+            "like_count": likes,
+            "quote_count": 0,
+            "bookmark_count": 0,
+            "impression_count": impressions
+        }
+    }))
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_and_growth() {
+        let store = MemoryStore::new();
+        for (hour, followers) in [(0, 100), (1, 104), (2, 103), (3, 110)] {
+            store.record(&sample("1", hour, followers)).unwrap();
+        }
+        store.record(&sample("2", 1, 5)).unwrap();
+        let tracker = MetricsTracker::new(TweetyClient::from_bearer_token("token"), store);
+
+        let history = tracker.history("1", hours(1), hours(2)).unwrap();
+        let followers: Vec<u32> = history.iter().map(|s| s.followers_count).collect();
+        assert_eq!(followers, vec![104, 103]);
+
+        assert_eq!(
+            tracker.follower_growth("1", hours(0), hours(3)).unwrap(),
+            Some(10)
+        );
+        assert_eq!(
+            tracker.follower_growth("1", hours(1), hours(2)).unwrap(),
+            Some(-1)
+        );
+        assert_eq!(
+            tracker.follower_growth("2", hours(0), hours(3)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_downsample_keeps_last_sample_per_bucket() {
+        let store = MemoryStore::new();
+        for (hour, followers) in [(0, 1), (1, 2), (2, 3), (3, 4), (5, 5)] {
+            store.record(&sample("1", hour, followers)).unwrap();
+        }
+        let tracker = MetricsTracker::new(TweetyClient::from_bearer_token("token"), store);
+
+        let buckets = tracker
+            .downsample("1", hours(0), hours(6), Duration::from_secs(2 * 3600))
+            .unwrap();
+        let followers: Vec<u32> = buckets.iter().map(|s| s.followers_count).collect();
+        assert_eq!(followers, vec![2, 4, 5]);
+    }
+
+    #[test]
+    fn test_json_lines_store_round_trips() {
+        let path = std::env::temp_dir().join("tweety_analytics_test.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let store = JsonLinesStore::new(&path);
+        assert!(store.samples(&"1".into()).unwrap().is_empty());
+        store.record(&sample("1", 0, 100)).unwrap();
+        store.record(&sample("2", 0, 7)).unwrap();
+        store.record(&sample("1", 1, 101)).unwrap();
+
+        let reopened = JsonLinesStore::new(&path);
+        assert_eq!(
+            reopened.samples(&"1".into()).unwrap(),
+            vec![sample("1", 0, 100), sample("1", 1, 101)]
+        );
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_summarize_engagement() {
+        let mut tweets = vec![
+            tweet("1", 10, 1, 1000),
+            tweet("2", 30, 0, 500),
+            tweet("3", 20, 5, 3000),
+        ];
+        tweets.push(
+            serde_json::from_value(json!({
+                "id": "4",
+                "edit_history_tweet_ids": ["4"],
+                "text": "no metrics"
+            }))
+            .unwrap(),
+        );
+
+        let report = summarize_engagement(&tweets);
+        assert_eq!(report.tweet_count, 3);
+        assert_eq!(report.totals.likes, 60);
+        assert_eq!(report.totals.replies, 3);
+        assert_eq!(report.totals.impressions, 4500);
+        assert_eq!(report.averages.likes, 20.0);
+        assert_eq!(report.averages.retweets, 2.0);
+
+        let ids = |top: &[TopTweet]| top.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&report.top_by_likes), ["2", "3", "1"]);
+        assert_eq!(ids(&report.top_by_retweets), ["3", "1", "2"]);
+        assert_eq!(ids(&report.top_by_impressions), ["3", "1", "2"]);
+        assert_eq!(report.top_by_likes[0].count, 30);
+    }
+}
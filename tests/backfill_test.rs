@@ -0,0 +1,47 @@
+use futures_util::StreamExt;
+use std::time::{Duration, UNIX_EPOCH};
+use tweety_rs::api::backfill::{to_rfc3339, Backfill, TimeWindows};
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::tier::Tier;
+use tweety_rs::TweetyClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_windows_cover_the_range() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_000);
+        let end = start + TimeWindows::DAY * 2 + TimeWindows::HOUR;
+
+        let windows: Vec<_> = TimeWindows::new(start, end, TimeWindows::DAY).collect();
+
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0].0, start);
+        assert_eq!(windows[0].1, windows[1].0);
+        assert_eq!(windows[2].1, end);
+        assert_eq!(TimeWindows::new(end, start, TimeWindows::DAY).count(), 0);
+        assert_eq!(TimeWindows::new(start, end, Duration::ZERO).count(), 1);
+    }
+
+    #[test]
+    fn test_rfc3339_formatting() {
+        assert_eq!(to_rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        // 2024-02-29, a leap day.
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_164_800 + 3_661);
+        assert_eq!(to_rfc3339(leap_day), "2024-02-29T01:01:01Z");
+    }
+
+    #[tokio::test]
+    async fn test_backfill_ends_after_an_error() {
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url("http://127.0.0.1:1")
+            .with_tier(Tier::Basic);
+        let backfill = Backfill::new("rust", UNIX_EPOCH, UNIX_EPOCH + TimeWindows::DAY * 3);
+
+        let results: Vec<_> = client.backfill(backfill).collect().await;
+
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(TweetyError::TierLimit { .. })));
+    }
+}
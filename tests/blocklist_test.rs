@@ -0,0 +1,139 @@
+mod common;
+
+use common::{serve, Response};
+#[cfg(feature = "lists")]
+use std::collections::HashSet;
+#[cfg(feature = "lists")]
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+#[cfg(feature = "lists")]
+use tweety_rs::api::blocklist::BlockListMembersOptions;
+use tweety_rs::api::blocklist::{BlocklistImport, BlocklistKind};
+use tweety_rs::api::endpoint::{Endpoint, RateLimitFamily};
+use tweety_rs::TweetyClient;
+
+/// Serves a List's members and two pages of blocked users, and accepts blocks of every
+/// account but `9`.
+fn blocklist_server() -> String {
+    serve(|request| {
+        if request.method == "GET" && request.path().starts_with("/2/lists/") {
+            Response::ok(
+                r#"{"data":[{"id":"100","name":"me","username":"me"},{"id":"1","name":"a","username":"a"},{"id":"5","name":"mod","username":"mod"},{"id":"2","name":"b","username":"b"}],"meta":{"result_count":4}}"#,
+            )
+        } else if request.method == "GET" {
+            if request.target.contains("pagination_token=p2") {
+                Response::ok(
+                    r#"{"data":[{"id":"3","name":"c","username":"c"}],"meta":{"result_count":1}}"#,
+                )
+            } else {
+                Response::ok(
+                    r#"{"data":[{"id":"1","name":"a","username":"a"},{"id":"2","name":"b","username":"b"}],"meta":{"result_count":2,"next_token":"p2"}}"#,
+                )
+            }
+        } else if request.json()["target_user_id"] == "9" {
+            Response::new(
+                "400 Bad Request",
+                r#"{"title":"Invalid Request","detail":"user not found"}"#,
+            )
+        } else {
+            Response::ok(r#"{"data":{"blocking":true}}"#)
+        }
+    })
+    .0
+}
+
+fn client(base_url: &str) -> TweetyClient {
+    TweetyClient::new(
+        "consumer_key",
+        "access_token",
+        "consumer_secret",
+        "token_secret",
+    )
+    .with_base_url(base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_and_mute_endpoints() {
+        let unmute = Endpoint::Unmute {
+            user_id: "1".into(),
+            target_user_id: "2".into(),
+        };
+        assert_eq!(unmute.path(), "/2/users/1/muting/2");
+        assert_eq!(unmute.method(), reqwest::Method::DELETE);
+        assert_eq!(Endpoint::Block("1".into()).method(), reqwest::Method::POST);
+        assert_eq!(
+            Endpoint::Blocking("1".into()).rate_limit_family(),
+            RateLimitFamily::Blocks
+        );
+        assert_eq!(
+            Endpoint::Mute("1".into()).rate_limit_family(),
+            RateLimitFamily::Mutes
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_blocklist_pages_through_all_users() {
+        let path = std::env::temp_dir().join("tweety_blocklist_export_test.txt");
+        let count = client(&blocklist_server())
+            .export_blocklist(BlocklistKind::Block, "100", &path)
+            .await
+            .unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_refused_ids_and_resumes() {
+        let dir = std::env::temp_dir();
+        let list = dir.join("tweety_blocklist_import_test.txt");
+        let progress = dir.join("tweety_blocklist_import_test.progress");
+        std::fs::write(&list, "# shared list\n1\n9\n\n2\n3\n").unwrap();
+        std::fs::write(&progress, "1").unwrap();
+
+        let mut import =
+            BlocklistImport::new(client(&blocklist_server()), BlocklistKind::Block, &list)
+                .unwrap()
+                .delay(Duration::ZERO)
+                .persist_to(&progress)
+                .unwrap();
+        assert_eq!(import.remaining(), ["9", "2", "3"]);
+
+        let summary = import.run("100").await.unwrap();
+        assert_eq!(summary.applied, 2);
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(summary.skipped[0].0, "9");
+        assert!(import.remaining().is_empty());
+        assert_eq!(std::fs::read_to_string(&progress).unwrap(), "4");
+
+        let _ = std::fs::remove_file(list);
+        let _ = std::fs::remove_file(progress);
+    }
+
+    #[cfg(feature = "lists")]
+    #[tokio::test]
+    async fn test_block_list_members_skips_self_and_excluded() {
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let reported = progress.clone();
+        let options = BlockListMembersOptions {
+            exclude: HashSet::from(["5".to_string()]),
+            delay: Duration::ZERO,
+        };
+
+        let summary = client(&blocklist_server())
+            .block_list_members("100", "42", options, move |handled, total| {
+                reported.lock().unwrap().push((handled, total));
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(summary.applied, 2);
+        assert!(summary.skipped.is_empty());
+        assert_eq!(*progress.lock().unwrap(), vec![(1, 2), (2, 2)]);
+    }
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "bluesky")]
+
+mod common;
+
+use common::{serve_in_order, Response};
+use tweety_rs::api::bluesky::Bluesky;
+use tweety_rs::api::crosspost::CrossPoster;
+
+const SESSION: &str = r#"{"did":"did:plc:z72i7hdynmk6r22z27h6tvur","handle":"tweety.bsky.social","accessJwt":"access-1","refreshJwt":"refresh-1"}"#;
+const EXPIRED: &str = r#"{"error":"ExpiredToken","message":"Token has expired"}"#;
+const RECORD: &str = r#"{"uri":"at://did:plc:z72i7hdynmk6r22z27h6tvur/app.bsky.feed.post/3l3qo2vuowo2b","cid":"bafyreig2fjxi3rptqdgylg7e5hmjl6mcke7rn2b6cugzlqq3i4zu6rq52q"}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_signs_in_and_creates_a_post_with_link_facets() {
+        let (base_url, requests) =
+            serve_in_order(vec![Response::ok(SESSION), Response::ok(RECORD)]);
+        let bluesky = Bluesky::new("tweety.bsky.social", "app-password").service(&base_url);
+
+        let posted = bluesky
+            .publish("Out now ✨ https://crates.io/crates/tweety-rs")
+            .await
+            .unwrap();
+        assert_eq!(
+            posted.id,
+            "at://did:plc:z72i7hdynmk6r22z27h6tvur/app.bsky.feed.post/3l3qo2vuowo2b"
+        );
+        assert_eq!(
+            posted.url.as_deref(),
+            Some("https://bsky.app/profile/did:plc:z72i7hdynmk6r22z27h6tvur/post/3l3qo2vuowo2b")
+        );
+
+        let request = requests.recv().unwrap();
+        let body = request.json();
+        assert_eq!(
+            request.line,
+            "POST /xrpc/com.atproto.server.createSession HTTP/1.1"
+        );
+        assert_eq!(body["identifier"], "tweety.bsky.social");
+        assert_eq!(body["password"], "app-password");
+
+        let request = requests.recv().unwrap();
+        let body = request.json();
+        assert_eq!(
+            request.line,
+            "POST /xrpc/com.atproto.repo.createRecord HTTP/1.1"
+        );
+        assert_eq!(request.header("Authorization"), Some("Bearer access-1"));
+        assert_eq!(body["repo"], "did:plc:z72i7hdynmk6r22z27h6tvur");
+        let record = &body["record"];
+        assert_eq!(record["$type"], "app.bsky.feed.post");
+        // "Out now ✨ " is 12 bytes: the sparkles take 3.
+        assert_eq!(record["facets"][0]["index"]["byteStart"], 12);
+        assert_eq!(record["facets"][0]["index"]["byteEnd"], 46);
+        assert_eq!(
+            record["facets"][0]["features"][0]["uri"],
+            "https://crates.io/crates/tweety-rs"
+        );
+        assert!(record["createdAt"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[tokio::test]
+    async fn test_signs_in_again_when_the_session_expires() {
+        let (base_url, requests) = serve_in_order(vec![
+            Response::ok(SESSION),
+            Response::ok(RECORD),
+            Response::new("400 Bad Request", EXPIRED),
+            Response::ok(SESSION),
+            Response::ok(RECORD),
+        ]);
+        let bluesky = Bluesky::new("tweety.bsky.social", "app-password").service(&base_url);
+
+        bluesky.create_post("first").await.unwrap();
+        bluesky.create_post("second").await.unwrap();
+
+        let paths: Vec<String> = requests.try_iter().map(|request| request.target).collect();
+        let methods: Vec<&str> = paths
+            .iter()
+            .map(|path| path.trim_start_matches("/xrpc/"))
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                "com.atproto.server.createSession",
+                "com.atproto.repo.createRecord",
+                "com.atproto.repo.createRecord",
+                "com.atproto.server.createSession",
+                "com.atproto.repo.createRecord",
+            ]
+        );
+    }
+}
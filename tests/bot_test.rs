@@ -0,0 +1,129 @@
+mod common;
+
+use common::serve_status;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tweety_rs::api::bot::{AutoReplier, Command, MentionListener, OverflowPolicy, ReplyLimits};
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::mentions::MentionsResponse;
+use tweety_rs::TweetyClient;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("tweety-{}-{}", std::process::id(), name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listener_resumes_from_saved_cursor() {
+        let path = temp_path("mentions.cursor");
+        fs::write(&path, "1822274639129612288\n").unwrap();
+
+        let client = TweetyClient::new("a", "b", "c", "d");
+        let listener = MentionListener::new(client).persist_to(&path).unwrap();
+        assert_eq!(listener.last_seen_id(), Some("1822274639129612288"));
+
+        let missing = temp_path("missing.cursor");
+        let client = TweetyClient::new("a", "b", "c", "d");
+        let listener = MentionListener::new(client).persist_to(&missing).unwrap();
+        assert_eq!(listener.last_seen_id(), None);
+
+        fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_listener_saves_cursor_in_place() {
+        let (base_url, _requests) = serve_status(
+            "200 OK",
+            r#"{"data":[{"id":"1900000000000000002","edit_history_tweet_ids":["1900000000000000002"],"text":"@bot hi"}],"meta":{"result_count":1}}"#,
+        );
+        let path = temp_path("saved.cursor");
+        fs::write(&path, "1900000000000000001").unwrap();
+
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+        let mut listener = MentionListener::new(client)
+            .user_id("2244994945")
+            .persist_to(&path)
+            .unwrap();
+        assert_eq!(listener.poll_once().await.unwrap(), 1);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1900000000000000002");
+        assert!(!path.with_extension("tmp").exists());
+        fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_poll_without_credentials_fails() {
+        let client = TweetyClient::new("", "", "", "");
+        let mut listener = MentionListener::new(client)
+            .user_id("2244994945")
+            .on_mention(|_| async { Ok(()) });
+
+        let result = listener.poll_once().await;
+        assert!(matches!(result, Err(TweetyError::MissingCredentials)));
+    }
+
+    #[test]
+    fn test_empty_mentions_page_parses() {
+        let json = serde_json::json!({ "meta": { "result_count": 0 } });
+        let response: MentionsResponse = serde_json::from_value(json).unwrap();
+
+        assert!(response.data.is_empty());
+        assert_eq!(response.meta.newest_id, None);
+    }
+
+    #[test]
+    fn test_parses_commands_after_bot_mention() {
+        let command =
+            Command::parse("@alice @MyBot: Translate fr \"good morning\" now", "mybot").unwrap();
+
+        assert_eq!(command.name, "translate");
+        assert_eq!(command.args, vec!["fr", "good morning", "now"]);
+        assert_eq!(command.rest, "fr \"good morning\" now");
+
+        let bare = Command::parse("hey @mybot /ping", "@mybot").unwrap();
+        assert_eq!(bare.name, "ping");
+        assert!(bare.args.is_empty());
+
+        assert_eq!(Command::parse("@mybot", "mybot"), None);
+        assert_eq!(Command::parse("@mybot @alice hi", "mybot"), None);
+        assert_eq!(Command::parse("@mybotfan ping", "mybot"), None);
+    }
+
+    #[test]
+    fn test_auto_replier_enforces_caps() {
+        let replier = AutoReplier::new(ReplyLimits {
+            per_user_max: 1,
+            per_user_window: Duration::from_secs(3600),
+            global_max: 2,
+            global_window: Duration::from_secs(3600),
+            user_cooldown: Duration::ZERO,
+            global_cooldown: Duration::ZERO,
+            policy: OverflowPolicy::Drop,
+        });
+
+        assert!(replier.try_acquire(Some("alice")).is_ok());
+        let retry_after = replier.try_acquire(Some("alice")).unwrap_err();
+        assert!(retry_after > Duration::from_secs(3500));
+
+        // Clones share the history, so bob takes the last global slot.
+        assert!(replier.clone().try_acquire(Some("bob")).is_ok());
+        assert!(replier.try_acquire(Some("carol")).is_err());
+    }
+
+    #[test]
+    fn test_auto_replier_cooldown() {
+        let replier = AutoReplier::new(ReplyLimits {
+            global_cooldown: Duration::from_secs(30),
+            ..ReplyLimits::default()
+        });
+
+        assert!(replier.try_acquire(None).is_ok());
+        let retry_after = replier.try_acquire(Some("alice")).unwrap_err();
+        assert!(retry_after <= Duration::from_secs(30));
+    }
+}
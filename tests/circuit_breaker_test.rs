@@ -0,0 +1,55 @@
+mod common;
+
+use common::serve_status;
+use std::time::Duration;
+use tweety_rs::api::circuit_breaker::CircuitBreaker;
+use tweety_rs::api::endpoint::RateLimitFamily;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::TweetyClient;
+
+/// Answers every request with a 503 and returns the server's base URL.
+fn failing_server() -> String {
+    serve_status("503 Service Unavailable", "{}").0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_circuit_opens_after_consecutive_server_errors() {
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url(&failing_server())
+            .with_circuit_breaker(CircuitBreaker::new(2, Duration::from_secs(60)));
+
+        for _ in 0..2 {
+            let result = client.get_user_by_id("1", None).await;
+            assert_eq!(result.unwrap_err().status().map(|s| s.as_u16()), Some(503));
+        }
+
+        let result = client.get_user_by_id("1", None).await;
+        assert!(matches!(
+            result,
+            Err(TweetyError::CircuitOpen {
+                family: RateLimitFamily::UserLookup,
+                ..
+            })
+        ));
+
+        let breaker = client.circuit_breaker().unwrap();
+        assert!(breaker.open_for(RateLimitFamily::UserLookup).is_some());
+        assert!(breaker.open_for(RateLimitFamily::TweetLookup).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_stays_closed_on_network_errors() {
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url("http://127.0.0.1:1")
+            .with_circuit_breaker(CircuitBreaker::new(1, Duration::from_secs(60)));
+
+        for _ in 0..2 {
+            let result = client.get_user_by_id("1", None).await;
+            assert!(matches!(result, Err(TweetyError::NetworkError(_))));
+        }
+    }
+}
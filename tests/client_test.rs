@@ -0,0 +1,143 @@
+use tweety_rs::api::client::{AuthMode, UserTokens};
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::oauth2::{OAuth2Token, Scope};
+use tweety_rs::api::secret::SecretString;
+use tweety_rs::TweetyClient;
+
+fn assert_send<T: Send>(_: &T) {}
+
+fn assert_send_sync_clone<T: Send + Sync + Clone>() {}
+
+fn setup_client() -> TweetyClient {
+    TweetyClient::new(
+        "consumer_key",
+        "access_token",
+        "consumer_key_secret",
+        "access_token_secret",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_is_send_sync_clone() {
+        assert_send_sync_clone::<TweetyClient>();
+    }
+
+    #[test]
+    fn test_cloned_client_keeps_credentials() {
+        let client = setup_client();
+        let cloned = client.clone();
+
+        assert!(client.is_initialized());
+        assert!(cloned.is_initialized());
+    }
+
+    /// Methods borrow the client, so one client can drive several requests and
+    /// the resulting futures can be spawned onto other tasks.
+    #[test]
+    fn test_client_methods_borrow() {
+        let client = setup_client();
+
+        let edit = client.edit_tweet("edited", "1234567890");
+        let hide = client.hide_tweet("1234567890");
+        let bookmarks = client.get_user_bookmark("2244994945");
+        let retweeters = client.fetch_retweeters("1234567890");
+        let retweets = client.fetch_retweets("1234567890", None);
+
+        assert_send(&edit);
+        assert_send(&hide);
+        assert_send(&bookmarks);
+        assert_send(&retweeters);
+        assert_send(&retweets);
+        assert!(client.is_initialized());
+    }
+
+    #[test]
+    fn test_debug_output_masks_secrets() {
+        let client = TweetyClient::new(
+            "consumer_key_value",
+            "access_token_value",
+            "consumer_secret_value",
+            "access_secret_value",
+        );
+        let debug = format!("{:?}", client);
+
+        assert!(debug.contains("cons****"), "{}", debug);
+        assert!(!debug.contains("consumer_secret_value"), "{}", debug);
+        assert!(!debug.contains("access_secret_value"), "{}", debug);
+    }
+
+    #[test]
+    fn test_secret_string_redaction() {
+        assert_eq!(SecretString::new("abcdefgh").redacted(), "abcd****");
+        assert_eq!(SecretString::new("abc").redacted(), "****");
+        assert_eq!(
+            format!("{:?}", SecretString::new("abcdefgh")),
+            "\"abcd****\""
+        );
+        assert_eq!(SecretString::new("abcdefgh").expose_secret(), "abcdefgh");
+    }
+
+    #[test]
+    fn test_auth_mode() {
+        let oauth2 =
+            TweetyClient::from_oauth2_token(OAuth2Token::new("token", vec![Scope::TweetRead]));
+
+        assert_eq!(setup_client().auth_mode(), Some(AuthMode::OAuth1User));
+        assert_eq!(oauth2.auth_mode(), Some(AuthMode::OAuth2User));
+        assert_eq!(
+            TweetyClient::from_bearer_token("bearer").auth_mode(),
+            Some(AuthMode::AppOnly)
+        );
+        assert_eq!(TweetyClient::new("", "", "", "").auth_mode(), None);
+    }
+
+    #[tokio::test]
+    async fn test_auth_mode_override() {
+        let client = setup_client();
+
+        assert_eq!(client.as_user().auth_mode(), Some(AuthMode::OAuth1User));
+        assert_eq!(client.as_app_only().auth_mode(), None);
+        // The override applies to the returned handle only.
+        assert_eq!(client.auth_mode(), Some(AuthMode::OAuth1User));
+
+        let result = client.as_app_only().get_user_by_id("1", None).await;
+        assert!(matches!(result, Err(TweetyError::MissingCredentials)));
+    }
+
+    #[test]
+    fn test_on_behalf_of() {
+        let app = TweetyClient::from_bearer_token("bearer");
+        let alice = app.on_behalf_of(OAuth2Token::new("alice", vec![Scope::TweetRead]));
+
+        assert_eq!(alice.auth_mode(), Some(AuthMode::OAuth2User));
+        assert_eq!(alice.as_app_only().auth_mode(), Some(AuthMode::AppOnly));
+        assert_eq!(
+            alice.missing_scopes(&reqwest::Method::POST, "/2/tweets"),
+            vec![Scope::UsersRead, Scope::TweetWrite]
+        );
+        assert_eq!(app.auth_mode(), Some(AuthMode::AppOnly));
+
+        let bob = setup_client().on_behalf_of(UserTokens::oauth1("bob", "bob_secret"));
+        assert_eq!(bob.auth_mode(), Some(AuthMode::OAuth1User));
+        // OAuth 1.0a tokens need the client's consumer key to be signed.
+        assert_eq!(
+            app.on_behalf_of(UserTokens::oauth1("bob", "bob_secret"))
+                .auth_mode(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_credentials_without_credentials() {
+        let client = TweetyClient::new("", "", "", "");
+
+        assert!(matches!(
+            client.verify_credentials().await,
+            Err(TweetyError::MissingCredentials)
+        ));
+    }
+}
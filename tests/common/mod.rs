@@ -0,0 +1,220 @@
+//! A mock X API server shared by the tests.
+//!
+//! [`serve`] answers every request with what its handler returns and sends the request
+//! to a receiver, so a test can check what the client sent once the call returned.
+
+// Each test crate uses its own part of this module.
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::thread;
+
+/// A request as the server received it.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// The request line, e.g. `GET /2/users/me HTTP/1.1`.
+    pub line: String,
+    pub method: String,
+    /// The path and query, or the absolute URL for requests sent to a proxy.
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// The target without its query.
+    pub fn path(&self) -> &str {
+        self.target.split('?').next().unwrap_or_default()
+    }
+
+    /// The first value of header `name`, whatever its case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The request line and headers, lowercased, for checks that don't care about case.
+    pub fn head(&self) -> String {
+        let mut head = self.line.clone();
+        for (name, value) in &self.headers {
+            head.push_str(&format!("\r\n{}: {}", name, value));
+        }
+        head.to_lowercase()
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// The body as JSON, `null` when it isn't.
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).unwrap_or_default()
+    }
+}
+
+/// A response to send, JSON unless a `Content-Type` header says otherwise.
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    /// A response with `status`, e.g. `"429 Too Many Requests"`, and `body`.
+    pub fn new(status: &str, body: impl Into<Vec<u8>>) -> Self {
+        Response {
+            status: status.to_string(),
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: body.into(),
+        }
+    }
+
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Response::new("200 OK", body)
+    }
+
+    /// Sets header `name`, replacing the value it had.
+    pub fn header(mut self, name: &str, value: impl ToString) -> Self {
+        self.headers
+            .retain(|(header, _)| !header.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Writes the response, asking the client to close the connection unless
+    /// `keep_alive`.
+    pub fn write_to(&self, stream: &mut TcpStream, keep_alive: bool) -> std::io::Result<()> {
+        let mut head = format!("HTTP/1.1 {}\r\n", self.status);
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        if !keep_alive {
+            head.push_str("Connection: close\r\n");
+        }
+        head.push_str("\r\n");
+
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&self.body)?;
+        stream.flush()
+    }
+}
+
+/// A status and a body.
+impl From<(&str, &str)> for Response {
+    fn from((status, body): (&str, &str)) -> Self {
+        Response::new(status, body)
+    }
+}
+
+/// Reads the next request off `reader`, its body as long as `Content-Length` says or
+/// chunk by chunk. `None` once the connection is closed.
+pub fn read_request(reader: &mut impl BufRead) -> Option<Request> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).ok()? == 0 {
+        return None;
+    }
+    let line = line.trim_end().to_string();
+    let mut parts = line.split(' ');
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let mut request = Request {
+        line,
+        method,
+        target,
+        headers,
+        body: Vec::new(),
+    };
+    let chunked = request
+        .header("Transfer-Encoding")
+        .is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked"));
+    if chunked {
+        loop {
+            let mut size = String::new();
+            reader.read_line(&mut size).ok()?;
+            let size = usize::from_str_radix(size.trim(), 16).ok()?;
+            let mut chunk = vec![0; size + 2];
+            reader.read_exact(&mut chunk).ok()?;
+            if size == 0 {
+                break;
+            }
+            request.body.extend_from_slice(&chunk[..size]);
+        }
+    } else {
+        let length = request
+            .header("Content-Length")
+            .and_then(|length| length.parse().ok())
+            .unwrap_or(0);
+        request.body = vec![0; length];
+        reader.read_exact(&mut request.body).ok()?;
+    }
+    Some(request)
+}
+
+/// Answers every request with what `respond` returns for it, one connection at a time,
+/// and sends each request to the returned receiver before answering it. Returns the
+/// server's base URL.
+pub fn serve(
+    mut respond: impl FnMut(&Request) -> Response + Send + 'static,
+) -> (String, mpsc::Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let Ok(read_half) = stream.try_clone() else {
+                continue;
+            };
+            let Some(request) = read_request(&mut BufReader::new(read_half)) else {
+                continue;
+            };
+            let response = respond(&request);
+            let _ = sender.send(request);
+            let _ = response.write_to(&mut stream, false);
+        }
+    });
+
+    (base_url, receiver)
+}
+
+/// Answers every request with `status` and `body`.
+pub fn serve_status(status: &'static str, body: &'static str) -> (String, mpsc::Receiver<Request>) {
+    serve(move |_| Response::new(status, body))
+}
+
+/// Answers the requests with `responses` in order, repeating the last one once they
+/// run out.
+pub fn serve_in_order(responses: Vec<impl Into<Response>>) -> (String, mpsc::Receiver<Request>) {
+    let responses: Vec<Response> = responses.into_iter().map(Into::into).collect();
+    let mut responses = responses.into_iter();
+    let mut last = None;
+    serve(move |_| {
+        if let Some(next) = responses.next() {
+            last = Some(next);
+        }
+        last.clone().expect("no responses to serve")
+    })
+}
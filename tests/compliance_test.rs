@@ -0,0 +1,99 @@
+#![cfg(feature = "streams")]
+
+use serde_json::json;
+use tweety_rs::api::compliance::{LikeComplianceEvent, TweetComplianceEvent, UserComplianceEvent};
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::tier::Tier;
+use tweety_rs::TweetyClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tweet_compliance_events_deserialize() {
+        let delete = json!({
+            "delete": {
+                "tweet": { "id": "2", "author_id": "10" },
+                "event_at": "2024-01-01T00:00:00.000Z"
+            }
+        });
+        let edit = json!({
+            "tweet_edit": {
+                "tweet": { "id": "3", "author_id": "10" },
+                "initial_tweet_id": "1",
+                "edit_tweet_ids": ["1", "3"],
+                "event_at": "2024-01-01T00:00:00.000Z"
+            }
+        });
+
+        match serde_json::from_value(delete).unwrap() {
+            TweetComplianceEvent::Delete(delete) => assert_eq!(delete.tweet.id, "2"),
+            other => panic!("expected a delete, got {:?}", other),
+        }
+        match serde_json::from_value(edit).unwrap() {
+            TweetComplianceEvent::TweetEdit(edit) => assert_eq!(edit.edit_tweet_ids.len(), 2),
+            other => panic!("expected an edit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_user_compliance_events_deserialize() {
+        let protect = json!({
+            "user_protect": {
+                "user": { "id": "10" },
+                "event_at": "2024-01-01T00:00:00.000Z"
+            }
+        });
+        let scrub_geo = json!({
+            "scrub_geo": {
+                "user": { "id": "10" },
+                "up_to_tweet_id": "5",
+                "event_at": "2024-01-01T00:00:00.000Z"
+            }
+        });
+
+        match serde_json::from_value(protect).unwrap() {
+            UserComplianceEvent::UserProtect(protect) => assert_eq!(protect.user.id, "10"),
+            other => panic!("expected a protect, got {:?}", other),
+        }
+        match serde_json::from_value(scrub_geo).unwrap() {
+            UserComplianceEvent::ScrubGeo(scrub) => assert_eq!(scrub.up_to_tweet_id, "5"),
+            other => panic!("expected a scrub_geo, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_like_compliance_events_deserialize() {
+        let unlike = json!({
+            "delete": {
+                "favorite": { "id": "2", "user_id": "10" },
+                "event_at": "2024-01-01T00:00:00.000Z"
+            }
+        });
+
+        match serde_json::from_value(unlike).unwrap() {
+            LikeComplianceEvent::Unlike(unlike) => assert_eq!(unlike.favorite.user_id, "10"),
+            other => panic!("expected an unlike, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compliance_stream_checks_partition_and_tier() {
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url("http://127.0.0.1:1")
+            .with_tier(Tier::Pro);
+
+        let result = client.tweets_compliance_stream(5).await;
+        assert!(matches!(result, Err(TweetyError::ConfigError(_))));
+
+        let result = client.tweets_compliance_stream(1).await;
+        assert!(matches!(result, Err(TweetyError::TierLimit { .. })));
+
+        let result = client.users_compliance_stream(0).await;
+        assert!(matches!(result, Err(TweetyError::ConfigError(_))));
+
+        let result = client.likes_compliance_stream(4).await;
+        assert!(matches!(result, Err(TweetyError::TierLimit { .. })));
+    }
+}
@@ -0,0 +1,194 @@
+mod common;
+
+use common::{serve, Request, Response};
+use std::sync::mpsc;
+use tweety_rs::api::compose::{weighted_length, Composer, LinkPlacement, Template};
+use tweety_rs::TweetyClient;
+
+const LINK: &str = "https://crates.io/crates/tweety-rs";
+
+/// Answers every request with a created tweet whose id counts up from 1.
+fn server() -> (String, mpsc::Receiver<Request>) {
+    let mut id = 0;
+    serve(move |_| {
+        id += 1;
+        Response::new(
+            "201 Created",
+            format!(
+                r#"{{"data":{{"id":"{}","text":"posted","edit_history_tweet_ids":["{}"]}}}}"#,
+                id, id
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_parse_and_render() {
+        let template = Template::parse("{{{name}}} is {state}").unwrap();
+        assert_eq!(template.variables().collect::<Vec<_>>(), ["name", "state"]);
+        assert_eq!(template.to_string(), "{{{name}}} is {state}");
+
+        let composer = Composer::new().template("status", template);
+        let text = composer
+            .draft("status")
+            .unwrap()
+            .var("name", "api")
+            .var("state", "up")
+            .to_text()
+            .unwrap();
+        assert_eq!(text, "{api} is up");
+
+        let missing = composer.draft("status").unwrap().var("name", "api");
+        assert!(missing.to_text().is_err());
+        assert!(composer.draft("unknown").is_err());
+
+        for source in ["{name", "{}", "name}", "{a{b}}"] {
+            assert!(Template::parse(source).is_err(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_weighted_length() {
+        assert_eq!(weighted_length("Hello, world"), 12);
+        assert_eq!(weighted_length("café – “quoted”"), 15);
+        assert_eq!(weighted_length("日本語"), 6);
+        assert_eq!(weighted_length("👩‍👩‍👧‍👦"), 2);
+        assert_eq!(weighted_length("🇺🇦🇯🇵"), 4);
+        assert_eq!(weighted_length("1️⃣ 🏳️‍🌈"), 5);
+        assert_eq!(weighted_length(&format!("see {}", LINK)), 27);
+    }
+
+    #[test]
+    fn test_helpers_and_link_placement() {
+        let composer = Composer::new();
+        let draft = composer
+            .draft_text("Release day")
+            .mention("@rustlang")
+            .hashtag("#rust lang")
+            .hashtag("opensource")
+            .link(LINK);
+        assert_eq!(
+            draft.to_text().unwrap(),
+            format!("@rustlang Release day #rustlang #opensource {}", LINK)
+        );
+
+        let composer = Composer::new().link_placement(LinkPlacement::BeforeHashtags);
+        let draft = composer
+            .draft_text("Release day")
+            .hashtag("rust")
+            .link(LINK);
+        assert_eq!(
+            draft.to_text().unwrap(),
+            format!("Release day {} #rust", LINK)
+        );
+
+        let composer = Composer::new();
+        assert!(composer
+            .draft_text("hi")
+            .mention("not valid")
+            .to_text()
+            .is_err());
+        assert!(composer.draft_text("hi").hashtag("2024").to_text().is_err());
+    }
+
+    #[test]
+    fn test_to_text_drops_hashtags_then_cuts_the_text() {
+        // 32 for the text and link, 10 and 12 for the hashtags.
+        let composer = Composer::new().max_length(50);
+        let draft = composer
+            .draft_text("Out now:")
+            .hashtag("rustlang")
+            .hashtag("opensource")
+            .link(LINK);
+        assert_eq!(
+            draft.to_text().unwrap(),
+            format!("Out now: #rustlang {}", LINK)
+        );
+
+        let composer = Composer::new().max_length(60);
+        let long = "A rewrite of the scheduler, cross-posting and templates for tweets";
+        let draft = composer.draft_text(long).hashtag("rustlang").link(LINK);
+        let text = draft.to_text().unwrap();
+        assert_eq!(text, format!("A rewrite of the scheduler… {}", LINK));
+        assert!(weighted_length(&text) <= 60);
+
+        let crowded = Composer::new().max_length(30);
+        let draft = crowded.draft_text(long).mention("rustlang").link(LINK);
+        assert!(draft.to_text().is_err());
+    }
+
+    #[test]
+    fn test_to_thread_splits_at_sentences_and_numbers_tweets() {
+        let composer = Composer::new().max_length(50);
+        let thread = composer
+            .draft_text(
+                "Version 2 is out. It adds threads and templates to the crate, and much more besides.",
+            )
+            .mention("rustlang")
+            .hashtag("rust")
+            .link(LINK)
+            .to_thread()
+            .unwrap();
+
+        assert_eq!(
+            thread,
+            [
+                "@rustlang Version 2 is out. 1/4",
+                "It adds threads and templates to the crate, 2/4",
+                "and much more besides. 3/4",
+                "#rust https://crates.io/crates/tweety-rs 4/4",
+            ]
+        );
+        assert!(thread.iter().all(|tweet| weighted_length(tweet) <= 50));
+
+        let short = Composer::new()
+            .draft_text("Short and sweet")
+            .to_thread()
+            .unwrap();
+        assert_eq!(short, ["Short and sweet"]);
+
+        let reply = Composer::new()
+            .link_placement(LinkPlacement::Reply)
+            .numbered(false);
+        let thread = reply
+            .draft_text("Short and sweet")
+            .link(LINK)
+            .to_thread()
+            .unwrap();
+        assert_eq!(thread, ["Short and sweet", LINK]);
+    }
+
+    #[test]
+    fn test_cuts_never_split_emoji() {
+        let composer = Composer::new().max_length(9).numbered(false);
+        let families = "👨‍👩‍👧‍👦".repeat(6);
+
+        let text = composer.draft_text(&families).to_text().unwrap();
+        assert_eq!(text, format!("{}…", "👨‍👩‍👧‍👦".repeat(3)));
+
+        let thread = composer.draft_text(&families).to_thread().unwrap();
+        assert_eq!(thread, ["👨‍👩‍👧‍👦".repeat(4), "👨‍👩‍👧‍👦".repeat(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_post_thread_replies_to_the_tweet_before() {
+        let (base_url, requests) = server();
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+
+        let thread = ["one 1/2".to_string(), "two 2/2".to_string()];
+        let ids = client.post_thread(&thread).await.unwrap();
+        assert_eq!(ids, ["1", "2"]);
+
+        let first = requests.recv().unwrap().json();
+        assert_eq!(first["text"], "one 1/2");
+        assert!(first.get("reply").is_none());
+        let second = requests.recv().unwrap().json();
+        assert_eq!(second["text"], "two 2/2");
+        assert_eq!(second["reply"]["in_reply_to_tweet_id"], "1");
+    }
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "compression")]
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::json;
+mod common;
+
+use common::{serve, serve_status, Request, Response};
+use std::io::Write;
+use std::sync::mpsc;
+use tweety_rs::api::endpoint::Endpoint;
+use tweety_rs::TweetyClient;
+
+/// Answers every request with `body` gzipped.
+fn gzip_server(body: &str) -> (String, mpsc::Receiver<Request>) {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body.as_bytes()).unwrap();
+    let body = encoder.finish().unwrap();
+
+    serve(move |_| Response::ok(body.clone()).header("Content-Encoding", "gzip"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_large_bodies_are_gzipped() {
+        let (base_url, requests) = serve_status("200 OK", "{}");
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&base_url)
+            .with_compression_threshold(1024);
+        let text = "a".repeat(4000);
+
+        client
+            .send_request_as::<serde_json::Value>(
+                &Endpoint::CreateTweet,
+                &[],
+                Some(json!({ "text": text })),
+            )
+            .await
+            .unwrap();
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.header("Content-Encoding"), Some("gzip"));
+        assert_eq!(&request.body[..2], [0x1f, 0x8b]);
+        assert!(request.body.len() < 1024);
+    }
+
+    #[tokio::test]
+    async fn test_small_bodies_are_sent_as_is() {
+        let (base_url, requests) = serve_status("200 OK", "{}");
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&base_url)
+            .with_compression_threshold(1024);
+
+        client
+            .send_request_as::<serde_json::Value>(
+                &Endpoint::CreateTweet,
+                &[],
+                Some(json!({ "text": "hello" })),
+            )
+            .await
+            .unwrap();
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.header("Content-Encoding"), None);
+        assert_eq!(request.body, br#"{"text":"hello"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_compressed_responses_are_decoded() {
+        let (base_url, requests) = gzip_server(r#"{"data":{"id":"1","text":"hello"}}"#);
+        let client = TweetyClient::from_bearer_token("token").with_base_url(&base_url);
+
+        let value: serde_json::Value = client
+            .send_request_as(&Endpoint::Tweet("1".into()), &[], None)
+            .await
+            .unwrap();
+
+        assert_eq!(value["data"]["text"], "hello");
+        let request = requests.recv().unwrap();
+        assert_eq!(request.header("Accept-Encoding"), Some("gzip, br"));
+    }
+}
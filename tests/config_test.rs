@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::PathBuf;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::TweetyClient;
+
+fn write_config(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("tweety-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).expect("failed to write config file");
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_config_file_toml_profiles() {
+        let path = write_config(
+            "profiles.toml",
+            r#"
+                [default]
+                consumer_key = "consumer_key"
+                consumer_key_secret = "consumer_key_secret"
+                access_token = "access_token"
+                access_token_secret = "access_token_secret"
+
+                [reader]
+                bearer_token = "bearer_token"
+
+                [broken]
+                consumer_key = "consumer_key"
+            "#,
+        );
+
+        let client = TweetyClient::from_config_file(&path).expect("default profile");
+        assert!(client.is_initialized());
+
+        let reader = TweetyClient::from_config_profile(&path, "reader").expect("reader profile");
+        assert!(reader.is_initialized());
+
+        match TweetyClient::from_config_profile(&path, "broken") {
+            Err(TweetyError::ConfigError(message)) => {
+                assert!(message.contains("access_token_secret"), "{}", message)
+            }
+            other => panic!("expected a config error, got {:?}", other),
+        }
+
+        match TweetyClient::from_config_profile(&path, "missing") {
+            Err(TweetyError::ConfigError(message)) => {
+                assert!(message.contains("default, reader"), "{}", message)
+            }
+            other => panic!("expected a config error, got {:?}", other),
+        }
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_partly_filled_oauth1_profile_is_an_error() {
+        let path = write_config(
+            "partial.toml",
+            r#"
+                [default]
+                consumer_key = "consumer_key"
+                access_token = "access_token"
+                bearer_token = "bearer_token"
+            "#,
+        );
+
+        // The bearer token isn't used in place of the missing OAuth1 keys.
+        match TweetyClient::from_config_file(&path) {
+            Err(TweetyError::ConfigError(message)) => assert_eq!(
+                message,
+                "profile `default` is missing consumer_key_secret, access_token_secret"
+            ),
+            other => panic!("expected a config error, got {:?}", other),
+        }
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_from_config_file_json() {
+        let path = write_config(
+            "profiles.json",
+            r#"{ "default": { "bearer_token": "bearer_token" } }"#,
+        );
+
+        let client = TweetyClient::from_config_file(&path).expect("default profile");
+        assert!(client.is_initialized());
+
+        fs::remove_file(path).ok();
+    }
+}
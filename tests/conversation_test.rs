@@ -0,0 +1,57 @@
+use serde_json::json;
+use tweety_rs::api::conversation::Conversation;
+use tweety_rs::api::mentions::TweetData;
+
+fn tweet(id: &str, replied_to: Option<&str>) -> TweetData {
+    let mut value = json!({
+        "id": id,
+        "edit_history_tweet_ids": [id],
+        "text": format!("tweet {}", id),
+        "conversation_id": "100",
+    });
+    if let Some(parent) = replied_to {
+        value["referenced_tweets"] = json!([{ "type": "replied_to", "id": parent }]);
+    }
+    serde_json::from_value(value).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builds_reply_tree() {
+        let replies = vec![
+            tweet("1002", Some("100")),
+            tweet("999", Some("100")),
+            tweet("1003", Some("999")),
+            tweet("1004", Some("1003")),
+            tweet("1005", Some("555")),
+        ];
+
+        let conversation = Conversation::from_tweets(tweet("100", None), replies);
+        let root = &conversation.root;
+
+        assert_eq!(conversation.len(), 6);
+        assert_eq!(root.len(), 5);
+        assert_eq!(root.depth(), 3);
+        assert_eq!(root.replies[0].tweet.id, "999");
+        assert_eq!(root.replies[0].replies[0].replies[0].tweet.id, "1004");
+        assert_eq!(root.replies[1].tweet.id, "1002");
+        assert_eq!(conversation.orphans.len(), 1);
+        assert_eq!(conversation.orphans[0].tweet.id, "1005");
+
+        let mut visited = Vec::new();
+        root.walk(&mut |tweet, level| visited.push((tweet.id.as_str(), level)));
+        assert_eq!(
+            visited,
+            [
+                ("100", 0),
+                ("999", 1),
+                ("1003", 2),
+                ("1004", 3),
+                ("1002", 1)
+            ]
+        );
+    }
+}
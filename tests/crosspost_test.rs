@@ -0,0 +1,130 @@
+mod common;
+
+use common::serve_status;
+use futures_util::future::BoxFuture;
+use std::sync::{Arc, Mutex};
+use tweety_rs::api::crosspost::{CrossPost, CrossPosted, CrossPoster, Post};
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::TweetyClient;
+
+/// Records what it is asked to publish, and fails when `fail` is set.
+struct Recorder {
+    network: &'static str,
+    max_length: usize,
+    published: Arc<Mutex<Vec<String>>>,
+    fail: bool,
+}
+
+impl Recorder {
+    fn new(network: &'static str, max_length: usize) -> (Self, Arc<Mutex<Vec<String>>>) {
+        let published = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Recorder {
+            network,
+            max_length,
+            published: published.clone(),
+            fail: false,
+        };
+        (recorder, published)
+    }
+}
+
+impl CrossPoster for Recorder {
+    fn network(&self) -> &str {
+        self.network
+    }
+
+    fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    fn publish<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<CrossPosted, TweetyError>> {
+        Box::pin(async move {
+            if self.fail {
+                return Err(TweetyError::UnexpectedResponse("down".to_string()));
+            }
+            let mut published = self.published.lock().unwrap();
+            published.push(text.to_string());
+            Ok(CrossPosted {
+                id: published.len().to_string(),
+                url: None,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_cuts_at_a_word_and_keeps_the_link() {
+        let (short, _) = Recorder::new("short", 20);
+        let post = Post::new("Rust 1.81 is out today").link("https://blog.rust-lang.org");
+
+        // 22 characters of text, a space and 26 of link.
+        assert_eq!(
+            post.fit_to(&Recorder::new("roomy", 49).0),
+            "Rust 1.81 is out today https://blog.rust-lang.org"
+        );
+        assert_eq!(
+            post.fit_to(&Recorder::new("tight", 48).0),
+            "Rust 1.81 is out… https://blog.rust-lang.org"
+        );
+        // Nothing of the text fits next to the link.
+        assert_eq!(post.fit_to(&short), "https://blog.rust-lang.org");
+
+        let word = Post::new("Supercalifragilistic");
+        assert_eq!(word.fit_to(&short), "Supercalifragilistic");
+        assert_eq!(word.fit_to(&Recorder::new("shorter", 6).0), "Super…");
+    }
+
+    #[test]
+    fn test_x_counts_links_at_a_fixed_length() {
+        let client = TweetyClient::from_bearer_token("token");
+        let link = format!("https://example.com/{}", "a".repeat(300));
+        let post = Post::new("word ".repeat(60)).link(link.clone());
+
+        let tweet = post.fit_to(&client);
+        assert!(tweet.ends_with(&format!("word… {}", link)));
+        // 280, less the link's 23, the space before it and the ellipsis, which X
+        // counts twice.
+        assert_eq!(tweet.chars().count() - link.chars().count(), 256);
+        assert_eq!(client.length(&tweet), 280);
+    }
+
+    #[tokio::test]
+    async fn test_publish_fits_to_each_network_and_reports_each_result() {
+        let (long, long_published) = Recorder::new("long", 500);
+        let (short, short_published) = Recorder::new("short", 12);
+        let (mut failing, _) = Recorder::new("failing", 500);
+        failing.fail = true;
+        let cross_post = CrossPost::new().to(long).to(failing).to(short);
+
+        let results = cross_post.publish(&Post::new("Hello from tweety-rs")).await;
+        let networks: Vec<&str> = results
+            .iter()
+            .map(|(network, _)| network.as_str())
+            .collect();
+        assert_eq!(networks, ["long", "failing", "short"]);
+        assert_eq!(results[0].1.as_ref().unwrap().id, "1");
+        assert!(results[1].1.is_err());
+        assert!(results[2].1.is_ok());
+
+        assert_eq!(*long_published.lock().unwrap(), ["Hello from tweety-rs"]);
+        assert_eq!(*short_published.lock().unwrap(), ["Hello from…"]);
+    }
+
+    #[tokio::test]
+    async fn test_x_links_the_tweet_like_tweet_data() {
+        let (base_url, _requests) = serve_status(
+            "201 Created",
+            r#"{"data":{"id":"20","text":"hi","edit_history_tweet_ids":["20"]}}"#,
+        );
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+
+        let posted = client.publish("hi").await.unwrap();
+        assert_eq!(posted.id, "20");
+        assert_eq!(posted.url.as_deref(), Some("https://x.com/i/web/status/20"));
+    }
+}
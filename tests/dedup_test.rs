@@ -0,0 +1,65 @@
+use futures_util::{stream, StreamExt};
+use serde_json::{json, Value};
+use tweety_rs::api::dedup::{dedupe, SeenIds, TweetIdentity};
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::types::borrowed::{from_slice_borrowed, StreamTweetRef};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seen_ids_forget_the_oldest() {
+        let mut seen = SeenIds::new(3);
+        for id in ["1", "2", "3", "4"] {
+            assert!(seen.insert(id));
+        }
+
+        assert_eq!(seen.len(), 3);
+        assert!(!seen.contains("1"));
+        assert!(!seen.insert("4"));
+    }
+
+    #[test]
+    fn test_tweet_identity() {
+        let message = json!({ "data": { "id": "10", "text": "hi" } });
+        let tweet = json!({ "id": "11", "text": "hi" });
+        let line = br#"{"data":{"id":"12","text":"hi"},"matching_rules":[]}"#;
+        let borrowed: StreamTweetRef = from_slice_borrowed(line).unwrap();
+
+        assert_eq!(message.tweet_id(), Some("10"));
+        assert_eq!(tweet.tweet_id(), Some("11"));
+        assert_eq!(borrowed.tweet_id(), Some("12"));
+        assert_eq!(json!({ "errors": [] }).tweet_id(), None);
+    }
+
+    #[test]
+    fn test_retain_new_across_pages() {
+        let mut seen = SeenIds::default();
+        let mut first = vec![json!({ "id": "1" }), json!({ "id": "2" })];
+        let mut second = vec![json!({ "id": "2" }), json!({ "id": "3" })];
+
+        seen.retain_new(&mut first);
+        seen.retain_new(&mut second);
+
+        assert_eq!(first.len(), 2);
+        assert_eq!(second, vec![json!({ "id": "3" })]);
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_stream_keeps_errors() {
+        let items: Vec<Result<Value, TweetyError>> = vec![
+            Ok(json!({ "id": "1" })),
+            Ok(json!({ "id": "1" })),
+            Err(TweetyError::AuthError),
+            Ok(json!({ "id": "2" })),
+        ];
+
+        let out: Vec<_> = dedupe(stream::iter(items), SeenIds::default())
+            .collect()
+            .await;
+
+        assert_eq!(out.len(), 3);
+        assert!(matches!(out[1], Err(TweetyError::AuthError)));
+    }
+}
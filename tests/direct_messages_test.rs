@@ -0,0 +1,139 @@
+#![cfg(feature = "dm")]
+
+mod common;
+
+use common::{serve_in_order, Response};
+use futures_util::StreamExt;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tweety_rs::api::direct_messages::{DmEventsResponse, EventType, QueryParams};
+use tweety_rs::api::endpoint::RateLimitFamily;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::observer::ClientEvent;
+use tweety_rs::TweetyClient;
+
+/// Serves two pages of one event each, reporting the rate-limit window used up until
+/// `reset`.
+fn exhausted_server(reset: u64) -> String {
+    let pages = [
+        r#"{"data":[{"id":"2","event_type":"MessageCreate","text":"b"}],"meta":{"result_count":1,"next_token":"abc"}}"#,
+        r#"{"data":[{"id":"1","event_type":"MessageCreate","text":"a"}],"meta":{"result_count":1}}"#,
+    ];
+    let responses = pages
+        .into_iter()
+        .map(|page| {
+            Response::ok(page)
+                .header("x-rate-limit-limit", 300)
+                .header("x-rate-limit-remaining", 0)
+                .header("x-rate-limit-reset", reset)
+        })
+        .collect();
+    serve_in_order(responses).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dm_events_page_deserializes() {
+        let page = json!({
+            "data": [
+                {
+                    "id": "2",
+                    "event_type": "MessageCreate",
+                    "text": "hello",
+                    "sender_id": "10",
+                    "dm_conversation_id": "10-20"
+                },
+                {
+                    "id": "1",
+                    "event_type": "ParticipantsJoin",
+                    "participant_ids": ["30"]
+                }
+            ],
+            "meta": { "result_count": 2, "next_token": "abc" }
+        });
+
+        let page: DmEventsResponse = serde_json::from_value(page).unwrap();
+
+        assert_eq!(page.data.len(), 2);
+        assert_eq!(page.data[0].event_type, EventType::MessageCreate);
+        assert_eq!(page.data[1].participant_ids, Some(vec!["30".to_string()]));
+        assert_eq!(page.meta.next_token.as_deref(), Some("abc"));
+    }
+
+    #[tokio::test]
+    async fn test_dm_events_stream_ends_after_an_error() {
+        let client = TweetyClient::from_bearer_token("token").with_base_url("http://127.0.0.1:1");
+
+        let events: Vec<_> = client
+            .paginate_dm_events_with_participant("20", QueryParams::default())
+            .events()
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Err(TweetyError::NetworkError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_paginator_waits_for_exhausted_window() {
+        let reset = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 2;
+        let waits = Arc::new(Mutex::new(Vec::new()));
+        let observed = waits.clone();
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&exhausted_server(reset))
+            .with_observer(move |event: &ClientEvent| {
+                if matches!(event, ClientEvent::RateLimitWait { .. }) {
+                    observed.lock().unwrap().push(event.clone());
+                }
+            });
+
+        let started = Instant::now();
+        let events: Vec<_> = client
+            .paginate_direct_messages(QueryParams::default())
+            .events()
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 2);
+        assert!(started.elapsed() >= Duration::from_millis(900));
+        let waits = waits.lock().unwrap();
+        assert_eq!(waits.len(), 1);
+        assert!(matches!(
+            waits[0],
+            ClientEvent::RateLimitWait {
+                family: RateLimitFamily::DirectMessages,
+                remaining,
+            } if remaining <= Duration::from_secs(2)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_paginator_without_pacing() {
+        let reset = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&exhausted_server(reset));
+
+        let started = Instant::now();
+        let events: Vec<_> = client
+            .paginate_direct_messages(QueryParams::default())
+            .pacing(false)
+            .events()
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 2);
+        assert!(started.elapsed() < Duration::from_secs(30));
+    }
+}
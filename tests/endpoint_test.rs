@@ -0,0 +1,73 @@
+use reqwest::Method;
+use tweety_rs::api::client::AuthMode;
+use tweety_rs::api::endpoint::{Endpoint, EndpointAuth, Host, RateLimitFamily};
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::oauth2::Scope;
+use tweety_rs::TweetyClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_paths() {
+        let unfollow = Endpoint::Unfollow {
+            user_id: "1".into(),
+            target_user_id: "2".into(),
+        };
+
+        assert_eq!(unfollow.method(), Method::DELETE);
+        assert_eq!(
+            unfollow.path_template(),
+            "/2/users/:source_user_id/following/:target_user_id"
+        );
+        assert_eq!(unfollow.path(), "/2/users/1/following/2");
+        assert_eq!(Endpoint::Tweets.url(), "https://api.x.com/2/tweets");
+        assert_eq!(
+            Endpoint::MediaUploadStatus.url(),
+            "https://upload.twitter.com/1.1/media/upload.json"
+        );
+        assert_eq!(Endpoint::GeoSearch.host(), Host::Api);
+        assert_eq!(
+            Endpoint::AdCampaigns("18ce54d4x5t".to_string()).url(),
+            "https://ads-api.twitter.com/12/accounts/18ce54d4x5t/campaigns"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_auth_and_rate_limits() {
+        assert_eq!(Endpoint::Me.required_auth(), EndpointAuth::UserContext);
+        assert!(!Endpoint::Me.required_auth().allows(AuthMode::AppOnly));
+        assert!(Endpoint::RecentSearch
+            .required_auth()
+            .allows(AuthMode::AppOnly));
+        assert!(!Endpoint::FullArchiveSearch
+            .required_auth()
+            .allows(AuthMode::OAuth1User));
+
+        assert_eq!(
+            Endpoint::Like("1".into()).rate_limit_family(),
+            Endpoint::LikingUsers("2".into()).rate_limit_family()
+        );
+        assert_eq!(
+            Endpoint::CreateTweet.rate_limit_family(),
+            RateLimitFamily::ManageTweets
+        );
+        assert_eq!(
+            Endpoint::UserTweets("1".into()).rate_limit_family(),
+            RateLimitFamily::Timelines
+        );
+        assert!(Endpoint::Bookmarks("1".into())
+            .scopes()
+            .contains(&Scope::BookmarkRead));
+    }
+
+    #[tokio::test]
+    async fn test_base_url_override_is_used() {
+        let client = TweetyClient::from_bearer_token("token").with_base_url("http://127.0.0.1:1/");
+
+        let result = client.get_user_by_id("1", None).await;
+
+        assert!(matches!(result, Err(TweetyError::NetworkError(_))));
+    }
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::serve_status;
+use reqwest::StatusCode;
+use std::error::Error;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::retry::{DefaultRetryClassifier, RetryClassifier, RetryDecision};
+use tweety_rs::TweetyClient;
+
+/// Answers every request with a 403 and `body`.
+fn forbidden_server(body: &'static str) -> String {
+    serve_status("403 Forbidden", body).0
+}
+
+fn client(base_url: &str) -> TweetyClient {
+    TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_error_keeps_source() {
+        let parse_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let message = parse_error.to_string();
+        let err = TweetyError::from(parse_error);
+
+        assert!(matches!(err, TweetyError::JsonParseError(_)));
+        assert_eq!(err.source().unwrap().to_string(), message);
+        assert_eq!(err.status(), None);
+    }
+
+    #[test]
+    fn test_api_error_reports_status() {
+        let err = TweetyError::ApiError {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            body: r#"{"title":"Too Many Requests"}"#.to_string(),
+        };
+
+        assert_eq!(err.status(), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(
+            err.to_string(),
+            r#"API responded with HTTP 429 Too Many Requests: {"title":"Too Many Requests"}"#
+        );
+    }
+
+    #[test]
+    fn test_non_json_response_reports_status() {
+        let err = TweetyError::NonJsonResponse {
+            status: StatusCode::BAD_GATEWAY,
+            body: "<html>Bad Gateway</html>".to_string(),
+        };
+
+        assert_eq!(err.status(), Some(StatusCode::BAD_GATEWAY));
+        assert!(err.to_string().ends_with("<html>Bad Gateway</html>"));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_tweet_is_typed() {
+        let base_url = forbidden_server(
+            r#"{"detail":"You are not allowed to create a Tweet with duplicate content.","type":"about:blank","title":"Forbidden","status":403}"#,
+        );
+
+        let err = client(&base_url)
+            .post_tweet("hello", None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            TweetyError::DuplicateTweet { ref detail } if detail.contains("duplicate content")
+        ));
+        assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+        assert_eq!(
+            DefaultRetryClassifier.classify(&err),
+            RetryDecision::Permanent
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_status_code_is_typed() {
+        let base_url =
+            forbidden_server(r#"{"errors":[{"code":187,"message":"Status is a duplicate."}]}"#);
+
+        let err = client(&base_url)
+            .post_tweet("hello", None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Duplicate tweet: Status is a duplicate.");
+    }
+
+    #[tokio::test]
+    async fn test_other_forbidden_responses_stay_api_errors() {
+        let base_url = forbidden_server(
+            r#"{"detail":"You are not permitted to perform this action.","title":"Forbidden","status":403}"#,
+        );
+
+        let err = client(&base_url)
+            .post_tweet("hello", None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, TweetyError::ApiError { .. }));
+    }
+}
@@ -0,0 +1,193 @@
+#![cfg(feature = "feed")]
+
+mod common;
+
+use common::{serve, Request, Response};
+use std::sync::mpsc;
+use tweety_rs::api::feed::{parse_feed, FeedBridge};
+use tweety_rs::api::state::FileStateStore;
+use tweety_rs::TweetyClient;
+
+const CREATED: &str = r#"{"data":{"id":"1445880548472328192","text":"posted","edit_history_tweet_ids":["1445880548472328192"]}}"#;
+
+const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Rust Blog</title>
+    <link>https://blog.rust-lang.org/</link>
+    <item>
+      <title>Announcing Rust 1.81.0</title>
+      <link>https://blog.rust-lang.org/2024/09/05/Rust-1.81.0.html</link>
+      <guid isPermaLink="false">rust-1.81.0</guid>
+      <pubDate>Thu, 05 Sep 2024 00:00:00 +0000</pubDate>
+      <description><![CDATA[<p>The Rust team is happy to announce <b>1.81.0</b>.</p><p>Enjoy!</p>]]></description>
+    </item>
+    <item>
+      <title>Rust 1.80 &amp; LazyCell &#8212; 1 &lt; 2</title>
+      <link>https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html</link>
+      <description>&lt;p&gt;Lazy types&lt;/p&gt;</description>
+    </item>
+  </channel>
+</rss>"#;
+
+const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>This Week in Rust</title>
+  <link href="https://this-week-in-rust.org/" rel="self"/>
+  <entry>
+    <title type="html">This Week in Rust 563</title>
+    <link rel="replies" href="https://this-week-in-rust.org/563#comments"/>
+    <link href="https://this-week-in-rust.org/blog/2024/09/04/this-week-in-rust-563/" rel="alternate"/>
+    <id>tag:this-week-in-rust.org,2024-09-04:/blog/563/</id>
+    <updated>2024-09-04T00:00:00Z</updated>
+    <summary>Hello and welcome to another issue!</summary>
+  </entry>
+</feed>"#;
+
+/// Serves `feeds` in order at every `GET` and a created tweet at every `POST`.
+fn server(feeds: Vec<&'static str>) -> (String, mpsc::Receiver<Request>) {
+    let mut feeds = feeds.into_iter();
+    serve(move |request| match request.method.as_str() {
+        "GET" => Response::ok(feeds.next().unwrap_or_default())
+            .header("Content-Type", "application/rss+xml"),
+        _ => Response::ok(CREATED),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss() {
+        let entries = parse_feed(RSS).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0].id, "rust-1.81.0");
+        assert_eq!(entries[0].title, "Announcing Rust 1.81.0");
+        assert_eq!(
+            entries[0].link.as_deref(),
+            Some("https://blog.rust-lang.org/2024/09/05/Rust-1.81.0.html")
+        );
+        assert_eq!(
+            entries[0].summary.as_deref(),
+            Some("The Rust team is happy to announce 1.81.0. Enjoy!")
+        );
+        assert_eq!(
+            entries[0].published.as_deref(),
+            Some("Thu, 05 Sep 2024 00:00:00 +0000")
+        );
+
+        assert_eq!(entries[1].title, "Rust 1.80 & LazyCell \u{2014} 1 < 2");
+        assert_eq!(
+            entries[1].id,
+            "https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html"
+        );
+        assert_eq!(entries[1].summary.as_deref(), Some("Lazy types"));
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let entries = parse_feed(ATOM).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].id,
+            "tag:this-week-in-rust.org,2024-09-04:/blog/563/"
+        );
+        assert_eq!(entries[0].title, "This Week in Rust 563");
+        assert_eq!(
+            entries[0].link.as_deref(),
+            Some("https://this-week-in-rust.org/blog/2024/09/04/this-week-in-rust-563/")
+        );
+        assert_eq!(
+            entries[0].published.as_deref(),
+            Some("2024-09-04T00:00:00Z")
+        );
+
+        assert!(parse_feed("<html><body>Not found</body></html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_namespaced_elements() {
+        let rss = r#"<rss version="2.0"
+            xmlns:atom="http://www.w3.org/2005/Atom"
+            xmlns:content="http://purl.org/rss/1.0/modules/content/"
+            xmlns:dc="http://purl.org/dc/elements/1.1/">
+          <channel>
+            <atom:link href="https://example.com/feed.xml" rel="self"/>
+            <item>
+              <title>Release notes</title>
+              <atom:link rel="self" href="https://example.com/notes.xml"/>
+              <atom:link href="https://example.com/notes?from=1>0" rel="alternate"/>
+              <content:encoded><![CDATA[<p>Full <em>notes</em></p>]]></content:encoded>
+              <dc:date>2024-09-05T00:00:00Z</dc:date>
+            </item>
+          </channel>
+        </rss>"#;
+        let entries = parse_feed(rss).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].link.as_deref(),
+            Some("https://example.com/notes?from=1>0")
+        );
+        assert_eq!(entries[0].id, "https://example.com/notes?from=1>0");
+        assert_eq!(entries[0].summary.as_deref(), Some("Full notes"));
+        assert_eq!(
+            entries[0].published.as_deref(),
+            Some("2024-09-05T00:00:00Z")
+        );
+
+        let rdf = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+            xmlns="http://purl.org/rss/1.0/">
+          <channel rdf:about="https://example.com/"><title>Example</title></channel>
+          <item rdf:about="https://example.com/1">
+            <title>First &gt; second</title>
+            <link>https://example.com/1</link>
+          </item>
+        </rdf:RDF>"#;
+        let entries = parse_feed(rdf).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].title, "First > second");
+        assert_eq!(entries[0].link.as_deref(), Some("https://example.com/1"));
+    }
+
+    #[tokio::test]
+    async fn test_bridge_posts_new_entries_once() {
+        let older = r#"<rss version="2.0"><channel>
+            <item><title>Rust 1.80</title><link>https://example.com/1.80</link></item>
+        </channel></rss>"#;
+        let newer = r#"<rss version="2.0"><channel>
+            <item><title>Rust 1.82</title><link>https://example.com/1.82</link></item>
+            <item><title>Rust 1.81</title><link>https://example.com/1.81</link></item>
+            <item><title>Rust 1.80</title><link>https://example.com/1.80</link></item>
+        </channel></rss>"#;
+        let (base_url, requests) = server(vec![older, newer, newer]);
+        let path = std::env::temp_dir().join(format!("tweety-feed-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let bridge = |base_url: &str| {
+            let client =
+                TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url);
+            FeedBridge::new(client, &format!("{}/feed.xml", base_url))
+                .state_store(FileStateStore::new(&path), "feed")
+                .format(|entry| Some(format!("New: {}", entry.title)))
+        };
+
+        // The first poll only records what is already in the feed.
+        let mut first = bridge(&base_url);
+        assert_eq!(first.poll_once().await.unwrap(), 0);
+        assert_eq!(requests.recv().unwrap().line, "GET /feed.xml HTTP/1.1");
+
+        assert_eq!(first.poll_once().await.unwrap(), 2);
+        requests.recv().unwrap();
+        for text in ["New: Rust 1.81", "New: Rust 1.82"] {
+            let request = requests.recv().unwrap();
+            assert_eq!(request.line, "POST /2/tweets HTTP/1.1");
+            assert_eq!(request.json()["text"], text);
+        }
+
+        let mut restarted = bridge(&base_url);
+        assert_eq!(restarted.poll_once().await.unwrap(), 0);
+        requests.recv().unwrap();
+        assert!(requests.try_recv().is_err());
+    }
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "streams")]
+
+use tweety_rs::api::endpoint::{Endpoint, EndpointAuth};
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::filtered_stream::FilteredStream;
+use tweety_rs::api::tier::Tier;
+use tweety_rs::TweetyClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filtered_stream_endpoint() {
+        assert_eq!(
+            Endpoint::FilteredStream.url(),
+            "https://api.x.com/2/tweets/search/stream"
+        );
+        assert_eq!(
+            Endpoint::FilteredStream.required_auth(),
+            EndpointAuth::AppOnly
+        );
+        assert!(!Tier::Basic.allows(&Endpoint::FilteredStream));
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_on_tier_limit() {
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url("http://127.0.0.1:1")
+            .with_tier(Tier::Basic);
+        let mut stream = FilteredStream::new(&client).backfill_minutes(5);
+
+        let result = stream.run(|_| {}).await;
+
+        assert!(matches!(result, Err(TweetyError::TierLimit { .. })));
+        assert_eq!(stream.last_tweet_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_resumed_stream_gap_fills_first() {
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url("http://127.0.0.1:1")
+            .with_tier(Tier::Basic);
+        let mut stream = FilteredStream::new(&client)
+            .gap_fill_query(&"a".repeat(600))
+            .since_id("1800000000000000000");
+
+        // The gap-fill search is checked against the tier before the stream is opened.
+        let result = stream.connect(|_| {}).await;
+
+        match result {
+            Err(TweetyError::TierLimit { reason, .. }) => assert!(reason.contains("query")),
+            other => panic!("expected a tier limit, got {:?}", other),
+        }
+        assert_eq!(stream.last_tweet_id(), Some("1800000000000000000"));
+    }
+}
@@ -0,0 +1,66 @@
+use std::env;
+use tweety_rs::api::client::{
+    ACCESS_TOKEN_ENV, ACCESS_TOKEN_SECRET_ENV, BEARER_TOKEN_ENV, CONSUMER_KEY_ENV,
+    CONSUMER_KEY_SECRET_ENV,
+};
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::TweetyClient;
+
+const OAUTH1_VARS: [&str; 4] = [
+    CONSUMER_KEY_ENV,
+    ACCESS_TOKEN_ENV,
+    CONSUMER_KEY_SECRET_ENV,
+    ACCESS_TOKEN_SECRET_ENV,
+];
+
+/// Empty values count as missing and are never overridden by a `.env` file.
+fn clear_vars() {
+    for name in OAUTH1_VARS.iter().chain([BEARER_TOKEN_ENV].iter()) {
+        env::set_var(name, "");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Environment variables are process-wide, so every case runs in one test.
+    #[test]
+    fn test_from_env() {
+        clear_vars();
+        match TweetyClient::from_env() {
+            Err(TweetyError::MissingEnvironmentVariables(missing)) => {
+                assert_eq!(missing.len(), 5, "{:?}", missing);
+                assert!(missing.iter().any(|name| name == CONSUMER_KEY_ENV));
+                assert!(missing
+                    .iter()
+                    .any(|name| name.starts_with(BEARER_TOKEN_ENV)));
+            }
+            other => panic!("expected missing variables, got {:?}", other),
+        }
+
+        // Partly configured OAuth 1.0a is an error, even with a bearer token to fall
+        // back to.
+        env::set_var(CONSUMER_KEY_ENV, "consumer_key");
+        env::set_var(ACCESS_TOKEN_ENV, "access_token");
+        env::set_var(BEARER_TOKEN_ENV, "bearer_token");
+        match TweetyClient::from_env() {
+            Err(TweetyError::MissingEnvironmentVariables(missing)) => {
+                assert_eq!(missing, [CONSUMER_KEY_SECRET_ENV, ACCESS_TOKEN_SECRET_ENV]);
+            }
+            other => panic!("expected missing variables, got {:?}", other),
+        }
+
+        env::set_var(CONSUMER_KEY_ENV, "");
+        env::set_var(ACCESS_TOKEN_ENV, "");
+        let client = TweetyClient::from_env().expect("bearer token is set");
+        assert!(client.is_initialized());
+
+        clear_vars();
+        for name in OAUTH1_VARS {
+            env::set_var(name, "value");
+        }
+        let client = TweetyClient::from_env().expect("OAuth1 credentials are set");
+        assert!(client.is_initialized());
+    }
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "v1")]
+
+mod common;
+
+use common::serve_status;
+use tweety_rs::types::tweet::PostTweetParams;
+use tweety_rs::TweetyClient;
+
+const PLACES: &str = r#"{"result":{"places":[{"id":"5a110d312052166f","name":"San Francisco","full_name":"San Francisco, CA","place_type":"city","country":"United States","country_code":"US","contained_within":[{"id":"fbd6d2f5a4e4a15e","name":"California","full_name":"California, USA","place_type":"admin"}]}]},"query":{"type":"search"}}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_search_places_near_coordinates() {
+        let (base_url, requests) = serve_status("200 OK", PLACES);
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+
+        let places = client
+            .v1()
+            .search_places("San Francisco", Some((37.7821, -122.4093)))
+            .await
+            .unwrap();
+
+        assert_eq!(places.len(), 1);
+        assert_eq!(places[0].contained_within[0].place_type, "admin");
+        assert!(requests.recv().unwrap().line.starts_with(
+            "GET /1.1/geo/search.json?query=San+Francisco&lat=37.7821&long=-122.4093 "
+        ));
+
+        let params = PostTweetParams::builder().place_id(&places[0].id).build();
+        assert_eq!(
+            params.to_json("hello")["geo"]["place_id"],
+            "5a110d312052166f"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_places_by_name_only() {
+        let (base_url, requests) = serve_status("200 OK", r#"{"result":{"places":[]}}"#);
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+
+        let places = client.v1().search_places("Nowhere", None).await.unwrap();
+
+        assert!(places.is_empty());
+        assert!(requests
+            .recv()
+            .unwrap()
+            .line
+            .starts_with("GET /1.1/geo/search.json?query=Nowhere "));
+    }
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::{serve, Request, Response};
+use serde_json::json;
+use std::sync::mpsc;
+use tweety_rs::api::conversation::Conversation;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::hide_replies::ModerationPolicy;
+use tweety_rs::api::mentions::TweetData;
+use tweety_rs::TweetyClient;
+
+fn tweet_json(id: &str, author: &str, text: &str, replied_to: Option<&str>) -> serde_json::Value {
+    let mut value = json!({
+        "id": id,
+        "edit_history_tweet_ids": [id],
+        "text": text,
+        "author_id": author,
+        "conversation_id": "100",
+    });
+    if let Some(parent) = replied_to {
+        value["referenced_tweets"] = json!([{ "type": "replied_to", "id": parent }]);
+    }
+    value
+}
+
+fn tweet(id: &str, author: &str, text: &str, replied_to: Option<&str>) -> TweetData {
+    serde_json::from_value(tweet_json(id, author, text, replied_to)).unwrap()
+}
+
+fn replies() -> Vec<serde_json::Value> {
+    vec![
+        tweet_json("104", "7", "Free GIVEAWAY, dm me", Some("100")),
+        tweet_json("101", "2", "great thread", Some("100")),
+        tweet_json("102", "1", "thanks!", Some("101")),
+        tweet_json("103", "9", "spam spam", Some("101")),
+    ]
+}
+
+/// Serves conversation 100 by user 1, and answers the first hide with 200 and the rest
+/// with 429.
+fn conversation_server() -> (String, mpsc::Receiver<Request>) {
+    let mut hides = 0;
+    serve(move |request| {
+        if request.line.starts_with("GET /2/tweets/100?") {
+            let root = tweet_json("100", "1", "my post", None);
+            Response::ok(json!({ "data": root }).to_string())
+        } else if request.line.starts_with("GET /2/tweets/search/recent?") {
+            Response::ok(json!({ "data": replies(), "meta": { "result_count": 4 } }).to_string())
+        } else if request.method == "PUT" && hides == 0 {
+            hides += 1;
+            Response::ok(r#"{"data":{"hidden":true}}"#)
+        } else {
+            Response::new("429 Too Many Requests", r#"{"title":"Too Many Requests"}"#)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_selects_matching_replies() {
+        let conversation = Conversation::from_tweets(
+            tweet("100", "1", "my post", None),
+            vec![
+                tweet("104", "7", "Free GIVEAWAY, dm me", Some("100")),
+                tweet("101", "2", "great thread", Some("100")),
+                tweet("102", "1", "giveaway rules", Some("101")),
+                tweet("103", "9", "spam spam", Some("101")),
+                tweet("105", "2", "https://a https://b https://c", Some("999")),
+            ],
+        );
+        let policy = ModerationPolicy::new()
+            .hide_words(["giveaway"])
+            .hide_authors(["9"])
+            .hide_if(|reply| reply.text.matches("https://").count() > 2);
+
+        let selected: Vec<&str> = policy
+            .select(&conversation)
+            .into_iter()
+            .map(|reply| reply.id.as_str())
+            .collect();
+
+        // 102 is by the author and kept despite matching.
+        assert_eq!(selected, ["103", "104", "105"]);
+        assert!(ModerationPolicy::new().select(&conversation).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_moderate_conversation_hides_until_error() {
+        let (base_url, requests) = conversation_server();
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+        let policy = ModerationPolicy::new()
+            .hide_words(["giveaway"])
+            .hide_authors(["9"]);
+
+        let report = client.moderate_conversation("100", &policy).await.unwrap();
+
+        assert_eq!(report.replies, 4);
+        assert_eq!(report.matched, ["103", "104"]);
+        assert_eq!(report.hidden, ["103"]);
+        assert!(matches!(
+            report.error,
+            Some(TweetyError::RateLimited { .. })
+        ));
+        assert_eq!(report.suggested_reply_settings, Some("following"));
+
+        let lines: Vec<String> = requests.try_iter().map(|request| request.line).collect();
+        assert!(lines.contains(&"PUT /2/tweets/103/hidden HTTP/1.1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_hides_nothing() {
+        let (base_url, requests) = conversation_server();
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+        let policy = ModerationPolicy::new().hide_authors(["7"]).dry_run(true);
+
+        let report = client.moderate_conversation("100", &policy).await.unwrap();
+
+        assert_eq!(report.matched, ["104"]);
+        assert!(report.hidden.is_empty());
+        assert!(report.error.is_none());
+        assert_eq!(report.suggested_reply_settings, Some("following"));
+        assert!(requests.try_iter().all(|request| request.method != "PUT"));
+    }
+}
@@ -0,0 +1,264 @@
+mod common;
+
+use common::{read_request, serve_status, Response};
+use std::io::BufReader;
+#[cfg(feature = "socks")]
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "socks")]
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use tweety_rs::api::client::UserTokens;
+use tweety_rs::api::endpoint::Endpoint;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::http::{
+    HttpConfig, Name, Proxy, Resolve, Resolving, DEFAULT_POOL_IDLE_TIMEOUT,
+};
+use tweety_rs::TweetyClient;
+
+const EMPTY: &str = r#"{"data":[]}"#;
+
+/// Answers every request with `{"data":[]}`.
+fn server() -> String {
+    serve_status("200 OK", EMPTY).0
+}
+
+/// Answers every request with `{"data":[]}`, keeping connections open, and counts the
+/// connections accepted.
+fn keep_alive_server() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+    let accepted = connections.clone();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            accepted.fetch_add(1, Ordering::SeqCst);
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            thread::spawn(move || {
+                while read_request(&mut reader).is_some() {
+                    if Response::ok(EMPTY).write_to(&mut stream, true).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    (base_url, connections)
+}
+
+/// A SOCKS5 proxy requiring a username and password that forwards one connection to
+/// `target`, and sends the username it was given to the returned receiver.
+#[cfg(feature = "socks")]
+fn socks_server(target: SocketAddr) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let proxy_url = format!("socks5h://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut client, _) = listener.accept().unwrap();
+        let mut buf = [0; 512];
+
+        // Greeting: pick username/password authentication.
+        let _ = client.read(&mut buf).unwrap();
+        client.write_all(&[5, 2]).unwrap();
+
+        // RFC 1929 sub-negotiation: version, username, password.
+        let read = client.read(&mut buf).unwrap();
+        let username_len = buf[1] as usize;
+        let username = String::from_utf8_lossy(&buf[2..2 + username_len]).to_string();
+        assert!(read > 2 + username_len);
+        let _ = sender.send(username);
+        client.write_all(&[1, 0]).unwrap();
+
+        // CONNECT: whatever was asked for, connect to `target`.
+        let _ = client.read(&mut buf).unwrap();
+        client.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+
+        let mut upstream = std::net::TcpStream::connect(target).unwrap();
+        let (mut from_client, mut to_upstream) =
+            (client.try_clone().unwrap(), upstream.try_clone().unwrap());
+        thread::spawn(move || std::io::copy(&mut from_client, &mut to_upstream));
+        let _ = std::io::copy(&mut upstream, &mut client);
+    });
+
+    (proxy_url, receiver)
+}
+
+/// Resolves every host to `addr`, counting lookups.
+struct FixedResolver {
+    addr: SocketAddr,
+    lookups: Arc<AtomicUsize>,
+}
+
+impl Resolve for FixedResolver {
+    fn resolve(&self, _: Name) -> Resolving {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        let addrs: Box<dyn Iterator<Item = SocketAddr> + Send> =
+            Box::new(std::iter::once(self.addr));
+        Box::pin(async move { Ok(addrs) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_differ_from_defaults() {
+        assert_eq!(HttpConfig::default(), HttpConfig::new());
+        assert_ne!(HttpConfig::streaming(), HttpConfig::new());
+        assert_ne!(HttpConfig::bursty(), HttpConfig::new());
+        assert_eq!(
+            HttpConfig::new().pool_idle_timeout(Some(DEFAULT_POOL_IDLE_TIMEOUT)),
+            HttpConfig::new()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_configured_client_sends_requests() {
+        let base_url = server();
+        let config = HttpConfig::streaming()
+            .pool_idle_timeout(None)
+            .pool_max_idle_per_host(2)
+            .tcp_keepalive(Some(Duration::from_secs(10)));
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url(&base_url)
+            .with_http_config(config.clone());
+
+        assert_eq!(client.http_config(), &config);
+        let value: serde_json::Value = client
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1")], None)
+            .await
+            .unwrap();
+        assert_eq!(value["data"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_clones_and_handles_reuse_one_connection() {
+        let (base_url, connections) = keep_alive_server();
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+        let clone = client.clone();
+        let alice = client.on_behalf_of(UserTokens::oauth1("alice", "alice_secret"));
+
+        for handle in [&client, &clone, &alice, &client] {
+            let value: serde_json::Value = handle
+                .send_request_as(&Endpoint::Tweets, &[("ids", "1")], None)
+                .await
+                .unwrap();
+            assert_eq!(value["data"], serde_json::json!([]));
+        }
+        client.lookup_tweets(vec!["1".into()]).await.unwrap();
+
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_overrides_host() {
+        let base_url = server();
+        let addr: SocketAddr = base_url.trim_start_matches("http://").parse().unwrap();
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url(&format!("http://api.x.test:{}", addr.port()))
+            .with_http_config(HttpConfig::new().resolve("api.x.test", addr));
+
+        let value: serde_json::Value = client
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1")], None)
+            .await
+            .unwrap();
+        assert_eq!(value["data"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_custom_resolver_is_used() {
+        let base_url = server();
+        let addr: SocketAddr = base_url.trim_start_matches("http://").parse().unwrap();
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let config = HttpConfig::new().dns_resolver(FixedResolver {
+            addr,
+            lookups: lookups.clone(),
+        });
+        assert_eq!(config, config.clone());
+        assert_ne!(config, HttpConfig::new());
+
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url(&format!("http://stream.x.test:{}", addr.port()))
+            .with_http_config(config);
+        let value: serde_json::Value = client
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1")], None)
+            .await
+            .unwrap();
+
+        assert_eq!(value["data"], serde_json::json!([]));
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_through_http_proxy() {
+        // A proxy is sent the absolute URL, and answers itself here.
+        let (proxy_url, requests) = serve_status("200 OK", EMPTY);
+        let proxy = Proxy::http(&proxy_url)
+            .unwrap()
+            .basic_auth("bot", "hunter2");
+        assert!(!format!("{:?}", proxy).contains("hunter2"));
+
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url("http://api.x.test")
+            .with_http_config(HttpConfig::new().proxy(proxy));
+        let value: serde_json::Value = client
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1")], None)
+            .await
+            .unwrap();
+
+        assert_eq!(value["data"], serde_json::json!([]));
+        let request = requests.recv().unwrap();
+        assert_eq!(
+            request.line,
+            "GET http://api.x.test/2/tweets?ids=1 HTTP/1.1"
+        );
+        // base64("bot:hunter2")
+        assert_eq!(
+            request.header("Proxy-Authorization"),
+            Some("Basic Ym90Omh1bnRlcjI=")
+        );
+    }
+
+    #[test]
+    fn test_proxy_rejects_other_schemes() {
+        assert!(matches!(
+            Proxy::http("ftp://proxy.internal"),
+            Err(TweetyError::ConfigError(_))
+        ));
+        assert!(matches!(
+            Proxy::http("not a url"),
+            Err(TweetyError::UrlParseError(_))
+        ));
+    }
+
+    #[cfg(feature = "socks")]
+    #[tokio::test]
+    async fn test_requests_go_through_socks5_proxy() {
+        let base_url = server();
+        let target: SocketAddr = base_url.trim_start_matches("http://").parse().unwrap();
+        let (proxy_url, usernames) = socks_server(target);
+        let proxy = Proxy::socks5(&proxy_url)
+            .unwrap()
+            .basic_auth("bot", "hunter2");
+
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url("http://api.x.test")
+            .with_http_config(HttpConfig::new().proxy(proxy));
+        let value: serde_json::Value = client
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1")], None)
+            .await
+            .unwrap();
+
+        assert_eq!(value["data"], serde_json::json!([]));
+        assert_eq!(usernames.recv().unwrap(), "bot");
+    }
+}
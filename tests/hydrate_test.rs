@@ -0,0 +1,95 @@
+mod common;
+
+use common::{serve, Request, Response};
+use futures_util::{stream, StreamExt};
+use std::sync::mpsc;
+use tweety_rs::types::lookup::LookupOutcome;
+use tweety_rs::TweetyClient;
+
+/// The ids a lookup asked for.
+fn requested_ids(request: &Request) -> Vec<String> {
+    request
+        .target
+        .split("ids=")
+        .nth(1)
+        .map(|ids| {
+            ids.split('&')
+                .next()
+                .unwrap_or_default()
+                .split("%2C")
+                .flat_map(|id| id.split(','))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Answers the first request with a 429 asking to retry after a second, then returns
+/// every requested id as a tweet except those ending in 7, which are not found.
+fn lookup_server() -> (String, mpsc::Receiver<Request>) {
+    let mut served = 0;
+    serve(move |request| {
+        served += 1;
+        if served == 1 {
+            return Response::new("429 Too Many Requests", "{}").header("Retry-After", 1);
+        }
+        let data: Vec<String> = requested_ids(request)
+            .iter()
+            .filter(|id| !id.ends_with('7'))
+            .map(|id| {
+                format!(r#"{{"id":"{id}","edit_history_tweet_ids":["{id}"],"text":"tweet {id}"}}"#)
+            })
+            .collect();
+        Response::ok(format!(r#"{{"data":[{}]}}"#, data.join(",")))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hydrate_tweets_batches_and_retries() {
+        let (base_url, requests) = lookup_server();
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+        let ids = (1000..1250).map(|id| id.to_string());
+
+        let hydrated: Vec<_> = client
+            .hydrate_tweets(stream::iter(ids))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(hydrated.len(), 250);
+        assert_eq!(hydrated[0].0, "1000");
+        assert_eq!(hydrated[249].0, "1249");
+        assert!(
+            matches!(&hydrated[1].1, LookupOutcome::Found(tweet) if tweet.text == "tweet 1001")
+        );
+        assert!(matches!(hydrated[7].1, LookupOutcome::NotFound));
+        let found = hydrated.iter().filter(|(_, outcome)| outcome.is_found());
+        assert_eq!(found.count(), 225);
+
+        let batches: Vec<usize> = requests
+            .try_iter()
+            .map(|request| requested_ids(&request).len())
+            .collect();
+        assert_eq!(batches, [100, 100, 100, 50]);
+    }
+
+    #[tokio::test]
+    async fn test_hydrate_tweets_stops_at_an_error() {
+        let client = TweetyClient::from_bearer_token("token").with_base_url("http://127.0.0.1:1");
+
+        let results: Vec<_> = client
+            .hydrate_tweets(stream::iter(["1", "2"]))
+            .collect()
+            .await;
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+}
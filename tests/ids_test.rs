@@ -0,0 +1,47 @@
+use serde_json::json;
+use tweety_rs::types::ids::{MediaId, TweetId, UserId};
+use tweety_rs::types::tweet::{Media, PostTweetParams, Reply};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_id_deserializes_from_number_or_string() {
+        let from_number: MediaId = serde_json::from_value(json!(710511363345354753u64)).unwrap();
+        let from_string: MediaId = serde_json::from_value(json!("710511363345354753")).unwrap();
+
+        assert_eq!(from_number, from_string);
+        assert_eq!(from_number.get(), 710511363345354753);
+        assert_eq!(
+            serde_json::to_value(from_number).unwrap(),
+            json!("710511363345354753")
+        );
+    }
+
+    #[test]
+    fn test_typed_ids_serialize_as_strings() {
+        let params = PostTweetParams {
+            media: Some(Media {
+                media_ids: Some(vec![MediaId::new(1)]),
+                tagged_user_ids: Some(vec![UserId::from(2u64)]),
+            }),
+            quote_tweet_id: Some(TweetId::from("3")),
+            reply: Some(Reply {
+                in_reply_to_tweet_id: Some("4".into()),
+                exclude_reply_user_ids: None,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            params.to_json("hello"),
+            json!({
+                "text": "hello",
+                "media": { "media_ids": ["1"], "tagged_user_ids": ["2"] },
+                "quote_tweet_id": "3",
+                "reply": { "in_reply_to_tweet_id": "4" }
+            })
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use dotenvy::dotenv;
 use std::env;
+#[cfg(feature = "dm")]
 use tweety_rs::api::direct_messages::*;
 use tweety_rs::api::user::{Expansions, TweetFields, UserFields, UserQueryParams};
 use tweety_rs::types::tweet::PostTweetParams;
@@ -23,8 +24,6 @@ fn setup_client() -> TweetyClient {
 
 #[cfg(test)]
 mod tests {
-    use tweety_rs::api::direct_messages::QueryParams;
-
     use super::*;
 
     #[tokio::test]
@@ -64,16 +63,7 @@ mod tests {
     async fn test_post_a_tweet() {
         let client = setup_client();
         let message = String::from("Hello testing tweety-rs in the attic");
-        let body_param = PostTweetParams {
-            direct_message_deep_link: None,
-            for_super_followers_only: None,
-            geo: None,
-            media: None,
-            poll: None,
-            quote_tweet_id: None,
-            reply: None,
-            reply_settings: None,
-        };
+        let body_param = PostTweetParams::builder().build();
 
         let response = client.post_tweet(&message, Some(body_param)).await;
         assert!(response.is_ok(), "Request failed {:?}", response);
@@ -87,6 +77,7 @@ mod tests {
         assert!(response.is_ok(), "Request failed {:?}", response);
     }
     /// test get dm messages
+    #[cfg(feature = "dm")]
     #[tokio::test]
     async fn test_get_dm_messages() {
         let client = setup_client();
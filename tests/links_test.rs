@@ -0,0 +1,86 @@
+mod common;
+
+use common::{serve, Response};
+use serde_json::json;
+use std::net::SocketAddr;
+use std::sync::{Arc, OnceLock};
+use tweety_rs::api::http::HttpConfig;
+use tweety_rs::api::mentions::TweetData;
+use tweety_rs::TweetyClient;
+
+/// Redirects `/short` to `http://example.test:{port}/article`, which answers 200.
+fn redirect_server() -> SocketAddr {
+    let port = Arc::new(OnceLock::new());
+    let location = port.clone();
+    let (base_url, _) = serve(
+        move |request| match request.line.starts_with("HEAD /short ") {
+            true => Response::new("301 Moved Permanently", "").header(
+                "Location",
+                format!("http://example.test:{}/article", location.get().unwrap()),
+            ),
+            false => Response::ok(""),
+        },
+    );
+
+    let addr: SocketAddr = base_url.trim_start_matches("http://").parse().unwrap();
+    port.set(addr.port()).unwrap();
+    addr
+}
+
+fn tweet(text: &str, entities: serde_json::Value) -> TweetData {
+    serde_json::from_value(json!({
+        "id": "1",
+        "edit_history_tweet_ids": ["1"],
+        "text": text,
+        "entities": entities
+    }))
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expanded_urls_prefer_unwound() {
+        let tweet = tweet(
+            "Read https://t.co/a1 and https://t.co/b2",
+            json!({ "urls": [
+                { "start": 5, "end": 23, "url": "https://t.co/a1", "expanded_url": "https://bit.ly/x", "display_url": "bit.ly/x", "unwound_url": "https://blog.rust-lang.org/x" },
+                { "start": 28, "end": 43, "url": "https://t.co/b2", "expanded_url": "https://docs.rs/tweety-rs", "display_url": "docs.rs/tweety-rs" }
+            ]}),
+        );
+
+        assert_eq!(
+            tweet.expanded_urls(),
+            ["https://blog.rust-lang.org/x", "https://docs.rs/tweety-rs"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expand_urls_follows_links_without_entities() {
+        let addr = redirect_server();
+        let client = TweetyClient::from_bearer_token("token").with_http_config(
+            HttpConfig::new()
+                .resolve("t.co", addr)
+                .resolve("example.test", addr),
+        );
+        let short = format!("http://t.co:{}/short", addr.port());
+        let tweet = tweet(
+            &format!("New post ({}), also https://t.co/b2.", short),
+            json!({ "urls": [
+                { "start": 0, "end": 1, "url": "https://t.co/b2", "expanded_url": "https://docs.rs/tweety-rs", "display_url": "docs.rs/tweety-rs" }
+            ]}),
+        );
+
+        let urls = client.expand_urls(&tweet).await.unwrap();
+
+        assert_eq!(
+            urls,
+            [
+                format!("http://example.test:{}/article", addr.port()),
+                "https://docs.rs/tweety-rs".to_string()
+            ]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::serve_status;
+use serde_json::json;
+use tweety_rs::types::lookup::LookupOutcome;
+use tweety_rs::TweetyClient;
+
+/// Answers every request with `body`.
+fn lookup_server(body: &'static str) -> String {
+    serve_status("200 OK", body).0
+}
+
+fn client(base_url: &str) -> TweetyClient {
+    TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url)
+}
+
+fn ids<I: From<&'static str>>(ids: &[&'static str]) -> Vec<I> {
+    ids.iter().map(|&id| I::from(id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors_are_sorted_by_cause() {
+        let suspended = json!({
+            "value": "3",
+            "detail": "User has been suspended: [3].",
+            "title": "Forbidden",
+            "resource_type": "user",
+            "resource_id": "3",
+            "type": "https://api.twitter.com/2/problems/resource-not-found"
+        });
+        let protected = json!({
+            "detail": "Sorry, you are not authorized to see the Tweet with ids: [4].",
+            "resource_id": "4",
+            "type": "https://api.twitter.com/2/problems/not-authorized-for-resource"
+        });
+        let odd = json!({ "resource_id": "5", "type": "https://api.twitter.com/2/problems/resource-unavailable" });
+
+        assert_eq!(
+            LookupOutcome::<()>::from_error(&suspended),
+            LookupOutcome::Suspended
+        );
+        assert_eq!(
+            LookupOutcome::<()>::from_error(&protected),
+            LookupOutcome::Unauthorized
+        );
+        assert_eq!(
+            LookupOutcome::<()>::from_error(&odd),
+            LookupOutcome::Other(odd.clone())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lookup_tweets_keeps_request_order() {
+        let base_url = lookup_server(
+            r#"{"data":[{"id":"3","edit_history_tweet_ids":["3"],"text":"third"},{"id":"1","edit_history_tweet_ids":["1"],"text":"first"}],"errors":[{"value":"2","detail":"Could not find tweet with ids: [2].","title":"Not Found Error","resource_type":"tweet","parameter":"ids","resource_id":"2","type":"https://api.twitter.com/2/problems/resource-not-found"},{"value":"4","detail":"Sorry, you are not authorized to see the Tweet with ids: [4].","title":"Authorization Error","resource_type":"tweet","parameter":"ids","resource_id":"4","type":"https://api.twitter.com/2/problems/not-authorized-for-resource"}]}"#,
+        );
+
+        let outcomes = client(&base_url)
+            .lookup_tweets(ids(&["1", "2", "3", "4", "5"]))
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = outcomes.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["1", "2", "3", "4", "5"]);
+        assert!(matches!(&outcomes[0].1, LookupOutcome::Found(tweet) if tweet.text == "first"));
+        assert!(matches!(outcomes[1].1, LookupOutcome::NotFound));
+        assert!(outcomes[2].1.is_found());
+        assert!(matches!(outcomes[3].1, LookupOutcome::Unauthorized));
+        assert!(matches!(outcomes[4].1, LookupOutcome::NotFound));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_users_reports_suspended() {
+        let base_url = lookup_server(
+            r#"{"errors":[{"value":"3","detail":"User has been suspended: [3].","title":"Forbidden","resource_type":"user","parameter":"ids","resource_id":"3","type":"https://api.twitter.com/2/problems/resource-not-found"}]}"#,
+        );
+
+        let outcomes = client(&base_url)
+            .lookup_users(ids(&["3"]), None)
+            .await
+            .unwrap();
+
+        assert_eq!(outcomes[0].0, "3");
+        let (_, outcome) = outcomes.into_iter().next().unwrap();
+        assert!(matches!(outcome, LookupOutcome::Suspended));
+        assert!(outcome.found().is_none());
+    }
+}
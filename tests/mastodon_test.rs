@@ -0,0 +1,64 @@
+#![cfg(feature = "mastodon")]
+
+mod common;
+
+use common::serve_status;
+use tweety_rs::api::crosspost::{CrossPoster, Post};
+use tweety_rs::api::mastodon::{Mastodon, Visibility};
+
+const STATUS: &str =
+    r#"{"id":"113052270187418010","url":"https://fosstodon.org/@tweety/113052270187418010"}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_post_status() {
+        let (base_url, requests) = serve_status("200 OK", STATUS);
+        let mastodon = Mastodon::new(&base_url, "mastodon-token")
+            .unwrap()
+            .visibility(Visibility::Unlisted);
+
+        let posted = mastodon.publish("Hello, fediverse!").await.unwrap();
+        assert_eq!(posted.id, "113052270187418010");
+        assert_eq!(
+            posted.url.as_deref(),
+            Some("https://fosstodon.org/@tweety/113052270187418010")
+        );
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "POST /api/v1/statuses HTTP/1.1");
+        assert_eq!(
+            request.header("Authorization"),
+            Some("Bearer mastodon-token")
+        );
+        let body = request.json();
+        assert_eq!(body["status"], "Hello, fediverse!");
+        assert_eq!(body["visibility"], "unlisted");
+    }
+
+    #[tokio::test]
+    async fn test_error_response() {
+        let (base_url, _requests) = serve_status(
+            "422 Unprocessable Entity",
+            r#"{"error":"Validation failed: Text character limit of 500 exceeded"}"#,
+        );
+        let mastodon = Mastodon::new(&base_url, "mastodon-token").unwrap();
+
+        let err = mastodon.post_status("too long").await.unwrap_err();
+        assert_eq!(err.status().map(|status| status.as_u16()), Some(422));
+    }
+
+    #[test]
+    fn test_length_counts_links_at_a_fixed_length() {
+        let mastodon = Mastodon::new("https://fosstodon.org", "token")
+            .unwrap()
+            .max_length(40);
+        let link = format!("https://example.com/{}", "a".repeat(100));
+
+        assert_eq!(mastodon.length(&format!("New: {}", link)), 28);
+        let post = Post::new("A release with many changes").link(link.clone());
+        assert_eq!(post.fit_to(&mastodon), format!("A release with… {}", link));
+    }
+}
@@ -0,0 +1,174 @@
+#![cfg(feature = "media-upload")]
+
+use std::fs;
+use std::path::PathBuf;
+use tweety_rs::api::error::TweetyError;
+#[cfg(feature = "image")]
+use tweety_rs::api::media::{prepare_image, MAX_IMAGE_DIMENSION};
+use tweety_rs::api::media::{MediaCategory, MediaInfo, MAX_IMAGE_BYTES};
+
+fn write_media(name: &str, contents: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("tweety-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).expect("failed to write media file");
+    path
+}
+
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&height.to_be_bytes());
+    bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+    bytes
+}
+
+fn mp4_box(kind: &[u8; 4], content: &[u8]) -> Vec<u8> {
+    let mut bytes = ((content.len() + 8) as u32).to_be_bytes().to_vec();
+    bytes.extend_from_slice(kind);
+    bytes.extend_from_slice(content);
+    bytes
+}
+
+/// An MP4 with a version 0 `mvhd` and a single `tkhd`, and no media data.
+fn mp4(duration_ms: u32, width: u32, height: u32) -> Vec<u8> {
+    let mut mvhd = vec![0u8; 100];
+    mvhd[12..16].copy_from_slice(&1000u32.to_be_bytes());
+    mvhd[16..20].copy_from_slice(&duration_ms.to_be_bytes());
+
+    let mut tkhd = vec![0u8; 84];
+    tkhd[76..80].copy_from_slice(&(width << 16).to_be_bytes());
+    tkhd[80..84].copy_from_slice(&(height << 16).to_be_bytes());
+
+    let moov = [
+        mp4_box(b"mvhd", &mvhd),
+        mp4_box(b"trak", &mp4_box(b"tkhd", &tkhd)),
+    ]
+    .concat();
+    [
+        mp4_box(b"ftyp", b"isom\x00\x00\x02\x00isomiso2mp41"),
+        mp4_box(b"mdat", &[0u8; 32]),
+        mp4_box(b"moov", &moov),
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_images() {
+        let path = write_media("image.bin", &png(1200, 675));
+        let info = MediaInfo::from_path(&path).unwrap();
+
+        assert_eq!(info.mime_type, "image/png");
+        assert_eq!(info.category, MediaCategory::TweetImage);
+        assert_eq!((info.width, info.height), (Some(1200), Some(675)));
+        assert!(info.validate().is_ok());
+
+        let gif = write_media("image.gif", b"GIF89a\x40\x01\xf0\x00\x00\x00\x00;");
+        let info = MediaInfo::from_path(&gif).unwrap();
+        assert_eq!(info.category, MediaCategory::TweetGif);
+        assert_eq!((info.width, info.height), (Some(320), Some(240)));
+
+        fs::remove_file(path).ok();
+        fs::remove_file(gif).ok();
+    }
+
+    #[test]
+    fn test_detects_video_duration() {
+        let short = write_media("short.mp4", &mp4(30_000, 1280, 720));
+        let info = MediaInfo::from_path(&short).unwrap();
+
+        assert_eq!(info.mime_type, "video/mp4");
+        assert_eq!(info.category, MediaCategory::TweetVideo);
+        assert_eq!(info.duration_ms, Some(30_000));
+        assert_eq!((info.width, info.height), (Some(1280), Some(720)));
+
+        let long = write_media("long.mp4", &mp4(600_000, 1280, 720));
+        assert_eq!(
+            MediaInfo::from_path(&long).unwrap().category,
+            MediaCategory::AmplifyVideo
+        );
+
+        fs::remove_file(short).ok();
+        fs::remove_file(long).ok();
+    }
+
+    #[test]
+    fn test_box_length_near_u64_max_is_ignored() {
+        let mut huge = 1u32.to_be_bytes().to_vec();
+        huge.extend_from_slice(b"free");
+        huge.extend_from_slice(&(u64::MAX - 8).to_be_bytes());
+        let contents = [
+            mp4_box(b"ftyp", b"isom\x00\x00\x02\x00isomiso2mp41"),
+            huge,
+            vec![0u8; 32],
+        ]
+        .concat();
+        let path = write_media("huge-box.mp4", &contents);
+
+        let info = MediaInfo::from_path(&path).unwrap();
+        assert_eq!(info.mime_type, "video/mp4");
+        assert_eq!(info.duration_ms, None);
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_validate_rejects_limits() {
+        let path = write_media("huge.png", &png(9000, 100));
+        let error = MediaInfo::from_path(&path).unwrap().validate().unwrap_err();
+        assert!(error.to_string().contains("9000x100"), "{}", error);
+
+        let oversized = MediaInfo {
+            size: MAX_IMAGE_BYTES + 1,
+            ..MediaInfo::from_path(&path).unwrap()
+        };
+        assert!(matches!(
+            oversized.validate(),
+            Err(TweetyError::UploadError(message)) if message.contains("at most 5.0 MB")
+        ));
+
+        let unknown = write_media("notes.txt", b"hello world");
+        assert!(matches!(
+            MediaInfo::from_path(&unknown),
+            Err(TweetyError::UploadError(_))
+        ));
+
+        fs::remove_file(path).ok();
+        fs::remove_file(unknown).ok();
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_prepare_image_fits_limits() {
+        use rand::RngCore;
+
+        let wide = std::env::temp_dir().join(format!("tweety-{}-wide.png", std::process::id()));
+        image::RgbImage::new(9000, 16).save(&wide).unwrap();
+        let prepared = prepare_image(&wide).unwrap();
+        assert!(prepared.modified);
+        assert!(prepared.width <= MAX_IMAGE_DIMENSION);
+
+        // Noise doesn't compress, so the PNG is well over 5 MB.
+        let mut pixels = vec![0u8; 1600 * 1200 * 3];
+        rand::thread_rng().fill_bytes(&mut pixels);
+        let noisy = std::env::temp_dir().join(format!("tweety-{}-noisy.png", std::process::id()));
+        image::RgbImage::from_raw(1600, 1200, pixels)
+            .unwrap()
+            .save(&noisy)
+            .unwrap();
+        assert!(fs::metadata(&noisy).unwrap().len() > MAX_IMAGE_BYTES);
+
+        let prepared = prepare_image(&noisy).unwrap();
+        assert_eq!(prepared.mime_type, "image/jpeg");
+        assert!(prepared.bytes.len() as u64 <= MAX_IMAGE_BYTES);
+
+        let small = write_media("small.png", &png(10, 10));
+        assert!(!prepare_image(&small).unwrap().modified);
+
+        fs::remove_file(wide).ok();
+        fs::remove_file(noisy).ok();
+        fs::remove_file(small).ok();
+    }
+}
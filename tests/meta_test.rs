@@ -0,0 +1,65 @@
+use serde_json::json;
+use tweety_rs::api::communities::CommunitySearchParams;
+use tweety_rs::api::mentions::{MentionsResponse, QueryParams};
+use tweety_rs::types::meta::Meta;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meta_parses_every_shape() {
+        let tweets: Meta = serde_json::from_value(json!({
+            "newest_id": "1900000000000000002",
+            "oldest_id": "1900000000000000001",
+            "result_count": 2,
+            "next_token": "b26v89c19zqg8o3fpzbkk"
+        }))
+        .unwrap();
+        assert_eq!(tweets.newest_id.as_deref(), Some("1900000000000000002"));
+        assert!(tweets.has_next());
+
+        let users: Meta = serde_json::from_value(json!({
+            "result_count": 1,
+            "previous_token": "77qp8"
+        }))
+        .unwrap();
+        assert_eq!(users.previous_token.as_deref(), Some("77qp8"));
+        assert!(!users.has_next());
+
+        let empty: Meta = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(empty, Meta::default());
+    }
+
+    #[test]
+    fn test_next_request_sets_the_endpoints_token() {
+        let response: MentionsResponse = serde_json::from_value(json!({
+            "meta": { "result_count": 0, "next_token": "abc" }
+        }))
+        .unwrap();
+        let params = QueryParams {
+            max_results: Some(100),
+            ..QueryParams::default()
+        };
+
+        let next = response.meta.next_request(params).unwrap();
+        assert_eq!(next.pagination_token.as_deref(), Some("abc"));
+        assert_eq!(next.max_results, Some(100));
+
+        let next = response
+            .meta
+            .next_request(CommunitySearchParams::default())
+            .unwrap();
+        assert_eq!(next.next_token.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_next_request_ends_on_last_page() {
+        let meta = Meta {
+            result_count: 3,
+            ..Meta::default()
+        };
+
+        assert!(meta.next_request(QueryParams::default()).is_none());
+    }
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "mirror")]
+
+mod common;
+
+use common::{serve_in_order, Response};
+use serde_json::json;
+use tweety_rs::api::mentions::TweetData;
+use tweety_rs::api::mirror::{Mirror, MirrorMode};
+use tweety_rs::api::state::{FileStateStore, StateStore};
+use tweety_rs::TweetyClient;
+
+const CREATED: &str = r#"{"data":{"id":"1850000000000000000","text":"mirrored","edit_history_tweet_ids":["1850000000000000000"]}}"#;
+
+fn tweet(value: serde_json::Value) -> TweetData {
+    serde_json::from_value(value).unwrap()
+}
+
+fn timeline(tweets: serde_json::Value) -> Response {
+    Response::ok(json!({ "data": tweets, "meta": { "result_count": 2 } }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_applies_the_hooks_in_order() {
+        let client = TweetyClient::new("key", "token", "secret", "token_secret");
+        let mirror = Mirror::new(client, "2244994945")
+            .filter(|tweet| !tweet.text.contains("#ad"))
+            .rewrite_links(|url| match url.contains("example.com/drop") {
+                true => None,
+                false => Some(url.replace("twitter.com", "x.com")),
+            })
+            .transform(|text, _| Some(text.replace("v2", "API v2")))
+            .transform(|text, _| (!text.is_empty()).then_some(text))
+            .prefix("From @XDevelopers: ");
+
+        let linked = tweet(json!({
+            "id": "1",
+            "edit_history_tweet_ids": ["1"],
+            "text": "New in v2: https://t.co/abc https://t.co/xyz",
+            "entities": { "urls": [
+                {
+                    "start": 11, "end": 27, "url": "https://t.co/abc",
+                    "expanded_url": "https://twitter.com/XDevelopers/status/1",
+                    "display_url": "twitter.com/XDevelopers/…"
+                },
+                {
+                    "start": 28, "end": 44, "url": "https://t.co/xyz",
+                    "expanded_url": "https://example.com/drop",
+                    "display_url": "example.com/drop"
+                }
+            ] }
+        }));
+        assert_eq!(
+            mirror.render(&linked).as_deref(),
+            Some("From @XDevelopers: New in API v2: https://x.com/XDevelopers/status/1")
+        );
+
+        let ad = tweet(json!({ "id": "2", "edit_history_tweet_ids": ["2"], "text": "Buy #ad" }));
+        assert_eq!(mirror.render(&ad), None);
+
+        let reply = tweet(json!({
+            "id": "3",
+            "edit_history_tweet_ids": ["3"],
+            "text": "@someone thanks",
+            "in_reply_to_user_id": "42",
+            "referenced_tweets": [{ "type": "replied_to", "id": "0" }]
+        }));
+        assert_eq!(mirror.render(&reply), None);
+
+        let retweet = tweet(json!({
+            "id": "4",
+            "edit_history_tweet_ids": ["4"],
+            "text": "RT @rustlang: 1.80 is out",
+            "referenced_tweets": [{ "type": "retweeted", "id": "0" }]
+        }));
+        assert_eq!(mirror.render(&retweet), None);
+        let client = TweetyClient::new("key", "token", "secret", "token_secret");
+        let including = Mirror::new(client, "2244994945").include_retweets();
+        assert_eq!(
+            including.render(&retweet).as_deref(),
+            Some("RT @rustlang: 1.80 is out")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_poll_mirrors_new_tweets_oldest_first() {
+        let (base_url, requests) = serve_in_order(vec![
+            timeline(json!([
+                { "id": "1800000000000000002", "edit_history_tweet_ids": ["1800000000000000002"], "text": "old" }
+            ])),
+            timeline(json!([
+                { "id": "1800000000000000004", "edit_history_tweet_ids": ["1800000000000000004"], "text": "second" },
+                { "id": "1800000000000000003", "edit_history_tweet_ids": ["1800000000000000003"], "text": "first" }
+            ])),
+            Response::ok(CREATED),
+        ]);
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+        let path = std::env::temp_dir().join(format!("tweety-mirror-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut mirror = Mirror::new(client, "2244994945")
+            .mode(MirrorMode::Quote)
+            .state_store(FileStateStore::new(&path), "mirror")
+            .prefix("> ");
+
+        assert_eq!(mirror.poll_once().await.unwrap(), 0);
+        assert!(requests
+            .recv()
+            .unwrap()
+            .line
+            .starts_with("GET /2/users/2244994945/tweets?"));
+        assert_eq!(mirror.last_seen_id(), Some("1800000000000000002"));
+
+        assert_eq!(mirror.poll_once().await.unwrap(), 2);
+        let request = requests.recv().unwrap();
+        assert!(request.target.contains("since_id=1800000000000000002"));
+        for (text, quoted) in [
+            ("> first", "1800000000000000003"),
+            ("> second", "1800000000000000004"),
+        ] {
+            let request = requests.recv().unwrap();
+            assert_eq!(request.line, "POST /2/tweets HTTP/1.1");
+            let body = request.json();
+            assert_eq!(body["text"], text);
+            assert_eq!(body["quote_tweet_id"], quoted);
+        }
+        assert_eq!(
+            FileStateStore::new(&path)
+                .get("mirror")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("1800000000000000004")
+        );
+
+        let old = tweet(json!({
+            "id": "1800000000000000003",
+            "edit_history_tweet_ids": ["1800000000000000003"],
+            "text": "first"
+        }));
+        assert!(!mirror.mirror_tweet(&old).await.unwrap());
+    }
+}
@@ -0,0 +1,118 @@
+use reqwest::Method;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+#[cfg(feature = "oauth2-flow")]
+use tweety_rs::api::oauth2::OAuth2FlowBuilder;
+use tweety_rs::api::oauth2::{OAuth2Token, Scope, TokenExpiry};
+use tweety_rs::TweetyClient;
+
+fn token_expiring_in(seconds: i64) -> OAuth2Token {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let mut token = OAuth2Token::new("token", vec![Scope::TweetRead]);
+    token.expires_at = Some((now + seconds) as u64);
+    token
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_round_trip() {
+        for scope in Scope::ALL {
+            assert_eq!(scope.as_str().parse::<Scope>().unwrap(), scope);
+            assert_eq!(
+                serde_json::to_string(&scope).unwrap(),
+                format!("\"{}\"", scope)
+            );
+        }
+        assert!("tweet.delete".parse::<Scope>().is_err());
+    }
+
+    #[test]
+    fn test_required_scopes() {
+        assert_eq!(
+            Scope::required_for(&Method::POST, "/2/tweets"),
+            vec![Scope::TweetRead, Scope::UsersRead, Scope::TweetWrite]
+        );
+        assert_eq!(
+            Scope::required_for(&Method::GET, "/2/users/123/bookmarks"),
+            vec![Scope::TweetRead, Scope::UsersRead, Scope::BookmarkRead]
+        );
+        assert_eq!(
+            Scope::required_for(&Method::DELETE, "/2/users/123/likes/456"),
+            vec![Scope::TweetRead, Scope::UsersRead, Scope::LikeWrite]
+        );
+        assert_eq!(
+            Scope::required_for(&Method::GET, "/2/communities/search"),
+            vec![Scope::TweetRead, Scope::UsersRead]
+        );
+        assert!(Scope::required_for(&Method::GET, "/1.1/help/languages.json").is_empty());
+    }
+
+    #[test]
+    fn test_client_missing_scopes() {
+        let token = OAuth2Token::new("token", vec![Scope::TweetRead, Scope::UsersRead]);
+        let client = TweetyClient::from_oauth2_token(token);
+
+        assert!(client.missing_scopes(&Method::GET, "/2/tweets").is_empty());
+        assert_eq!(
+            client.missing_scopes(&Method::POST, "/2/tweets"),
+            vec![Scope::TweetWrite]
+        );
+
+        let oauth1 = TweetyClient::new("a", "b", "c", "d");
+        assert!(oauth1.missing_scopes(&Method::POST, "/2/tweets").is_empty());
+    }
+
+    #[cfg(feature = "oauth2-flow")]
+    #[test]
+    fn test_authorization_request() {
+        let flow = OAuth2FlowBuilder::new("client_id", "https://example.com/callback")
+            .scopes([Scope::TweetRead, Scope::UsersRead, Scope::TweetRead])
+            .build();
+        let request = flow.authorization_request();
+
+        assert_eq!(flow.scopes(), &[Scope::TweetRead, Scope::UsersRead]);
+        assert!(request.url.starts_with("https://x.com/i/oauth2/authorize?"));
+        assert!(request.url.contains("scope=tweet.read+users.read"));
+        assert!(request.url.contains("code_challenge_method=S256"));
+        assert!(request.url.contains(&format!("state={}", request.state)));
+        assert_eq!(request.code_verifier.expose_secret().len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_watch_token_expiry() {
+        let client = TweetyClient::from_oauth2_token(token_expiring_in(3600));
+        let mut expiry = client.watch_token_expiry(Duration::from_secs(60));
+        let wait = Duration::from_secs(5);
+
+        assert_eq!(*expiry.borrow_and_update(), TokenExpiry::Valid);
+
+        let expiring = token_expiring_in(30);
+        let expires_at = expiring.expires_at.unwrap();
+        client.set_oauth2_token(expiring);
+        tokio::time::timeout(wait, expiry.changed())
+            .await
+            .expect("state change")
+            .unwrap();
+        assert_eq!(
+            *expiry.borrow_and_update(),
+            TokenExpiry::ExpiringSoon { expires_at }
+        );
+
+        let expired = token_expiring_in(-1);
+        let expires_at = expired.expires_at.unwrap();
+        client.set_oauth2_token(expired);
+        tokio::time::timeout(wait, expiry.changed())
+            .await
+            .expect("state change")
+            .unwrap();
+        assert_eq!(
+            *expiry.borrow_and_update(),
+            TokenExpiry::Expired { expires_at }
+        );
+    }
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{serve, Response};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use tweety_rs::api::endpoint::{Endpoint, RateLimitFamily};
+use tweety_rs::api::observer::ClientEvent;
+use tweety_rs::api::usage::UsageKind;
+use tweety_rs::TweetyClient;
+
+/// Answers every request with two tweets and a rate-limit window.
+fn server() -> String {
+    let response = Response::ok(r#"{"data":[{"id":"1","text":"one"},{"id":"2","text":"two"}]}"#)
+        .header("x-rate-limit-limit", 15)
+        .header("x-rate-limit-remaining", 14)
+        .header("x-rate-limit-reset", 1700000000);
+    serve(move |_| response.clone()).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_events_carry_the_tag_of_the_handle() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = events.clone();
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url(&server())
+            .with_observer(move |event: &ClientEvent| seen.lock().unwrap().push(event.clone()));
+
+        let search = client.tagged("search");
+        let _: Value = search
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1,2")], None)
+            .await
+            .unwrap();
+        let _: Value = client
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1,2")], None)
+            .await
+            .unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 4);
+        match &events[0] {
+            ClientEvent::Response {
+                tag,
+                family,
+                status,
+                rate_limit,
+            } => {
+                assert_eq!(tag.as_deref(), Some("search"));
+                assert_eq!(*family, RateLimitFamily::TweetLookup);
+                assert_eq!(*status, StatusCode::OK);
+                assert_eq!(rate_limit.unwrap().remaining, 14);
+            }
+            other => panic!("expected a response, got {:?}", other),
+        }
+        assert_eq!(
+            events[1],
+            ClientEvent::PostsConsumed {
+                tag: Some("search".to_string()),
+                kind: UsageKind::Read,
+                posts: 2,
+            }
+        );
+        assert!(matches!(
+            &events[2],
+            ClientEvent::Response { tag: None, .. }
+        ));
+        assert!(matches!(
+            &events[3],
+            ClientEvent::PostsConsumed { tag: None, .. }
+        ));
+    }
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "outbox-sqlite")]
+
+mod common;
+
+use common::serve_in_order;
+use std::time::Duration;
+use tweety_rs::api::outbox::{Outbox, OutboxStatus, SqliteOutboxStore};
+use tweety_rs::TweetyClient;
+
+const CREATED: (&str, &str) = (
+    "201 Created",
+    r#"{"data":{"id":"1445880548472328192","text":"hello","edit_history_tweet_ids":["1445880548472328192"]}}"#,
+);
+const UNAVAILABLE: (&str, &str) = (
+    "503 Service Unavailable",
+    r#"{"title":"Service Unavailable","status":503}"#,
+);
+
+fn outbox(base_url: &str, store: SqliteOutboxStore) -> Outbox {
+    let client =
+        TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url);
+    Outbox::with_store(client, store).retry_backoff(Duration::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_enqueue_is_idempotent_and_durable() {
+        let dir = std::env::temp_dir().join(format!("tweety-outbox-sqlite-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("outbox.db");
+        let queue = outbox(
+            "http://127.0.0.1:1",
+            SqliteOutboxStore::open(&path).unwrap(),
+        );
+
+        let first = queue.enqueue_tweet("launch", "hello", None).await.unwrap();
+        let again = queue
+            .enqueue_tweet("launch", "hello again", None)
+            .await
+            .unwrap();
+        assert_eq!(first, again);
+        queue
+            .enqueue_tweet("follow-up", "more", None)
+            .await
+            .unwrap();
+
+        // A new process opening the same database sees the entries, in order.
+        let reopened = outbox(
+            "http://127.0.0.1:1",
+            SqliteOutboxStore::open(&path).unwrap(),
+        );
+        let keys: Vec<String> = reopened
+            .entries()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.key)
+            .collect();
+        assert_eq!(keys, ["launch", "follow-up"]);
+        drop((queue, reopened));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_posts_are_retried_until_posted() {
+        let (base_url, _requests) = serve_in_order(vec![UNAVAILABLE, CREATED]);
+        let queue = outbox(&base_url, SqliteOutboxStore::open_in_memory().unwrap());
+        queue.enqueue_tweet("launch", "hello", None).await.unwrap();
+
+        assert_eq!(queue.process_once().await.unwrap(), 0);
+        let entry = &queue.entries().await.unwrap()[0];
+        assert_eq!(entry.status, OutboxStatus::Pending);
+        assert_eq!(entry.attempts, 1);
+
+        assert_eq!(queue.process_once().await.unwrap(), 1);
+        let entry = &queue.entries().await.unwrap()[0];
+        assert_eq!(
+            entry.status,
+            OutboxStatus::Posted {
+                tweet_id: Some("1445880548472328192".to_string())
+            }
+        );
+        assert_eq!(entry.body["text"], "hello");
+
+        assert_eq!(queue.remove_posted().await.unwrap(), 1);
+        assert!(queue.entries().await.unwrap().is_empty());
+    }
+}
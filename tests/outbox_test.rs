@@ -0,0 +1,152 @@
+#![cfg(feature = "outbox")]
+
+mod common;
+
+use common::serve_in_order;
+use std::time::Duration;
+use tweety_rs::api::outbox::{Outbox, OutboxStatus};
+use tweety_rs::api::shutdown::Shutdown;
+use tweety_rs::api::state::{FileStateStore, MemoryStateStore, StateStore};
+use tweety_rs::TweetyClient;
+
+const CREATED: (&str, &str) = (
+    "201 Created",
+    r#"{"data":{"id":"1445880548472328192","text":"hello","edit_history_tweet_ids":["1445880548472328192"]}}"#,
+);
+const UNAVAILABLE: (&str, &str) = (
+    "503 Service Unavailable",
+    r#"{"title":"Service Unavailable","status":503}"#,
+);
+const DUPLICATE: (&str, &str) = (
+    "403 Forbidden",
+    r#"{"detail":"You are not allowed to create a Tweet with duplicate content.","type":"about:blank","title":"Forbidden","status":403}"#,
+);
+
+fn outbox(base_url: &str, store: impl StateStore + 'static) -> Outbox {
+    let client =
+        TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url);
+    Outbox::new(client, store, "outbox").retry_backoff(Duration::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_enqueue_is_idempotent_and_durable() {
+        let dir = std::env::temp_dir().join(format!("tweety-outbox-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("outbox.json");
+        let queue = outbox("http://127.0.0.1:1", FileStateStore::new(&path));
+
+        let first = queue.enqueue_tweet("launch", "hello", None).await.unwrap();
+        let again = queue
+            .enqueue_tweet("launch", "hello again", None)
+            .await
+            .unwrap();
+        assert_eq!(first, again);
+        assert_eq!(first.status, OutboxStatus::Pending);
+        assert_eq!(first.body["text"], "hello");
+
+        // A new process reading the same file sees the entry.
+        let reopened = outbox("http://127.0.0.1:1", FileStateStore::new(&path));
+        assert_eq!(reopened.entries().await.unwrap(), [first]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_posts_are_retried_until_posted() {
+        let (base_url, bodies) = serve_in_order(vec![UNAVAILABLE, CREATED]);
+        let queue = outbox(&base_url, MemoryStateStore::new());
+        queue.enqueue_tweet("launch", "hello", None).await.unwrap();
+
+        assert_eq!(queue.process_once().await.unwrap(), 0);
+        let entry = &queue.entries().await.unwrap()[0];
+        assert_eq!(entry.status, OutboxStatus::Pending);
+        assert_eq!(entry.attempts, 1);
+
+        assert_eq!(queue.process_once().await.unwrap(), 1);
+        let entry = &queue.entries().await.unwrap()[0];
+        assert_eq!(
+            entry.status,
+            OutboxStatus::Posted {
+                tweet_id: Some("1445880548472328192".to_string())
+            }
+        );
+        assert_eq!(entry.attempts, 2);
+        assert_eq!(
+            bodies
+                .try_iter()
+                .map(|request| request.text())
+                .collect::<Vec<_>>(),
+            [r#"{"text":"hello"}"#; 2]
+        );
+
+        // Posted entries aren't sent again.
+        assert_eq!(queue.process_once().await.unwrap(), 0);
+        assert_eq!(queue.remove_posted().await.unwrap(), 1);
+        assert!(queue.entries().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_after_an_unknown_outcome_counts_as_posted() {
+        let (base_url, _bodies) = serve_in_order(vec![DUPLICATE, DUPLICATE]);
+        let store = MemoryStateStore::new();
+        // Left mid-request by a crashed process.
+        store
+            .set(
+                "outbox",
+                r#"[{"key":"crashed","body":{"text":"hello"},"status":{"state":"sending"},"attempts":1,"not_before":0},
+                    {"key":"fresh","body":{"text":"hello"},"status":{"state":"pending"},"attempts":0,"not_before":0}]"#,
+            )
+            .await
+            .unwrap();
+        let queue = outbox(&base_url, store);
+
+        assert_eq!(queue.process_once().await.unwrap(), 1);
+        let entries = queue.entries().await.unwrap();
+        assert_eq!(entries[0].status, OutboxStatus::Posted { tweet_id: None });
+        // A first attempt refused as a duplicate is a genuine duplicate.
+        assert!(
+            matches!(entries[1].status, OutboxStatus::Failed { ref error } if error.contains("duplicate"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let (base_url, _bodies) = serve_in_order(vec![UNAVAILABLE, UNAVAILABLE]);
+        let queue = outbox(&base_url, MemoryStateStore::new()).max_attempts(2);
+        queue.enqueue_tweet("launch", "hello", None).await.unwrap();
+
+        queue.process_once().await.unwrap();
+        queue.process_once().await.unwrap();
+        let entry = &queue.entries().await.unwrap()[0];
+        assert!(matches!(entry.status, OutboxStatus::Failed { .. }));
+        assert_eq!(entry.attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_posts_until_shutdown() {
+        let (base_url, bodies) = serve_in_order(vec![CREATED]);
+        let shutdown = Shutdown::new();
+        let queue = outbox(&base_url, MemoryStateStore::new())
+            .poll_interval(Duration::from_millis(10))
+            .shutdown_on(&shutdown);
+        queue.enqueue_tweet("launch", "hello", None).await.unwrap();
+
+        let worker = queue.clone();
+        let running = tokio::spawn(async move { worker.run().await });
+        assert_eq!(
+            tokio::task::spawn_blocking(move || bodies.recv().unwrap().text())
+                .await
+                .unwrap(),
+            r#"{"text":"hello"}"#
+        );
+        shutdown.shutdown().await;
+        running.await.unwrap().unwrap();
+        assert!(matches!(
+            queue.entries().await.unwrap()[0].status,
+            OutboxStatus::Posted { .. }
+        ));
+    }
+}
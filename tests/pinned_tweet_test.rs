@@ -0,0 +1,75 @@
+mod common;
+
+use common::serve_status;
+use tweety_rs::api::user::{TweetFields, UserQueryParams};
+use tweety_rs::TweetyClient;
+
+fn client(base_url: &str) -> TweetyClient {
+    TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_pinned_tweet_hydrates_includes() {
+        let (base_url, requests) = serve_status(
+            "200 OK",
+            r#"{"data":{"id":"2244994945","name":"Developers","username":"XDevelopers","pinned_tweet_id":"2"},"includes":{"tweets":[{"id":"1","edit_history_tweet_ids":["1"],"text":"quoted"},{"id":"2","edit_history_tweet_ids":["2"],"text":"pinned"}]}}"#,
+        );
+        let params = UserQueryParams {
+            tweet_fields: Some(vec![TweetFields::CreatedAt]),
+            ..UserQueryParams::default()
+        };
+
+        let pinned = client(&base_url)
+            .get_pinned_tweet("2244994945", Some(params))
+            .await
+            .unwrap();
+
+        assert_eq!(pinned.user.username, "XDevelopers");
+        assert_eq!(pinned.tweet.unwrap().text, "pinned");
+        let request = requests.recv().unwrap().line;
+        assert!(request.contains("/2/users/2244994945?"));
+        assert!(request.contains("expansions=pinned_tweet_id"));
+        assert!(request.contains("tweet.fields=created_at"));
+    }
+
+    #[tokio::test]
+    async fn test_get_pinned_tweet_without_pin() {
+        let (base_url, _requests) = serve_status(
+            "200 OK",
+            r#"{"data":{"id":"2244994945","name":"Developers","username":"XDevelopers"}}"#,
+        );
+
+        let pinned = client(&base_url)
+            .get_pinned_tweet("2244994945", None)
+            .await
+            .unwrap();
+
+        assert!(pinned.user.pinned_tweet_id.is_none());
+        assert!(pinned.tweet.is_none());
+    }
+
+    #[cfg(feature = "v1")]
+    #[tokio::test]
+    async fn test_pin_and_unpin_tweet() {
+        let (base_url, requests) = serve_status("200 OK", r#"{"pinned_tweets":["2"]}"#);
+        let client = client(&base_url);
+
+        client.v1().pin_tweet("2").await.unwrap();
+        assert!(requests
+            .recv()
+            .unwrap()
+            .line
+            .starts_with("POST /1.1/account/pin_tweet.json?id=2 "));
+
+        client.v1().unpin_tweet("2").await.unwrap();
+        assert!(requests
+            .recv()
+            .unwrap()
+            .line
+            .starts_with("POST /1.1/account/unpin_tweet.json?id=2 "));
+    }
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::{serve, Response};
+use serde_json::json;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::types::user::{BannerSize, UserResponse};
+use tweety_rs::TweetyClient;
+
+/// Answers every request with `body` and returns the server's base URL.
+fn image_server(body: &'static [u8]) -> String {
+    serve(move |_| Response::ok(body).header("Content-Type", "image/jpeg")).0
+}
+
+fn user(profile_image_url: Option<&str>, profile_banner_url: Option<&str>) -> UserResponse {
+    serde_json::from_value(json!({
+        "id": "2244994945",
+        "name": "Developers",
+        "username": "XDevelopers",
+        "profile_image_url": profile_image_url,
+        "profile_banner_url": profile_banner_url
+    }))
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_original_profile_image_url() {
+        let user = user(
+            Some("https://pbs.twimg.com/profile_images/1/abc_normal.jpg"),
+            None,
+        );
+        assert_eq!(
+            user.original_profile_image_url().as_deref(),
+            Some("https://pbs.twimg.com/profile_images/1/abc.jpg")
+        );
+    }
+
+    #[test]
+    fn test_profile_banner_url_at_size() {
+        let user = user(
+            None,
+            Some("https://pbs.twimg.com/profile_banners/2244994945/1690"),
+        );
+        assert_eq!(
+            user.profile_banner_url_at(BannerSize::Large).as_deref(),
+            Some("https://pbs.twimg.com/profile_banners/2244994945/1690/1500x500")
+        );
+        assert_eq!(
+            user.profile_banner_url_at(BannerSize::MobileRetina)
+                .as_deref(),
+            Some("https://pbs.twimg.com/profile_banners/2244994945/1690/mobile_retina")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_and_save_profile_image() {
+        let url = format!("{}/profile_images/1/abc_normal.jpg", image_server(b"jpeg"));
+        let user = user(Some(&url), None);
+        let client = TweetyClient::from_bearer_token("token");
+
+        let bytes = client.download_profile_image(&user).await.unwrap();
+        assert_eq!(bytes, b"jpeg");
+
+        let path = std::env::temp_dir().join("tweety_profile_image_test.jpg");
+        client.save_profile_image(&user, &path).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"jpeg");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_download_banner_without_field_is_a_config_error() {
+        let client = TweetyClient::from_bearer_token("token");
+        let result = client
+            .download_profile_banner(&user(None, None), BannerSize::Web)
+            .await;
+        assert!(matches!(result, Err(TweetyError::ConfigError(_))));
+    }
+}
@@ -0,0 +1,162 @@
+use tweety_rs::api::bookmark;
+use tweety_rs::api::communities::{CommunityFields, CommunitySearchParams};
+#[cfg(feature = "dm")]
+use tweety_rs::api::direct_messages::{self, DMEventField, EventType};
+use tweety_rs::api::error::TweetyError;
+#[cfg(feature = "lists")]
+use tweety_rs::api::lists::ListFollowersParams;
+use tweety_rs::api::mentions::{ExpansionType, TweetField};
+use tweety_rs::api::search::{self, Expansion, MediaField};
+#[cfg(feature = "lists")]
+use tweety_rs::api::user::{Expansions, UserFields};
+#[cfg(feature = "v1")]
+use tweety_rs::api::v1::{GeoSearchParams, Granularity};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "lists")]
+    #[test]
+    fn test_list_followers_query_string() {
+        let params = ListFollowersParams {
+            expansions: Some(Expansions::PinnedTweetId),
+            max_results: Some(100),
+            pagination_token: Some("next".to_string()),
+            tweet_fields: None,
+            user_fields: Some(vec![UserFields::Username, UserFields::PublicMetrics]),
+        };
+
+        assert_eq!(
+            params.to_query_string(),
+            "expansions=pinned_tweet_id&max_results=100&pagination_token=next&user.fields=username,public_metrics"
+        );
+        assert_eq!(ListFollowersParams::default().to_query_string(), "");
+    }
+
+    #[test]
+    fn test_community_search_query_string() {
+        let params = CommunitySearchParams {
+            max_results: Some(10),
+            next_token: None,
+            community_fields: Some(vec![
+                CommunityFields::MemberCount,
+                CommunityFields::JoinPolicy,
+            ]),
+        };
+
+        assert_eq!(
+            params.to_query_string(),
+            "max_results=10&community.fields=member_count,join_policy"
+        );
+        assert_eq!(CommunitySearchParams::default().to_query_string(), "");
+    }
+
+    #[cfg(feature = "v1")]
+    #[test]
+    fn test_geo_search_query_string() {
+        let params = GeoSearchParams {
+            query: Some("San Francisco, CA".to_string()),
+            granularity: Some(Granularity::City),
+            max_results: Some(5),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            params.to_query_string(),
+            "query=San+Francisco,+CA&granularity=city&max_results=5"
+        );
+    }
+
+    #[test]
+    fn test_search_query_string_joins_lists() {
+        let params = search::QueryParams {
+            query: "from:rustlang #rust".to_string(),
+            expansions: Some(vec![Expansion::AuthorId, Expansion::AttachmentsMediaKeys]),
+            media_fields: Some(vec![MediaField::Url, MediaField::AltText]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            params.to_query_string().unwrap(),
+            "expansions=author_id,attachments.media_keys&media.fields=url,alt_text&query=from%3Arustlang+%23rust"
+        );
+    }
+
+    #[cfg(feature = "dm")]
+    #[test]
+    fn test_direct_messages_query_string() {
+        let params = direct_messages::QueryParams {
+            dm_event_fields: Some(vec![DMEventField::Id, DMEventField::SenderId]),
+            event_types: None,
+            expansions: None,
+            max_results: Some(50),
+            media_fields: None,
+            pagination_token: None,
+            tweet_fields: None,
+            user_fields: None,
+        };
+
+        assert_eq!(
+            params.to_query_string(),
+            "dm_event.fields=id,sender_id&max_results=50"
+        );
+    }
+
+    #[cfg(feature = "dm")]
+    #[test]
+    fn test_direct_messages_event_types_and_pagination() {
+        let params = direct_messages::QueryParams {
+            event_types: Some(vec![EventType::MessageCreate, EventType::ParticipantsJoin]),
+            expansions: Some(vec![
+                direct_messages::Expansion::SenderId,
+                direct_messages::Expansion::ReferencedTweetsId,
+            ]),
+            pagination_token: Some("next-page".to_string()),
+            ..direct_messages::QueryParams::default()
+        };
+
+        assert_eq!(
+            params.to_query_string(),
+            "event_types=MessageCreate,ParticipantsJoin&expansions=sender_id,referenced_tweets.id&pagination_token=next-page"
+        );
+    }
+
+    #[test]
+    fn test_field_names_round_trip() {
+        let expansion: ExpansionType = "referenced_tweets.id".parse().unwrap();
+        assert_eq!(expansion.to_string(), "referenced_tweets.id");
+
+        let field: TweetField = "public_metrics".parse().unwrap();
+        assert_eq!(field.to_string(), "public_metrics");
+        let json = serde_json::to_string(&field).unwrap();
+        assert_eq!(json, "\"public_metrics\"");
+        assert_eq!(
+            serde_json::from_str::<TweetField>(&json)
+                .unwrap()
+                .to_string(),
+            "public_metrics"
+        );
+
+        let expansion: bookmark::Expansions = "attachments.media_keys".parse().unwrap();
+        assert_eq!(expansion.to_string(), "attachments.media_keys");
+        assert_eq!(Expansion::GeoPlaceId.to_string(), "geo.place_id");
+    }
+
+    #[test]
+    fn test_unknown_field_name_is_a_config_error() {
+        match "favourite_count".parse::<TweetField>() {
+            Err(TweetyError::ConfigError(message)) => {
+                assert_eq!(message, "unknown TweetField `favourite_count`")
+            }
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "dm")]
+    #[test]
+    fn test_dm_event_type_round_trips() {
+        let event_type: EventType = "MessageCreate".parse().unwrap();
+        assert_eq!(event_type.to_string(), "MessageCreate");
+    }
+}
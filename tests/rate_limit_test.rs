@@ -0,0 +1,196 @@
+mod common;
+
+#[cfg(feature = "v1")]
+use common::Request;
+use common::{serve, Response};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tweety_rs::api::endpoint::RateLimitFamily;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::rate_limit::RateLimitStatus;
+use tweety_rs::TweetyClient;
+
+const USER: &str = r#"{"data":{"id":"1","name":"a","username":"a"}}"#;
+
+/// An answer carrying a window of 300 calls with `remaining` left until `reset`.
+fn windowed(body: impl Into<Vec<u8>>, remaining: u32, reset: u64) -> Response {
+    Response::ok(body)
+        .header("x-rate-limit-limit", 300)
+        .header("x-rate-limit-remaining", remaining)
+        .header("x-rate-limit-reset", reset)
+}
+
+/// Answers every request with an empty user lookup and the given rate-limit headers,
+/// counting `remaining` down from 299, and returns the server's base URL.
+fn rate_limited_server(reset: u64) -> String {
+    let mut remaining = 300;
+    serve(move |_| {
+        remaining -= 1;
+        windowed(USER, remaining, reset)
+    })
+    .0
+}
+
+/// Like [`rate_limited_server`], except `/1.1/application/rate_limit_status.json`
+/// reports `/geo/search` with 15 calls left.
+#[cfg(feature = "v1")]
+fn rate_limit_status_server(reset: u64) -> (String, std::sync::mpsc::Receiver<Request>) {
+    serve(move |request| {
+        let body = match request.path() {
+            "/1.1/application/rate_limit_status.json" => format!(
+                r#"{{"rate_limit_context":{{"application":"abc"}},"resources":{{"geo":{{"/geo/search":{{"limit":15,"remaining":15,"reset":{reset}}},"/geo/id/:place_id":{{"limit":75,"remaining":75,"reset":{reset}}}}}}}}}"#
+            ),
+            _ => USER.to_string(),
+        };
+        windowed(body, 299, reset)
+    })
+}
+
+/// Answers every request with a 429 carrying `headers`.
+fn too_many_requests_server(headers: Vec<(&str, String)>) -> String {
+    let response = headers.into_iter().fold(
+        Response::new(
+            "429 Too Many Requests",
+            r#"{"title":"Too Many Requests","status":429}"#,
+        ),
+        |response, (name, value)| response.header(name, value),
+    );
+    serve(move |_| response.clone()).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rate_limits_published_after_each_response() {
+        let reset = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 900;
+        let client =
+            TweetyClient::from_bearer_token("token").with_base_url(&rate_limited_server(reset));
+        let mut rate_limits = client.rate_limits();
+        assert!(client.rate_limit(RateLimitFamily::UserLookup).is_none());
+
+        client.get_user_by_id("1", None).await.unwrap();
+        assert!(rate_limits.has_changed().unwrap());
+        let status = rate_limits.borrow_and_update()[&RateLimitFamily::UserLookup];
+        assert_eq!((status.limit, status.remaining), (300, 299));
+        assert_eq!(status.reset, UNIX_EPOCH + Duration::from_secs(reset));
+        assert!(!status.is_exhausted());
+        assert!(status
+            .to_string()
+            .starts_with("299/300 remaining, resets in "));
+
+        client.clone().get_user_by_id("1", None).await.unwrap();
+        let status = client.rate_limit(RateLimitFamily::UserLookup).unwrap();
+        assert_eq!(status.remaining, 298);
+    }
+
+    #[test]
+    fn test_pace_spreads_the_rest_of_the_window() {
+        let status = |remaining| RateLimitStatus {
+            limit: 300,
+            remaining,
+            reset: SystemTime::now() + Duration::from_secs(600),
+        };
+
+        assert_eq!(status(200).pace(), Duration::ZERO);
+        let paced = status(100).pace();
+        assert!(paced > Duration::from_secs(5) && paced <= Duration::from_secs(6));
+        assert!(status(0).pace() > Duration::from_secs(590));
+
+        let reset = RateLimitStatus {
+            reset: SystemTime::now() - Duration::from_secs(1),
+            ..status(0)
+        };
+        assert_eq!(reset.pace(), Duration::ZERO);
+    }
+
+    #[cfg(feature = "v1")]
+    #[tokio::test]
+    async fn test_rate_limit_status_merges_with_headers() {
+        let reset = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 900;
+        let (base_url, requests) = rate_limit_status_server(reset);
+        let client = TweetyClient::from_bearer_token("token").with_base_url(&base_url);
+        client.get_user_by_id("1", None).await.unwrap();
+        requests.recv().unwrap();
+
+        let families = [
+            RateLimitFamily::Geo,
+            RateLimitFamily::UserLookup,
+            RateLimitFamily::Spaces,
+        ];
+        let report = client.v1().get_rate_limit_status(&families).await.unwrap();
+
+        assert_eq!(
+            requests.recv().unwrap().target,
+            "/1.1/application/rate_limit_status.json?resources=geo"
+        );
+        assert_eq!(report.families[&RateLimitFamily::Geo].limit, 15);
+        assert_eq!(report.families[&RateLimitFamily::UserLookup].limit, 300);
+        assert_eq!(report.resources["/geo/id/:place_id"].remaining, 75);
+        assert_eq!(
+            report.unknown(&families).collect::<Vec<_>>(),
+            [RateLimitFamily::Spaces]
+        );
+        assert_eq!(
+            client.rate_limit(RateLimitFamily::Geo).unwrap().remaining,
+            15
+        );
+        assert_eq!(
+            client
+                .rate_limit(RateLimitFamily::Application)
+                .unwrap()
+                .limit,
+            300
+        );
+    }
+
+    #[tokio::test]
+    async fn test_too_many_requests_carries_the_wait() {
+        let now = SystemTime::now();
+        let reset = now.duration_since(UNIX_EPOCH).unwrap().as_secs() + 300;
+        let retry_date = httpdate::fmt_http_date(now + Duration::from_secs(120));
+        let cases = [
+            (
+                vec![
+                    ("Retry-After", "7".to_string()),
+                    ("x-rate-limit-reset", reset.to_string()),
+                ],
+                7..=7,
+            ),
+            (vec![("Retry-After", retry_date)], 118..=120),
+            (vec![("x-rate-limit-reset", reset.to_string())], 298..=300),
+            (vec![], 60..=60),
+        ];
+
+        for (headers, expected) in cases {
+            let client = TweetyClient::from_bearer_token("token")
+                .with_base_url(&too_many_requests_server(headers));
+            let err = client.get_user_by_id("1", None).await.unwrap_err();
+
+            let TweetyError::RateLimited {
+                family,
+                retry_after,
+                ..
+            } = err
+            else {
+                panic!("expected a RateLimited error, got {:?}", err);
+            };
+            assert_eq!(family, RateLimitFamily::UserLookup);
+            assert!(
+                expected.contains(&retry_after.as_secs()),
+                "{:?}",
+                retry_after
+            );
+            assert_eq!(err.status().unwrap().as_u16(), 429);
+            assert_eq!(err.rate_limit_family(), Some(RateLimitFamily::UserLookup));
+        }
+    }
+}
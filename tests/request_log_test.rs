@@ -0,0 +1,136 @@
+#![cfg(feature = "request-log")]
+
+mod common;
+
+use common::serve_status;
+use log::{Log, Metadata, Record};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use std::sync::{Mutex, Once};
+use tweety_rs::api::endpoint::Endpoint;
+use tweety_rs::api::request_log::{RequestLog, Verbosity, LOG_TARGET, REDACTED};
+use tweety_rs::TweetyClient;
+use url::Url;
+
+/// Keeps the messages logged under [`LOG_TARGET`].
+struct CaptureLogger {
+    messages: Mutex<Vec<String>>,
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target() == LOG_TARGET
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.messages
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CaptureLogger = CaptureLogger {
+    messages: Mutex::new(Vec::new()),
+};
+
+fn install_logger() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+    });
+}
+
+/// Answers every request with `{"data":{"id":"1"}}`.
+fn server() -> String {
+    serve_status("200 OK", r#"{"data":{"id":"1"}}"#).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_redacts_token_params() {
+        let log = RequestLog::new(Verbosity::Url).redact_param("Email");
+        let url =
+            Url::parse("https://api.x.com/oauth/access_token?oauth_token=abc&email=a%40b.c&x=1")
+                .unwrap();
+
+        assert_eq!(
+            log.url(&url),
+            "https://api.x.com/oauth/access_token?oauth_token=REDACTED&email=REDACTED&x=1"
+        );
+        let plain = Url::parse("https://api.x.com/2/tweets?ids=1%2C2").unwrap();
+        assert_eq!(log.url(&plain), plain.as_str());
+    }
+
+    #[test]
+    fn test_headers_and_bodies_are_redacted() {
+        let log = RequestLog::new(Verbosity::Body).redact_header("X-Api-Key");
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        headers.insert("x-api-key", HeaderValue::from_static("secret"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let logged = log.headers(&headers);
+        assert!(!logged.contains("secret"));
+        assert!(logged.contains("authorization: REDACTED"));
+        assert!(logged.contains("content-type: application/json"));
+
+        let json = log.body(
+            Some("application/json"),
+            br#"{"text":"hi","auth":{"access_token":"secret"}}"#,
+        );
+        assert_eq!(json, r#"{"auth":{"access_token":"REDACTED"},"text":"hi"}"#);
+        let form = log.body(
+            Some("application/x-www-form-urlencoded"),
+            b"grant_type=refresh_token&refresh_token=secret",
+        );
+        assert_eq!(form, "grant_type=refresh_token&refresh_token=REDACTED");
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_logged_without_credentials() {
+        install_logger();
+        let base_url = server();
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&base_url)
+            .with_request_log(RequestLog::new(Verbosity::Body));
+
+        let _: serde_json::Value = client
+            .send_request_as(
+                &Endpoint::CreateTweet,
+                &[],
+                Some(serde_json::json!({ "text": "logged" })),
+            )
+            .await
+            .unwrap();
+
+        let messages = LOGGER.messages.lock().unwrap().clone();
+        let request = messages
+            .iter()
+            .find(|message| message.starts_with("POST ") && !message.contains(" -> "))
+            .unwrap();
+        assert!(request.contains(&format!("authorization: {}", REDACTED)));
+        assert!(!request.contains("oauth_signature"));
+        assert!(request.ends_with(r#"{"text":"logged"}"#));
+        assert!(messages
+            .iter()
+            .any(|message| message.contains("/2/tweets -> 200 OK in ")));
+
+        let _: serde_json::Value = client
+            .tagged("composer")
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1")], None)
+            .await
+            .unwrap();
+        let messages = LOGGER.messages.lock().unwrap().clone();
+        assert!(messages
+            .iter()
+            .any(|message| message.starts_with("[composer] GET ") && message.contains(" -> ")));
+    }
+}
@@ -0,0 +1,81 @@
+use reqwest::StatusCode;
+use std::time::Duration;
+use tweety_rs::api::endpoint::RateLimitFamily;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::retry::{
+    DefaultRetryClassifier, RetryClassifier, RetryDecision, RATE_LIMIT_WAIT,
+};
+
+fn api_error(status: u16, body: &str) -> TweetyError {
+    TweetyError::ApiError {
+        status: StatusCode::from_u16(status).unwrap(),
+        body: body.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_retry_classification() {
+        let classify = |err: TweetyError| DefaultRetryClassifier.classify(&err);
+
+        assert_eq!(
+            classify(api_error(
+                403,
+                r#"{"detail":"You are not allowed to create a Tweet with duplicate content."}"#
+            )),
+            RetryDecision::Permanent
+        );
+        assert_eq!(
+            classify(api_error(429, "Too Many Requests")),
+            RetryDecision::RetryAfter(RATE_LIMIT_WAIT)
+        );
+        assert_eq!(classify(api_error(503, "{}")), RetryDecision::Retry);
+        assert_eq!(
+            classify(TweetyError::MissingCredentials),
+            RetryDecision::Permanent
+        );
+        assert_eq!(
+            classify(TweetyError::RateLimited {
+                family: RateLimitFamily::RecentSearch,
+                retry_after: Duration::from_secs(42),
+                body: String::new(),
+            }),
+            RetryDecision::RetryAfter(Duration::from_secs(42))
+        );
+        assert_eq!(
+            classify(TweetyError::CircuitOpen {
+                family: RateLimitFamily::ManageTweets,
+                retry_after: Duration::from_secs(5),
+            }),
+            RetryDecision::RetryAfter(Duration::from_secs(5))
+        );
+    }
+
+    #[cfg(feature = "streams")]
+    #[tokio::test]
+    async fn test_filtered_stream_consults_the_classifier() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tweety_rs::api::filtered_stream::FilteredStream;
+        use tweety_rs::api::tier::Tier;
+        use tweety_rs::TweetyClient;
+
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url("http://127.0.0.1:1")
+            .with_tier(Tier::Basic);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = calls.clone();
+        let mut stream = FilteredStream::new(&client).retry_classifier(move |_: &TweetyError| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            RetryDecision::Permanent
+        });
+
+        let result = stream.run(|_| {}).await;
+
+        assert!(matches!(result, Err(TweetyError::TierLimit { .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,227 @@
+#![cfg(feature = "schedule")]
+
+mod common;
+
+use common::{serve, Request, Response};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tweety_rs::api::schedule::{Cron, Scheduler};
+use tweety_rs::api::shutdown::Shutdown;
+use tweety_rs::api::state::{FileStateStore, MemoryStateStore, StateStore};
+use tweety_rs::TweetyClient;
+
+const CREATED: &str = r#"{"data":{"id":"1445880548472328192","text":"digest","edit_history_tweet_ids":["1445880548472328192"]}}"#;
+const DELETED: &str = r#"{"data":{"deleted":true}}"#;
+
+/// Friday 15 March 2024, 08:30 UTC.
+const FRIDAY_0830: u64 = 1_710_491_400;
+const FRIDAY_0900: u64 = 1_710_493_200;
+const SATURDAY_0900: u64 = 1_710_579_600;
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+fn next(expression: &str, after: u64) -> Option<SystemTime> {
+    expression.parse::<Cron>().unwrap().next_after(at(after))
+}
+
+/// Answers `DELETE`s with a deleted tweet and other requests with a created one.
+fn server() -> (String, mpsc::Receiver<Request>) {
+    serve(|request| match request.method.as_str() {
+        "DELETE" => Response::ok(DELETED),
+        _ => Response::new("201 Created", CREATED),
+    })
+}
+
+fn scheduler(base_url: &str, store: impl StateStore + 'static) -> Scheduler {
+    let client =
+        TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url);
+    Scheduler::new(client).state_store(store, "schedule").job(
+        "digest",
+        "0 9 * * *".parse().unwrap(),
+        |run| async move {
+            Ok(Some(format!(
+                "{} digest for {}",
+                run.name,
+                run.scheduled_at_secs()
+            )))
+        },
+    )
+}
+
+/// A fresh path for a state file.
+fn state_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tweety-schedule-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_after() {
+        assert_eq!(next("0 9 * * *", FRIDAY_0830), Some(at(FRIDAY_0900)));
+        assert_eq!(next("0 9 * * *", FRIDAY_0900), Some(at(SATURDAY_0900)));
+        assert_eq!(next("@daily", FRIDAY_0830), Some(at(1_710_547_200)));
+        assert_eq!(next("*/15 * * * *", FRIDAY_0830), Some(at(1_710_492_300)));
+        assert_eq!(next("5/30 8-9 * * *", FRIDAY_0830), Some(at(1_710_491_700)));
+        // Monday 18 March.
+        assert_eq!(next("0 9 * * 1-5", FRIDAY_0900), Some(at(1_710_752_400)));
+        assert_eq!(
+            next("0 9 * * 7", FRIDAY_0900),
+            next("0 9 * * 0", FRIDAY_0900)
+        );
+        // The 1st of the month or a Sunday: Sunday 17 March comes first.
+        assert_eq!(next("0 0 1 * 0", FRIDAY_0830), Some(at(1_710_633_600)));
+        assert_eq!(next("@monthly", FRIDAY_0830), Some(at(1_711_929_600)));
+        assert_eq!(next("0 0 29 2 *", FRIDAY_0830), Some(at(1_835_395_200)));
+        assert_eq!(next("0 0 31 2 *", FRIDAY_0830), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_expressions() {
+        for expression in [
+            "0 9 * *",
+            "0 9 * * * *",
+            "60 * * * *",
+            "0 24 * * *",
+            "0 0 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "1,,2 * * * *",
+        ] {
+            assert!(Cron::parse(expression).is_err(), "{}", expression);
+        }
+
+        let cron = Cron::parse(" 0 9 * * 1-5 ").unwrap();
+        assert_eq!(cron.to_string(), "0 9 * * 1-5");
+    }
+
+    #[tokio::test]
+    async fn test_posts_when_due_and_not_again_after_a_restart() {
+        let (base_url, requests) = server();
+        let path = state_path("restart.json");
+        let first = scheduler(&base_url, FileStateStore::new(&path));
+
+        assert_eq!(first.run_due(at(FRIDAY_0830)).await.unwrap(), 0);
+        assert_eq!(first.next_run("digest"), Some(at(FRIDAY_0900)));
+        assert_eq!(first.run_due(at(FRIDAY_0900 + 30)).await.unwrap(), 1);
+        let body = requests.recv().unwrap().json();
+        assert_eq!(body["text"], format!("digest digest for {}", FRIDAY_0900));
+        assert_eq!(first.last_run("digest"), Some(at(FRIDAY_0900)));
+        assert_eq!(first.next_run("digest"), Some(at(SATURDAY_0900)));
+
+        let restarted = scheduler(&base_url, FileStateStore::new(&path));
+        assert_eq!(restarted.run_due(at(FRIDAY_0900 + 40)).await.unwrap(), 0);
+        assert_eq!(restarted.last_run("digest"), Some(at(FRIDAY_0900)));
+        assert_eq!(restarted.next_run("digest"), Some(at(SATURDAY_0900)));
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_missed_runs_are_skipped_unless_caught_up_on() {
+        let (base_url, requests) = server();
+        let noon = FRIDAY_0900 + 3 * 3600;
+        // Last ran on Thursday.
+        let thursday = r#"{"last_runs":{"digest":1710406800}}"#;
+
+        let store = MemoryStateStore::default();
+        store.set("schedule", thursday).await.unwrap();
+        let skipping = scheduler(&base_url, store);
+        assert_eq!(skipping.run_due(at(noon)).await.unwrap(), 0);
+        assert_eq!(skipping.next_run("digest"), Some(at(SATURDAY_0900)));
+
+        let store = MemoryStateStore::default();
+        store.set("schedule", thursday).await.unwrap();
+        let catching_up = scheduler(&base_url, store).catch_up();
+        assert_eq!(catching_up.run_due(at(noon)).await.unwrap(), 1);
+        let body = requests.recv().unwrap().json();
+        assert_eq!(body["text"], format!("digest digest for {}", FRIDAY_0900));
+        assert_eq!(catching_up.next_run("digest"), Some(at(SATURDAY_0900)));
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_callback_returning_none_skips_the_post() {
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url("http://127.0.0.1:1");
+        let scheduler =
+            Scheduler::new(client).job("quiet", "*/5 * * * *".parse().unwrap(), |_| async {
+                Ok(None)
+            });
+
+        assert_eq!(scheduler.run_due(at(FRIDAY_0830)).await.unwrap(), 0);
+        assert_eq!(scheduler.run_due(at(FRIDAY_0830 + 300)).await.unwrap(), 0);
+        assert_eq!(scheduler.last_run("quiet"), Some(at(FRIDAY_0830 + 300)));
+        assert_eq!(scheduler.next_run("quiet"), Some(at(FRIDAY_0830 + 600)));
+    }
+
+    #[tokio::test]
+    async fn test_ephemeral_tweet_is_deleted_after_a_restart() {
+        let (base_url, requests) = server();
+        let path = state_path("ephemeral.json");
+        let first = scheduler(&base_url, FileStateStore::new(&path));
+
+        let posted = first
+            .post_ephemeral_tweet("live now", Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(posted.data.id, "1445880548472328192");
+        assert!(requests.recv().unwrap().line.starts_with("POST /2/tweets"));
+        let pending = first.pending_deletions();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].tweet_id, "1445880548472328192");
+        first.run_due(SystemTime::now()).await.unwrap();
+        assert!(requests.try_recv().is_err());
+
+        let restarted = scheduler(&base_url, FileStateStore::new(&path));
+        restarted
+            .run_due(SystemTime::now() + Duration::from_secs(61))
+            .await
+            .unwrap();
+        assert!(requests
+            .recv()
+            .unwrap()
+            .line
+            .starts_with("DELETE /2/tweets/1445880548472328192"));
+        assert!(restarted.pending_deletions().is_empty());
+
+        let again = scheduler(&base_url, FileStateStore::new(&path));
+        again.run_due(SystemTime::now()).await.unwrap();
+        assert!(again.pending_deletions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_running_scheduler_wakes_for_new_deletions() {
+        let (base_url, requests) = server();
+        let shutdown = Shutdown::new();
+        let scheduler = scheduler(&base_url, MemoryStateStore::default()).shutdown_on(&shutdown);
+        let worker = scheduler.clone();
+        let running = tokio::spawn(async move { worker.run().await });
+
+        scheduler
+            .post_ephemeral_tweet("gone soon", Duration::ZERO)
+            .await
+            .unwrap();
+        let request = tokio::task::spawn_blocking(move || {
+            requests.recv().unwrap();
+            requests.recv_timeout(Duration::from_secs(5)).unwrap()
+        })
+        .await
+        .unwrap();
+        assert!(request
+            .line
+            .starts_with("DELETE /2/tweets/1445880548472328192"));
+
+        shutdown.shutdown().await;
+        assert!(running.await.unwrap().is_ok());
+        assert!(scheduler.pending_deletions().is_empty());
+    }
+}
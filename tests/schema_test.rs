@@ -0,0 +1,36 @@
+use serde_json::json;
+use tweety_rs::api::mentions::TweetData;
+
+fn tweet_with_unknown_field() -> serde_json::Value {
+    json!({
+        "id": "1",
+        "edit_history_tweet_ids": ["1"],
+        "text": "hello",
+        "community_id": "42"
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "strict-schema"))]
+    #[test]
+    fn test_unknown_fields_are_kept_in_extra() {
+        let tweet: TweetData = serde_json::from_value(tweet_with_unknown_field()).unwrap();
+
+        assert_eq!(tweet.extra.get("community_id"), Some(&json!("42")));
+        assert!(!tweet.extra.contains_key("text"));
+
+        let round_trip = serde_json::to_value(&tweet).unwrap();
+        assert_eq!(round_trip["community_id"], "42");
+    }
+
+    #[cfg(feature = "strict-schema")]
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        let result = serde_json::from_value::<TweetData>(tweet_with_unknown_field());
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::serve_status;
+use std::net::TcpListener;
+use std::time::Duration;
+use tweety_rs::api::blocklist::{BlocklistImport, BlocklistKind};
+use tweety_rs::api::bot::MentionListener;
+use tweety_rs::api::shutdown::Shutdown;
+use tweety_rs::TweetyClient;
+
+/// Accepts every block and returns the server's base URL.
+fn blocking_server() -> String {
+    serve_status("200 OK", r#"{"data":{"blocking":true}}"#).0
+}
+
+/// A base URL nothing listens on.
+fn closed_port() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_without_components_resolves() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_requested());
+        shutdown.shutdown().await;
+        assert!(shutdown.is_requested());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_mention_listener_between_polls() {
+        let shutdown = Shutdown::new();
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&closed_port());
+        let mut listener = MentionListener::new(client)
+            .poll_interval(Duration::from_secs(3600))
+            .shutdown_on(&shutdown);
+        let run = tokio::spawn(async move { listener.run().await });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        tokio::time::timeout(Duration::from_secs(5), shutdown.shutdown())
+            .await
+            .unwrap();
+        assert!(run.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_saves_import_progress() {
+        let dir = std::env::temp_dir();
+        let list = dir.join("tweety_shutdown_import_test.txt");
+        let progress = dir.join("tweety_shutdown_import_test.progress");
+        std::fs::write(&list, "1\n2\n3\n").unwrap();
+        let _ = std::fs::remove_file(&progress);
+
+        let shutdown = Shutdown::new();
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&blocking_server());
+        let mut import = BlocklistImport::new(client, BlocklistKind::Block, &list)
+            .unwrap()
+            .delay(Duration::from_secs(3600))
+            .persist_to(&progress)
+            .unwrap()
+            .shutdown_on(&shutdown);
+        let run = tokio::spawn(async move { import.run("100").await });
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        tokio::time::timeout(Duration::from_secs(5), shutdown.shutdown())
+            .await
+            .unwrap();
+        let summary = run.await.unwrap().unwrap();
+        assert_eq!(summary.applied, 1);
+        assert_eq!(std::fs::read_to_string(&progress).unwrap(), "1");
+
+        let _ = std::fs::remove_file(list);
+        let _ = std::fs::remove_file(progress);
+    }
+}
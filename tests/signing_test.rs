@@ -0,0 +1,239 @@
+mod common;
+
+use common::{serve, Request, Response};
+use reqwest::Method;
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tweety_rs::api::endpoint::Endpoint;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::signing::{sign_request, sign_request_at, OAuth1Credentials};
+use tweety_rs::TweetyClient;
+
+/// The credentials of X's signature guide.
+fn guide_client() -> TweetyClient {
+    TweetyClient::new(
+        "xvz1evFS4wEEPTGEFPHBog",
+        "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb",
+        "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw",
+        "LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE",
+    )
+}
+
+/// Answers the first request with a 401 dated `skew` from now and the rest with `{}`.
+fn skewed_server(skew: Duration) -> (String, mpsc::Receiver<Request>) {
+    let mut answered = 0;
+    serve(move |_| {
+        answered += 1;
+        let response = match answered {
+            1 => Response::new(
+                "401 Unauthorized",
+                r#"{"title":"Unauthorized","status":401}"#,
+            ),
+            _ => Response::ok("{}"),
+        };
+        response.header("Date", httpdate::fmt_http_date(SystemTime::now() + skew))
+    })
+}
+
+/// The `oauth_timestamp` `request` was signed with.
+fn oauth_timestamp(request: &Request) -> u64 {
+    let authorization = request.header("Authorization").unwrap_or_default();
+    oauth_parameter(authorization, "oauth_timestamp")
+        .parse()
+        .unwrap_or_default()
+}
+
+/// The value of `oauth_*` parameter `name` in an `Authorization` header.
+fn oauth_parameter(authorization: &str, name: &str) -> String {
+    authorization
+        .split(&format!("{}=\"", name))
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inspection_matches_signature_guide() {
+        let inspection = guide_client()
+            .inspect_signature_at(
+                Method::POST,
+                "https://api.twitter.com/1.1/statuses/update.json?include_entities=true&status=Hello%20Ladies%20%2B%20Gentlemen%2C%20a%20signed%20OAuth%20request%21",
+                "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg",
+                1318622958,
+            )
+            .unwrap();
+
+        assert_eq!(
+            inspection.base_url,
+            "https://api.twitter.com/1.1/statuses/update.json"
+        );
+        assert_eq!(
+            inspection.normalized_parameters,
+            "include_entities=true&oauth_consumer_key=xvz1evFS4wEEPTGEFPHBog&oauth_nonce=kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg&oauth_signature_method=HMAC-SHA1&oauth_timestamp=1318622958&oauth_token=370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb&status=Hello%20Ladies%20%2B%20Gentlemen%2C%20a%20signed%20OAuth%20request%21"
+        );
+        assert_eq!(
+            inspection.base_string,
+            "POST&https%3A%2F%2Fapi.twitter.com%2F1.1%2Fstatuses%2Fupdate.json&include_entities%3Dtrue%26oauth_consumer_key%3Dxvz1evFS4wEEPTGEFPHBog%26oauth_nonce%3DkYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg%26oauth_signature_method%3DHMAC-SHA1%26oauth_timestamp%3D1318622958%26oauth_token%3D370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb%26status%3DHello%2520Ladies%2520%252B%2520Gentlemen%252C%2520a%2520signed%2520OAuth%2520request%2521"
+        );
+        // HMAC-SHA1 of the base string above, so the header was signed over it.
+        assert!(inspection
+            .authorization
+            .contains("oauth_signature=\"PDAgbKh4%2FK8%2FIq0aD2RCV8xh8zc%3D\""));
+    }
+
+    #[test]
+    fn test_sign_request_signs_form_params() {
+        let credentials = guide_client().oauth1_credentials().unwrap();
+
+        let authorization = sign_request_at(
+            &Method::POST,
+            "https://api.twitter.com/1.1/statuses/update.json?include_entities=true",
+            &[(
+                "status",
+                "Hello Ladies + Gentlemen, a signed OAuth request!",
+            )],
+            &credentials,
+            "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg",
+            1318622958,
+        )
+        .unwrap();
+
+        // The same signature as with the status in the query, see above.
+        assert!(authorization.starts_with("OAuth "));
+        assert!(authorization.contains("oauth_signature=\"PDAgbKh4%2FK8%2FIq0aD2RCV8xh8zc%3D\""));
+        assert!(authorization
+            .contains("oauth_token=\"370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb\""));
+    }
+
+    #[test]
+    fn test_sign_request_without_user() {
+        let credentials = OAuth1Credentials::consumer_only("key", "secret");
+        assert!(!format!("{:?}", credentials).contains("secret\""));
+
+        let authorization = sign_request(
+            &Method::POST,
+            "https://api.x.com/oauth/request_token?oauth_callback=oob",
+            &[],
+            &credentials,
+        )
+        .unwrap();
+        assert!(authorization.contains("oauth_consumer_key=\"key\""));
+        assert!(!authorization.contains("oauth_token="));
+
+        let missing = OAuth1Credentials::consumer_only("", "");
+        assert!(matches!(
+            sign_request(&Method::GET, "https://api.x.com/2/users/me", &[], &missing),
+            Err(TweetyError::MissingCredentials)
+        ));
+        assert!(TweetyClient::from_bearer_token("token")
+            .oauth1_credentials()
+            .is_none());
+    }
+
+    #[test]
+    fn test_nonces_are_random() {
+        let credentials = guide_client().oauth1_credentials().unwrap();
+        let sign = || {
+            let authorization = sign_request(
+                &Method::GET,
+                "https://api.x.com/2/users/me",
+                &[],
+                &credentials,
+            )
+            .unwrap();
+            oauth_parameter(&authorization, "oauth_nonce")
+        };
+
+        let first = sign();
+        assert!(first.len() >= 32, "{}", first);
+        assert_ne!(first, sign());
+
+        // The inspection signs with the nonce it reports.
+        let inspection = guide_client()
+            .inspect_signature(&Endpoint::Me, &[])
+            .unwrap();
+        let nonce = oauth_parameter(&inspection.authorization, "oauth_nonce");
+        assert!(nonce.len() >= 32, "{}", nonce);
+        assert!(inspection
+            .normalized_parameters
+            .contains(&format!("oauth_nonce={}", nonce)));
+    }
+
+    #[test]
+    fn test_inspection_redacts_secrets() {
+        let inspection = guide_client()
+            .inspect_signature(&Endpoint::Me, &[("user.fields", "created_at")])
+            .unwrap();
+        let report = inspection.to_string();
+
+        assert_eq!(inspection.signing_key, "kAcS****&Lsww****");
+        assert!(inspection.base_string.starts_with("GET&"));
+        assert!(!report.contains("kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw"));
+        assert!(!report.contains("LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE"));
+    }
+
+    #[test]
+    fn test_inspection_needs_oauth1_credentials() {
+        let client = TweetyClient::from_bearer_token("token");
+
+        let result = client.inspect_signature(&Endpoint::Me, &[]);
+
+        assert!(matches!(result, Err(TweetyError::MissingCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_clock_skew_is_corrected_and_retried() {
+        let (base_url, requests) = skewed_server(Duration::from_secs(3600));
+        let client = guide_client().with_base_url(&base_url);
+
+        let value: serde_json::Value = client
+            .send_request_as(&Endpoint::Me, &[], None)
+            .await
+            .unwrap();
+
+        assert_eq!(value, serde_json::json!({}));
+        assert!((3595..=3605).contains(&client.clock_skew()));
+        let first = oauth_timestamp(&requests.recv().unwrap());
+        let second = oauth_timestamp(&requests.recv().unwrap());
+        assert!(first.abs_diff(unix_now()) < 5);
+        assert!(second.abs_diff(unix_now() + 3600) < 5);
+
+        let inspection = client.inspect_signature(&Endpoint::Me, &[]).unwrap();
+        let timestamp: u64 = inspection
+            .normalized_parameters
+            .split("oauth_timestamp=")
+            .nth(1)
+            .and_then(|rest| rest.split('&').next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(timestamp.abs_diff(unix_now() + 3600) < 5);
+    }
+
+    #[tokio::test]
+    async fn test_small_skew_is_not_retried() {
+        let (base_url, requests) = skewed_server(Duration::from_secs(2));
+        let client = guide_client().with_base_url(&base_url);
+
+        let result = client
+            .send_request_as::<serde_json::Value>(&Endpoint::Me, &[], None)
+            .await;
+
+        assert!(matches!(result, Err(TweetyError::ApiError { .. })));
+        assert_eq!(client.clock_skew(), 0);
+        requests.recv().unwrap();
+        assert!(requests.try_recv().is_err());
+    }
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "spaces")]
+
+mod common;
+
+use common::{serve_in_order, Response};
+use std::time::Duration;
+use tweety_rs::api::endpoint::{Endpoint, EndpointAuth};
+use tweety_rs::api::oauth2::Scope;
+use tweety_rs::api::shutdown::Shutdown;
+use tweety_rs::api::spaces::{SpaceEvent, SpaceFields, SpaceState, SpacesResponse, SpacesWatcher};
+use tweety_rs::TweetyClient;
+
+/// Answers each request with the next body, repeating the last one.
+fn spaces_server(bodies: Vec<&'static str>) -> String {
+    serve_in_order(bodies.into_iter().map(Response::ok).collect()).0
+}
+
+const FIRST_POLL: &str = r#"{"data":[{"id":"1aaa","state":"scheduled","title":"Launch","creator_id":"2244994945","scheduled_start":"2026-10-16T17:00:00.000Z"},{"id":"1bbb","state":"live","creator_id":"2244994945","participant_count":12}],"meta":{"result_count":2}}"#;
+const SECOND_POLL: &str = r#"{"data":[{"id":"1aaa","state":"live","title":"Launch","creator_id":"2244994945","started_at":"2026-10-16T17:01:00.000Z"},{"id":"1ccc","state":"scheduled","creator_id":"2244994945"}],"meta":{"result_count":2}}"#;
+
+fn client(base_url: &str) -> TweetyClient {
+    TweetyClient::from_bearer_token("token").with_base_url(base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spaces_by_creator_ids_endpoint() {
+        let endpoint = Endpoint::SpacesByCreatorIds;
+        assert_eq!(endpoint.url(), "https://api.x.com/2/spaces/by/creator_ids");
+        assert_eq!(endpoint.required_auth(), EndpointAuth::Any);
+        assert!(endpoint.scopes().contains(&Scope::SpaceRead));
+        assert_eq!(SpaceFields::ScheduledStart.to_string(), "scheduled_start");
+    }
+
+    #[test]
+    fn test_spaces_response_parses() {
+        let response: SpacesResponse = serde_json::from_str(FIRST_POLL).unwrap();
+        assert_eq!(response.data.len(), 2);
+        assert_eq!(response.data[0].state, SpaceState::Scheduled);
+        assert_eq!(response.data[0].url(), "https://x.com/i/spaces/1aaa");
+        assert_eq!(response.data[1].participant_count, Some(12));
+
+        let empty: SpacesResponse = serde_json::from_str(r#"{"meta":{"result_count":0}}"#).unwrap();
+        assert!(empty.data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watcher_reports_changes_after_first_poll() {
+        let base_url = spaces_server(vec![FIRST_POLL, SECOND_POLL]);
+        let mut watcher = SpacesWatcher::new(client(&base_url)).host("2244994945");
+
+        assert!(watcher.poll_once().await.unwrap().is_empty());
+
+        let events = watcher.poll_once().await.unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(&events[0], SpaceEvent::Started(space) if space.id == "1aaa"));
+        assert!(
+            matches!(&events[1], SpaceEvent::Ended(space) if space.id == "1bbb" && space.state == SpaceState::Ended)
+        );
+        assert!(matches!(&events[2], SpaceEvent::Scheduled(space) if space.id == "1ccc"));
+
+        assert!(watcher.poll_once().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watcher_replays_existing_spaces() {
+        let base_url = spaces_server(vec![FIRST_POLL]);
+        let mut watcher = SpacesWatcher::new(client(&base_url))
+            .host("2244994945")
+            .replay_existing(true);
+
+        let events = watcher.poll_once().await.unwrap();
+        assert!(matches!(&events[0], SpaceEvent::Scheduled(space) if space.id == "1aaa"));
+        assert!(matches!(&events[1], SpaceEvent::Started(space) if space.id == "1bbb"));
+    }
+
+    #[tokio::test]
+    async fn test_run_sends_events_until_shutdown() {
+        let base_url = spaces_server(vec![FIRST_POLL, SECOND_POLL]);
+        let shutdown = Shutdown::new();
+        let mut watcher = SpacesWatcher::new(client(&base_url))
+            .host("2244994945")
+            .interval(Duration::from_millis(10))
+            .shutdown_on(&shutdown);
+        let (events, mut received) = tokio::sync::mpsc::channel(16);
+        let run = tokio::spawn(async move { watcher.run(events).await });
+
+        let event = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.space().id, "1aaa");
+
+        tokio::time::timeout(Duration::from_secs(5), shutdown.shutdown())
+            .await
+            .unwrap();
+        assert!(run.await.unwrap().is_ok());
+    }
+}
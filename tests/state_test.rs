@@ -0,0 +1,134 @@
+mod common;
+
+use common::{serve_status, Request};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use tweety_rs::api::bot::MentionListener;
+use tweety_rs::api::state::{FileStateStore, MemoryStateStore, StateStore};
+use tweety_rs::TweetyClient;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("tweety-state-{}-{}", std::process::id(), name))
+}
+
+/// Answers every request with one mention.
+fn mentions_server() -> (String, mpsc::Receiver<Request>) {
+    serve_status(
+        "200 OK",
+        r#"{"data":[{"id":"1900000000000000002","edit_history_tweet_ids":["1900000000000000002"],"text":"@bot hi"}],"meta":{"result_count":1,"newest_id":"1900000000000000002","oldest_id":"1900000000000000002"}}"#,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_store_round_trip() {
+        let store = MemoryStateStore::new();
+        assert_eq!(store.get("mentions").await.unwrap(), None);
+
+        store.set("mentions", "1").await.unwrap();
+        store.set("mentions", "2").await.unwrap();
+        assert_eq!(store.get("mentions").await.unwrap().as_deref(), Some("2"));
+        assert_eq!(store.get("stream").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_file_store_survives_reopening() {
+        let path = temp_path("round-trip.json");
+        let _ = fs::remove_file(&path);
+
+        let store = FileStateStore::new(&path);
+        assert_eq!(store.get("mentions").await.unwrap(), None);
+        store.set("mentions", "1822274639129612288").await.unwrap();
+        store.set("stream", "1822274639129612290").await.unwrap();
+
+        let reopened = FileStateStore::new(&path);
+        assert_eq!(
+            reopened.get("mentions").await.unwrap().as_deref(),
+            Some("1822274639129612288")
+        );
+        assert_eq!(
+            reopened.get("stream").await.unwrap().as_deref(),
+            Some("1822274639129612290")
+        );
+
+        fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_file_store_rejects_corrupt_file() {
+        let path = temp_path("corrupt.json");
+        fs::write(&path, "not json").unwrap();
+
+        let store = FileStateStore::new(&path);
+        assert!(store.get("mentions").await.is_err());
+
+        fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_mention_listener_resumes_from_store() {
+        let path = temp_path("listener.json");
+        let _ = fs::remove_file(&path);
+        let (base_url, requests) = mentions_server();
+
+        // Without a stored cursor, the first poll only records the newest mention.
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+        let mut listener = MentionListener::new(client)
+            .user_id("2244994945")
+            .state_store(FileStateStore::new(&path), "mentions");
+        assert_eq!(listener.poll_once().await.unwrap(), 0);
+        assert!(!requests.recv().unwrap().target.contains("since_id"));
+
+        let store = FileStateStore::new(&path);
+        assert_eq!(
+            store.get("mentions").await.unwrap().as_deref(),
+            Some("1900000000000000002")
+        );
+
+        // A restarted listener polls from the stored cursor.
+        store.set("mentions", "1900000000000000001").await.unwrap();
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+        let mut listener = MentionListener::new(client)
+            .user_id("2244994945")
+            .state_store(FileStateStore::new(&path), "mentions");
+        assert_eq!(listener.poll_once().await.unwrap(), 1);
+        assert!(requests
+            .recv()
+            .unwrap()
+            .target
+            .contains("since_id=1900000000000000001"));
+        assert_eq!(listener.last_seen_id(), Some("1900000000000000002"));
+
+        fs::remove_file(path).ok();
+    }
+
+    #[cfg(feature = "streams")]
+    #[tokio::test]
+    async fn test_filtered_stream_gap_fills_from_store() {
+        use tweety_rs::api::error::TweetyError;
+        use tweety_rs::api::filtered_stream::FilteredStream;
+        use tweety_rs::api::tier::Tier;
+
+        let store = MemoryStateStore::new();
+        store.set("stream", "1800000000000000000").await.unwrap();
+
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url("http://127.0.0.1:1")
+            .with_tier(Tier::Basic);
+        let mut stream = FilteredStream::new(&client)
+            .gap_fill_query(&"a".repeat(600))
+            .state_store(store, "stream");
+
+        // The stored id triggers a gap fill, whose search fails the tier check.
+        let result = stream.connect(|_| {}).await;
+
+        assert!(matches!(result, Err(TweetyError::TierLimit { .. })));
+        assert_eq!(stream.last_tweet_id(), Some("1800000000000000000"));
+    }
+}
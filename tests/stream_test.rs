@@ -0,0 +1,95 @@
+#![cfg(feature = "streams")]
+
+use serde::Deserialize;
+use std::borrow::Cow;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::stream::LineParser;
+use tweety_rs::types::borrowed::{from_slice_borrowed, StreamTweetRef};
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_split_across_chunks() {
+        let mut parser = LineParser::<Message>::new();
+
+        assert!(parser.push(b"{\"id\":").is_empty());
+        assert!(parser.push(b"\"1\"").is_empty());
+        let items = parser.push(b"}\r\n{\"id\":\"2\"}\n{\"id\"");
+
+        let ids: Vec<_> = items.into_iter().map(|item| item.unwrap().id).collect();
+        assert_eq!(ids, ["1", "2"]);
+        assert_eq!(parser.push(b":\"3\"}\n")[0].as_ref().unwrap().id, "3");
+    }
+
+    #[test]
+    fn test_keep_alive_lines_are_skipped() {
+        let mut parser = LineParser::<Message>::new();
+
+        assert!(parser.push(b"\r\n\r\n\n").is_empty());
+        assert!(parser.finish().is_none());
+    }
+
+    #[test]
+    fn test_malformed_line_does_not_end_stream() {
+        let mut parser = LineParser::<Message>::new();
+        let items = parser.push(b"<html>\n{\"id\":\"1\"}\n");
+
+        assert!(matches!(
+            &items[0],
+            Err(TweetyError::MalformedStreamLine { line, .. }) if line == "<html>"
+        ));
+        assert_eq!(items[1].as_ref().unwrap().id, "1");
+    }
+
+    #[test]
+    fn test_oversized_line_is_skipped() {
+        let mut parser = LineParser::<Message>::new().max_line_bytes(16);
+
+        let items = parser.push(b"{\"id\":\"0123456789");
+        assert!(matches!(
+            items[..],
+            [Err(TweetyError::StreamLineTooLong { limit: 16 })]
+        ));
+        assert!(parser.push(b"0123456789\"}").is_empty());
+
+        let items = parser.push(b"\n{\"id\":\"1\"}\n");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].as_ref().unwrap().id, "1");
+    }
+
+    #[test]
+    fn test_finish_parses_unterminated_line() {
+        let mut parser = LineParser::<Message>::new();
+
+        assert!(parser.push(b"{\"id\":\"1\"}").is_empty());
+        assert_eq!(parser.finish().unwrap().unwrap().id, "1");
+    }
+
+    #[test]
+    fn test_borrowed_lines() {
+        let mut parser: LineParser = LineParser::new();
+        let mut texts = Vec::new();
+
+        let chunk =
+            br#"{"data":{"id":"1","text":"plain"},"matching_rules":[{"id":"9","tag":"rust"}]}
+{"data":{"id":"2","text":"caf\u00e9"}}
+"#;
+        parser.push_raw(chunk, |line| {
+            let tweet: StreamTweetRef = from_slice_borrowed(line.unwrap()).unwrap();
+            let borrowed = matches!(tweet.data.text, Cow::Borrowed(_));
+            texts.push((tweet.data.full_text().to_string(), borrowed));
+        });
+
+        assert_eq!(
+            texts,
+            [("plain".to_string(), true), ("café".to_string(), false)]
+        );
+    }
+}
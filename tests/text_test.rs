@@ -0,0 +1,84 @@
+use tweety_rs::api::text::{grapheme_count, graphemes, slice, truncate, truncate_with_ellipsis};
+
+const FAMILY: &str = "👨‍👩‍👧‍👦";
+const THUMBS_UP: &str = "👍🏽";
+const FLAG: &str = "🇯🇵";
+const KEYCAP: &str = "1️⃣";
+const ACCENTED: &str = "e\u{301}";
+const RAINBOW_FLAG: &str = "🏳️‍🌈";
+/// Bengali ki: a consonant and a vowel sign.
+const BENGALI: &str = "\u{995}\u{9BF}";
+/// Tamil ko: a consonant and a two-part vowel sign.
+const TAMIL: &str = "\u{B95}\u{BCA}";
+/// Hangul han spelled with three jamo.
+const HANGUL_JAMO: &str = "\u{1112}\u{1161}\u{11AB}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphemes_keep_emoji_and_accents_whole() {
+        let text = format!(
+            "{}{}{}{}{}{}a\r\nb",
+            FAMILY, THUMBS_UP, FLAG, KEYCAP, ACCENTED, RAINBOW_FLAG
+        );
+        let clusters: Vec<&str> = graphemes(&text).collect();
+        assert_eq!(
+            clusters,
+            [
+                FAMILY,
+                THUMBS_UP,
+                FLAG,
+                KEYCAP,
+                ACCENTED,
+                RAINBOW_FLAG,
+                "a",
+                "\r\n",
+                "b"
+            ]
+        );
+        assert_eq!(grapheme_count(""), 0);
+
+        let text = format!("{}{}{}", BENGALI, TAMIL, HANGUL_JAMO);
+        let clusters: Vec<&str> = graphemes(&text).collect();
+        assert_eq!(clusters, [BENGALI, TAMIL, HANGUL_JAMO]);
+
+        // Three flags in a row pair up from the start.
+        let flags: Vec<&str> = graphemes("🇯🇵🇫🇷🇺").collect();
+        assert_eq!(flags, ["🇯🇵", "🇫🇷", "🇺"]);
+    }
+
+    #[test]
+    fn test_truncate_and_slice() {
+        let text = format!("Hi {}{}!", FAMILY, FLAG);
+        assert_eq!(grapheme_count(&text), 6);
+
+        assert_eq!(truncate(&text, 4), format!("Hi {}", FAMILY));
+        assert_eq!(truncate(&text, 10), text);
+        assert_eq!(truncate(&text, 0), "");
+
+        assert_eq!(slice(&text, 3..5), format!("{}{}", FAMILY, FLAG));
+        assert_eq!(slice(&text, 4..=4), FLAG);
+        assert_eq!(slice(&text, 5..), "!");
+        assert_eq!(slice(&text, 8..), "");
+        assert_eq!(slice(&text, 5..=usize::MAX), "!");
+    }
+
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        let text = format!("ok {}{}!", THUMBS_UP, FLAG);
+        assert_eq!(truncate_with_ellipsis(&text, 6), text);
+        // The ellipsis takes the place of the last cluster that fits.
+        assert_eq!(
+            truncate_with_ellipsis(&text, 5),
+            format!("ok {}…", THUMBS_UP)
+        );
+        assert_eq!(truncate_with_ellipsis(&text, 1), "…");
+        assert_eq!(truncate_with_ellipsis(&text, 0), "");
+        assert_eq!(truncate_with_ellipsis(&text, usize::MAX), text);
+
+        let indic = format!("{}{}{}", BENGALI, TAMIL, BENGALI);
+        assert_eq!(truncate_with_ellipsis(&indic, 2), format!("{}…", BENGALI));
+    }
+}
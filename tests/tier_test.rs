@@ -0,0 +1,58 @@
+use tweety_rs::api::endpoint::Endpoint;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::search::QueryParams;
+use tweety_rs::api::tier::Tier;
+use tweety_rs::TweetyClient;
+
+fn client(tier: Tier) -> TweetyClient {
+    TweetyClient::from_bearer_token("token")
+        .with_base_url("http://127.0.0.1:1")
+        .with_tier(tier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tier_endpoints() {
+        assert!(Tier::Free.allows(&Endpoint::CreateTweet));
+        assert!(!Tier::Free.allows(&Endpoint::RecentSearch));
+        assert!(Tier::Basic.allows(&Endpoint::RecentSearch));
+        assert!(!Tier::Basic.allows(&Endpoint::FullArchiveSearch));
+        assert!(Tier::Pro.allows(&Endpoint::FullArchiveSearch));
+        assert_eq!(Tier::Enterprise.monthly_post_cap(), None);
+        assert_eq!(Tier::Pro.max_rule_length(), 1024);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_endpoint_fails_before_sending() {
+        let result = client(Tier::Basic).full_archive_search("rust", None).await;
+
+        match result {
+            Err(TweetyError::TierLimit { tier, reason }) => {
+                assert_eq!(tier, Tier::Basic);
+                assert!(reason.contains("/2/tweets/search/all"));
+            }
+            other => panic!("expected a tier limit, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_and_max_results_limits() {
+        let long_query = "a".repeat(600);
+        let result = client(Tier::Basic).recent_search(&long_query, None).await;
+        assert!(matches!(result, Err(TweetyError::TierLimit { .. })));
+
+        let params = QueryParams {
+            max_results: Some(200),
+            ..Default::default()
+        };
+        let result = client(Tier::Pro).recent_search("rust", Some(params)).await;
+        assert!(matches!(result, Err(TweetyError::TierLimit { .. })));
+
+        // Within the limits the request goes out and fails on the closed port instead.
+        let result = client(Tier::Pro).recent_search(&long_query, None).await;
+        assert!(matches!(result, Err(TweetyError::NetworkError(_))));
+    }
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::serve_status;
+use tweety_rs::api::tweet::Ids;
+use tweety_rs::TweetyClient;
+
+/// Answers every request with `body`.
+fn lookup_server(body: &'static str) -> String {
+    serve_status("200 OK", body).0
+}
+
+fn client(base_url: &str) -> TweetyClient {
+    TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(base_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_tweet_info_is_typed() {
+        let base_url = lookup_server(
+            r#"{"data":{"id":"1828783668107559176","edit_history_tweet_ids":["1828783668107559176"],"text":"hello"}}"#,
+        );
+        let client = client(&base_url);
+
+        let response = client.get_tweet_info("1828783668107559176").await.unwrap();
+        assert_eq!(response.data.text, "hello");
+
+        let raw = client
+            .get_tweet_info_raw("1828783668107559176")
+            .await
+            .unwrap();
+        assert_eq!(raw["data"]["id"], "1828783668107559176");
+    }
+
+    #[tokio::test]
+    async fn test_get_tweet_keeps_partial_errors() {
+        let base_url = lookup_server(
+            r#"{"data":[{"id":"1","edit_history_tweet_ids":["1"],"text":"first"}],"errors":[{"value":"2","detail":"Could not find tweet with ids: [2].","title":"Not Found Error","resource_type":"tweet","parameter":"ids","resource_id":"2","type":"https://api.twitter.com/2/problems/resource-not-found"}]}"#,
+        );
+        let client = client(&base_url);
+
+        let response = client
+            .get_tweet(Ids::Multiple(vec!["1".into(), "2".into()]))
+            .await
+            .unwrap();
+        assert_eq!(response.data.len(), 1);
+        assert_eq!(response.errors[0]["resource_id"], "2");
+
+        let raw = client.get_tweet_raw(Ids::Single("1".into())).await.unwrap();
+        assert_eq!(raw["data"][0]["text"], "first");
+    }
+}
@@ -0,0 +1,267 @@
+use serde_json::{json, Value};
+use tweety_rs::api::endpoint::Endpoint;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::mentions::{TweetData, TweetField, VotingStatus};
+use tweety_rs::api::tweet::LookupResponse;
+use tweety_rs::types::tweet::{AnnotationType, ContextDomainKind, PostTweetParams};
+use tweety_rs::types::user::WithheldScope;
+use tweety_rs::TweetyClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tweet_metrics_deserialize() {
+        let tweet: TweetData = serde_json::from_value(json!({
+            "id": "1",
+            "edit_history_tweet_ids": ["1"],
+            "text": "hello",
+            "public_metrics": {
+                "retweet_count": 1,
+                "reply_count": 2,
+                "like_count": 3,
+                "quote_count": 4,
+                "bookmark_count": 5,
+                "impression_count": 600
+            },
+            "non_public_metrics": {
+                "impression_count": 600,
+                "url_link_clicks": 7,
+                "user_profile_clicks": 8
+            },
+            "organic_metrics": {
+                "impression_count": 600,
+                "like_count": 3,
+                "reply_count": 2,
+                "retweet_count": 1,
+                "url_link_clicks": 7,
+                "user_profile_clicks": 8
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(tweet.public_metrics.unwrap().like_count, Some(3));
+        assert_eq!(tweet.non_public_metrics.unwrap().url_link_clicks, Some(7));
+        assert_eq!(tweet.organic_metrics.unwrap().impression_count, Some(600));
+        assert!(tweet.promoted_metrics.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_private_metric_fields_require_user_context() {
+        assert!(TweetField::NonPublicMetrics.requires_user_context());
+        assert!(TweetField::OrganicMetrics.requires_user_context());
+        assert!(TweetField::PromotedMetrics.requires_user_context());
+        assert!(!TweetField::PublicMetrics.requires_user_context());
+
+        // App-only requests for them fail before they are sent.
+        let client = TweetyClient::from_bearer_token("token").with_base_url("http://127.0.0.1:1");
+        let query = [
+            ("ids", "1"),
+            (
+                "tweet.fields",
+                "public_metrics,non_public_metrics,organic_metrics",
+            ),
+        ];
+        let result: Result<Value, _> = client
+            .send_request_as(&Endpoint::Tweets, &query, None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(TweetyError::ConfigError(ref message))
+                if message.contains("non_public_metrics, organic_metrics")
+        ));
+
+        let query = [("ids", "1"), ("tweet.fields", "public_metrics")];
+        let result: Result<Value, _> = client
+            .send_request_as(&Endpoint::Tweets, &query, None)
+            .await;
+        assert!(matches!(result, Err(TweetyError::NetworkError(_))));
+    }
+
+    #[test]
+    fn test_poll_attached_from_includes() {
+        let response: LookupResponse = serde_json::from_value(json!({
+            "data": {
+                "id": "1",
+                "edit_history_tweet_ids": ["1"],
+                "text": "Tabs or spaces?",
+                "attachments": { "poll_ids": ["99"] }
+            },
+            "includes": {
+                "polls": [{
+                    "id": "99",
+                    "options": [
+                        { "position": 1, "label": "Tabs", "votes": 12 },
+                        { "position": 2, "label": "Spaces", "votes": 30 }
+                    ],
+                    "duration_minutes": 1440,
+                    "end_datetime": "2024-01-02T00:00:00.000Z",
+                    "voting_status": "closed"
+                }]
+            }
+        }))
+        .unwrap();
+
+        let includes = response.includes.as_ref().unwrap();
+        let poll = response.data.poll(includes).unwrap();
+
+        assert_eq!(poll.options[1].label, "Spaces");
+        assert_eq!(poll.total_votes(), 42);
+        assert_eq!(poll.voting_status, Some(VotingStatus::Closed));
+        assert!(includes.users.is_empty());
+    }
+
+    #[test]
+    fn test_context_annotations_and_entities() {
+        let tweet: TweetData = serde_json::from_value(json!({
+            "id": "1",
+            "edit_history_tweet_ids": ["1"],
+            "text": "Watching the Lakers with @jack",
+            "context_annotations": [{
+                "domain": { "id": "12", "name": "Sports Team", "description": "A sports team" },
+                "entity": { "id": "10000", "name": "Los Angeles Lakers" }
+            }, {
+                "domain": { "id": "999", "name": "Brand New Domain" },
+                "entity": { "id": "20000", "name": "Something" }
+            }],
+            "entities": {
+                "annotations": [{
+                    "start": 13,
+                    "end": 18,
+                    "probability": 0.87,
+                    "type": "Organization",
+                    "normalized_text": "Lakers"
+                }],
+                "mentions": [{ "start": 25, "end": 30, "username": "jack", "id": "12" }]
+            }
+        }))
+        .unwrap();
+
+        let annotations = tweet.context_annotations.as_ref().unwrap();
+        assert_eq!(annotations[0].domain.kind(), ContextDomainKind::SportsTeam);
+        assert_eq!(ContextDomainKind::SportsTeam.name(), Some("Sports Team"));
+        assert_eq!(annotations[1].domain.kind(), ContextDomainKind::Other(999));
+        assert!(tweet.has_context_domain(ContextDomainKind::SportsTeam));
+        assert!(!tweet.has_context_domain(ContextDomainKind::Brand));
+
+        let entities = tweet.entities.as_ref().unwrap();
+        let annotation = &entities.annotations.as_ref().unwrap()[0];
+        assert_eq!(annotation.r#type, AnnotationType::Organization);
+        assert_eq!(
+            entities.mentions.as_ref().unwrap()[0].id.as_deref(),
+            Some("12")
+        );
+    }
+
+    #[test]
+    fn test_note_tweet_full_text() {
+        let long_text = "a".repeat(600);
+        let tweet: TweetData = serde_json::from_value(json!({
+            "id": "1",
+            "edit_history_tweet_ids": ["1"],
+            "text": format!("{}…", &long_text[..279]),
+            "note_tweet": { "text": long_text, "entities": { "hashtags": [] } }
+        }))
+        .unwrap();
+
+        assert_eq!(tweet.full_text().len(), 600);
+
+        let short: TweetData = serde_json::from_value(json!({
+            "id": "2",
+            "edit_history_tweet_ids": ["2"],
+            "text": "short"
+        }))
+        .unwrap();
+        assert_eq!(short.full_text(), "short");
+        assert_eq!(
+            serde_json::to_string(&TweetField::NoteTweet).unwrap(),
+            "\"note_tweet\""
+        );
+    }
+
+    #[test]
+    fn test_edit_controls_and_withheld() {
+        let tweet: TweetData = serde_json::from_value(json!({
+            "id": "1",
+            "edit_history_tweet_ids": ["1"],
+            "text": "hello",
+            "edit_controls": {
+                "edits_remaining": 4,
+                "is_edit_eligible": true,
+                "editable_until": "2024-01-01T00:30:00.000Z"
+            },
+            "withheld": { "copyright": true, "country_codes": ["DE", "FR"], "scope": "tweet" }
+        }))
+        .unwrap();
+
+        assert!(tweet.edit_controls.unwrap().can_edit());
+        let withheld = tweet.withheld.unwrap();
+        assert_eq!(withheld.copyright, Some(true));
+        assert_eq!(withheld.country_codes.unwrap(), ["DE", "FR"]);
+        assert!(matches!(withheld.scope, Some(WithheldScope::Tweet)));
+    }
+
+    #[test]
+    fn test_post_tweet_params_builder() {
+        let params = PostTweetParams::builder()
+            .poll(vec!["yes".to_string(), "no".to_string()], 60)
+            .in_reply_to("1")
+            .exclude_reply_users(vec!["2".into()])
+            .reply_settings("following")
+            .build();
+
+        assert_eq!(
+            params.to_json("vote"),
+            json!({
+                "text": "vote",
+                "poll": { "duration_minutes": 60, "options": ["yes", "no"] },
+                "reply": { "in_reply_to_tweet_id": "1", "exclude_reply_user_ids": ["2"] },
+                "reply_settings": "following"
+            })
+        );
+    }
+
+    #[test]
+    fn test_tweet_accessors() {
+        let tweet: TweetData = serde_json::from_value(json!({
+            "id": "20",
+            "edit_history_tweet_ids": ["20"],
+            "text": "#rust is nice https://t.co/x",
+            "in_reply_to_user_id": "7",
+            "referenced_tweets": [
+                { "type": "replied_to", "id": "19" },
+                { "type": "quoted", "id": "12" }
+            ],
+            "attachments": { "media_keys": ["3_1", "3_2"] },
+            "entities": {
+                "hashtags": [{ "start": 0, "end": 5, "tag": "rust" }]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(tweet.url(), "https://x.com/i/web/status/20");
+        assert!(tweet.is_reply());
+        assert!(!tweet.is_retweet());
+        assert_eq!(tweet.quoted_tweet_id(), Some("12"));
+        assert_eq!(tweet.hashtags(), vec!["rust"]);
+        assert_eq!(tweet.media_keys(), ["3_1", "3_2"]);
+    }
+
+    #[test]
+    fn test_tweet_accessors_without_fields() {
+        let tweet: TweetData = serde_json::from_value(json!({
+            "id": "21",
+            "edit_history_tweet_ids": ["21"],
+            "text": "RT @someone: hi",
+            "referenced_tweets": [{ "type": "retweeted", "id": "5" }]
+        }))
+        .unwrap();
+
+        assert!(tweet.is_retweet());
+        assert!(!tweet.is_reply());
+        assert_eq!(tweet.quoted_tweet_id(), None);
+        assert!(tweet.hashtags().is_empty());
+        assert!(tweet.media_keys().is_empty());
+    }
+}
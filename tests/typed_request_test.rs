@@ -0,0 +1,81 @@
+mod common;
+
+use common::serve_status;
+use serde::Deserialize;
+use tweety_rs::api::endpoint::Endpoint;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::tier::Tier;
+use tweety_rs::TweetyClient;
+
+#[derive(Debug, Deserialize)]
+struct Tweets {
+    data: Vec<Tweet>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tweet {
+    id: String,
+    text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_send_request_as_deserializes_own_structs() {
+        let (base_url, requests) = serve_status(
+            "200 OK",
+            r#"{"data":[{"id":"1","text":"first","edit_history_tweet_ids":["1"]},{"id":"2","text":"second","edit_history_tweet_ids":["2"]}]}"#,
+        );
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url(&base_url)
+            .with_tier(Tier::Basic);
+
+        let tweets: Tweets = client
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1,2")], None)
+            .await
+            .unwrap();
+
+        assert_eq!(tweets.data.len(), 2);
+        assert_eq!(tweets.data[1].id, "2");
+        assert_eq!(tweets.data[0].text, "first");
+        assert_eq!(requests.recv().unwrap().target, "/2/tweets?ids=1,2");
+    }
+
+    #[tokio::test]
+    async fn test_send_request_as_reports_api_errors() {
+        let (base_url, _requests) = serve_status(
+            "404 Not Found",
+            r#"{"title":"Not Found Error","detail":"Could not find tweet."}"#,
+        );
+        let client = TweetyClient::from_bearer_token("token").with_base_url(&base_url);
+
+        let result = client
+            .send_request_as::<Tweets>(&Endpoint::Tweet("1".into()), &[], None)
+            .await;
+
+        assert!(matches!(result, Err(TweetyError::ApiError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_send_request_as_reads_empty_body_as_null() {
+        let (base_url, _requests) = serve_status("204 No Content", "");
+        let client =
+            TweetyClient::new("key", "token", "secret", "token_secret").with_base_url(&base_url);
+
+        let result: Option<Tweets> = client
+            .send_request_as(
+                &Endpoint::Unlike {
+                    user_id: "2244994945".into(),
+                    tweet_id: "1".into(),
+                },
+                &[],
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,116 @@
+use serde_json::json;
+use tweety_rs::api::mentions::TweetData;
+use tweety_rs::types::borrowed::TweetRef;
+use tweety_rs::types::dm::{DmEvent, EventType};
+use tweety_rs::types::lang::Lang;
+use tweety_rs::types::list::List;
+use tweety_rs::types::space::{Space, SpaceState};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_parses_and_round_trips() {
+        let value = json!({
+            "id": "1146654567674912769",
+            "name": "Rustaceans",
+            "private": false,
+            "member_count": 42,
+            "owner_id": "2244994945"
+        });
+        let list: List = serde_json::from_value(value).unwrap();
+
+        assert_eq!(list.member_count, Some(42));
+        assert_eq!(list.url(), "https://x.com/i/lists/1146654567674912769");
+
+        let round_trip: List =
+            serde_json::from_value(serde_json::to_value(&list).unwrap()).unwrap();
+        assert_eq!(round_trip, list);
+    }
+
+    #[test]
+    fn test_space_parses() {
+        let space: Space =
+            serde_json::from_value(json!({ "id": "1DXxyRYNejbKM", "state": "live" })).unwrap();
+
+        assert_eq!(space.state, SpaceState::Live);
+        assert!(space.host_ids.is_empty());
+        assert_eq!(space.clone(), space);
+    }
+
+    #[test]
+    fn test_dm_event_parses() {
+        let event: DmEvent = serde_json::from_value(json!({
+            "id": "1580705921830768647",
+            "event_type": "MessageCreate",
+            "text": "Hello",
+            "sender_id": "2244994945",
+            "attachments": { "media_keys": ["3_1"] }
+        }))
+        .unwrap();
+
+        assert_eq!(event.event_type, EventType::MessageCreate);
+        assert_eq!(event.attachments.as_ref().unwrap().media_keys, ["3_1"]);
+        assert_eq!(event.clone(), event);
+    }
+
+    #[cfg(feature = "dm")]
+    #[test]
+    fn test_dm_event_is_reexported() {
+        let event: tweety_rs::api::direct_messages::DmEvent = serde_json::from_value(json!({
+            "id": "1",
+            "event_type": "ParticipantsJoin",
+            "participant_ids": ["2244994945"]
+        }))
+        .unwrap();
+        let _: DmEvent = event;
+    }
+
+    #[test]
+    fn test_lang_codes_round_trip() {
+        assert_eq!(Lang::from_code("PT"), Lang::Portuguese);
+        assert_eq!("und".parse::<Lang>().unwrap(), Lang::Undetermined);
+        assert_eq!(Lang::Japanese.name(), Some("Japanese"));
+        assert_eq!(Lang::from_code("Xx"), Lang::Other("xx".to_string()));
+        assert_eq!(Lang::from_code("xx").name(), None);
+        assert_eq!(Lang::English.operator(), "lang:en");
+        assert_eq!(Lang::Other("tlh".to_string()).to_string(), "tlh");
+
+        assert_eq!(serde_json::to_value(Lang::Chinese).unwrap(), json!("zh"));
+        let other: Lang = serde_json::from_value(json!("tlh")).unwrap();
+        assert_eq!(serde_json::to_value(other).unwrap(), json!("tlh"));
+    }
+
+    #[test]
+    fn test_tweets_carry_lang() {
+        let tweet: TweetData = serde_json::from_value(json!({
+            "id": "1",
+            "edit_history_tweet_ids": ["1"],
+            "text": "hola",
+            "lang": "es"
+        }))
+        .unwrap();
+        assert_eq!(tweet.lang, Some(Lang::Spanish));
+
+        let line = br#"{"id":"1","text":"hallo","lang":"de"}"#;
+        let tweet: TweetRef = serde_json::from_slice(line).unwrap();
+        assert_eq!(tweet.language(), Some(Lang::German));
+    }
+
+    #[cfg(feature = "lang-detect")]
+    #[test]
+    fn test_detect_lang_when_undetermined() {
+        let tweet: TweetData = serde_json::from_value(json!({
+            "id": "1",
+            "edit_history_tweet_ids": ["1"],
+            "text": "@rustlang #rust Aujourd'hui nous avons publié une nouvelle version de la bibliothèque https://t.co/abc",
+            "lang": "und"
+        }))
+        .unwrap();
+
+        assert_eq!(tweet.lang, Some(Lang::Undetermined));
+        assert_eq!(tweet.detect_lang(), Some(Lang::French));
+        assert_eq!(Lang::detect("@rustlang #rust https://t.co/abc"), None);
+    }
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "media-upload")]
+
+mod common;
+
+use common::serve_status;
+use std::fs;
+use std::path::PathBuf;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::uploads::{UploadState, DEFAULT_UPLOAD_CONCURRENCY, MAX_CHUNK_SIZE};
+use tweety_rs::TweetyClient;
+
+fn assert_send<T: Send>(_: &T) {}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("tweety-{}-{}", std::process::id(), name))
+}
+
+fn write_state(name: &str, segments_appended: u32, expires_at: u64) -> PathBuf {
+    let path = temp_path(name);
+    let state = serde_json::json!({
+        "media_id": 710511363345354753u64,
+        "path": "video.mp4",
+        "media_type": "video/mp4",
+        "total_bytes": 10 * 1024 * 1024,
+        "chunk_size": 4 * 1024 * 1024,
+        "segments_appended": segments_appended,
+        "expires_at": expires_at,
+    });
+    fs::write(&path, state.to_string()).expect("failed to write upload state");
+    path
+}
+
+/// Answers every request with an INIT response.
+fn init_server() -> String {
+    serve_status(
+        "200 OK",
+        r#"{"media_id":710511363345354753,"media_id_string":"710511363345354753","expires_after_secs":86400}"#,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_state_persists_progress() {
+        let path = write_state("upload-state.json", 1, u64::MAX);
+        let state = UploadState::load(&path).unwrap();
+
+        assert_eq!(state.total_segments(), 3);
+        assert!(!state.is_complete());
+
+        assert_eq!(state.concurrency(), DEFAULT_UPLOAD_CONCURRENCY);
+        assert_eq!(state.clone().with_concurrency(0).concurrency(), 1);
+
+        let copy = temp_path("upload-state-copy.json");
+        let saved = state.clone().persist_to(&copy).unwrap();
+        assert_eq!(UploadState::load(&copy).unwrap(), saved);
+
+        fs::remove_file(path).ok();
+        fs::remove_file(copy).ok();
+    }
+
+    #[tokio::test]
+    async fn test_resume_expired_upload_fails_before_sending() {
+        let path = write_state("expired-upload-state.json", 2, 1);
+        let mut state = UploadState::load(&path).unwrap();
+        let client = TweetyClient::new("a", "b", "c", "d");
+
+        let result = client.resume_upload(&mut state).await;
+
+        assert!(matches!(result, Err(TweetyError::UploadError(_))));
+        assert_eq!(state.segments_appended, 2);
+        fs::remove_file(path).ok();
+    }
+
+    /// Progress-reporting uploads can still be spawned onto other tasks.
+    #[test]
+    fn test_progress_uploads_are_send() {
+        let client = TweetyClient::new("a", "b", "c", "d");
+        let path = temp_path("video.mp4");
+        let report = |sent: u64, total: u64| println!("{}/{}", sent, total);
+
+        let single = client.upload_file_with_progress(&path, report);
+        let chunked = client.upload_chunked_with_progress(&path, "video/mp4", None, report);
+
+        assert_send(&single);
+        assert_send(&chunked);
+    }
+
+    #[tokio::test]
+    async fn test_init_uses_configured_chunk_size() {
+        let base_url = init_server();
+        let path = temp_path("chunk-size.mp4");
+        fs::write(&path, vec![0; 10_000]).unwrap();
+
+        let client = TweetyClient::new("a", "b", "c", "d")
+            .with_base_url(&base_url)
+            .with_upload_chunk_size(1024);
+        let state = client
+            .init_chunked_upload(&path, "video/mp4", None)
+            .await
+            .unwrap();
+        assert_eq!(state.chunk_size, 1024);
+        assert_eq!(state.total_segments(), 10);
+
+        // Too small for 1000 segments: the segments grow to fit.
+        let client = client.with_upload_chunk_size(1);
+        let state = client
+            .init_chunked_upload(&path, "video/mp4", None)
+            .await
+            .unwrap();
+        assert_eq!(state.chunk_size, 10);
+
+        let client = client.with_upload_chunk_size(u64::MAX);
+        let state = client
+            .init_chunked_upload(&path, "video/mp4", None)
+            .await
+            .unwrap();
+        assert_eq!(state.chunk_size, MAX_CHUNK_SIZE);
+        assert_eq!(state.total_segments(), 1);
+
+        fs::remove_file(path).ok();
+    }
+}
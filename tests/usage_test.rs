@@ -0,0 +1,148 @@
+mod common;
+
+use common::{serve, Request, Response};
+use serde_json::Value;
+use std::sync::mpsc;
+use tweety_rs::api::endpoint::Endpoint;
+use tweety_rs::api::error::TweetyError;
+use tweety_rs::api::state::FileStateStore;
+use tweety_rs::api::tier::Tier;
+use tweety_rs::api::usage::{UsageGuard, UsageKind};
+use tweety_rs::TweetyClient;
+
+const USAGE: &str = r#"{"data":{"cap_reset_day":19,"project_id":"1491859712468779008","project_cap":"10000","project_usage":"8999","daily_project_usage":{"project_id":"1491859712468779008","usage":[{"date":"2024-05-02T00:00:00.000Z","usage":"31"}]},"daily_client_app_usage":[{"client_app_id":"28712364","usage":[{"date":"2024-05-02T00:00:00.000Z","usage":"31"}],"usage_result_count":1}]}}"#;
+
+/// Answers `GET /2/usage/tweets` with [`USAGE`], tweet lookups with two tweets and
+/// anything else with a created tweet.
+fn server() -> (String, mpsc::Receiver<Request>) {
+    serve(|request| match (request.method.as_str(), request.path()) {
+        ("GET", "/2/usage/tweets") => Response::ok(USAGE),
+        ("GET", "/2/tweets") => {
+            Response::ok(r#"{"data":[{"id":"1","text":"one"},{"id":"2","text":"two"}]}"#)
+        }
+        _ => Response::ok(r#"{"data":{"id":"3","text":"posted"}}"#),
+    })
+}
+
+async fn post(client: &TweetyClient) -> Result<Value, TweetyError> {
+    client
+        .send_request_as(
+            &Endpoint::CreateTweet,
+            &[],
+            Some(serde_json::json!({ "text": "hi" })),
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_requests_past_the_budget_are_refused() {
+        let (base_url, requests) = server();
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&base_url)
+            .with_usage_guard(UsageGuard::new(0.5).post_cap(4));
+
+        post(&client).await.unwrap();
+        post(&client).await.unwrap();
+        match post(&client).await {
+            Err(TweetyError::UsageBudgetExceeded { kind, used, budget }) => {
+                assert_eq!(kind, UsageKind::Sent);
+                assert_eq!((used, budget), (2, 2));
+            }
+            other => panic!("expected the budget to be used up, got {:?}", other),
+        }
+        assert_eq!(requests.try_iter().count(), 2);
+
+        // Reads have no cap without a tier, so they aren't limited.
+        let guard = client.usage_guard().unwrap();
+        assert_eq!(guard.budget(UsageKind::Read, None), None);
+        assert_eq!(
+            guard.budget(UsageKind::Read, Some(Tier::Basic)),
+            Some(5_000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_posts_sent_are_remembered_across_restarts() {
+        let (base_url, requests) = server();
+        let path = std::env::temp_dir().join(format!("tweety-usage-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let guard = || {
+            UsageGuard::new(0.5)
+                .post_cap(4)
+                .state_store(FileStateStore::new(&path), "usage")
+        };
+
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&base_url)
+            .with_usage_guard(guard());
+        post(&client).await.unwrap();
+        post(&client).await.unwrap();
+
+        // A new process starts from the saved count.
+        let restarted = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&base_url)
+            .with_usage_guard(guard());
+        assert!(matches!(
+            post(&restarted).await,
+            Err(TweetyError::UsageBudgetExceeded { used: 2, .. })
+        ));
+        assert_eq!(requests.try_iter().count(), 2);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_warn_only_lets_requests_through() {
+        let (base_url, requests) = server();
+        let client = TweetyClient::new("key", "token", "secret", "token_secret")
+            .with_base_url(&base_url)
+            .with_tier(Tier::Free)
+            .with_usage_guard(UsageGuard::new(0.0).warn_only());
+
+        post(&client).await.unwrap();
+        post(&client).await.unwrap();
+        assert_eq!(requests.try_iter().count(), 2);
+        assert_eq!(client.usage_guard().unwrap().used(UsageKind::Sent), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sync_usage_replaces_the_read_count_and_cap() {
+        let (base_url, requests) = server();
+        let client = TweetyClient::from_bearer_token("token")
+            .with_base_url(&base_url)
+            .with_usage_guard(UsageGuard::new(0.9).read_cap(100));
+
+        let usage = client.sync_usage().await.unwrap();
+        assert_eq!(usage.project_cap, 10_000);
+        assert_eq!(usage.project_usage, 8_999);
+        assert_eq!(usage.cap_reset_day, 19);
+        assert_eq!(client.usage_guard().unwrap().reset_day(), 19);
+        let daily = usage.daily_project_usage.unwrap();
+        assert_eq!(daily.usage[0].usage, 31);
+        assert_eq!(usage.daily_client_app_usage[0].client_app_id, "28712364");
+        assert_eq!(requests.recv().unwrap().path(), "/2/usage/tweets");
+
+        let guard = client.usage_guard().unwrap();
+        assert_eq!(guard.used(UsageKind::Read), 8_999);
+        assert_eq!(guard.budget(UsageKind::Read, None), Some(9_000));
+
+        let _: Value = client
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1,2")], None)
+            .await
+            .unwrap();
+        assert_eq!(guard.used(UsageKind::Read), 9_001);
+        let result: Result<Value, _> = client
+            .send_request_as(&Endpoint::Tweets, &[("ids", "1,2")], None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(TweetyError::UsageBudgetExceeded {
+                kind: UsageKind::Read,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,210 @@
+use serde_json::{json, Value};
+use tweety_rs::types::webhook::{RelationshipAction, WebhookEvent, WebhookPayload};
+
+fn user(id: &str, screen_name: &str) -> Value {
+    json!({
+        "id": id.parse::<i64>().unwrap(),
+        "id_str": id,
+        "name": screen_name,
+        "screen_name": screen_name,
+        "followers_count": 10,
+        "friends_count": 5,
+        "listed_count": 0,
+        "favourites_count": 3,
+        "statuses_count": 42
+    })
+}
+
+fn tweet(id: &str, text: &str) -> Value {
+    json!({
+        "created_at": "Mon Feb 12 16:06:48 +0000 2018",
+        "id": id.parse::<i64>().unwrap(),
+        "id_str": id,
+        "text": text,
+        "truncated": false,
+        "user": user("2244994945", "XDevelopers"),
+        "is_quote_status": false,
+        "retweet_count": 0,
+        "favorite_count": 0,
+        "favorited": false,
+        "retweeted": false,
+        "lang": "en"
+    })
+}
+
+fn relationship(action: &str) -> Value {
+    json!({
+        "type": action,
+        "created_timestamp": "1517588749178",
+        "source": user("3001969357", "jordan"),
+        "target": user("2244994945", "XDevelopers")
+    })
+}
+
+fn parse(payload: Value) -> Vec<WebhookEvent> {
+    let payload = WebhookPayload::from_value(payload).unwrap();
+    assert_eq!(payload.for_user_id, "2244994945");
+    payload.events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tweet_and_favorite_events() {
+        let events = parse(json!({
+            "for_user_id": "2244994945",
+            "tweet_create_events": [tweet("963073287419346944", "@XDevelopers hello")],
+        }));
+        assert!(
+            matches!(&events[..], [WebhookEvent::TweetCreate(tweet)] if tweet.content() == "@XDevelopers hello")
+        );
+
+        let events = parse(json!({
+            "for_user_id": "2244994945",
+            "favorite_events": [{
+                "id": "a7ba59eab0bfcba386f7acedac279542",
+                "created_at": "Mon Mar 26 16:33:26 +0000 2018",
+                "timestamp_ms": 1522082006140u64,
+                "favorited_status": tweet("963073287419346944", "liked"),
+                "user": user("3001969357", "jordan")
+            }],
+        }));
+        assert!(matches!(&events[..], [WebhookEvent::Favorite(favorite)]
+            if favorite.user.screen_name() == "jordan" && favorite.favorited_status.id() == 963073287419346944));
+    }
+
+    #[test]
+    fn test_relationship_events() {
+        let events = parse(json!({
+            "for_user_id": "2244994945",
+            "follow_events": [relationship("follow"), relationship("unfollow")],
+            "block_events": [relationship("block")],
+            "mute_events": [relationship("unmute")],
+        }));
+
+        let mut actions: Vec<(&str, RelationshipAction)> = events
+            .iter()
+            .map(|event| match event {
+                WebhookEvent::Follow(event) => ("follow", event.action),
+                WebhookEvent::Block(event) => ("block", event.action),
+                WebhookEvent::Mute(event) => ("mute", event.action),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        actions.sort_by_key(|(kind, _)| *kind);
+        assert_eq!(
+            actions,
+            [
+                ("block", RelationshipAction::Block),
+                ("follow", RelationshipAction::Follow),
+                ("follow", RelationshipAction::Unfollow),
+                ("mute", RelationshipAction::Unmute),
+            ]
+        );
+        let block = events
+            .iter()
+            .find_map(|event| match event {
+                WebhookEvent::Block(block) => Some(block),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(block.source.id(), "3001969357");
+        assert_eq!(block.target.screen_name(), "XDevelopers");
+    }
+
+    #[test]
+    fn test_direct_message_events() {
+        let events = parse(json!({
+            "for_user_id": "2244994945",
+            "direct_message_events": [{
+                "type": "message_create",
+                "id": "954491830116155396",
+                "created_timestamp": "1516403560557",
+                "message_create": {
+                    "target": { "recipient_id": "2244994945" },
+                    "sender_id": "3001969357",
+                    "source_app_id": "13090192",
+                    "message_data": {
+                        "text": "Hello World!",
+                        "entities": { "hashtags": [], "symbols": [], "user_mentions": [], "urls": [] }
+                    }
+                }
+            }],
+            "direct_message_indicate_typing_events": [{
+                "created_timestamp": "1518127183443",
+                "sender_id": "3001969357",
+                "target": { "recipient_id": "2244994945" }
+            }],
+            "users": { "3001969357": user("3001969357", "jordan") },
+        }));
+
+        assert_eq!(events.len(), 2);
+        let message = events
+            .iter()
+            .find_map(|event| match event {
+                WebhookEvent::DirectMessage(message) => Some(message),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(message.sender_id(), "3001969357");
+        assert_eq!(message.recipient_id(), "2244994945");
+        assert_eq!(message.text(), "Hello World!");
+        assert!(message.message_create.message_data.attachment.is_none());
+        assert!(events.iter().any(
+            |event| matches!(event, WebhookEvent::DirectMessageIndicateTyping(typing)
+            if typing.sender_id == "3001969357" && typing.target.recipient_id == "2244994945")
+        ));
+    }
+
+    #[test]
+    fn test_tweet_delete_events() {
+        let events = parse(json!({
+            "for_user_id": "2244994945",
+            "tweet_delete_events": [{
+                "status": { "id": "601430178305220608", "user_id": "3198576760" },
+                "timestamp_ms": "1432228155593"
+            }],
+        }));
+
+        assert!(matches!(&events[..], [WebhookEvent::TweetDelete(delete)]
+            if delete.status.id == "601430178305220608" && delete.status.user_id == "3198576760"));
+    }
+
+    #[test]
+    fn test_unknown_and_malformed_events_keep_their_value() {
+        let revoke = json!({ "revoke": { "date_time": "2018-05-24T09:48:12+00:00" } });
+        let events = parse(json!({
+            "for_user_id": "2244994945",
+            "user_event": revoke,
+            "direct_message_mark_read_events": [{ "created_timestamp": "1518452444662" }],
+            "follow_events": [{ "type": "poke" }],
+        }));
+
+        let mut kinds: Vec<&str> = events
+            .iter()
+            .map(|event| match event {
+                WebhookEvent::Other { kind, .. } => kind.as_str(),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        kinds.sort();
+        assert_eq!(
+            kinds,
+            [
+                "direct_message_mark_read_events",
+                "follow_events",
+                "user_event"
+            ]
+        );
+        assert!(events.iter().any(
+            |event| matches!(event, WebhookEvent::Other { kind, event } if kind == "user_event" && *event == revoke)
+        ));
+    }
+
+    #[test]
+    fn test_non_objects_are_not_payloads() {
+        assert!(WebhookPayload::from_value(json!([1, 2])).is_none());
+    }
+}
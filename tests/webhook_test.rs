@@ -0,0 +1,143 @@
+#![cfg(feature = "webhooks")]
+
+use hyper::{Body, Method, Request};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use tweety_rs::api::shutdown::Shutdown;
+use tweety_rs::api::webhook::{verify_signature, WebhookHandler, SIGNATURE_HEADER};
+use tweety_rs::TweetyClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc_response_is_signed_with_the_consumer_secret() {
+        let handler = WebhookHandler::new("consumer_secret", |_| {});
+        assert_eq!(
+            handler.crc_response("crc_token_1"),
+            "sha256=HEr3uqlJr6fQa1Q0PWeTG+AJiVIVKqVXG9vPnosgx9U="
+        );
+
+        let client = TweetyClient::new("key", "token", "consumer_secret", "token_secret");
+        assert_eq!(
+            client.webhook_handler(|_| {}).crc_response("crc_token_1"),
+            handler.crc_response("crc_token_1")
+        );
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let body = br#"{"for_user_id":"2244994945"}"#;
+        let signature = "sha256=u4lZKKrBfHwAe+776eHnVf2VV1ggheRb1fS3xokQxlA=";
+
+        assert!(verify_signature("consumer_secret", body, signature));
+        assert!(!verify_signature("other_secret", body, signature));
+        assert!(!verify_signature(
+            "consumer_secret",
+            br#"{"for_user_id":"1"}"#,
+            signature
+        ));
+        assert!(!verify_signature(
+            "consumer_secret",
+            body,
+            signature.trim_start_matches("sha256=")
+        ));
+        assert!(!verify_signature(
+            "consumer_secret",
+            body,
+            "sha256=not base64!"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_bodies_are_refused_before_verifying() {
+        let handler = WebhookHandler::new("consumer_secret", |_| {
+            panic!("oversized deliveries aren't passed on")
+        })
+        .max_body_size(1024);
+        let post = |content_length: Option<usize>| {
+            let mut request = Request::builder().method(Method::POST).header(
+                SIGNATURE_HEADER,
+                "sha256=qIzpTlwJgXPPUhEOAoAX3CAaBq8ciLoS/5oMo2j37dM=",
+            );
+            if let Some(length) = content_length {
+                request = request.header("content-length", length);
+            }
+            request.body(Body::from(vec![b' '; 2048])).unwrap()
+        };
+
+        // Refused on the header alone, or once the bytes received pass the limit.
+        assert_eq!(handler.handle(post(Some(2048))).await.status(), 413);
+        assert_eq!(handler.handle(post(None)).await.status(), 413);
+    }
+
+    #[tokio::test]
+    async fn test_server_answers_crc_and_dispatches_events() {
+        let (sender, events) = mpsc::channel();
+        let shutdown = Shutdown::new();
+        let handler = WebhookHandler::new("consumer_secret", move |event| {
+            let _ = sender.send(event);
+        })
+        .shutdown_on(&shutdown);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move { handler.serve(listener).await });
+
+        let http = reqwest::Client::new();
+        let crc: serde_json::Value = http
+            .get(format!("{}?crc_token=crc_token_1", url))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            crc["response_token"],
+            "sha256=HEr3uqlJr6fQa1Q0PWeTG+AJiVIVKqVXG9vPnosgx9U="
+        );
+
+        let missing = http.get(&url).send().await.unwrap();
+        assert_eq!(missing.status(), 400);
+
+        let delivered = http
+            .post(&url)
+            .header(
+                SIGNATURE_HEADER,
+                "sha256=qIzpTlwJgXPPUhEOAoAX3CAaBq8ciLoS/5oMo2j37dM=",
+            )
+            .body(r#"{"for_user_id":"2244994945","favorite_events":[]}"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(delivered.status(), 200);
+        let payload = events.recv().unwrap();
+        assert_eq!(payload.for_user_id, "2244994945");
+        assert!(payload.events.is_empty());
+
+        let malformed = http
+            .post(&url)
+            .header(
+                SIGNATURE_HEADER,
+                "sha256=Tbfm1z2sDFaWMUV4eKVQQz3q6yr/uM/8bjBVApZLftI=",
+            )
+            .body("not json")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(malformed.status(), 400);
+
+        let unsigned = http
+            .post(&url)
+            .body(r#"{"for_user_id":"2244994945"}"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unsigned.status(), 401);
+        assert!(events.try_recv().is_err());
+
+        shutdown.shutdown().await;
+        assert!(server.await.unwrap().is_ok());
+    }
+}